@@ -1,7 +1,33 @@
-use ghostty_vt::Terminal;
-use std::time::Instant;
+//! Style dump benchmark.
+//!
+//! `cargo run --release -p ghostty_vt --example perf_style_dump -- [--iters N] [--save FILE]
+//! [--baseline FILE]`
+//!
+//! Prints nanoseconds per row for each dump path. `--save` writes the results as
+//! `name ns_per_row` lines; `--baseline` compares against such a file and exits with an error if
+//! any path got more than 20% slower, so the numbers can be tracked across changes.
+
+use ghostty_vt::{StyleRun, Terminal};
+use std::collections::BTreeMap;
+use std::time::{Duration, Instant};
+
+/// Slowdown against the baseline that counts as a regression.
+const REGRESSION_THRESHOLD: f64 = 1.2;
 
 fn main() {
+    let mut iters: usize = 50;
+    let mut save = None;
+    let mut baseline = None;
+    let mut args = std::env::args().skip(1);
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--iters" => iters = args.next().and_then(|n| n.parse().ok()).expect("--iters N"),
+            "--save" => save = Some(args.next().expect("--save FILE")),
+            "--baseline" => baseline = Some(args.next().expect("--baseline FILE")),
+            other => panic!("unknown argument: {other}"),
+        }
+    }
+
     let cols: u16 = 120;
     let rows: u16 = 40;
     let mut terminal = Terminal::new(cols, rows).expect("terminal init failed");
@@ -25,7 +51,9 @@ fn main() {
 
     terminal.feed(input.as_bytes()).expect("feed failed");
 
-    let iters: usize = 50;
+    let mut results = BTreeMap::new();
+    let per_row = |elapsed: Duration| elapsed.as_nanos() as f64 / (iters * rows as usize) as f64;
+
     let mut total_cell_styles = 0usize;
     let start = Instant::now();
     for _ in 0..iters {
@@ -36,7 +64,7 @@ fn main() {
                 .len();
         }
     }
-    let cell_styles_elapsed = start.elapsed();
+    results.insert("cell_styles", per_row(start.elapsed()));
 
     let mut total_style_runs = 0usize;
     let start = Instant::now();
@@ -48,9 +76,78 @@ fn main() {
                 .len();
         }
     }
-    let style_runs_elapsed = start.elapsed();
+    results.insert("style_runs", per_row(start.elapsed()));
+
+    // The renderer's path: one buffer per row, refilled every frame.
+    let mut buffers: Vec<Vec<StyleRun>> = vec![Vec::new(); rows as usize];
+    let start = Instant::now();
+    for _ in 0..iters {
+        for (row, runs) in buffers.iter_mut().enumerate() {
+            terminal
+                .dump_viewport_row_style_runs_into(row as u16, runs)
+                .expect("style run dump failed");
+        }
+    }
+    results.insert("style_runs_reused", per_row(start.elapsed()));
+
+    // Text and style runs for the whole viewport in one FFI call.
+    let mut lines = Vec::new();
+    let start = Instant::now();
+    for _ in 0..iters {
+        terminal
+            .dump_viewport_rows_into(0..rows, &mut lines, &mut buffers)
+            .expect("row dump failed");
+    }
+    results.insert("rows_batched", per_row(start.elapsed()));
+
+    let mut dirty = Vec::new();
+    let start = Instant::now();
+    for _ in 0..iters {
+        for row in 0..rows {
+            terminal
+                .feed(format!("\x1b[{};2Hx", row + 1).as_bytes())
+                .expect("feed failed");
+        }
+        terminal
+            .take_dirty_viewport_rows_into(rows, &mut dirty)
+            .expect("dirty rows failed");
+    }
+    results.insert("dirty_rows_reused", per_row(start.elapsed()));
 
     println!("iters={iters} rows={rows} cols={cols}");
-    println!("cell_styles: {cell_styles_elapsed:?} (records={total_cell_styles})");
-    println!("style_runs:  {style_runs_elapsed:?} (records={total_style_runs})");
+    println!("records: cell_styles={total_cell_styles} style_runs={total_style_runs}");
+    for (name, ns) in &results {
+        println!("{name:<18} {ns:>10.1} ns/row");
+    }
+
+    if let Some(path) = save {
+        let text: String = results
+            .iter()
+            .map(|(name, ns)| format!("{name} {ns:.1}\n"))
+            .collect();
+        std::fs::write(&path, text).expect("failed to write results");
+    }
+
+    if let Some(path) = baseline {
+        let text = std::fs::read_to_string(&path).expect("failed to read baseline");
+        let mut regressed = false;
+        for line in text.lines() {
+            let Some((name, ns)) = line.split_once(' ') else {
+                continue;
+            };
+            let (Ok(before), Some(after)) = (ns.trim().parse::<f64>(), results.get(name)) else {
+                continue;
+            };
+            let ratio = after / before;
+            println!("{name:<18} {:>+9.1}% vs baseline", (ratio - 1.0) * 100.0);
+            regressed |= ratio > REGRESSION_THRESHOLD;
+        }
+        if regressed {
+            eprintln!(
+                "regression over {:.0}%",
+                (REGRESSION_THRESHOLD - 1.0) * 100.0
+            );
+            std::process::exit(1);
+        }
+    }
 }
@@ -1,5 +1,6 @@
 use std::ffi::c_void;
 use std::fmt;
+use std::num::NonZeroU32;
 use std::ptr::NonNull;
 
 #[derive(Debug)]
@@ -7,7 +8,9 @@ pub enum Error {
     CreateFailed,
     FeedFailed(i32),
     ScrollFailed(i32),
+    ResetFailed(i32),
     DumpFailed,
+    InvalidSize { cols: u16, rows: u16 },
 }
 
 impl fmt::Display for Error {
@@ -16,7 +19,9 @@ impl fmt::Display for Error {
             Error::CreateFailed => write!(f, "terminal create failed"),
             Error::FeedFailed(code) => write!(f, "terminal feed failed: {code}"),
             Error::ScrollFailed(code) => write!(f, "terminal scroll failed: {code}"),
+            Error::ResetFailed(code) => write!(f, "terminal reset failed: {code}"),
             Error::DumpFailed => write!(f, "terminal dump failed"),
+            Error::InvalidSize { cols, rows } => write!(f, "invalid terminal size: {cols}x{rows}"),
         }
     }
 }
@@ -53,6 +58,31 @@ pub struct CellStyle {
     pub flags: u8,
 }
 
+/// SGR 4 underline sub-style (`4:x`, and `21` for double).
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub enum Underline {
+    #[default]
+    None,
+    Single,
+    Double,
+    Curly,
+    Dotted,
+    Dashed,
+}
+
+impl Underline {
+    fn from_raw(raw: u8) -> Self {
+        match raw {
+            1 => Self::Single,
+            2 => Self::Double,
+            3 => Self::Curly,
+            4 => Self::Dotted,
+            5 => Self::Dashed,
+            _ => Self::None,
+        }
+    }
+}
+
 #[derive(Clone, Copy, Debug, PartialEq, Eq)]
 pub struct StyleRun {
     pub start_col: u16,
@@ -60,6 +90,89 @@ pub struct StyleRun {
     pub fg: Rgb,
     pub bg: Rgb,
     pub flags: u8,
+    pub underline: Underline,
+    /// SGR 58 underline color; `None` means the underline uses the text color.
+    pub underline_color: Option<Rgb>,
+    /// OSC 8 link the run's cells belong to. Runs of the same link share an id, also across
+    /// rows, so a whole link can be underlined or highlighted at once.
+    pub hyperlink_id: Option<NonZeroU32>,
+}
+
+/// A text match located by [`Terminal::search`].
+///
+/// `row` is screen-absolute (see [`Terminal::active_top_row`]); columns are 1-based and inclusive.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct SearchMatch {
+    pub row: u32,
+    pub start_col: u16,
+    pub end_col: u16,
+}
+
+/// A 0-based `(col, row)` index into the viewport's cell grid.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub struct Cell {
+    pub col: u16,
+    pub row: u16,
+}
+
+/// A 1-based `(col, row)` position in the viewport, as used by cursor reports, mouse reporting,
+/// and [`Terminal::hyperlink_at`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct ViewportPoint {
+    pub col: u16,
+    pub row: u16,
+}
+
+/// A position anywhere on the screen: `row` is screen-absolute (0 = oldest scrollback row, see
+/// [`Terminal::active_top_row`]) and `col` is 1-based, matching [`SearchMatch`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct AbsolutePoint {
+    pub row: u32,
+    pub col: u16,
+}
+
+impl From<Cell> for ViewportPoint {
+    fn from(cell: Cell) -> Self {
+        Self {
+            col: cell.col.saturating_add(1),
+            row: cell.row.saturating_add(1),
+        }
+    }
+}
+
+impl From<ViewportPoint> for Cell {
+    fn from(point: ViewportPoint) -> Self {
+        Self {
+            col: point.col.saturating_sub(1),
+            row: point.row.saturating_sub(1),
+        }
+    }
+}
+
+impl ViewportPoint {
+    /// Converts to screen-absolute coordinates given the screen row shown at the top of the
+    /// viewport (see [`Terminal::viewport_top_row`]).
+    pub fn to_absolute(self, viewport_top_row: u32) -> AbsolutePoint {
+        AbsolutePoint {
+            row: viewport_top_row + u32::from(self.row.saturating_sub(1)),
+            col: self.col,
+        }
+    }
+}
+
+impl AbsolutePoint {
+    /// Converts to viewport coordinates, or `None` if the row is outside the `rows` rows
+    /// starting at `viewport_top_row`.
+    pub fn to_viewport(self, viewport_top_row: u32, rows: u16) -> Option<ViewportPoint> {
+        let offset = self.row.checked_sub(viewport_top_row)?;
+        if offset >= u32::from(rows) {
+            return None;
+        }
+        Some(ViewportPoint {
+            col: self.col,
+            row: offset as u16 + 1,
+        })
+    }
 }
 
 #[derive(Clone, Copy, Debug, Default)]
@@ -89,13 +202,52 @@ impl KeyModifiers {
     }
 }
 
+/// Keyboard modes an application switched on, which change how some keys are encoded.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct KeyModes {
+    /// DECCKM (`CSI ? 1 h`): unmodified arrows, home and end send `SS3` (`ESC O A`) instead
+    /// of `CSI` sequences.
+    pub application_cursor_keys: bool,
+    /// DECKPAM (`ESC =` or `CSI ? 66 h`): keypad keys send `SS3` application codes instead of
+    /// the characters on their caps.
+    pub application_keypad: bool,
+}
+
+impl KeyModes {
+    fn bits(self) -> u16 {
+        let mut bits = 0u16;
+        if self.application_cursor_keys {
+            bits |= 0x0001;
+        }
+        if self.application_keypad {
+            bits |= 0x0002;
+        }
+        bits
+    }
+}
+
 pub fn encode_key_named(name: &str, modifiers: KeyModifiers) -> Option<Vec<u8>> {
+    encode_key_named_with_modes(name, modifiers, KeyModes::default())
+}
+
+/// Like [`encode_key_named`], honoring the application's cursor and keypad modes. Keypad keys
+/// are named after Ghostty's keys, e.g. `numpad_5` or `numpad_enter`.
+pub fn encode_key_named_with_modes(
+    name: &str,
+    modifiers: KeyModifiers,
+    modes: KeyModes,
+) -> Option<Vec<u8>> {
     if name.is_empty() {
         return None;
     }
 
     let bytes = unsafe {
-        ghostty_vt_sys::ghostty_vt_encode_key_named(name.as_ptr(), name.len(), modifiers.bits())
+        ghostty_vt_sys::ghostty_vt_encode_key_named_with_modes(
+            name.as_ptr(),
+            name.len(),
+            modifiers.bits(),
+            modes.bits(),
+        )
     };
     if bytes.ptr.is_null() || bytes.len == 0 {
         return None;
@@ -109,6 +261,9 @@ pub fn encode_key_named(name: &str, modifiers: KeyModifiers) -> Option<Vec<u8>>
 
 impl Terminal {
     pub fn new(cols: u16, rows: u16) -> Result<Self, Error> {
+        if cols == 0 || rows == 0 {
+            return Err(Error::InvalidSize { cols, rows });
+        }
         let ptr = unsafe { ghostty_vt_sys::ghostty_vt_terminal_new(cols, rows) };
         let ptr = NonNull::new(ptr).ok_or(Error::CreateFailed)?;
         Ok(Self { ptr })
@@ -155,7 +310,33 @@ impl Terminal {
         }
     }
 
+    /// Draws `styled_text` starting at the 1-based `col`/`row` of the active area, for host-drawn
+    /// status lines or overlays that stay in the grid like application output.
+    ///
+    /// The text may carry SGR sequences; it starts from default attributes and is clipped at the
+    /// right edge. The application's cursor position, attributes and saved cursor are left as
+    /// they were, and an escape sequence split across [`feed`](Self::feed) calls is not disturbed.
+    pub fn write_text_at(&mut self, col: u16, row: u16, styled_text: &[u8]) -> Result<(), Error> {
+        let rc = unsafe {
+            ghostty_vt_sys::ghostty_vt_terminal_write_text_at(
+                self.ptr.as_ptr(),
+                col,
+                row,
+                styled_text.as_ptr(),
+                styled_text.len(),
+            )
+        };
+        if rc == 0 {
+            Ok(())
+        } else {
+            Err(Error::FeedFailed(rc))
+        }
+    }
+
     pub fn resize(&mut self, cols: u16, rows: u16) -> Result<(), Error> {
+        if cols == 0 || rows == 0 {
+            return Err(Error::InvalidSize { cols, rows });
+        }
         let rc =
             unsafe { ghostty_vt_sys::ghostty_vt_terminal_resize(self.ptr.as_ptr(), cols, rows) };
         if rc == 0 {
@@ -165,8 +346,106 @@ impl Terminal {
         }
     }
 
+    /// Limits scrollback to roughly `lines` rows above the active area.
+    ///
+    /// Ghostty prunes whole pages of the oldest rows, so slightly more may be kept. Rows past a
+    /// lowered limit are dropped as new output arrives.
+    pub fn set_max_scrollback(&mut self, lines: u32) {
+        unsafe { ghostty_vt_sys::ghostty_vt_terminal_set_max_scrollback(self.ptr.as_ptr(), lines) }
+    }
+
+    /// Returns the bytes of page memory held by both screens, including scrollback.
+    pub fn memory_bytes(&self) -> usize {
+        unsafe { ghostty_vt_sys::ghostty_vt_terminal_memory_bytes(self.ptr.as_ptr()) }
+    }
+
+    /// Hard reset (RIS): clears the screen and scrollback and restores modes, colors, charsets
+    /// and the cursor to their defaults. Default colors, palette and cursor style set through
+    /// this API are kept.
+    pub fn reset(&mut self) -> Result<(), Error> {
+        let rc = unsafe { ghostty_vt_sys::ghostty_vt_terminal_reset(self.ptr.as_ptr()) };
+        if rc == 0 {
+            Ok(())
+        } else {
+            Err(Error::ResetFailed(rc))
+        }
+    }
+
+    /// Drops all scrollback rows while keeping the active screen, like a terminal's "Clear
+    /// Scrollback" command. Screen-absolute rows shift up by the previous
+    /// [`scrollback_len`](Self::scrollback_len).
+    pub fn clear_scrollback(&mut self) -> Result<(), Error> {
+        let rc = unsafe { ghostty_vt_sys::ghostty_vt_terminal_clear_scrollback(self.ptr.as_ptr()) };
+        if rc == 0 {
+            Ok(())
+        } else {
+            Err(Error::ResetFailed(rc))
+        }
+    }
+
+    /// Soft reset (DECSTR): restores modes, scroll margins, charsets and text attributes while
+    /// keeping the screen contents, scrollback and cursor position.
+    pub fn reset_soft(&mut self) -> Result<(), Error> {
+        let rc = unsafe { ghostty_vt_sys::ghostty_vt_terminal_reset_soft(self.ptr.as_ptr()) };
+        if rc == 0 {
+            Ok(())
+        } else {
+            Err(Error::ResetFailed(rc))
+        }
+    }
+
     pub fn dump_viewport(&self) -> Result<String, Error> {
+        let mut out = Vec::new();
+        self.dump_viewport_into(&mut out)?;
+        Ok(String::from_utf8_lossy(&out).into_owned())
+    }
+
+    /// Like [`dump_viewport`](Self::dump_viewport), but replaces the contents of `out` with the
+    /// raw UTF-8 bytes, so callers that dump every frame can keep one buffer.
+    pub fn dump_viewport_into(&self, out: &mut Vec<u8>) -> Result<(), Error> {
         let bytes = unsafe { ghostty_vt_sys::ghostty_vt_terminal_dump_viewport(self.ptr.as_ptr()) };
+        bytes_into(bytes, out)
+    }
+
+    pub fn dump_viewport_row(&self, row: u16) -> Result<String, Error> {
+        let mut out = Vec::new();
+        self.dump_viewport_row_into(row, &mut out)?;
+        Ok(String::from_utf8_lossy(&out).into_owned())
+    }
+
+    /// Like [`dump_viewport_row`](Self::dump_viewport_row), but replaces the contents of `out`
+    /// with the raw UTF-8 bytes.
+    pub fn dump_viewport_row_into(&self, row: u16, out: &mut Vec<u8>) -> Result<(), Error> {
+        let bytes = unsafe {
+            ghostty_vt_sys::ghostty_vt_terminal_dump_viewport_row(self.ptr.as_ptr(), row)
+        };
+        bytes_into(bytes, out)
+    }
+
+    /// Returns the number of rows of history above the active area.
+    ///
+    /// Screen-absolute rows `0..scrollback_len()` are scrollback; the active area follows them.
+    pub fn scrollback_len(&self) -> u32 {
+        self.active_top_row()
+    }
+
+    /// Dumps screen-absolute rows in `range`, one line per row, joined with `\n`.
+    ///
+    /// Rows are not unwrapped, so soft-wrapped lines appear as separate rows. Use
+    /// `0..scrollback_len()` for history only, or extend the end by the grid height to include
+    /// the active area. Rows past the end of the screen are ignored.
+    pub fn dump_scrollback(&self, range: std::ops::Range<u32>) -> Result<String, Error> {
+        if range.is_empty() {
+            return Ok(String::new());
+        }
+
+        let bytes = unsafe {
+            ghostty_vt_sys::ghostty_vt_terminal_dump_screen_rows(
+                self.ptr.as_ptr(),
+                range.start,
+                range.end,
+            )
+        };
         if bytes.ptr.is_null() {
             return Err(Error::DumpFailed);
         }
@@ -177,18 +456,38 @@ impl Terminal {
         Ok(s)
     }
 
-    pub fn dump_viewport_row(&self, row: u16) -> Result<String, Error> {
+    /// Returns, for each screen-absolute row in `range`, whether it soft-wraps into the next
+    /// row. Rows past the end of the screen are ignored.
+    pub fn row_wraps(&self, range: std::ops::Range<u32>) -> Result<Vec<bool>, Error> {
+        if range.is_empty() {
+            return Ok(Vec::new());
+        }
+
         let bytes = unsafe {
-            ghostty_vt_sys::ghostty_vt_terminal_dump_viewport_row(self.ptr.as_ptr(), row)
+            ghostty_vt_sys::ghostty_vt_terminal_screen_row_wraps(
+                self.ptr.as_ptr(),
+                range.start,
+                range.end,
+            )
         };
         if bytes.ptr.is_null() {
             return Err(Error::DumpFailed);
         }
 
         let slice = unsafe { std::slice::from_raw_parts(bytes.ptr, bytes.len) };
-        let s = String::from_utf8_lossy(slice).into_owned();
+        let wraps = slice.iter().map(|&wrap| wrap != 0).collect();
         unsafe { ghostty_vt_sys::ghostty_vt_bytes_free(bytes) };
-        Ok(s)
+        Ok(wraps)
+    }
+
+    /// Dumps screen-absolute rows in `range` as logical lines: rows that soft-wrap into the next
+    /// one are joined to it, so a long command reads back as it was typed.
+    ///
+    /// A line still wrapping at the end of `range` is cut there.
+    pub fn dump_logical_lines(&self, range: std::ops::Range<u32>) -> Result<Vec<String>, Error> {
+        let text = self.dump_scrollback(range.clone())?;
+        let wraps = self.row_wraps(range)?;
+        Ok(join_wrapped_rows(text.split('\n'), &wraps))
     }
 
     pub fn dump_viewport_row_cell_styles(&self, row: u16) -> Result<Vec<CellStyle>, Error> {
@@ -233,51 +532,95 @@ impl Terminal {
     }
 
     pub fn dump_viewport_row_style_runs(&self, row: u16) -> Result<Vec<StyleRun>, Error> {
+        let mut out = Vec::new();
+        self.dump_viewport_row_style_runs_into(row, &mut out)?;
+        Ok(out)
+    }
+
+    /// Like [`dump_viewport_row_style_runs`](Self::dump_viewport_row_style_runs), but replaces
+    /// the contents of `out`, so a renderer can keep one buffer per row across frames.
+    pub fn dump_viewport_row_style_runs_into(
+        &self,
+        row: u16,
+        out: &mut Vec<StyleRun>,
+    ) -> Result<(), Error> {
         let bytes = unsafe {
             ghostty_vt_sys::ghostty_vt_terminal_dump_viewport_row_style_runs(self.ptr.as_ptr(), row)
         };
+        style_runs_from_bytes(bytes, out)
+    }
+
+    /// Dumps the viewport rows in `rows` with a single FFI call, replacing `lines` and
+    /// `style_runs` with one entry per row: its text (without a trailing newline) and its style
+    /// runs. Existing buffers are refilled rather than reallocated. Rows past the bottom of the
+    /// viewport are left out.
+    pub fn dump_viewport_rows_into(
+        &self,
+        rows: std::ops::Range<u16>,
+        lines: &mut Vec<String>,
+        style_runs: &mut Vec<Vec<StyleRun>>,
+    ) -> Result<(), Error> {
+        let bytes = unsafe {
+            ghostty_vt_sys::ghostty_vt_terminal_dump_viewport_rows(
+                self.ptr.as_ptr(),
+                rows.start,
+                rows.end,
+            )
+        };
         if bytes.ptr.is_null() {
             return Err(Error::DumpFailed);
         }
-        if bytes.len == 0 {
-            unsafe { ghostty_vt_sys::ghostty_vt_bytes_free(bytes) };
-            return Ok(Vec::new());
-        }
-        if bytes.len % 12 != 0 {
-            unsafe { ghostty_vt_sys::ghostty_vt_bytes_free(bytes) };
-            return Err(Error::DumpFailed);
-        }
-
-        let slice = unsafe { std::slice::from_raw_parts(bytes.ptr, bytes.len) };
-        let mut out = Vec::with_capacity(bytes.len / 12);
-        for chunk in slice.chunks_exact(12) {
-            out.push(StyleRun {
-                start_col: u16::from_ne_bytes([chunk[0], chunk[1]]),
-                end_col: u16::from_ne_bytes([chunk[2], chunk[3]]),
-                fg: Rgb {
-                    r: chunk[4],
-                    g: chunk[5],
-                    b: chunk[6],
-                },
-                bg: Rgb {
-                    r: chunk[7],
-                    g: chunk[8],
-                    b: chunk[9],
-                },
-                flags: chunk[10],
-            });
-        }
-
+        let result = if bytes.len == 0 {
+            decode_viewport_rows(&[], lines, style_runs)
+        } else {
+            let slice = unsafe { std::slice::from_raw_parts(bytes.ptr, bytes.len) };
+            decode_viewport_rows(slice, lines, style_runs)
+        };
         unsafe { ghostty_vt_sys::ghostty_vt_bytes_free(bytes) };
+        result
+    }
+
+    /// Returns the style runs of a screen-absolute row (0 = oldest scrollback row).
+    ///
+    /// Works for any row in scrollback or the active area without scrolling the viewport.
+    pub fn dump_screen_row_style_runs(&self, row: u32) -> Result<Vec<StyleRun>, Error> {
+        let mut out = Vec::new();
+        self.dump_screen_row_style_runs_into(row, &mut out)?;
         Ok(out)
     }
 
+    /// Like [`dump_screen_row_style_runs`](Self::dump_screen_row_style_runs), but replaces the
+    /// contents of `out`.
+    pub fn dump_screen_row_style_runs_into(
+        &self,
+        row: u32,
+        out: &mut Vec<StyleRun>,
+    ) -> Result<(), Error> {
+        let bytes = unsafe {
+            ghostty_vt_sys::ghostty_vt_terminal_dump_screen_row_style_runs(self.ptr.as_ptr(), row)
+        };
+        style_runs_from_bytes(bytes, out)
+    }
+
     pub fn take_dirty_viewport_rows(&mut self, rows: u16) -> Result<Vec<u16>, Error> {
+        let mut out = Vec::new();
+        self.take_dirty_viewport_rows_into(rows, &mut out)?;
+        Ok(out)
+    }
+
+    /// Like [`take_dirty_viewport_rows`](Self::take_dirty_viewport_rows), but replaces the
+    /// contents of `out` so its allocation can be reused every frame.
+    pub fn take_dirty_viewport_rows_into(
+        &mut self,
+        rows: u16,
+        out: &mut Vec<u16>,
+    ) -> Result<(), Error> {
+        out.clear();
         let bytes = unsafe {
             ghostty_vt_sys::ghostty_vt_terminal_take_dirty_viewport_rows(self.ptr.as_ptr(), rows)
         };
         if bytes.ptr.is_null() || bytes.len == 0 {
-            return Ok(Vec::new());
+            return Ok(());
         }
         if bytes.len % 2 != 0 {
             unsafe { ghostty_vt_sys::ghostty_vt_bytes_free(bytes) };
@@ -285,19 +628,34 @@ impl Terminal {
         }
 
         let slice = unsafe { std::slice::from_raw_parts(bytes.ptr, bytes.len) };
-        let mut out = Vec::with_capacity(bytes.len / 2);
-        for chunk in slice.chunks_exact(2) {
-            out.push(u16::from_le_bytes([chunk[0], chunk[1]]));
-        }
+        out.extend(
+            slice
+                .chunks_exact(2)
+                .map(|chunk| u16::from_le_bytes([chunk[0], chunk[1]])),
+        );
         unsafe { ghostty_vt_sys::ghostty_vt_bytes_free(bytes) };
-        Ok(out)
+        Ok(())
+    }
+
+    /// Returns true if [`take_dirty_viewport_rows`](Self::take_dirty_viewport_rows) would report
+    /// any row, without clearing the dirty state.
+    pub fn has_dirty_viewport_rows(&self, rows: u16) -> bool {
+        unsafe {
+            ghostty_vt_sys::ghostty_vt_terminal_has_dirty_viewport_rows(self.ptr.as_ptr(), rows)
+        }
     }
 
     pub fn take_viewport_scroll_delta(&mut self) -> i32 {
         unsafe { ghostty_vt_sys::ghostty_vt_terminal_take_viewport_scroll_delta(self.ptr.as_ptr()) }
     }
 
-    pub fn cursor_position(&self) -> Option<(u16, u16)> {
+    /// Returns how many BEL characters were printed since the last call, ignoring BELs that
+    /// terminate escape sequences.
+    pub fn take_bell_count(&mut self) -> u32 {
+        unsafe { ghostty_vt_sys::ghostty_vt_terminal_take_bell_count(self.ptr.as_ptr()) }
+    }
+
+    pub fn cursor_position(&self) -> Option<ViewportPoint> {
         let mut col: u16 = 0;
         let mut row: u16 = 0;
         let ok = unsafe {
@@ -307,7 +665,14 @@ impl Terminal {
                 &mut row as *mut u16,
             )
         };
-        ok.then_some((col, row))
+        ok.then_some(ViewportPoint { col, row })
+    }
+
+    /// Returns the cursor in screen-absolute coordinates, matching those used by
+    /// [`Terminal::search`].
+    pub fn cursor_position_absolute(&self) -> Option<AbsolutePoint> {
+        // The cursor addresses the active area, which may differ from the viewport.
+        Some(self.cursor_position()?.to_absolute(self.active_top_row()))
     }
 
     /// Returns the current cursor style as set by DECSCUSR escape sequences.
@@ -321,6 +686,19 @@ impl Terminal {
         }
     }
 
+    /// Sets the cursor style shown until the application sends DECSCUSR, which DECSCUSR 0 also
+    /// restores.
+    pub fn set_default_cursor_style(&mut self, style: CursorStyle) {
+        let style = match style {
+            CursorStyle::Block => 0,
+            CursorStyle::Bar => 1,
+            CursorStyle::Underline => 2,
+        };
+        unsafe {
+            ghostty_vt_sys::ghostty_vt_terminal_set_default_cursor_style(self.ptr.as_ptr(), style)
+        }
+    }
+
     /// Returns true if cursor blink is enabled (DEC mode 12).
     pub fn cursor_blink(&self) -> bool {
         unsafe { ghostty_vt_sys::ghostty_vt_terminal_cursor_blink(self.ptr.as_ptr()) }
@@ -331,9 +709,75 @@ impl Terminal {
         unsafe { ghostty_vt_sys::ghostty_vt_terminal_cursor_visible(self.ptr.as_ptr()) }
     }
 
-    pub fn hyperlink_at(&self, col: u16, row: u16) -> Option<String> {
+    /// Returns the keyboard modes set by the application (DEC modes 1 and 66, `ESC =`).
+    pub fn key_modes(&self) -> KeyModes {
+        let bits = unsafe { ghostty_vt_sys::ghostty_vt_terminal_key_modes(self.ptr.as_ptr()) };
+        KeyModes {
+            application_cursor_keys: bits & 0x0001 != 0,
+            application_keypad: bits & 0x0002 != 0,
+        }
+    }
+
+    /// Returns the screen-absolute row of the viewport's top row.
+    ///
+    /// Row 0 is the oldest row still held in scrollback.
+    pub fn viewport_top_row(&self) -> u32 {
+        unsafe { ghostty_vt_sys::ghostty_vt_terminal_viewport_top_row(self.ptr.as_ptr()) }
+    }
+
+    /// Returns the screen-absolute row of the active area's top row.
+    ///
+    /// The active area is the bottom `rows` rows of the screen that the cursor
+    /// addresses; everything above it is scrollback.
+    pub fn active_top_row(&self) -> u32 {
+        unsafe { ghostty_vt_sys::ghostty_vt_terminal_active_top_row(self.ptr.as_ptr()) }
+    }
+
+    /// Finds every occurrence of `needle` in scrollback and the active area, top to bottom.
+    ///
+    /// When `case_sensitive` is false, ASCII letters match regardless of case. Matches do not
+    /// span soft-wrapped rows.
+    pub fn search(&self, needle: &str, case_sensitive: bool) -> Result<Vec<SearchMatch>, Error> {
+        if needle.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        let bytes = unsafe {
+            ghostty_vt_sys::ghostty_vt_terminal_search(
+                self.ptr.as_ptr(),
+                needle.as_ptr(),
+                needle.len(),
+                case_sensitive,
+            )
+        };
+        if bytes.ptr.is_null() || bytes.len == 0 {
+            return Ok(Vec::new());
+        }
+        if !bytes.len.is_multiple_of(8) {
+            unsafe { ghostty_vt_sys::ghostty_vt_bytes_free(bytes) };
+            return Err(Error::DumpFailed);
+        }
+
+        let slice = unsafe { std::slice::from_raw_parts(bytes.ptr, bytes.len) };
+        let out = slice
+            .chunks_exact(8)
+            .map(|chunk| SearchMatch {
+                row: u32::from_ne_bytes([chunk[0], chunk[1], chunk[2], chunk[3]]),
+                start_col: u16::from_ne_bytes([chunk[4], chunk[5]]),
+                end_col: u16::from_ne_bytes([chunk[6], chunk[7]]),
+            })
+            .collect();
+        unsafe { ghostty_vt_sys::ghostty_vt_bytes_free(bytes) };
+        Ok(out)
+    }
+
+    pub fn hyperlink_at(&self, point: ViewportPoint) -> Option<String> {
         let bytes = unsafe {
-            ghostty_vt_sys::ghostty_vt_terminal_hyperlink_at(self.ptr.as_ptr(), col, row)
+            ghostty_vt_sys::ghostty_vt_terminal_hyperlink_at(
+                self.ptr.as_ptr(),
+                point.col,
+                point.row,
+            )
         };
         if bytes.ptr.is_null() || bytes.len == 0 {
             return None;
@@ -384,6 +828,136 @@ impl Drop for Terminal {
     }
 }
 
+const STYLE_RUN_RECORD_LEN: usize = 20;
+
+/// Decodes style run records into `out`, replacing its contents.
+fn style_runs_from_bytes(
+    bytes: ghostty_vt_sys::ghostty_vt_bytes_t,
+    out: &mut Vec<StyleRun>,
+) -> Result<(), Error> {
+    out.clear();
+    if bytes.ptr.is_null() {
+        return Err(Error::DumpFailed);
+    }
+    if bytes.len == 0 {
+        unsafe { ghostty_vt_sys::ghostty_vt_bytes_free(bytes) };
+        return Ok(());
+    }
+    if !bytes.len.is_multiple_of(STYLE_RUN_RECORD_LEN) {
+        unsafe { ghostty_vt_sys::ghostty_vt_bytes_free(bytes) };
+        return Err(Error::DumpFailed);
+    }
+
+    let slice = unsafe { std::slice::from_raw_parts(bytes.ptr, bytes.len) };
+    decode_style_runs(slice, out);
+    unsafe { ghostty_vt_sys::ghostty_vt_bytes_free(bytes) };
+    Ok(())
+}
+
+/// Replaces the contents of `out` with `bytes` and frees them.
+fn bytes_into(bytes: ghostty_vt_sys::ghostty_vt_bytes_t, out: &mut Vec<u8>) -> Result<(), Error> {
+    out.clear();
+    if bytes.ptr.is_null() {
+        return Err(Error::DumpFailed);
+    }
+
+    let slice = unsafe { std::slice::from_raw_parts(bytes.ptr, bytes.len) };
+    out.extend_from_slice(slice);
+    unsafe { ghostty_vt_sys::ghostty_vt_bytes_free(bytes) };
+    Ok(())
+}
+
+/// Joins each row flagged in `wraps` with the row after it. Rows past the end of `wraps` are
+/// treated as hard line ends.
+fn join_wrapped_rows<'a>(rows: impl Iterator<Item = &'a str>, wraps: &[bool]) -> Vec<String> {
+    let mut lines = Vec::new();
+    let mut current = String::new();
+    for (index, row) in rows.enumerate() {
+        current.push_str(row);
+        if !wraps.get(index).copied().unwrap_or(false) {
+            lines.push(std::mem::take(&mut current));
+        }
+    }
+    if !current.is_empty() {
+        lines.push(current);
+    }
+    lines
+}
+
+/// Appends the packed style run records in `slice` to `out`.
+fn decode_style_runs(slice: &[u8], out: &mut Vec<StyleRun>) {
+    out.reserve(slice.len() / STYLE_RUN_RECORD_LEN);
+    for chunk in slice.chunks_exact(STYLE_RUN_RECORD_LEN) {
+        out.push(StyleRun {
+            start_col: u16::from_ne_bytes([chunk[0], chunk[1]]),
+            end_col: u16::from_ne_bytes([chunk[2], chunk[3]]),
+            fg: Rgb {
+                r: chunk[4],
+                g: chunk[5],
+                b: chunk[6],
+            },
+            bg: Rgb {
+                r: chunk[7],
+                g: chunk[8],
+                b: chunk[9],
+            },
+            flags: chunk[10],
+            underline: Underline::from_raw(chunk[11]),
+            underline_color: (chunk[15] != 0).then_some(Rgb {
+                r: chunk[12],
+                g: chunk[13],
+                b: chunk[14],
+            }),
+            hyperlink_id: NonZeroU32::new(u32::from_ne_bytes([
+                chunk[16], chunk[17], chunk[18], chunk[19],
+            ])),
+        });
+    }
+}
+
+/// Length of the `text_len: u32, run_count: u32` header before each row of a batched dump.
+const ROW_RECORD_HEADER_LEN: usize = 8;
+
+/// Fills `lines` and `style_runs` from the row records of a batched viewport dump.
+fn decode_viewport_rows(
+    mut bytes: &[u8],
+    lines: &mut Vec<String>,
+    style_runs: &mut Vec<Vec<StyleRun>>,
+) -> Result<(), Error> {
+    let mut count = 0usize;
+    while !bytes.is_empty() {
+        let header = bytes
+            .get(..ROW_RECORD_HEADER_LEN)
+            .ok_or(Error::DumpFailed)?;
+        let text_len = u32::from_ne_bytes([header[0], header[1], header[2], header[3]]) as usize;
+        let run_count = u32::from_ne_bytes([header[4], header[5], header[6], header[7]]) as usize;
+        let end = ROW_RECORD_HEADER_LEN + text_len + run_count * STYLE_RUN_RECORD_LEN;
+        let record = bytes
+            .get(ROW_RECORD_HEADER_LEN..end)
+            .ok_or(Error::DumpFailed)?;
+        let (text, runs) = record.split_at(text_len);
+
+        if count == lines.len() {
+            lines.push(String::new());
+        }
+        if count == style_runs.len() {
+            style_runs.push(Vec::new());
+        }
+        let text = String::from_utf8_lossy(text);
+        let line = &mut lines[count];
+        line.clear();
+        line.push_str(text.strip_suffix('\n').unwrap_or(&text));
+        style_runs[count].clear();
+        decode_style_runs(runs, &mut style_runs[count]);
+
+        bytes = &bytes[end..];
+        count += 1;
+    }
+    lines.truncate(count);
+    style_runs.truncate(count);
+    Ok(())
+}
+
 pub fn terminal_new(cols: u16, rows: u16) -> Result<Terminal, Error> {
     Terminal::new(cols, rows)
 }
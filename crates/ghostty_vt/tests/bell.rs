@@ -0,0 +1,11 @@
+use ghostty_vt::Terminal;
+
+#[test]
+fn counts_bells_but_not_osc_terminators() {
+    let mut t = Terminal::new(80, 24).unwrap();
+    assert_eq!(t.take_bell_count(), 0);
+
+    t.feed(b"a\x07b\x1b]0;title\x07c\x07").unwrap();
+    assert_eq!(t.take_bell_count(), 2);
+    assert_eq!(t.take_bell_count(), 0);
+}
@@ -0,0 +1,24 @@
+use ghostty_vt::{AbsolutePoint, Cell, ViewportPoint};
+
+#[test]
+fn cell_and_viewport_point_differ_by_one() {
+    let cell = Cell { col: 0, row: 2 };
+    let point = ViewportPoint::from(cell);
+    assert_eq!(point, ViewportPoint { col: 1, row: 3 });
+    assert_eq!(Cell::from(point), cell);
+}
+
+#[test]
+fn viewport_and_absolute_points_round_trip() {
+    let point = ViewportPoint { col: 5, row: 1 };
+    let absolute = point.to_absolute(100);
+    assert_eq!(absolute, AbsolutePoint { row: 100, col: 5 });
+    assert_eq!(absolute.to_viewport(100, 24), Some(point));
+
+    assert_eq!(absolute.to_viewport(101, 24), None);
+    assert_eq!(absolute.to_viewport(76, 24), None);
+    assert_eq!(
+        absolute.to_viewport(77, 24),
+        Some(ViewportPoint { col: 5, row: 24 })
+    );
+}
@@ -0,0 +1,28 @@
+use ghostty_vt::{CursorStyle, Terminal};
+
+#[test]
+fn decscusr_changes_cursor_shape_and_blink() {
+    let mut t = Terminal::new(80, 24).unwrap();
+    assert_eq!(t.cursor_style(), CursorStyle::Block);
+
+    t.feed(b"\x1b[6 q").unwrap();
+    assert_eq!(t.cursor_style(), CursorStyle::Bar);
+    assert!(!t.cursor_blink());
+
+    t.feed(b"\x1b[3 q").unwrap();
+    assert_eq!(t.cursor_style(), CursorStyle::Underline);
+    assert!(t.cursor_blink());
+}
+
+#[test]
+fn decscusr_zero_restores_default_cursor_style() {
+    let mut t = Terminal::new(80, 24).unwrap();
+    t.set_default_cursor_style(CursorStyle::Bar);
+    assert_eq!(t.cursor_style(), CursorStyle::Bar);
+
+    t.feed(b"\x1b[2 q").unwrap();
+    assert_eq!(t.cursor_style(), CursorStyle::Block);
+
+    t.feed(b"\x1b[0 q").unwrap();
+    assert_eq!(t.cursor_style(), CursorStyle::Bar);
+}
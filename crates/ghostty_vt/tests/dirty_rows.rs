@@ -17,3 +17,16 @@ fn dirty_rows_are_cleared_after_take() {
     let dirty = terminal.take_dirty_viewport_rows(24).unwrap();
     assert!(dirty.contains(&0));
 }
+
+#[test]
+fn has_dirty_rows_peeks_without_clearing() {
+    let mut terminal = Terminal::new(80, 24).unwrap();
+    let _ = terminal.take_dirty_viewport_rows(24).unwrap();
+    assert!(!terminal.has_dirty_viewport_rows(24));
+
+    terminal.feed(b"hi").unwrap();
+    assert!(terminal.has_dirty_viewport_rows(24));
+    assert!(terminal.has_dirty_viewport_rows(24));
+    assert!(terminal.take_dirty_viewport_rows(24).unwrap().contains(&0));
+    assert!(!terminal.has_dirty_viewport_rows(24));
+}
@@ -0,0 +1,59 @@
+use ghostty_vt::Terminal;
+
+#[test]
+fn batched_row_dump_matches_per_row_dumps() {
+    let mut t = Terminal::new(10, 4).unwrap();
+    t.feed(b"plain\r\n\x1b[1;31mred\x1b[0m text\r\n\x1b[44mblue\x1b[0m")
+        .unwrap();
+
+    let mut lines = vec!["stale".to_string(); 6];
+    let mut style_runs = Vec::new();
+    t.dump_viewport_rows_into(0..4, &mut lines, &mut style_runs)
+        .unwrap();
+
+    assert_eq!(lines.len(), 4);
+    assert_eq!(style_runs.len(), 4);
+    for row in 0..4u16 {
+        let text = t.dump_viewport_row(row).unwrap();
+        assert_eq!(
+            lines[row as usize],
+            text.strip_suffix('\n').unwrap_or(&text)
+        );
+        assert_eq!(
+            style_runs[row as usize],
+            t.dump_viewport_row_style_runs(row).unwrap()
+        );
+    }
+    assert_eq!(lines[1].trim_end(), "red text");
+
+    t.dump_viewport_rows_into(1..3, &mut lines, &mut style_runs)
+        .unwrap();
+    assert_eq!(lines.len(), 2);
+    assert_eq!(lines[0].trim_end(), "red text");
+
+    t.dump_viewport_rows_into(2..9, &mut lines, &mut style_runs)
+        .unwrap();
+    assert_eq!(lines.len(), 2);
+}
+
+#[test]
+fn dump_into_variants_reuse_caller_buffers() {
+    let mut t = Terminal::new(10, 3).unwrap();
+    t.feed(b"one\r\n\x1b[1mtwo\x1b[0m").unwrap();
+
+    let mut bytes = b"stale".to_vec();
+    t.dump_viewport_into(&mut bytes).unwrap();
+    assert_eq!(String::from_utf8_lossy(&bytes), t.dump_viewport().unwrap());
+
+    t.dump_viewport_row_into(1, &mut bytes).unwrap();
+    assert_eq!(
+        String::from_utf8_lossy(&bytes),
+        t.dump_viewport_row(1).unwrap()
+    );
+
+    let mut runs = t.dump_viewport_row_style_runs(0).unwrap();
+    let top = t.active_top_row() + 1;
+    t.dump_screen_row_style_runs_into(top, &mut runs).unwrap();
+    assert_eq!(runs, t.dump_viewport_row_style_runs(1).unwrap());
+    assert!(runs.iter().any(|run| run.flags & 0x02 != 0));
+}
@@ -1,4 +1,4 @@
-use ghostty_vt::Terminal;
+use ghostty_vt::{Terminal, ViewportPoint};
 
 #[test]
 fn hyperlink_at_returns_osc8_uri() {
@@ -8,7 +8,41 @@ fn hyperlink_at_returns_osc8_uri() {
         .unwrap();
 
     assert_eq!(
-        term.hyperlink_at(1, 1).as_deref(),
+        term.hyperlink_at(ViewportPoint { col: 1, row: 1 })
+            .as_deref(),
         Some("https://example.com")
     );
 }
+
+#[test]
+fn style_runs_carry_the_hyperlink_id() {
+    let mut term = Terminal::new(20, 2).unwrap();
+
+    term.feed(
+        b"a\x1b]8;id=one;https://example.com\x07link\x1b]8;;\x07b\r\n\
+          \x1b]8;id=one;https://example.com\x07more\x1b]8;;\x07 \
+          \x1b]8;;https://other.example\x07x\x1b]8;;\x07",
+    )
+    .unwrap();
+
+    let first = term.dump_viewport_row_style_runs(0).unwrap();
+    let link = first
+        .iter()
+        .find(|run| run.start_col == 2)
+        .expect("link run");
+    assert_eq!(link.end_col, 5);
+    assert!(link.hyperlink_id.is_some());
+    assert!(
+        first
+            .iter()
+            .filter(|run| run.start_col != 2)
+            .all(|run| run.hyperlink_id.is_none())
+    );
+
+    let second = term.dump_viewport_row_style_runs(1).unwrap();
+    assert_eq!(second[0].hyperlink_id, link.hyperlink_id);
+    assert_eq!(second[0].end_col, 4);
+    let other = second.iter().find(|run| run.start_col == 6).unwrap();
+    assert!(other.hyperlink_id.is_some());
+    assert_ne!(other.hyperlink_id, link.hyperlink_id);
+}
@@ -1,4 +1,4 @@
-use ghostty_vt::{KeyModifiers, encode_key_named};
+use ghostty_vt::{KeyModes, KeyModifiers, encode_key_named, encode_key_named_with_modes};
 
 #[test]
 fn encodes_common_special_keys() {
@@ -30,3 +30,96 @@ fn encoding_changes_with_modifiers_for_special_keys() {
 
     assert_ne!(no_mods, ctrl);
 }
+
+#[test]
+fn modified_special_keys_use_xterm_parameters() {
+    let shift = KeyModifiers {
+        shift: true,
+        ..Default::default()
+    };
+    let alt = KeyModifiers {
+        alt: true,
+        ..Default::default()
+    };
+    let ctrl = KeyModifiers {
+        control: true,
+        ..Default::default()
+    };
+    let ctrl_shift = KeyModifiers {
+        control: true,
+        shift: true,
+        ..Default::default()
+    };
+
+    let cases: &[(&str, KeyModifiers, &[u8])] = &[
+        ("right", ctrl, b"\x1b[1;5C"),
+        ("left", alt, b"\x1b[1;3D"),
+        ("up", ctrl_shift, b"\x1b[1;6A"),
+        ("home", shift, b"\x1b[1;2H"),
+        ("end", ctrl, b"\x1b[1;5F"),
+        ("f1", shift, b"\x1b[1;2P"),
+        ("f4", alt, b"\x1b[1;3S"),
+        ("f5", ctrl, b"\x1b[15;5~"),
+        ("f12", shift, b"\x1b[24;2~"),
+        ("pageup", ctrl, b"\x1b[5;5~"),
+        ("delete", ctrl_shift, b"\x1b[3;6~"),
+        ("insert", shift, b"\x1b[2;2~"),
+    ];
+    for (name, mods, expected) in cases {
+        assert_eq!(
+            encode_key_named(name, *mods).as_deref(),
+            Some(*expected),
+            "{name} {mods:?}"
+        );
+    }
+}
+
+#[test]
+fn application_cursor_keys_use_ss3() {
+    let modes = KeyModes {
+        application_cursor_keys: true,
+        ..Default::default()
+    };
+    let cases: &[(&str, &[u8])] = &[
+        ("up", b"\x1bOA"),
+        ("down", b"\x1bOB"),
+        ("right", b"\x1bOC"),
+        ("left", b"\x1bOD"),
+        ("home", b"\x1bOH"),
+        ("end", b"\x1bOF"),
+    ];
+    for (name, expected) in cases {
+        assert_eq!(
+            encode_key_named_with_modes(name, KeyModifiers::default(), modes).as_deref(),
+            Some(*expected),
+            "{name}"
+        );
+    }
+
+    // Modified arrows keep their xterm parameters.
+    let ctrl = KeyModifiers {
+        control: true,
+        ..Default::default()
+    };
+    assert_eq!(
+        encode_key_named_with_modes("up", ctrl, modes).as_deref(),
+        Some(&b"\x1b[1;5A"[..])
+    );
+}
+
+#[test]
+fn application_keypad_sends_keypad_codes() {
+    let modes = KeyModes {
+        application_keypad: true,
+        ..Default::default()
+    };
+    assert_eq!(
+        encode_key_named_with_modes("numpad_enter", KeyModifiers::default(), modes).as_deref(),
+        Some(&b"\x1bOM"[..])
+    );
+    assert_eq!(
+        encode_key_named_with_modes("numpad_enter", KeyModifiers::default(), KeyModes::default())
+            .as_deref(),
+        Some(&b"\r"[..])
+    );
+}
@@ -0,0 +1,36 @@
+use ghostty_vt::{CursorStyle, Terminal, ViewportPoint};
+
+#[test]
+fn hard_reset_clears_screen_scrollback_and_modes() {
+    let mut t = Terminal::new(10, 2).unwrap();
+    t.set_default_cursor_style(CursorStyle::Bar);
+    t.feed(b"one\r\ntwo\r\nthree\x1b[?25l\x1b[2 q").unwrap();
+    assert_eq!(t.scrollback_len(), 1);
+    assert!(!t.cursor_visible());
+
+    t.reset().unwrap();
+
+    assert_eq!(t.scrollback_len(), 0);
+    assert_eq!(t.dump_viewport().unwrap().trim(), "");
+    assert_eq!(t.cursor_position(), Some(ViewportPoint { col: 1, row: 1 }));
+    assert!(t.cursor_visible());
+    assert_eq!(t.cursor_style(), CursorStyle::Bar);
+
+    t.feed(b"after").unwrap();
+    assert_eq!(t.dump_viewport().unwrap().trim(), "after");
+}
+
+#[test]
+fn soft_reset_keeps_screen_but_restores_modes() {
+    let mut t = Terminal::new(10, 3).unwrap();
+    t.feed(b"one\r\ntwo\x1b[?25l\x1b[2;3r\x1b[4h").unwrap();
+
+    t.reset_soft().unwrap();
+
+    assert!(t.cursor_visible());
+    assert!(t.dump_viewport().unwrap().starts_with("one\ntwo"));
+
+    // Insert mode is off again, so this overwrites instead of shifting.
+    t.feed(b"\x1b[1;1HX").unwrap();
+    assert!(t.dump_viewport().unwrap().starts_with("Xne\ntwo"));
+}
@@ -0,0 +1,54 @@
+use ghostty_vt::{AbsolutePoint, Terminal, ViewportPoint};
+
+#[test]
+fn viewport_and_active_top_rows_track_scrollback() {
+    let mut t = Terminal::new(10, 3).unwrap();
+    assert_eq!(t.viewport_top_row(), 0);
+    assert_eq!(t.active_top_row(), 0);
+
+    t.feed(b"1\r\n2\r\n3\r\n4\r\n5").unwrap();
+    assert_eq!(t.active_top_row(), 2);
+    assert_eq!(t.viewport_top_row(), 2);
+
+    t.scroll_viewport_top().unwrap();
+    assert_eq!(t.viewport_top_row(), 0);
+    assert_eq!(t.active_top_row(), 2);
+}
+
+#[test]
+fn cursor_position_absolute_accounts_for_scrollback() {
+    let mut t = Terminal::new(10, 3).unwrap();
+    t.feed(b"ab").unwrap();
+    assert_eq!(
+        t.cursor_position_absolute(),
+        Some(AbsolutePoint { row: 0, col: 3 })
+    );
+
+    t.feed(b"\r\n2\r\n3\r\n4\r\nxyz").unwrap();
+    assert_eq!(t.cursor_position(), Some(ViewportPoint { col: 4, row: 3 }));
+    assert_eq!(
+        t.cursor_position_absolute(),
+        Some(AbsolutePoint { row: 4, col: 4 })
+    );
+
+    // Scrolling the viewport does not move the cursor in absolute terms.
+    t.scroll_viewport_top().unwrap();
+    assert_eq!(
+        t.cursor_position_absolute(),
+        Some(AbsolutePoint { row: 4, col: 4 })
+    );
+}
+
+#[test]
+fn logical_lines_join_soft_wrapped_rows() {
+    let mut t = Terminal::new(10, 4).unwrap();
+    t.feed(b"echo 0123456789abc\r\nshort").unwrap();
+
+    assert_eq!(t.row_wraps(0..4).unwrap(), [true, false, false, false]);
+    assert_eq!(
+        t.dump_logical_lines(0..3).unwrap(),
+        ["echo 0123456789abc", "short"]
+    );
+    // A range ending mid-line cuts it.
+    assert_eq!(t.dump_logical_lines(0..1).unwrap(), ["echo 01234"]);
+}
@@ -0,0 +1,14 @@
+use ghostty_vt::Terminal;
+
+#[test]
+fn screen_row_style_runs_survive_scrolling_into_history() {
+    let mut t = Terminal::new(4, 2).unwrap();
+    t.feed(b"\x1b[41mX\x1b[0m").unwrap();
+    let viewport_runs = t.dump_viewport_row_style_runs(0).unwrap();
+
+    t.feed(b"\r\n\r\n\r\n").unwrap();
+    assert!(t.scrollback_len() >= 1);
+
+    let screen_runs = t.dump_screen_row_style_runs(0).unwrap();
+    assert_eq!(screen_runs, viewport_runs);
+}
@@ -29,3 +29,19 @@ fn resize_does_not_break_dump_or_feed() {
     let s = t.dump_viewport().unwrap();
     assert!(s.contains("after-resize"));
 }
+
+#[test]
+fn zero_sized_grids_are_rejected() {
+    assert!(matches!(
+        ghostty_vt::Terminal::new(0, 24),
+        Err(ghostty_vt::Error::InvalidSize { cols: 0, rows: 24 })
+    ));
+
+    let mut t = ghostty_vt::Terminal::new(10, 3).unwrap();
+    assert!(matches!(
+        t.resize(10, 0),
+        Err(ghostty_vt::Error::InvalidSize { cols: 10, rows: 0 })
+    ));
+    t.feed(b"still-usable").unwrap();
+    assert!(t.dump_viewport().unwrap().contains("still-usable"));
+}
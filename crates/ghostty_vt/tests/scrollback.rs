@@ -0,0 +1,60 @@
+use ghostty_vt::Terminal;
+
+fn lines(s: String) -> Vec<String> {
+    s.lines().map(|line| line.trim_end().to_string()).collect()
+}
+
+#[test]
+fn dump_scrollback_returns_history_without_scrolling_viewport() {
+    let mut t = Terminal::new(10, 2).unwrap();
+    t.feed(b"one\r\ntwo\r\nthree\r\nfour").unwrap();
+
+    assert_eq!(t.scrollback_len(), 2);
+    assert_eq!(
+        lines(t.dump_scrollback(0..t.scrollback_len()).unwrap()),
+        ["one", "two"]
+    );
+    assert_eq!(
+        lines(t.dump_scrollback(1..4).unwrap()),
+        ["two", "three", "four"]
+    );
+    assert_eq!(lines(t.dump_scrollback(3..100).unwrap()), ["four"]);
+    assert_eq!(t.dump_scrollback(2..2).unwrap(), "");
+
+    // The viewport is untouched.
+    assert_eq!(t.viewport_top_row(), 2);
+}
+
+#[test]
+fn max_scrollback_bounds_retained_rows_and_memory() {
+    let mut small = Terminal::new(80, 24).unwrap();
+    let mut large = Terminal::new(80, 24).unwrap();
+    small.set_max_scrollback(0);
+    large.set_max_scrollback(100_000);
+    assert!(small.memory_bytes() > 0);
+
+    let mut output = Vec::new();
+    for i in 0..20_000 {
+        output.extend_from_slice(format!("line {i}\r\n").as_bytes());
+    }
+    small.feed(&output).unwrap();
+    large.feed(&output).unwrap();
+
+    assert_eq!(large.active_top_row(), 20_000 - 23);
+    assert!(small.active_top_row() < large.active_top_row());
+    assert!(small.memory_bytes() < large.memory_bytes());
+}
+
+#[test]
+fn clear_scrollback_keeps_active_screen() {
+    let mut t = Terminal::new(10, 2).unwrap();
+    t.feed(b"one\r\ntwo\r\nthree\r\nfour").unwrap();
+    t.scroll_viewport_top().unwrap();
+
+    t.clear_scrollback().unwrap();
+
+    assert_eq!(t.scrollback_len(), 0);
+    assert_eq!(t.viewport_top_row(), 0);
+    assert_eq!(lines(t.dump_viewport().unwrap()), ["three", "four"]);
+    assert_eq!(t.take_viewport_scroll_delta(), 0);
+}
@@ -0,0 +1,47 @@
+use ghostty_vt::{SearchMatch, Terminal};
+
+#[test]
+fn search_finds_matches_in_scrollback_and_active_area() {
+    let mut t = Terminal::new(20, 2).unwrap();
+    t.feed(b"error one\r\nok\r\nError two\r\nlast").unwrap();
+
+    let matches = t.search("error", false).unwrap();
+    assert_eq!(
+        matches,
+        vec![
+            SearchMatch {
+                row: 0,
+                start_col: 1,
+                end_col: 5
+            },
+            SearchMatch {
+                row: 2,
+                start_col: 1,
+                end_col: 5
+            },
+        ]
+    );
+
+    let matches = t.search("Error", true).unwrap();
+    assert_eq!(matches.len(), 1);
+    assert_eq!(matches[0].row, 2);
+
+    assert!(t.search("", false).unwrap().is_empty());
+    assert!(t.search("missing", false).unwrap().is_empty());
+}
+
+#[test]
+fn search_reports_wide_character_columns() {
+    let mut t = Terminal::new(20, 2).unwrap();
+    t.feed("a漢字b".as_bytes()).unwrap();
+
+    let matches = t.search("字b", true).unwrap();
+    assert_eq!(
+        matches,
+        vec![SearchMatch {
+            row: 0,
+            start_col: 4,
+            end_col: 6
+        }]
+    );
+}
@@ -1,4 +1,4 @@
-use ghostty_vt::{Rgb, Terminal};
+use ghostty_vt::{Rgb, Terminal, Underline};
 
 #[test]
 fn viewport_row_cell_styles_reflect_sgr_background() {
@@ -109,3 +109,70 @@ fn default_colors_can_be_overridden() {
         }
     );
 }
+
+#[test]
+fn viewport_row_style_runs_carry_underline_style_and_color() {
+    let mut t = Terminal::new(4, 1).unwrap();
+    t.feed(b"\x1b[4:3;58:2::255:0:0mX\x1b[0m").unwrap();
+    let runs = t.dump_viewport_row_style_runs(0).unwrap();
+    assert_eq!(runs[0].underline, Underline::Curly);
+    assert_eq!(
+        runs[0].underline_color,
+        Some(Rgb {
+            r: 0xFF,
+            g: 0x00,
+            b: 0x00
+        })
+    );
+    assert_eq!(runs.last().unwrap().underline, Underline::None);
+
+    for (sgr, expected) in [
+        (&b"\x1b[4mX"[..], Underline::Single),
+        (b"\x1b[21mX", Underline::Double),
+        (b"\x1b[4:4mX", Underline::Dotted),
+        (b"\x1b[4:5mX", Underline::Dashed),
+    ] {
+        let mut t = Terminal::new(4, 1).unwrap();
+        t.feed(sgr).unwrap();
+        let runs = t.dump_viewport_row_style_runs(0).unwrap();
+        assert_eq!(runs[0].underline, expected);
+        assert_eq!(runs[0].underline_color, None);
+        assert_ne!(runs[0].flags & 0x08, 0);
+    }
+}
+
+#[test]
+fn viewport_row_style_runs_reflect_blink_flag() {
+    let mut t = Terminal::new(4, 1).unwrap();
+    t.feed(b"\x1b[5mX\x1b[0mY").unwrap();
+    let styles = t.dump_viewport_row_cell_styles(0).unwrap();
+    assert_ne!(styles[0].flags & 0x80, 0);
+    assert_eq!(styles[1].flags & 0x80, 0);
+
+    let runs = t.dump_viewport_row_style_runs(0).unwrap();
+    assert_ne!(runs[0].flags & 0x80, 0);
+    assert_eq!(runs.last().unwrap().flags & 0x80, 0);
+}
+
+#[test]
+fn default_palette_colors_ansi_text() {
+    let mut palette = [Rgb { r: 0, g: 0, b: 0 }; 16];
+    palette[1] = Rgb {
+        r: 0x12,
+        g: 0x34,
+        b: 0x56,
+    };
+    palette[12] = Rgb {
+        r: 0xAB,
+        g: 0xCD,
+        b: 0xEF,
+    };
+
+    let mut t = Terminal::new(4, 1).unwrap();
+    t.set_default_palette(&palette);
+    t.feed(b"\x1b[31mA\x1b[94mB\x1b[0m").unwrap();
+
+    let styles = t.dump_viewport_row_cell_styles(0).unwrap();
+    assert_eq!(styles[0].fg, palette[1]);
+    assert_eq!(styles[1].fg, palette[12]);
+}
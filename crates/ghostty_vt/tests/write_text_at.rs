@@ -0,0 +1,27 @@
+use ghostty_vt::{Terminal, ViewportPoint};
+
+#[test]
+fn write_text_at_draws_without_moving_the_application_cursor() {
+    let mut t = Terminal::new(12, 3).unwrap();
+    // Leave the application mid-way through an SGR sequence.
+    t.feed(b"hello\x1b[3").unwrap();
+
+    t.write_text_at(9, 3, b"\x1b[1mSTATUS").unwrap();
+
+    assert_eq!(t.cursor_position(), Some(ViewportPoint { col: 6, row: 1 }));
+    assert_eq!(t.dump_viewport_row(2).unwrap().trim_end(), "        STAT");
+    let status = t.dump_viewport_row_style_runs(2).unwrap();
+    assert!(
+        status
+            .iter()
+            .any(|run| run.start_col == 9 && run.flags & 0x02 != 0)
+    );
+
+    t.feed(b"1mX").unwrap();
+    assert_eq!(t.dump_viewport_row(0).unwrap().trim_end(), "helloX");
+    let runs = t.dump_viewport_row_style_runs(0).unwrap();
+    assert!(runs.iter().all(|run| run.flags & 0x02 == 0));
+
+    assert!(t.write_text_at(0, 1, b"x").is_err());
+    assert!(t.write_text_at(1, 4, b"x").is_err());
+}
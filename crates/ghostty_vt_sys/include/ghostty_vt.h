@@ -35,6 +35,7 @@ typedef struct ghostty_vt_cell_style_s {
 // - 0x10: faint
 // - 0x20: invisible
 // - 0x40: strikethrough
+// - 0x80: blink
 
 ghostty_vt_terminal_t ghostty_vt_terminal_new(uint16_t cols, uint16_t rows);
 void ghostty_vt_terminal_free(ghostty_vt_terminal_t terminal);
@@ -53,6 +54,18 @@ int ghostty_vt_terminal_feed(ghostty_vt_terminal_t terminal,
 
 int ghostty_vt_terminal_resize(ghostty_vt_terminal_t terminal, uint16_t cols, uint16_t rows);
 
+void ghostty_vt_terminal_set_max_scrollback(ghostty_vt_terminal_t terminal, uint32_t lines);
+size_t ghostty_vt_terminal_memory_bytes(ghostty_vt_terminal_t terminal);
+
+int ghostty_vt_terminal_reset(ghostty_vt_terminal_t terminal);
+int ghostty_vt_terminal_reset_soft(ghostty_vt_terminal_t terminal);
+int ghostty_vt_terminal_clear_scrollback(ghostty_vt_terminal_t terminal);
+int ghostty_vt_terminal_write_text_at(ghostty_vt_terminal_t terminal,
+                                      uint16_t col,
+                                      uint16_t row,
+                                      const uint8_t* bytes,
+                                      size_t len);
+
 int ghostty_vt_terminal_scroll_viewport(ghostty_vt_terminal_t terminal, int32_t delta_lines);
 int ghostty_vt_terminal_scroll_viewport_top(ghostty_vt_terminal_t terminal);
 int ghostty_vt_terminal_scroll_viewport_bottom(ghostty_vt_terminal_t terminal);
@@ -66,17 +79,46 @@ ghostty_vt_bytes_t ghostty_vt_terminal_dump_viewport_row(ghostty_vt_terminal_t t
                                                          uint16_t row);
 ghostty_vt_bytes_t ghostty_vt_terminal_dump_viewport_row_cell_styles(ghostty_vt_terminal_t terminal,
                                                                      uint16_t row);
+// Style runs are packed 20-byte records: start_col/end_col (uint16, 1-based inclusive),
+// fg rgb, bg rgb, flags, underline (0 none, 1 single, 2 double, 3 curly, 4 dotted, 5 dashed),
+// underline rgb (SGR 58), an underline-color-set byte, and a uint32 hyperlink id (0 = none).
 ghostty_vt_bytes_t ghostty_vt_terminal_dump_viewport_row_style_runs(ghostty_vt_terminal_t terminal,
                                                                     uint16_t row);
+ghostty_vt_bytes_t ghostty_vt_terminal_dump_screen_row_style_runs(ghostty_vt_terminal_t terminal,
+                                                                  uint32_t row);
+// Viewport rows [start_row, end_row), each as a uint32 text length and uint32 run count
+// (native endian), the row's UTF-8 text, then its style run records.
+ghostty_vt_bytes_t ghostty_vt_terminal_dump_viewport_rows(ghostty_vt_terminal_t terminal,
+                                                          uint16_t start_row,
+                                                          uint16_t end_row);
 ghostty_vt_bytes_t ghostty_vt_terminal_take_dirty_viewport_rows(ghostty_vt_terminal_t terminal,
                                                                 uint16_t rows);
+bool ghostty_vt_terminal_has_dirty_viewport_rows(ghostty_vt_terminal_t terminal, uint16_t rows);
 int32_t ghostty_vt_terminal_take_viewport_scroll_delta(ghostty_vt_terminal_t terminal);
+uint32_t ghostty_vt_terminal_take_bell_count(ghostty_vt_terminal_t terminal);
+ghostty_vt_bytes_t ghostty_vt_terminal_dump_screen_rows(ghostty_vt_terminal_t terminal,
+                                                       uint32_t start_row,
+                                                       uint32_t end_row);
+ghostty_vt_bytes_t ghostty_vt_terminal_screen_row_wraps(ghostty_vt_terminal_t terminal,
+                                                       uint32_t start_row,
+                                                       uint32_t end_row);
+uint32_t ghostty_vt_terminal_viewport_top_row(ghostty_vt_terminal_t terminal);
+uint32_t ghostty_vt_terminal_active_top_row(ghostty_vt_terminal_t terminal);
+ghostty_vt_bytes_t ghostty_vt_terminal_search(ghostty_vt_terminal_t terminal,
+                                              const uint8_t* needle,
+                                              size_t needle_len,
+                                              bool case_sensitive);
 ghostty_vt_bytes_t ghostty_vt_terminal_hyperlink_at(ghostty_vt_terminal_t terminal,
                                                     uint16_t col,
                                                     uint16_t row);
+uint16_t ghostty_vt_terminal_key_modes(ghostty_vt_terminal_t terminal);
 ghostty_vt_bytes_t ghostty_vt_encode_key_named(const uint8_t* name,
                                                size_t name_len,
                                                uint16_t modifiers);
+ghostty_vt_bytes_t ghostty_vt_encode_key_named_with_modes(const uint8_t* name,
+                                                          size_t name_len,
+                                                          uint16_t modifiers,
+                                                          uint16_t modes);
 void ghostty_vt_bytes_free(ghostty_vt_bytes_t bytes);
 
 #ifdef __cplusplus
@@ -40,6 +40,28 @@ unsafe extern "C" {
         rows: u16,
     ) -> core::ffi::c_int;
 
+    pub fn ghostty_vt_terminal_set_max_scrollback(terminal: *mut core::ffi::c_void, lines: u32);
+
+    pub fn ghostty_vt_terminal_memory_bytes(terminal: *mut core::ffi::c_void) -> usize;
+
+    pub fn ghostty_vt_terminal_reset(terminal: *mut core::ffi::c_void) -> core::ffi::c_int;
+
+    pub fn ghostty_vt_terminal_reset_soft(terminal: *mut core::ffi::c_void) -> core::ffi::c_int;
+
+    pub fn ghostty_vt_terminal_clear_scrollback(
+        terminal: *mut core::ffi::c_void,
+    ) -> core::ffi::c_int;
+
+    /// Writes text (with optional SGR sequences) at a 1-based active-area cell, restoring the
+    /// cursor and attributes afterwards.
+    pub fn ghostty_vt_terminal_write_text_at(
+        terminal: *mut core::ffi::c_void,
+        col: u16,
+        row: u16,
+        bytes: *const u8,
+        len: usize,
+    ) -> core::ffi::c_int;
+
     pub fn ghostty_vt_terminal_scroll_viewport(
         terminal: *mut core::ffi::c_void,
         delta_lines: i32,
@@ -62,12 +84,22 @@ unsafe extern "C" {
     /// Returns cursor style: 0 = block, 1 = bar, 2 = underline
     pub fn ghostty_vt_terminal_cursor_style(terminal: *mut core::ffi::c_void) -> u8;
 
+    /// Sets the cursor style used until DECSCUSR changes it and restored by DECSCUSR 0
+    /// (0 = block, 1 = bar, 2 = underline)
+    pub fn ghostty_vt_terminal_set_default_cursor_style(
+        terminal: *mut core::ffi::c_void,
+        style: u8,
+    );
+
     /// Returns true if cursor blink is enabled (DEC mode 12)
     pub fn ghostty_vt_terminal_cursor_blink(terminal: *mut core::ffi::c_void) -> bool;
 
     /// Returns true if cursor is visible (DEC mode 25)
     pub fn ghostty_vt_terminal_cursor_visible(terminal: *mut core::ffi::c_void) -> bool;
 
+    /// Returns the keyboard mode bits (application cursor keys, application keypad)
+    pub fn ghostty_vt_terminal_key_modes(terminal: *mut core::ffi::c_void) -> u16;
+
     pub fn ghostty_vt_terminal_dump_viewport(
         terminal: *mut core::ffi::c_void,
     ) -> ghostty_vt_bytes_t;
@@ -87,13 +119,64 @@ unsafe extern "C" {
         row: u16,
     ) -> ghostty_vt_bytes_t;
 
+    /// Like `ghostty_vt_terminal_dump_viewport_row_style_runs`, with a screen-absolute row.
+    pub fn ghostty_vt_terminal_dump_screen_row_style_runs(
+        terminal: *mut core::ffi::c_void,
+        row: u32,
+    ) -> ghostty_vt_bytes_t;
+
+    /// Dumps viewport rows `[start_row, end_row)`: per row, a `u32` text length and `u32` style
+    /// run count (native endian), the UTF-8 text, then the style run records.
+    pub fn ghostty_vt_terminal_dump_viewport_rows(
+        terminal: *mut core::ffi::c_void,
+        start_row: u16,
+        end_row: u16,
+    ) -> ghostty_vt_bytes_t;
+
     pub fn ghostty_vt_terminal_take_dirty_viewport_rows(
         terminal: *mut core::ffi::c_void,
         rows: u16,
     ) -> ghostty_vt_bytes_t;
 
+    pub fn ghostty_vt_terminal_has_dirty_viewport_rows(
+        terminal: *mut core::ffi::c_void,
+        rows: u16,
+    ) -> bool;
+
     pub fn ghostty_vt_terminal_take_viewport_scroll_delta(terminal: *mut core::ffi::c_void) -> i32;
 
+    pub fn ghostty_vt_terminal_take_bell_count(terminal: *mut core::ffi::c_void) -> u32;
+
+    /// Dumps screen-absolute rows `[start_row, end_row)` as newline-separated UTF-8.
+    pub fn ghostty_vt_terminal_dump_screen_rows(
+        terminal: *mut core::ffi::c_void,
+        start_row: u32,
+        end_row: u32,
+    ) -> ghostty_vt_bytes_t;
+
+    /// One byte per screen-absolute row in `[start_row, end_row)`: 1 if it soft-wraps into the
+    /// next row.
+    pub fn ghostty_vt_terminal_screen_row_wraps(
+        terminal: *mut core::ffi::c_void,
+        start_row: u32,
+        end_row: u32,
+    ) -> ghostty_vt_bytes_t;
+
+    /// Screen-absolute row (0 = oldest scrollback row) of the viewport's top row.
+    pub fn ghostty_vt_terminal_viewport_top_row(terminal: *mut core::ffi::c_void) -> u32;
+
+    /// Screen-absolute row (0 = oldest scrollback row) of the active area's top row.
+    pub fn ghostty_vt_terminal_active_top_row(terminal: *mut core::ffi::c_void) -> u32;
+
+    /// Searches all screen rows for `needle`. Returns packed 8-byte records
+    /// (row: u32, start_col: u16, end_col: u16; native endian, 1-based inclusive columns).
+    pub fn ghostty_vt_terminal_search(
+        terminal: *mut core::ffi::c_void,
+        needle: *const u8,
+        needle_len: usize,
+        case_sensitive: bool,
+    ) -> ghostty_vt_bytes_t;
+
     pub fn ghostty_vt_terminal_hyperlink_at(
         terminal: *mut core::ffi::c_void,
         col: u16,
@@ -106,5 +189,12 @@ unsafe extern "C" {
         modifiers: u16,
     ) -> ghostty_vt_bytes_t;
 
+    pub fn ghostty_vt_encode_key_named_with_modes(
+        name: *const u8,
+        name_len: usize,
+        modifiers: u16,
+        modes: u16,
+    ) -> ghostty_vt_bytes_t;
+
     pub fn ghostty_vt_bytes_free(bytes: ghostty_vt_bytes_t);
 }
@@ -52,6 +52,14 @@ const TerminalHandle = struct {
 
 const Handler = struct {
     terminal: *terminal.Terminal,
+    /// BEL characters seen outside escape sequences since the last take.
+    bell_count: u32 = 0,
+    /// Cursor style restored by DECSCUSR 0.
+    default_cursor_style: terminal.CursorStyle = .block,
+
+    pub fn bell(self: *Handler) !void {
+        self.bell_count +|= 1;
+    }
 
     pub fn print(self: *Handler, c: u21) !void {
         try self.terminal.print(c);
@@ -237,7 +245,8 @@ const Handler = struct {
     pub fn setCursorStyle(self: *Handler, style: terminal.CursorStyleReq) !void {
         // Convert DECSCUSR style to screen cursor style
         const screen_style: terminal.CursorStyle = switch (style) {
-            .default, .blinking_block, .steady_block => .block,
+            .default => self.default_cursor_style,
+            .blinking_block, .steady_block => .block,
             .blinking_underline, .steady_underline => .underline,
             .blinking_bar, .steady_bar => .bar,
             _ => .block, // Unknown styles default to block
@@ -253,6 +262,7 @@ const Handler = struct {
 };
 
 export fn ghostty_vt_terminal_new(cols: u16, rows: u16) callconv(.C) ?*anyopaque {
+    if (cols == 0 or rows == 0) return null;
     const alloc = std.heap.c_allocator;
     const handle = TerminalHandle.init(alloc, cols, rows) catch return null;
     return @ptrCast(handle);
@@ -326,6 +336,7 @@ export fn ghostty_vt_terminal_resize(
     rows: u16,
 ) callconv(.C) c_int {
     if (terminal_ptr == null) return 1;
+    if (cols == 0 or rows == 0) return 3;
     const handle: *TerminalHandle = @ptrCast(@alignCast(terminal_ptr.?));
 
     handle.terminal.resize(
@@ -336,6 +347,115 @@ export fn ghostty_vt_terminal_resize(
     return 0;
 }
 
+export fn ghostty_vt_terminal_set_max_scrollback(terminal_ptr: ?*anyopaque, lines: u32) callconv(.C) void {
+    if (terminal_ptr == null) return;
+    const handle: *TerminalHandle = @ptrCast(@alignCast(terminal_ptr.?));
+
+    // Ghostty caps scrollback by bytes, so convert using the size of a row in the primary
+    // screen's pages. The alternate screen never keeps scrollback.
+    const primary = if (handle.terminal.active_screen == .primary)
+        &handle.terminal.screen
+    else
+        &handle.terminal.secondary_screen;
+    const pages = &primary.pages;
+    const first = pages.pages.first orelse return;
+    const bytes_per_row = first.data.memory.len / @max(first.data.capacity.rows, 1);
+    pages.explicit_max_size = (@as(usize, lines) + pages.rows) * bytes_per_row;
+}
+
+/// Hard reset, like RIS (`ESC c`): clears the screen and scrollback and restores modes, colors,
+/// charsets and the cursor (including its default style).
+export fn ghostty_vt_terminal_reset(terminal_ptr: ?*anyopaque) callconv(.C) c_int {
+    if (terminal_ptr == null) return 1;
+    const handle: *TerminalHandle = @ptrCast(@alignCast(terminal_ptr.?));
+
+    handle.terminal.fullReset();
+    handle.terminal.eraseDisplay(.scrollback, false);
+    handle.terminal.color_palette.colors = handle.terminal.default_palette;
+    handle.terminal.color_palette.mask = .initEmpty();
+    handle.terminal.flags.dirty.palette = true;
+    handle.terminal.screen.cursor.cursor_style = handle.handler.default_cursor_style;
+    handle.has_viewport_top_y_screen = false;
+    return 0;
+}
+
+/// Drops every scrollback row, keeping the active screen. The viewport returns to the bottom.
+export fn ghostty_vt_terminal_clear_scrollback(terminal_ptr: ?*anyopaque) callconv(.C) c_int {
+    if (terminal_ptr == null) return 1;
+    const handle: *TerminalHandle = @ptrCast(@alignCast(terminal_ptr.?));
+
+    handle.terminal.eraseDisplay(.scrollback, false);
+    handle.has_viewport_top_y_screen = false;
+    return 0;
+}
+
+/// Soft reset, like DECSTR (`CSI ! p`): restores modes, scroll margins, charsets, text attributes
+/// and the saved cursor, but keeps the screen contents, scrollback and cursor position.
+export fn ghostty_vt_terminal_reset_soft(terminal_ptr: ?*anyopaque) callconv(.C) c_int {
+    if (terminal_ptr == null) return 1;
+    const handle: *TerminalHandle = @ptrCast(@alignCast(terminal_ptr.?));
+    const t = &handle.terminal;
+
+    t.modes.set(.cursor_visible, true);
+    t.modes.set(.insert, false);
+    t.modes.set(.origin, false);
+    t.modes.set(.wraparound, true);
+    t.modes.set(.cursor_keys, false);
+    t.modes.set(.keypad_keys, false);
+    t.setTopAndBottomMargin(0, 0);
+    t.setLeftAndRightMargin(0, 0);
+    t.setAttribute(.{ .unset = {} }) catch return 2;
+    t.screen.charset = .{};
+    t.screen.saved_cursor = null;
+    return 0;
+}
+
+/// Writes `bytes` (text, optionally with SGR sequences) at the 1-based `col`/`row` of the active
+/// area, like application output but without moving the application's cursor or attributes.
+/// A separate parser is used so a PTY escape sequence split across feeds is not disturbed, and
+/// wraparound is off so the text is clipped at the right edge instead of scrolling the screen.
+export fn ghostty_vt_terminal_write_text_at(
+    terminal_ptr: ?*anyopaque,
+    col: u16,
+    row: u16,
+    bytes: [*]const u8,
+    len: usize,
+) callconv(.C) c_int {
+    if (terminal_ptr == null) return 1;
+    const handle: *TerminalHandle = @ptrCast(@alignCast(terminal_ptr.?));
+    const t = &handle.terminal;
+    if (col == 0 or row == 0 or col > t.cols or row > t.rows) return 3;
+
+    const previous_saved = t.screen.saved_cursor;
+    t.saveCursor();
+    defer {
+        t.restoreCursor() catch {};
+        t.screen.saved_cursor = previous_saved;
+    }
+
+    const wraparound = t.modes.get(.wraparound);
+    t.modes.set(.wraparound, false);
+    defer t.modes.set(.wraparound, wraparound);
+    t.modes.set(.origin, false);
+
+    t.setCursorPos(row, col);
+    t.setAttribute(.{ .unset = {} }) catch return 2;
+
+    var stream = terminal.Stream(*Handler).init(&handle.handler);
+    defer stream.deinit();
+    stream.parser.osc_parser.alloc = handle.alloc;
+    for (bytes[0..len]) |b| {
+        stream.next(b) catch return 2;
+    }
+    return 0;
+}
+
+export fn ghostty_vt_terminal_memory_bytes(terminal_ptr: ?*anyopaque) callconv(.C) usize {
+    if (terminal_ptr == null) return 0;
+    const handle: *TerminalHandle = @ptrCast(@alignCast(terminal_ptr.?));
+    return handle.terminal.screen.pages.page_size + handle.terminal.secondary_screen.pages.page_size;
+}
+
 export fn ghostty_vt_terminal_scroll_viewport(
     terminal_ptr: ?*anyopaque,
     delta_lines: i32,
@@ -389,6 +509,20 @@ export fn ghostty_vt_terminal_cursor_style(terminal_ptr: ?*anyopaque) callconv(.
     };
 }
 
+/// Sets the cursor style used until the application sends DECSCUSR, and restored by DECSCUSR 0.
+/// Style values match ghostty_vt_terminal_cursor_style; unknown values select block.
+export fn ghostty_vt_terminal_set_default_cursor_style(terminal_ptr: ?*anyopaque, style: u8) callconv(.C) void {
+    if (terminal_ptr == null) return;
+    const handle: *TerminalHandle = @ptrCast(@alignCast(terminal_ptr.?));
+    const screen_style: terminal.CursorStyle = switch (style) {
+        1 => .bar,
+        2 => .underline,
+        else => .block,
+    };
+    handle.handler.default_cursor_style = screen_style;
+    handle.terminal.screen.cursor.cursor_style = screen_style;
+}
+
 /// Returns true if cursor blink is enabled (DEC mode 12)
 export fn ghostty_vt_terminal_cursor_blink(terminal_ptr: ?*anyopaque) callconv(.C) bool {
     if (terminal_ptr == null) return false;
@@ -403,6 +537,17 @@ export fn ghostty_vt_terminal_cursor_visible(terminal_ptr: ?*anyopaque) callconv
     return handle.terminal.modes.get(.cursor_visible);
 }
 
+/// Returns the keyboard modes in the bit layout of `ghostty_vt_encode_key_named_with_modes`:
+/// 0x0001 application cursor keys (DEC mode 1), 0x0002 application keypad (DEC mode 66, DECKPAM).
+export fn ghostty_vt_terminal_key_modes(terminal_ptr: ?*anyopaque) callconv(.C) u16 {
+    if (terminal_ptr == null) return 0;
+    const handle: *TerminalHandle = @ptrCast(@alignCast(terminal_ptr.?));
+    var modes: u16 = 0;
+    if (handle.terminal.modes.get(.cursor_keys)) modes |= 0x0001;
+    if (handle.terminal.modes.get(.keypad_keys)) modes |= 0x0002;
+    return modes;
+}
+
 export fn ghostty_vt_terminal_dump_viewport(terminal_ptr: ?*anyopaque) callconv(.C) ghostty_vt_bytes_t {
     if (terminal_ptr == null) return .{ .ptr = null, .len = 0 };
     const handle: *TerminalHandle = @ptrCast(@alignCast(terminal_ptr.?));
@@ -439,6 +584,66 @@ export fn ghostty_vt_terminal_dump_viewport_row(
     return .{ .ptr = slice.ptr, .len = slice.len };
 }
 
+/// Dumps screen-absolute rows `[start_row, end_row)` as UTF-8, one line per row.
+///
+/// Rows past the end of the screen are ignored.
+export fn ghostty_vt_terminal_dump_screen_rows(
+    terminal_ptr: ?*anyopaque,
+    start_row: u32,
+    end_row: u32,
+) callconv(.C) ghostty_vt_bytes_t {
+    if (terminal_ptr == null) return .{ .ptr = null, .len = 0 };
+    const handle: *TerminalHandle = @ptrCast(@alignCast(terminal_ptr.?));
+
+    const alloc = std.heap.c_allocator;
+    var builder = std.ArrayList(u8).init(alloc);
+    errdefer builder.deinit();
+
+    var y: u32 = start_row;
+    while (y < end_row) : (y += 1) {
+        const pt: terminal.point.Point = .{ .screen = .{ .x = 0, .y = y } };
+        const pin = handle.terminal.screen.pages.pin(pt) orelse break;
+
+        if (y != start_row) builder.append('\n') catch return .{ .ptr = null, .len = 0 };
+        handle.terminal.screen.pages.encodeUtf8(builder.writer(), .{
+            .tl = pin,
+            .br = pin,
+            .unwrap = false,
+        }) catch return .{ .ptr = null, .len = 0 };
+    }
+
+    const slice = builder.toOwnedSlice() catch return .{ .ptr = null, .len = 0 };
+    return .{ .ptr = slice.ptr, .len = slice.len };
+}
+
+/// Returns one byte per screen-absolute row in `[start_row, end_row)`: 1 if the row soft-wraps
+/// into the next one, 0 otherwise.
+///
+/// Rows past the end of the screen are ignored.
+export fn ghostty_vt_terminal_screen_row_wraps(
+    terminal_ptr: ?*anyopaque,
+    start_row: u32,
+    end_row: u32,
+) callconv(.C) ghostty_vt_bytes_t {
+    if (terminal_ptr == null) return .{ .ptr = null, .len = 0 };
+    const handle: *TerminalHandle = @ptrCast(@alignCast(terminal_ptr.?));
+
+    const alloc = std.heap.c_allocator;
+    var builder = std.ArrayList(u8).init(alloc);
+    errdefer builder.deinit();
+
+    var y: u32 = start_row;
+    while (y < end_row) : (y += 1) {
+        const pt: terminal.point.Point = .{ .screen = .{ .x = 0, .y = y } };
+        const pin = handle.terminal.screen.pages.pin(pt) orelse break;
+        const wrap: u8 = if (pin.rowAndCell().row.wrap) 1 else 0;
+        builder.append(wrap) catch return .{ .ptr = null, .len = 0 };
+    }
+
+    const slice = builder.toOwnedSlice() catch return .{ .ptr = null, .len = 0 };
+    return .{ .ptr = slice.ptr, .len = slice.len };
+}
+
 const CellStyle = extern struct {
     fg_r: u8,
     fg_g: u8,
@@ -485,6 +690,7 @@ export fn ghostty_vt_terminal_dump_viewport_row_cell_styles(
         if (s.flags.faint) flags |= 0x10;
         if (s.flags.invisible) flags |= 0x20;
         if (s.flags.strikethrough) flags |= 0x40;
+        if (s.flags.blink) flags |= 0x80;
 
         if (s.flags.inverse) {
             const tmp = fg;
@@ -522,9 +728,70 @@ const StyleRun = extern struct {
     bg_g: u8,
     bg_b: u8,
     flags: u8,
-    reserved: u8,
+    /// SGR 4 sub-style: 0 none, 1 single, 2 double, 3 curly, 4 dotted, 5 dashed.
+    underline: u8,
+    /// SGR 58 underline color; only meaningful when `underline_color_set` is 1.
+    ul_r: u8,
+    ul_g: u8,
+    ul_b: u8,
+    underline_color_set: u8,
+    /// OSC 8 link the run belongs to (see `cellHyperlinkId`); 0 when none.
+    hyperlink_id: u32,
 };
 
+/// Stable id for the OSC 8 link on `cell`, or 0. Hashes the link's id and URI rather than
+/// using the page-local set index, so a link split across pages keeps one id.
+fn cellHyperlinkId(page: anytype, cell: anytype) u32 {
+    if (!cell.hyperlink) return 0;
+    const id = page.lookupHyperlink(cell) orelse return 0;
+    const entry = page.hyperlink_set.get(page.memory, id).*;
+
+    var hasher = std.hash.Wyhash.init(0);
+    switch (entry.id) {
+        .explicit => |slice| {
+            hasher.update("e");
+            hasher.update(slice.offset.ptr(page.memory)[0..slice.len]);
+        },
+        .implicit => |n| {
+            hasher.update("i");
+            hasher.update(std.mem.asBytes(&n));
+        },
+    }
+    hasher.update(entry.uri.offset.ptr(page.memory)[0..entry.uri.len]);
+    const hash: u32 = @truncate(hasher.final());
+    return if (hash == 0) 1 else hash;
+}
+
+fn styleRunRecord(
+    start_col: u16,
+    end_col: u16,
+    fg: terminal.color.RGB,
+    bg: terminal.color.RGB,
+    flags: u8,
+    s: anytype,
+    palette: *const terminal.color.Palette,
+    hyperlink_id: u32,
+) StyleRun {
+    const ul = s.underlineColor(palette);
+    return .{
+        .start_col = start_col,
+        .end_col = end_col,
+        .fg_r = fg.r,
+        .fg_g = fg.g,
+        .fg_b = fg.b,
+        .bg_r = bg.r,
+        .bg_g = bg.g,
+        .bg_b = bg.b,
+        .flags = flags,
+        .underline = @intFromEnum(s.flags.underline),
+        .ul_r = if (ul) |c| c.r else 0,
+        .ul_g = if (ul) |c| c.g else 0,
+        .ul_b = if (ul) |c| c.b else 0,
+        .underline_color_set = @intFromBool(ul != null),
+        .hyperlink_id = hyperlink_id,
+    };
+}
+
 fn resolvedStyle(
     default_fg: terminal.color.RGB,
     default_bg: terminal.color.RGB,
@@ -543,6 +810,7 @@ fn resolvedStyle(
     if (s.flags.faint) flags |= 0x10;
     if (s.flags.invisible) flags |= 0x20;
     if (s.flags.strikethrough) flags |= 0x40;
+    if (s.flags.blink) flags |= 0x80;
 
     const fg = s.fg(.{ .default = default_fg, .palette = palette, .bold = null });
     return .{ .fg = fg, .bg = default_bg, .flags = flags };
@@ -557,20 +825,44 @@ export fn ghostty_vt_terminal_dump_viewport_row_style_runs(
 
     const pt: terminal.point.Point = .{ .viewport = .{ .x = 0, .y = row } };
     const pin = handle.terminal.screen.pages.pin(pt) orelse return .{ .ptr = null, .len = 0 };
-    const cells = pin.cells(.all);
+    return dumpRowStyleRuns(handle, pin);
+}
 
-    const default_fg: terminal.color.RGB = handle.default_fg;
-    const default_bg: terminal.color.RGB = handle.default_bg;
-    const palette: *const terminal.color.Palette = &handle.terminal.color_palette.colors;
+/// Same as `ghostty_vt_terminal_dump_viewport_row_style_runs`, but `row` is screen-absolute
+/// (0 = oldest scrollback row), so history can be styled without scrolling the viewport.
+export fn ghostty_vt_terminal_dump_screen_row_style_runs(
+    terminal_ptr: ?*anyopaque,
+    row: u32,
+) callconv(.C) ghostty_vt_bytes_t {
+    if (terminal_ptr == null) return .{ .ptr = null, .len = 0 };
+    const handle: *TerminalHandle = @ptrCast(@alignCast(terminal_ptr.?));
 
+    const pt: terminal.point.Point = .{ .screen = .{ .x = 0, .y = row } };
+    const pin = handle.terminal.screen.pages.pin(pt) orelse return .{ .ptr = null, .len = 0 };
+    return dumpRowStyleRuns(handle, pin);
+}
+
+fn dumpRowStyleRuns(handle: *TerminalHandle, pin: terminal.Pin) ghostty_vt_bytes_t {
     const alloc = std.heap.c_allocator;
     var out = std.ArrayList(u8).init(alloc);
     errdefer out.deinit();
 
-    if (cells.len == 0) {
-        const slice = out.toOwnedSlice() catch return .{ .ptr = null, .len = 0 };
-        return .{ .ptr = slice.ptr, .len = slice.len };
-    }
+    _ = appendRowStyleRuns(handle, pin, &out) catch return .{ .ptr = null, .len = 0 };
+
+    const slice = out.toOwnedSlice() catch return .{ .ptr = null, .len = 0 };
+    return .{ .ptr = slice.ptr, .len = slice.len };
+}
+
+/// Appends the style run records of the row at `pin` to `out`, returning how many were written.
+fn appendRowStyleRuns(handle: *TerminalHandle, pin: terminal.Pin, out: *std.ArrayList(u8)) !u32 {
+    const cells = pin.cells(.all);
+
+    const default_fg: terminal.color.RGB = handle.default_fg;
+    const default_bg: terminal.color.RGB = handle.default_bg;
+    const palette: *const terminal.color.Palette = &handle.terminal.color_palette.colors;
+
+    if (cells.len == 0) return 0;
+    var count: u32 = 0;
 
     var current_style_id = cells[0].style_id;
     var current_style = pin.style(&cells[0]);
@@ -593,27 +885,21 @@ export fn ghostty_vt_terminal_dump_viewport_row_style_runs(
     }
 
     var current_resolved = .{ .fg = current_fg, .bg = current_bg, .flags = current_flags };
+    const page = &pin.node.data;
+    var current_link = cellHyperlinkId(page, &cells[0]);
     var run_start: u16 = 1;
 
     var col_idx: usize = 1;
     while (col_idx < cells.len) : (col_idx += 1) {
         const cell = &cells[col_idx];
+        const link = cellHyperlinkId(page, cell);
         if (cell.style_id != current_style_id) {
             const end_col: u16 = @intCast(col_idx);
-            const rec = StyleRun{
-                .start_col = run_start,
-                .end_col = end_col,
-                .fg_r = current_resolved.fg.r,
-                .fg_g = current_resolved.fg.g,
-                .fg_b = current_resolved.fg.b,
-                .bg_r = current_resolved.bg.r,
-                .bg_g = current_resolved.bg.g,
-                .bg_b = current_resolved.bg.b,
-                .flags = current_resolved.flags,
-                .reserved = 0,
-            };
-            out.appendSlice(std.mem.asBytes(&rec)) catch return .{ .ptr = null, .len = 0 };
+            const rec = styleRunRecord(run_start, end_col, current_resolved.fg, current_resolved.bg, current_resolved.flags, current_style, palette, current_link);
+            try out.appendSlice(std.mem.asBytes(&rec));
+            count += 1;
 
+            current_link = link;
             current_style_id = cell.style_id;
             current_style = pin.style(cell);
             const resolved = resolvedStyle(default_fg, default_bg, palette, current_style);
@@ -654,41 +940,65 @@ export fn ghostty_vt_terminal_dump_viewport_row_style_runs(
 
         const same = fg_cell.r == current_resolved.fg.r and fg_cell.g == current_resolved.fg.g and fg_cell.b == current_resolved.fg.b and
             bg.r == current_resolved.bg.r and bg.g == current_resolved.bg.g and bg.b == current_resolved.bg.b and
-            current_flags == current_resolved.flags;
+            current_flags == current_resolved.flags and link == current_link;
         if (same) continue;
 
         const end_col: u16 = @intCast(col_idx);
-        const rec = StyleRun{
-            .start_col = run_start,
-            .end_col = end_col,
-            .fg_r = current_resolved.fg.r,
-            .fg_g = current_resolved.fg.g,
-            .fg_b = current_resolved.fg.b,
-            .bg_r = current_resolved.bg.r,
-            .bg_g = current_resolved.bg.g,
-            .bg_b = current_resolved.bg.b,
-            .flags = current_resolved.flags,
-            .reserved = 0,
-        };
-        out.appendSlice(std.mem.asBytes(&rec)) catch return .{ .ptr = null, .len = 0 };
+        const rec = styleRunRecord(run_start, end_col, current_resolved.fg, current_resolved.bg, current_resolved.flags, current_style, palette, current_link);
+        try out.appendSlice(std.mem.asBytes(&rec));
+        count += 1;
 
         run_start = @intCast(col_idx + 1);
         current_resolved = .{ .fg = fg_cell, .bg = bg, .flags = current_flags };
+        current_link = link;
     }
 
-    const last = StyleRun{
-        .start_col = run_start,
-        .end_col = @intCast(cells.len),
-        .fg_r = current_resolved.fg.r,
-        .fg_g = current_resolved.fg.g,
-        .fg_b = current_resolved.fg.b,
-        .bg_r = current_resolved.bg.r,
-        .bg_g = current_resolved.bg.g,
-        .bg_b = current_resolved.bg.b,
-        .flags = current_resolved.flags,
-        .reserved = 0,
-    };
-    out.appendSlice(std.mem.asBytes(&last)) catch return .{ .ptr = null, .len = 0 };
+    const last = styleRunRecord(run_start, @intCast(cells.len), current_resolved.fg, current_resolved.bg, current_resolved.flags, current_style, palette, current_link);
+    try out.appendSlice(std.mem.asBytes(&last));
+    return count + 1;
+}
+
+/// Header of each row record returned by `ghostty_vt_terminal_dump_viewport_rows`.
+const RowRecordHeader = extern struct {
+    text_len: u32,
+    run_count: u32,
+};
+
+/// Dumps viewport rows `[start_row, end_row)` in one call. Each row is a `RowRecordHeader`
+/// followed by `text_len` bytes of UTF-8 text and `run_count` style run records, as returned by
+/// `ghostty_vt_terminal_dump_viewport_row` and `ghostty_vt_terminal_dump_viewport_row_style_runs`.
+///
+/// Rows past the bottom of the viewport are skipped.
+export fn ghostty_vt_terminal_dump_viewport_rows(
+    terminal_ptr: ?*anyopaque,
+    start_row: u16,
+    end_row: u16,
+) callconv(.C) ghostty_vt_bytes_t {
+    if (terminal_ptr == null) return .{ .ptr = null, .len = 0 };
+    const handle: *TerminalHandle = @ptrCast(@alignCast(terminal_ptr.?));
+
+    const alloc = std.heap.c_allocator;
+    var out = std.ArrayList(u8).init(alloc);
+    errdefer out.deinit();
+
+    var y: u16 = start_row;
+    while (y < end_row) : (y += 1) {
+        const pt: terminal.point.Point = .{ .viewport = .{ .x = 0, .y = y } };
+        const pin = handle.terminal.screen.pages.pin(pt) orelse break;
+
+        const header_at = out.items.len;
+        out.appendNTimes(0, @sizeOf(RowRecordHeader)) catch return .{ .ptr = null, .len = 0 };
+        handle.terminal.screen.pages.encodeUtf8(out.writer(), .{
+            .tl = pin,
+            .br = pin,
+            .unwrap = false,
+        }) catch return .{ .ptr = null, .len = 0 };
+        const text_len = out.items.len - header_at - @sizeOf(RowRecordHeader);
+        const run_count = appendRowStyleRuns(handle, pin, &out) catch return .{ .ptr = null, .len = 0 };
+
+        const header = RowRecordHeader{ .text_len = @intCast(text_len), .run_count = run_count };
+        @memcpy(out.items[header_at..][0..@sizeOf(RowRecordHeader)], std.mem.asBytes(&header));
+    }
 
     const slice = out.toOwnedSlice() catch return .{ .ptr = null, .len = 0 };
     return .{ .ptr = slice.ptr, .len = slice.len };
@@ -733,6 +1043,27 @@ export fn ghostty_vt_terminal_take_dirty_viewport_rows(
     return .{ .ptr = slice.ptr, .len = slice.len };
 }
 
+/// Whether any of the first `rows` viewport rows would be reported by
+/// ghostty_vt_terminal_take_dirty_viewport_rows, without clearing anything.
+export fn ghostty_vt_terminal_has_dirty_viewport_rows(
+    terminal_ptr: ?*anyopaque,
+    rows: u16,
+) callconv(.C) bool {
+    if (terminal_ptr == null) return false;
+    const handle: *TerminalHandle = @ptrCast(@alignCast(terminal_ptr.?));
+
+    const dirty = handle.terminal.flags.dirty;
+    if (dirty.clear or dirty.palette or dirty.reverse_colors or dirty.preedit) return true;
+
+    var y: u32 = 0;
+    while (y < rows) : (y += 1) {
+        const pt: terminal.point.Point = .{ .viewport = .{ .x = 0, .y = y } };
+        const pin = handle.terminal.screen.pages.pin(pt) orelse continue;
+        if (pin.isDirty()) return true;
+    }
+    return false;
+}
+
 fn pinScreenRow(pin: terminal.Pin) u32 {
     var y: u32 = @intCast(pin.y);
     var node_ = pin.node;
@@ -743,6 +1074,14 @@ fn pinScreenRow(pin: terminal.Pin) u32 {
     return y;
 }
 
+export fn ghostty_vt_terminal_take_bell_count(terminal_ptr: ?*anyopaque) callconv(.C) u32 {
+    if (terminal_ptr == null) return 0;
+    const handle: *TerminalHandle = @ptrCast(@alignCast(terminal_ptr.?));
+    const count = handle.handler.bell_count;
+    handle.handler.bell_count = 0;
+    return count;
+}
+
 export fn ghostty_vt_terminal_take_viewport_scroll_delta(
     terminal_ptr: ?*anyopaque,
 ) callconv(.C) i32 {
@@ -767,6 +1106,104 @@ export fn ghostty_vt_terminal_take_viewport_scroll_delta(
     return @intCast(delta64);
 }
 
+/// Screen-absolute row index (0 = oldest scrollback row) of the viewport's top row.
+export fn ghostty_vt_terminal_viewport_top_row(terminal_ptr: ?*anyopaque) callconv(.C) u32 {
+    if (terminal_ptr == null) return 0;
+    const handle: *TerminalHandle = @ptrCast(@alignCast(terminal_ptr.?));
+    return pinScreenRow(handle.terminal.screen.pages.getTopLeft(.viewport));
+}
+
+/// Screen-absolute row index (0 = oldest scrollback row) of the active area's top row.
+export fn ghostty_vt_terminal_active_top_row(terminal_ptr: ?*anyopaque) callconv(.C) u32 {
+    if (terminal_ptr == null) return 0;
+    const handle: *TerminalHandle = @ptrCast(@alignCast(terminal_ptr.?));
+    return pinScreenRow(handle.terminal.screen.pages.getTopLeft(.active));
+}
+
+const SearchMatch = extern struct {
+    row: u32,
+    start_col: u16,
+    end_col: u16,
+};
+
+fn foldAscii(cp: u21) u21 {
+    if (cp >= 'A' and cp <= 'Z') return cp + ('a' - 'A');
+    return cp;
+}
+
+/// Searches every screen row (scrollback and active area) for `needle`.
+///
+/// Returns packed `SearchMatch` records ordered top to bottom. Rows are screen-absolute and
+/// columns are 1-based and inclusive. Matches never span rows.
+export fn ghostty_vt_terminal_search(
+    terminal_ptr: ?*anyopaque,
+    needle_ptr: ?[*]const u8,
+    needle_len: usize,
+    case_sensitive: bool,
+) callconv(.C) ghostty_vt_bytes_t {
+    if (terminal_ptr == null or needle_ptr == null or needle_len == 0) return .{ .ptr = null, .len = 0 };
+    const handle: *TerminalHandle = @ptrCast(@alignCast(terminal_ptr.?));
+
+    const alloc = std.heap.c_allocator;
+
+    var needle = std.ArrayList(u21).init(alloc);
+    defer needle.deinit();
+    var utf8 = (std.unicode.Utf8View.init(needle_ptr.?[0..needle_len]) catch return .{ .ptr = null, .len = 0 }).iterator();
+    while (utf8.nextCodepoint()) |cp| {
+        needle.append(if (case_sensitive) cp else foldAscii(cp)) catch return .{ .ptr = null, .len = 0 };
+    }
+
+    var out = std.ArrayList(u8).init(alloc);
+    errdefer out.deinit();
+
+    // Codepoints of the current row, with the 1-based column each starts at and its width.
+    var row_cps = std.ArrayList(u21).init(alloc);
+    defer row_cps.deinit();
+    var row_cols = std.ArrayList(u16).init(alloc);
+    defer row_cols.deinit();
+    var row_widths = std.ArrayList(u16).init(alloc);
+    defer row_widths.deinit();
+
+    var it = handle.terminal.screen.pages.rowIterator(.right_down, .{ .screen = .{} }, null);
+    var screen_row: u32 = 0;
+    while (it.next()) |row_pin| : (screen_row += 1) {
+        row_cps.clearRetainingCapacity();
+        row_cols.clearRetainingCapacity();
+        row_widths.clearRetainingCapacity();
+
+        const cells = row_pin.cells(.all);
+        for (cells, 0..) |*cell, x| {
+            if (cell.wide == .spacer_tail or cell.wide == .spacer_head) continue;
+            const raw: u21 = if (cell.hasText()) cell.codepoint() else ' ';
+            row_cps.append(if (case_sensitive) raw else foldAscii(raw)) catch return .{ .ptr = null, .len = 0 };
+            row_cols.append(@intCast(x + 1)) catch return .{ .ptr = null, .len = 0 };
+            row_widths.append(if (cell.wide == .wide) 2 else 1) catch return .{ .ptr = null, .len = 0 };
+        }
+
+        const hay = row_cps.items;
+        if (hay.len < needle.items.len) continue;
+        var start: usize = 0;
+        while (start + needle.items.len <= hay.len) {
+            if (!std.mem.eql(u21, hay[start .. start + needle.items.len], needle.items)) {
+                start += 1;
+                continue;
+            }
+
+            const last = start + needle.items.len - 1;
+            const rec = SearchMatch{
+                .row = screen_row,
+                .start_col = row_cols.items[start],
+                .end_col = row_cols.items[last] + row_widths.items[last] - 1,
+            };
+            out.appendSlice(std.mem.asBytes(&rec)) catch return .{ .ptr = null, .len = 0 };
+            start += needle.items.len;
+        }
+    }
+
+    const slice = out.toOwnedSlice() catch return .{ .ptr = null, .len = 0 };
+    return .{ .ptr = slice.ptr, .len = slice.len };
+}
+
 export fn ghostty_vt_terminal_hyperlink_at(
     terminal_ptr: ?*anyopaque,
     col: u16,
@@ -795,6 +1232,16 @@ export fn ghostty_vt_encode_key_named(
     name_ptr: ?[*]const u8,
     name_len: usize,
     modifiers: u16,
+) callconv(.C) ghostty_vt_bytes_t {
+    return ghostty_vt_encode_key_named_with_modes(name_ptr, name_len, modifiers, 0);
+}
+
+/// `modes` bits: 0x0001 application cursor keys (DECCKM), 0x0002 application keypad (DECKPAM).
+export fn ghostty_vt_encode_key_named_with_modes(
+    name_ptr: ?[*]const u8,
+    name_len: usize,
+    modifiers: u16,
+    modes: u16,
 ) callconv(.C) ghostty_vt_bytes_t {
     if (name_ptr == null or name_len == 0) return .{ .ptr = null, .len = 0 };
 
@@ -830,6 +1277,8 @@ export fn ghostty_vt_encode_key_named(
         .escape
     else if (name.len >= 2 and name[0] == 'f')
         parse_function_key(name[1..]) orelse return .{ .ptr = null, .len = 0 }
+    else if (std.mem.startsWith(u8, name, "numpad_"))
+        std.meta.stringToEnum(ghostty_input.Key, name) orelse return .{ .ptr = null, .len = 0 }
     else
         return .{ .ptr = null, .len = 0 };
 
@@ -839,6 +1288,14 @@ export fn ghostty_vt_encode_key_named(
     if ((modifiers & 0x0004) != 0) mods.alt = true;
     if ((modifiers & 0x0008) != 0) mods.super = true;
 
+    const alloc = std.heap.c_allocator;
+
+    var modified_buf: [32]u8 = undefined;
+    if (xtermModifiedSequence(key_value, mods, modified_buf[0..])) |seq| {
+        const duped = alloc.dupe(u8, seq) catch return .{ .ptr = null, .len = 0 };
+        return .{ .ptr = duped.ptr, .len = duped.len };
+    }
+
     const event: ghostty_input.KeyEvent = .{
         .action = .press,
         .key = key_value,
@@ -848,17 +1305,64 @@ export fn ghostty_vt_encode_key_named(
     const enc: ghostty_input.KeyEncoder = .{
         .event = event,
         .alt_esc_prefix = true,
+        .cursor_key_application = (modes & 0x0001) != 0,
+        .keypad_key_application = (modes & 0x0002) != 0,
     };
 
     var buf: [128]u8 = undefined;
     const encoded = enc.encode(buf[0..]) catch return .{ .ptr = null, .len = 0 };
     if (encoded.len == 0) return .{ .ptr = null, .len = 0 };
 
-    const alloc = std.heap.c_allocator;
     const duped = alloc.dupe(u8, encoded) catch return .{ .ptr = null, .len = 0 };
     return .{ .ptr = duped.ptr, .len = duped.len };
 }
 
+/// xterm-style encoding of a modified cursor, navigation, or F1-F12 key, e.g. `CSI 1;5C` for
+/// ctrl+right or `CSI 15;2~` for shift+F5. Returns null when no modifier is held or the key is
+/// not one of those, leaving it to the key encoder.
+fn xtermModifiedSequence(key: ghostty_input.Key, mods: ghostty_input.Mods, buf: []u8) ?[]const u8 {
+    var param: u8 = 1;
+    if (mods.shift) param += 1;
+    if (mods.alt) param += 2;
+    if (mods.ctrl) param += 4;
+    if (mods.super) param += 8;
+    if (param == 1) return null;
+
+    // CSI 1 ; m <final>
+    const final: ?u8 = switch (key) {
+        .arrow_up => 'A',
+        .arrow_down => 'B',
+        .arrow_right => 'C',
+        .arrow_left => 'D',
+        .home => 'H',
+        .end => 'F',
+        .f1 => 'P',
+        .f2 => 'Q',
+        .f3 => 'R',
+        .f4 => 'S',
+        else => null,
+    };
+    if (final) |c| return std.fmt.bufPrint(buf, "\x1b[1;{d}{c}", .{ param, c }) catch null;
+
+    // CSI n ; m ~
+    const number: u8 = switch (key) {
+        .insert => 2,
+        .delete => 3,
+        .page_up => 5,
+        .page_down => 6,
+        .f5 => 15,
+        .f6 => 17,
+        .f7 => 18,
+        .f8 => 19,
+        .f9 => 20,
+        .f10 => 21,
+        .f11 => 23,
+        .f12 => 24,
+        else => return null,
+    };
+    return std.fmt.bufPrint(buf, "\x1b[{d};{d}~", .{ number, param }) catch null;
+}
+
 fn parse_function_key(digits: []const u8) ?ghostty_input.Key {
     if (digits.len == 1) {
         return switch (digits[0]) {
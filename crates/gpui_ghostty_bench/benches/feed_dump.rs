@@ -0,0 +1,68 @@
+use criterion::{BenchmarkId, Criterion, Throughput, criterion_group, criterion_main};
+use gpui_ghostty_bench::{log_output, tui_output};
+use gpui_ghostty_terminal::{TerminalConfig, TerminalSession};
+use std::hint::black_box;
+
+const COLS: u16 = 120;
+const ROWS: u16 = 40;
+
+fn session() -> TerminalSession {
+    TerminalSession::new(TerminalConfig {
+        cols: COLS,
+        rows: ROWS,
+        ..TerminalConfig::default()
+    })
+    .expect("session init failed")
+}
+
+/// Feeds the whole input in `slice`-sized chunks and dumps the viewport after each chunk, as the
+/// view does when it parses one slice per frame.
+fn feed_and_dump(
+    session: &mut TerminalSession,
+    input: &[u8],
+    slice: usize,
+    lines: &mut Vec<String>,
+    style_runs: &mut Vec<Vec<gpui_ghostty_terminal::StyleRun>>,
+) {
+    for chunk in input.chunks(slice) {
+        session.feed(chunk).expect("feed failed");
+        session
+            .dump_viewport_rows_into(0..session.rows(), lines, style_runs)
+            .expect("dump failed");
+    }
+}
+
+fn bench_pipeline(c: &mut Criterion, name: &str, input: &[u8]) {
+    let mut group = c.benchmark_group(name);
+    group.throughput(Throughput::Bytes(input.len() as u64));
+    for slice in [4 * 1024, 64 * 1024, 256 * 1024] {
+        group.bench_with_input(BenchmarkId::new("feed_dump", slice), &slice, |b, &slice| {
+            let mut session = session();
+            let mut lines = Vec::new();
+            let mut style_runs = Vec::new();
+            b.iter(|| {
+                feed_and_dump(
+                    &mut session,
+                    black_box(input),
+                    slice,
+                    &mut lines,
+                    &mut style_runs,
+                );
+                black_box(&lines);
+            });
+        });
+    }
+    group.bench_function("feed_only", |b| {
+        let mut session = session();
+        b.iter(|| session.feed(black_box(input)).expect("feed failed"));
+    });
+    group.finish();
+}
+
+fn pipeline(c: &mut Criterion) {
+    bench_pipeline(c, "log", &log_output(20_000, COLS as usize));
+    bench_pipeline(c, "tui", &tui_output(200, COLS as usize, ROWS as usize));
+}
+
+criterion_group!(benches, pipeline);
+criterion_main!(benches);
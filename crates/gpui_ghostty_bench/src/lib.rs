@@ -0,0 +1,44 @@
+//! Criterion benchmarks for the output pipeline.
+//!
+//! `cargo bench -p gpui_ghostty_bench`
+//!
+//! The benches feed generated PTY output to a `TerminalSession` and dump the viewport the way
+//! `TerminalView` does each frame. The helpers here build that output.
+
+/// Build-log style output: plain lines with an occasional colored status word.
+pub fn log_output(lines: usize, cols: usize) -> Vec<u8> {
+    let mut out = Vec::with_capacity(lines * (cols + 16));
+    for i in 0..lines {
+        let status: &[u8] = match i % 8 {
+            0 => b"\x1b[1;32m  ok \x1b[0m",
+            5 => b"\x1b[1;31mFAIL \x1b[0m",
+            _ => b"     ",
+        };
+        out.extend_from_slice(status);
+        let text = format!("{i:>8} compiling module_{i}");
+        out.extend_from_slice(text.as_bytes());
+        let pad = cols.saturating_sub(5 + text.len()).min(cols / 2);
+        out.extend(std::iter::repeat_n(b'.', pad));
+        out.extend_from_slice(b"\r\n");
+    }
+    out
+}
+
+/// Full-screen TUI style output: every frame repaints all rows with truecolor backgrounds.
+pub fn tui_output(frames: usize, cols: usize, rows: usize) -> Vec<u8> {
+    let mut out = Vec::new();
+    for frame in 0..frames {
+        out.extend_from_slice(b"\x1b[H");
+        for row in 0..rows {
+            let shade = (frame * 7 + row * 3) % 200;
+            let text = format!("row {row} frame {frame} │ cpu {:>3}%", (frame + row) % 100);
+            let line = format!(
+                "\x1b[{};1H\x1b[48;2;{shade};40;60m\x1b[38;5;{}m{text:<cols$}\x1b[0m",
+                row + 1,
+                16 + (row % 216),
+            );
+            out.extend_from_slice(line.as_bytes());
+        }
+    }
+    out
+}
@@ -1,3 +1,6 @@
+use std::path::PathBuf;
+
+use crate::ConfigError;
 use ghostty_vt::{CursorStyle, Rgb};
 
 /// Cursor color configuration.
@@ -12,6 +15,151 @@ pub enum CursorColor {
     CellBackground,
 }
 
+/// What the terminal does with key presses made while the fn/globe modifier is held.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum FunctionKeyPolicy {
+    /// Encode the key as if fn were not held, so F-keys on laptop keyboards (where the F-row
+    /// needs fn) reach the terminal.
+    #[default]
+    Encode,
+    /// Leave fn chords to the host application; nothing is sent to the terminal.
+    Ignore,
+}
+
+/// What a view does with the click that activates its window.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum FocusClickPolicy {
+    /// Swallow the click and leave keyboard focus where it was.
+    Ignore,
+    /// Focus the terminal but swallow the click, its drag and its release, so neither the
+    /// selection nor a mouse-reporting app sees it.
+    #[default]
+    FocusOnly,
+    /// Focus the terminal and handle the click like any other (select, or report to the app).
+    Forward,
+}
+
+/// When a view shows the "cols×rows" overlay after its grid is resized.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum ResizeOverlayPolicy {
+    /// On every resize, including the first fit to the view's bounds.
+    Always,
+    /// Never.
+    Never,
+    /// On every resize except the first fit, so opening a view stays quiet.
+    #[default]
+    AfterFirst,
+}
+
+/// How a view marks a session whose foreground process runs as root (see
+/// [`TerminalSession::set_foreground_root`](crate::TerminalSession::set_foreground_root)).
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum RootIndicator {
+    /// No marking.
+    Off,
+    /// A red border around the view.
+    Border,
+    /// A red warning strip across the top of the view.
+    #[default]
+    Strip,
+}
+
+/// What a view does once the host reports that its process exited (see
+/// [`TerminalView::process_exited`](crate::TerminalView::process_exited)).
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum ProcessExitPolicy {
+    /// Dim the terminal and show the exit status until Enter restarts the process (when the host
+    /// set a restart handler) or closes the view.
+    #[default]
+    Wait,
+    /// Ask to be closed right away.
+    Close,
+    /// Restart the process right away, if the host set a restart handler; otherwise `Wait`.
+    Restart,
+}
+
+/// A pattern that turns matching terminal text into a clickable link.
+///
+/// The URL template may reference capture groups with `$0`, `$1`, `${name}`, and so on. Rules
+/// without a template ([`LinkRule::smart`]) only reach the view's
+/// [`SmartLinkHandler`](crate::SmartLinkHandler), which gets the matched text and the rule's id,
+/// e.g. to open `src/main.rs:12:5` in an editor.
+#[derive(Clone, Debug)]
+pub struct LinkRule {
+    id: Option<String>,
+    pattern: regex::Regex,
+    url_template: String,
+}
+
+impl LinkRule {
+    pub fn new(pattern: &str, url_template: impl Into<String>) -> Result<Self, regex::Error> {
+        Ok(Self {
+            id: None,
+            pattern: regex::Regex::new(pattern)?,
+            url_template: url_template.into(),
+        })
+    }
+
+    /// A rule named `id` whose matches have no URL and are handed to the host as they are.
+    pub fn smart(id: impl Into<String>, pattern: &str) -> Result<Self, regex::Error> {
+        Ok(Self::new(pattern, String::new())?.with_id(id))
+    }
+
+    /// `file:line:col` locations such as `src/main.rs:12:5`, `./build.rs:3` or
+    /// `/tmp/out.log:10:1`, under the id `file`.
+    pub fn file_location() -> Self {
+        Self::smart(
+            "file",
+            r"(?:~|\.{1,2})?/?(?:[\w.-]+/)*[\w.-]+\.\w+:\d+(?::\d+)?",
+        )
+        .expect("valid file location pattern")
+    }
+
+    /// Abbreviated and full git commit hashes (7-40 hex digits), under the id `git-sha`.
+    pub fn git_sha() -> Self {
+        Self::smart("git-sha", r"\b[0-9a-f]{7,40}\b").expect("valid git sha pattern")
+    }
+
+    /// Name the rule, so the host can tell its matches apart.
+    pub fn with_id(mut self, id: impl Into<String>) -> Self {
+        self.id = Some(id.into());
+        self
+    }
+
+    pub fn id(&self) -> Option<&str> {
+        self.id.as_deref()
+    }
+
+    pub fn pattern(&self) -> &str {
+        self.pattern.as_str()
+    }
+
+    /// The URL template; empty for [`LinkRule::smart`] rules.
+    pub fn url_template(&self) -> &str {
+        &self.url_template
+    }
+
+    /// Returns the byte range and expanded URL of every match in `text`. The URL is empty for
+    /// rules without a template.
+    pub fn find_links(&self, text: &str) -> Vec<(std::ops::Range<usize>, String)> {
+        self.pattern
+            .captures_iter(text)
+            .filter_map(|caps| {
+                let whole = caps.get(0)?;
+                if whole.is_empty() {
+                    return None;
+                }
+                let mut url = String::new();
+                caps.expand(&self.url_template, &mut url);
+                Some((whole.range(), url))
+            })
+            .collect()
+    }
+}
+
+/// Default [`TerminalConfig::word_separators`], matching Ghostty's `selection-word-chars`.
+pub const DEFAULT_WORD_SEPARATORS: &str = "'\"│`|:;,()[]{}<>$";
+
 /// Default 16-color ANSI palette (colors 0-15).
 /// Standard terminal colors: 0-7 normal, 8-15 bright variants.
 pub const DEFAULT_PALETTE: [Rgb; 16] = [
@@ -101,6 +249,10 @@ pub const DEFAULT_PALETTE: [Rgb; 16] = [
 pub struct TerminalConfig {
     pub cols: u16,
     pub rows: u16,
+    /// Largest grid the session will resize to; larger requests (e.g. from a huge window with a
+    /// tiny font) are clamped. See [`TerminalConfig::clamp_grid_size`].
+    pub max_cols: u16,
+    pub max_rows: u16,
     pub default_fg: Rgb,
     pub default_bg: Rgb,
     pub update_window_title: bool,
@@ -108,8 +260,13 @@ pub struct TerminalConfig {
     pub font_family: Option<String>,
     /// Font size in points. If `None`, uses the system default.
     pub font_size: Option<f32>,
+    /// Render programming ligatures (`->`, `!=`) for fonts that have them. Off by default so
+    /// every character keeps its own cell.
+    pub font_ligatures: bool,
     /// Shell command to run. If `None`, uses `$SHELL` or platform default.
     pub command: Option<String>,
+    /// Directory the command starts in. If `None`, the host's working directory.
+    pub working_directory: Option<PathBuf>,
 
     /// Default cursor style (block/bar/underline). Can be overridden by DECSCUSR.
     pub cursor_style: CursorStyle,
@@ -119,9 +276,15 @@ pub struct TerminalConfig {
     pub cursor_color: CursorColor,
     /// Color for text under the cursor. Defaults to `CellBackground`.
     pub cursor_text: CursorColor,
-    /// Adjust cursor height as percentage (0.0-1.0). Only affects bar/underline.
+    /// Adjust cursor height as percentage (0.0-1.0) of the line height, anchored to the bottom
+    /// of the cell. Affects block and bar cursors.
     /// Values > 1.0 are treated as percentages (e.g., 47 means 47%).
     pub adjust_cursor_height: Option<f32>,
+    /// Width of the bar cursor and height of the underline cursor, in pixels. If `None`, 2px.
+    pub cursor_thickness: Option<f32>,
+    /// Glide the cursor between cells with a short fading trail instead of jumping. Off by
+    /// default.
+    pub cursor_animation: bool,
 
     /// 16-color ANSI palette (colors 0-15). If `None`, uses default palette.
     pub palette: Option<[Rgb; 16]>,
@@ -136,8 +299,139 @@ pub struct TerminalConfig {
     pub theme_spec: Option<String>,
 
     /// Background opacity (0.0 = fully transparent, 1.0 = fully opaque).
-    /// Values below 1.0 enable a frosted-glass blur effect behind the window on macOS.
+    /// Values below 1.0 enable a frosted-glass blur effect behind the window on macOS, unless
+    /// `background_blur` is off.
     pub background_opacity: f32,
+
+    /// Blur what is behind a translucent window. Ignored while `background_opacity` is 1.0.
+    pub background_blur: bool,
+
+    /// Apply `background_opacity` to cells with an explicit background color too. By default
+    /// only the default background is translucent and colored cells stay opaque.
+    pub background_opacity_cells: bool,
+
+    /// Patterns that make matching text behave like OSC 8 hyperlinks, or that hand it to the
+    /// host (`smart-link`).
+    pub link_rules: Vec<LinkRule>,
+
+    /// Arrow key presses sent per scroll-wheel line while an alternate screen app without mouse
+    /// reporting (e.g. `less`) is running. `0` disables the translation.
+    pub alternate_scroll_lines: u16,
+
+    /// Jump back to the bottom when a keystroke is sent to the PTY while scrolled into history.
+    pub scroll_to_bottom_on_keystroke: bool,
+
+    /// Draw a marker in the left gutter next to commands that exited non-zero (requires OSC 133
+    /// shell integration).
+    pub failed_command_gutter: bool,
+
+    /// Characters that end a word for double-click and `SelectWord` selection, in addition to
+    /// whitespace.
+    pub word_separators: String,
+
+    /// Characters that stay part of a word even when listed in `word_separators`, like xterm's
+    /// `charClass` (e.g. `:` to select `host:port` in one double-click).
+    pub word_chars: String,
+
+    /// Briefly flash the terminal when the application rings the bell.
+    pub visual_bell: bool,
+
+    /// Maximum scrollback rows kept above the active area. If `None`, uses Ghostty's default
+    /// limit.
+    pub scrollback_lines: Option<u32>,
+
+    /// Handling of keys pressed with the fn/globe modifier. Keys the terminal has no encoding
+    /// for (media keys and the like) are always left to the host.
+    pub function_key_policy: FunctionKeyPolicy,
+
+    /// Handling of the mouse click that activates the window.
+    pub focus_click_policy: FocusClickPolicy,
+
+    /// How strongly faint (`SGR 2`) text keeps its color, from 0.0 (invisible, the cell
+    /// background) to 1.0 (the same as normal text). Faint text is blended toward its background.
+    pub faint_opacity: f32,
+
+    /// Blink text drawn with `SGR 5`. When `false`, blinking text is drawn steadily.
+    pub text_blink: bool,
+
+    /// Time between blinking text turning off and on again, in milliseconds.
+    pub text_blink_interval_ms: u32,
+
+    /// Convert pasted HTML markup (as copied from a browser) to plain text instead of pasting
+    /// the tags.
+    pub paste_html_as_text: bool,
+
+    /// Keep escape sequences (ANSI colors and styles) in pasted text. When `false`, they are
+    /// stripped so a paste cannot restyle or reprogram the terminal.
+    pub paste_preserve_ansi: bool,
+
+    /// Drop control characters other than newline and tab from pasted text (escape sequences
+    /// kept by `paste_preserve_ansi` are left alone).
+    pub paste_strip_control: bool,
+
+    /// Ask the host's [`PasteConfirmCallback`](crate::PasteConfirmCallback) before pasting text
+    /// that could run commands: multi-line text while the shell has not enabled bracketed paste,
+    /// or text containing the bracketed paste end marker.
+    pub paste_protection: bool,
+
+    /// Trim trailing whitespace from each copied line. Rows are padded to the grid width, so
+    /// without this copies end every line in spaces. Turn off to keep column-aligned text (e.g.
+    /// a block of a table) intact.
+    pub clipboard_trim_trailing_spaces: bool,
+
+    /// Draw an overlay scrollbar along the right edge while scrolling through history. It fades
+    /// out shortly after the viewport stops moving and can be dragged.
+    pub scrollbar: bool,
+
+    /// When to flash the grid size in the middle of the view while it is being resized.
+    pub resize_overlay: ResizeOverlayPolicy,
+
+    /// Marking shown while the host reports the foreground process as running as root.
+    pub root_indicator: RootIndicator,
+
+    /// What happens when the process exits.
+    pub process_exit: ProcessExitPolicy,
+}
+
+impl TerminalConfig {
+    /// Clamps a requested grid size to `1..=max_cols` by `1..=max_rows`.
+    pub fn clamp_grid_size(&self, cols: u16, rows: u16) -> (u16, u16) {
+        (
+            cols.clamp(1, self.max_cols.max(1)),
+            rows.clamp(1, self.max_rows.max(1)),
+        )
+    }
+
+    /// Checks that the grid size is non-zero and within `max_cols`/`max_rows`.
+    pub fn validate(&self) -> Result<(), ConfigError> {
+        if self.max_cols == 0 || self.max_rows == 0 {
+            return Err(ConfigError::Invalid(format!(
+                "maximum grid size must be non-zero, got {}x{}",
+                self.max_cols, self.max_rows
+            )));
+        }
+        if self.cols == 0 || self.rows == 0 {
+            return Err(ConfigError::Invalid(format!(
+                "grid size must be non-zero, got {}x{}",
+                self.cols, self.rows
+            )));
+        }
+        if self.cols > self.max_cols || self.rows > self.max_rows {
+            return Err(ConfigError::Invalid(format!(
+                "grid size {}x{} exceeds the maximum {}x{}",
+                self.cols, self.rows, self.max_cols, self.max_rows
+            )));
+        }
+        Ok(())
+    }
+
+    /// Characters that end a word for selection: `word_separators` minus `word_chars`.
+    pub fn effective_word_separators(&self) -> String {
+        self.word_separators
+            .chars()
+            .filter(|c| !self.word_chars.contains(*c))
+            .collect()
+    }
 }
 
 impl Default for TerminalConfig {
@@ -145,6 +439,8 @@ impl Default for TerminalConfig {
         Self {
             cols: 80,
             rows: 24,
+            max_cols: 2048,
+            max_rows: 1024,
             default_fg: Rgb {
                 r: 0xFF,
                 g: 0xFF,
@@ -158,17 +454,45 @@ impl Default for TerminalConfig {
             update_window_title: true,
             font_family: None,
             font_size: None,
+            font_ligatures: false,
             command: None,
+            working_directory: None,
             cursor_style: CursorStyle::Block,
             cursor_style_blink: None,
+            cursor_animation: false,
             cursor_color: CursorColor::CellForeground,
             cursor_text: CursorColor::CellBackground,
             adjust_cursor_height: None,
+            cursor_thickness: None,
             palette: None,
             selection_background: None,
             selection_foreground: None,
             theme_spec: None,
             background_opacity: 1.0,
+            background_blur: true,
+            background_opacity_cells: false,
+            link_rules: Vec::new(),
+            alternate_scroll_lines: 3,
+            scroll_to_bottom_on_keystroke: true,
+            failed_command_gutter: false,
+            word_separators: DEFAULT_WORD_SEPARATORS.to_string(),
+            word_chars: String::new(),
+            visual_bell: false,
+            scrollback_lines: None,
+            function_key_policy: FunctionKeyPolicy::Encode,
+            focus_click_policy: FocusClickPolicy::FocusOnly,
+            faint_opacity: 0.65,
+            text_blink: true,
+            text_blink_interval_ms: 500,
+            paste_html_as_text: false,
+            paste_preserve_ansi: false,
+            paste_strip_control: true,
+            paste_protection: true,
+            clipboard_trim_trailing_spaces: true,
+            scrollbar: true,
+            resize_overlay: ResizeOverlayPolicy::AfterFirst,
+            root_indicator: RootIndicator::Strip,
+            process_exit: ProcessExitPolicy::Wait,
         }
     }
 }
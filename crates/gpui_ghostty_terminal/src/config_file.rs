@@ -5,12 +5,15 @@
 
 use std::fs;
 use std::io;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 
 use ghostty_vt::{CursorStyle, Rgb};
 
 use crate::TerminalConfig;
-use crate::config::{CursorColor, DEFAULT_PALETTE};
+use crate::config::{
+    CursorColor, DEFAULT_PALETTE, DEFAULT_WORD_SEPARATORS, FocusClickPolicy, FunctionKeyPolicy,
+    LinkRule, ProcessExitPolicy, ResizeOverlayPolicy, RootIndicator,
+};
 
 /// Errors that can occur when loading a config file.
 #[derive(Debug)]
@@ -21,6 +24,8 @@ pub enum ConfigError {
     Io(io::Error),
     /// Parse error on a specific line.
     Parse { line: usize, message: String },
+    /// A value that parsed but is out of range (see [`TerminalConfig::validate`]).
+    Invalid(String),
 }
 
 impl std::fmt::Display for ConfigError {
@@ -31,6 +36,7 @@ impl std::fmt::Display for ConfigError {
             ConfigError::Parse { line, message } => {
                 write!(f, "parse error on line {}: {}", line, message)
             }
+            ConfigError::Invalid(message) => write!(f, "invalid config: {}", message),
         }
     }
 }
@@ -63,7 +69,7 @@ pub fn load_config() -> Result<TerminalConfig, ConfigError> {
 }
 
 /// Load configuration from a specific file path.
-pub fn load_config_from_path(path: &std::path::Path) -> Result<TerminalConfig, ConfigError> {
+pub fn load_config_from_path(path: &Path) -> Result<TerminalConfig, ConfigError> {
     let contents = fs::read_to_string(path)?;
     parse_config(&contents)
 }
@@ -181,7 +187,74 @@ pub fn reload_theme_for_appearance(config: &mut TerminalConfig, is_dark: bool) -
     // Clone to avoid borrow issues
     let theme_name = theme_name.to_string();
 
-    // Reset theme-related fields before applying new theme
+    reset_theme_colors(config);
+    load_theme(config, &theme_name).is_ok()
+}
+
+/// Replace the theme colors in `config` with the named theme, looked up like the `theme`
+/// config option: embedded themes first, then Ghostty theme files on disk.
+///
+/// Colors the theme does not set fall back to the defaults instead of keeping the previous
+/// theme's values. On error `config` is left unchanged. The `theme_spec` becomes `name`, so a
+/// later [`reload_theme_for_appearance`] keeps the picked theme.
+pub fn apply_theme(config: &mut TerminalConfig, name: &str) -> Result<(), ConfigError> {
+    let mut themed = config.clone();
+    reset_theme_colors(&mut themed);
+    load_theme(&mut themed, name)?;
+    themed.theme_spec = Some(name.to_string());
+    *config = themed;
+    Ok(())
+}
+
+/// Apply a color scheme file to `config`, like [`apply_theme`]: an iTerm2 `.itermcolors`
+/// preset, a Windows Terminal `.json` scheme (the first one in the file), or a Ghostty theme
+/// file. The `theme_spec` becomes the scheme's name.
+pub fn import_theme_file(config: &mut TerminalConfig, path: &Path) -> Result<(), ConfigError> {
+    let extension = path
+        .extension()
+        .and_then(|ext| ext.to_str())
+        .map(str::to_ascii_lowercase);
+    let stem = path
+        .file_stem()
+        .and_then(|stem| stem.to_str())
+        .unwrap_or_default()
+        .to_string();
+    let (name, contents) = match extension.as_deref() {
+        Some("itermcolors") => (stem, crate::themes::import_itermcolors(&fs::read(path)?)?),
+        Some("json") => {
+            let json = fs::read_to_string(path)?;
+            crate::themes::import_windows_terminal_schemes(&json)?
+                .into_iter()
+                .next()
+                .ok_or(ConfigError::NotFound)?
+        }
+        _ => (
+            path.file_name()
+                .and_then(|name| name.to_str())
+                .unwrap_or_default()
+                .to_string(),
+            fs::read_to_string(path)?,
+        ),
+    };
+    apply_theme_with_contents(config, &name, &contents)
+}
+
+/// [`apply_theme`] with theme contents the caller already loaded.
+pub(crate) fn apply_theme_with_contents(
+    config: &mut TerminalConfig,
+    name: &str,
+    contents: &str,
+) -> Result<(), ConfigError> {
+    let mut themed = config.clone();
+    reset_theme_colors(&mut themed);
+    apply_theme_contents(&mut themed, contents)?;
+    themed.theme_spec = Some(name.to_string());
+    *config = themed;
+    Ok(())
+}
+
+/// Reset the fields a theme sets to their defaults.
+fn reset_theme_colors(config: &mut TerminalConfig) {
     config.default_fg = Rgb {
         r: 0xFF,
         g: 0xFF,
@@ -197,9 +270,6 @@ pub fn reload_theme_for_appearance(config: &mut TerminalConfig, is_dark: bool) -
     config.selection_foreground = None;
     config.cursor_color = CursorColor::CellForeground;
     config.cursor_text = CursorColor::CellBackground;
-
-    // Load the theme
-    load_theme(config, &theme_name).is_ok()
 }
 
 /// Resolve theme name for a specific appearance (dark or light).
@@ -260,8 +330,20 @@ fn find_config_file() -> Option<PathBuf> {
     None
 }
 
+/// The config file path to watch for changes: the existing file, or where one would be
+/// created when none exists yet.
+pub(crate) fn watched_config_path() -> Option<PathBuf> {
+    if let Some(path) = find_config_file() {
+        return Some(path);
+    }
+    if let Ok(xdg_config) = std::env::var("XDG_CONFIG_HOME") {
+        return Some(PathBuf::from(xdg_config).join("Job/terminal/config"));
+    }
+    home_dir().map(|home| home.join(".config/Job/terminal/config"))
+}
+
 /// Get the user's home directory.
-fn home_dir() -> Option<PathBuf> {
+pub(crate) fn home_dir() -> Option<PathBuf> {
     std::env::var("HOME").ok().map(PathBuf::from)
 }
 
@@ -273,42 +355,10 @@ fn home_dir() -> Option<PathBuf> {
 /// 3. `/Applications/Ghostty.app/Contents/Resources/ghostty/themes/{name}` (macOS)
 /// 4. `/usr/share/ghostty/themes/{name}` (Linux system-wide)
 fn find_theme_file(name: &str) -> Option<PathBuf> {
-    // Try XDG_CONFIG_HOME first
-    if let Ok(xdg_config) = std::env::var("XDG_CONFIG_HOME") {
-        let path = PathBuf::from(xdg_config).join("ghostty/themes").join(name);
-        if path.exists() {
-            return Some(path);
-        }
-    }
-
-    // Try ~/.config/ghostty/themes/
-    if let Some(home) = home_dir() {
-        let path = home.join(".config/ghostty/themes").join(name);
-        if path.exists() {
-            return Some(path);
-        }
-    }
-
-    // macOS: Try Ghostty.app bundle
-    #[cfg(target_os = "macos")]
-    {
-        let path =
-            PathBuf::from("/Applications/Ghostty.app/Contents/Resources/ghostty/themes").join(name);
-        if path.exists() {
-            return Some(path);
-        }
-    }
-
-    // Linux: Try system-wide location
-    #[cfg(target_os = "linux")]
-    {
-        let path = PathBuf::from("/usr/share/ghostty/themes").join(name);
-        if path.exists() {
-            return Some(path);
-        }
-    }
-
-    None
+    crate::themes::default_theme_dirs()
+        .into_iter()
+        .map(|dir| dir.join(name))
+        .find(|path| path.exists())
 }
 
 /// Load and apply a theme by name.
@@ -614,6 +664,16 @@ fn apply_config_option(
                 config.font_size = Some(size);
             }
         }
+        "font-ligatures" => {
+            if value.is_empty() {
+                config.font_ligatures = false;
+            } else {
+                config.font_ligatures = parse_bool(value).ok_or_else(|| ConfigError::Parse {
+                    line: line_num,
+                    message: format!("invalid boolean: {} (expected true or false)", value),
+                })?;
+            }
+        }
         "command" => {
             if value.is_empty() {
                 config.command = None;
@@ -621,6 +681,9 @@ fn apply_config_option(
                 config.command = Some(value.to_string());
             }
         }
+        "working-directory" => {
+            config.working_directory = parse_working_directory(value);
+        }
         "cursor-style" => {
             config.cursor_style = parse_cursor_style(value).ok_or_else(|| ConfigError::Parse {
                 line: line_num,
@@ -641,6 +704,16 @@ fn apply_config_option(
                     })?);
             }
         }
+        "cursor-animation" => {
+            if value.is_empty() {
+                config.cursor_animation = false;
+            } else {
+                config.cursor_animation = parse_bool(value).ok_or_else(|| ConfigError::Parse {
+                    line: line_num,
+                    message: format!("invalid boolean: {} (expected true or false)", value),
+                })?;
+            }
+        }
         "cursor-color" => {
             if value.is_empty() {
                 config.cursor_color = CursorColor::CellForeground;
@@ -673,6 +746,26 @@ fn apply_config_option(
                 })?);
             }
         }
+        "cursor-thickness" => {
+            if value.is_empty() {
+                config.cursor_thickness = None;
+            } else {
+                let thickness = value
+                    .trim_end_matches("px")
+                    .trim()
+                    .parse::<f32>()
+                    .ok()
+                    .filter(|thickness| *thickness > 0.0)
+                    .ok_or_else(|| ConfigError::Parse {
+                        line: line_num,
+                        message: format!(
+                            "invalid cursor thickness: {} (expected a positive number of pixels)",
+                            value
+                        ),
+                    })?;
+                config.cursor_thickness = Some(thickness);
+            }
+        }
         // cursor-invert-fg-bg is deprecated but we support it for compatibility
         "cursor-invert-fg-bg" => {
             if parse_bool(value).unwrap_or(false) {
@@ -749,6 +842,296 @@ fn apply_config_option(
                 config.background_opacity = opacity.clamp(0.0, 1.0);
             }
         }
+        "background-blur" => {
+            // Ghostty also accepts a blur radius; any non-zero radius enables the blur.
+            if value.is_empty() {
+                config.background_blur = true;
+            } else {
+                config.background_blur = parse_bool(value)
+                    .or_else(|| value.parse::<u32>().ok().map(|radius| radius > 0))
+                    .ok_or_else(|| ConfigError::Parse {
+                        line: line_num,
+                        message: format!("invalid background blur: {}", value),
+                    })?;
+            }
+        }
+        "background-opacity-cells" => {
+            if value.is_empty() {
+                config.background_opacity_cells = false;
+            } else {
+                config.background_opacity_cells =
+                    parse_bool(value).ok_or_else(|| ConfigError::Parse {
+                        line: line_num,
+                        message: format!("invalid boolean: {} (expected true or false)", value),
+                    })?;
+            }
+        }
+        "link-rule" => {
+            // Repeatable: each line adds a rule, an empty value clears them.
+            if value.is_empty() {
+                config.link_rules.clear();
+            } else {
+                config.link_rules.push(parse_link_rule(value, line_num)?);
+            }
+        }
+        "smart-link" => {
+            // Repeatable, sharing `link_rules` with `link-rule`; an empty value clears only the
+            // smart links.
+            if value.is_empty() {
+                config
+                    .link_rules
+                    .retain(|rule| !rule.url_template().is_empty());
+            } else {
+                config.link_rules.push(parse_smart_link(value, line_num)?);
+            }
+        }
+        "alternate-scroll-lines" => {
+            if value.is_empty() {
+                config.alternate_scroll_lines = TerminalConfig::default().alternate_scroll_lines;
+            } else {
+                config.alternate_scroll_lines =
+                    value.parse::<u16>().map_err(|_| ConfigError::Parse {
+                        line: line_num,
+                        message: format!("invalid alternate scroll lines: {}", value),
+                    })?;
+            }
+        }
+        "scroll-to-bottom" => {
+            if value.is_empty() {
+                config.scroll_to_bottom_on_keystroke =
+                    TerminalConfig::default().scroll_to_bottom_on_keystroke;
+            } else {
+                config.scroll_to_bottom_on_keystroke =
+                    parse_scroll_to_bottom(value).ok_or_else(|| ConfigError::Parse {
+                        line: line_num,
+                        message: format!(
+                            "invalid scroll-to-bottom: {} (expected keystroke or no-keystroke)",
+                            value
+                        ),
+                    })?;
+            }
+        }
+        "failed-command-gutter" => {
+            if value.is_empty() {
+                config.failed_command_gutter = false;
+            } else {
+                config.failed_command_gutter =
+                    parse_bool(value).ok_or_else(|| ConfigError::Parse {
+                        line: line_num,
+                        message: format!("invalid boolean: {} (expected true or false)", value),
+                    })?;
+            }
+        }
+        "word-separators" => {
+            config.word_separators = if value.is_empty() {
+                DEFAULT_WORD_SEPARATORS.to_string()
+            } else {
+                value.to_string()
+            };
+        }
+        "visual-bell" => {
+            if value.is_empty() {
+                config.visual_bell = false;
+            } else {
+                config.visual_bell = parse_bool(value).ok_or_else(|| ConfigError::Parse {
+                    line: line_num,
+                    message: format!("invalid boolean: {} (expected true or false)", value),
+                })?;
+            }
+        }
+        "scrollback-lines" => {
+            if value.is_empty() {
+                config.scrollback_lines = None;
+            } else {
+                config.scrollback_lines =
+                    Some(value.parse::<u32>().map_err(|_| ConfigError::Parse {
+                        line: line_num,
+                        message: format!("invalid scrollback lines: {}", value),
+                    })?);
+            }
+        }
+        "word-chars" => {
+            config.word_chars = value.to_string();
+        }
+        "function-key-policy" => {
+            if value.is_empty() {
+                config.function_key_policy = FunctionKeyPolicy::default();
+            } else {
+                config.function_key_policy =
+                    parse_function_key_policy(value).ok_or_else(|| ConfigError::Parse {
+                        line: line_num,
+                        message: format!(
+                            "invalid function key policy: {} (expected encode or ignore)",
+                            value
+                        ),
+                    })?;
+            }
+        }
+        "focus-click" => {
+            if value.is_empty() {
+                config.focus_click_policy = FocusClickPolicy::default();
+            } else {
+                config.focus_click_policy =
+                    parse_focus_click_policy(value).ok_or_else(|| ConfigError::Parse {
+                        line: line_num,
+                        message: format!(
+                            "invalid focus click policy: {} (expected ignore, focus, or forward)",
+                            value
+                        ),
+                    })?;
+            }
+        }
+        "scrollbar" => {
+            // Ghostty spells these `system` and `never`.
+            if value.is_empty() {
+                config.scrollbar = true;
+            } else {
+                config.scrollbar = match value.to_lowercase().as_str() {
+                    "system" => Some(true),
+                    "never" => Some(false),
+                    _ => parse_bool(value),
+                }
+                .ok_or_else(|| ConfigError::Parse {
+                    line: line_num,
+                    message: format!(
+                        "invalid scrollbar: {} (expected true, false, system, or never)",
+                        value
+                    ),
+                })?;
+            }
+        }
+        "resize-overlay" => {
+            if value.is_empty() {
+                config.resize_overlay = ResizeOverlayPolicy::default();
+            } else {
+                config.resize_overlay =
+                    parse_resize_overlay(value).ok_or_else(|| ConfigError::Parse {
+                        line: line_num,
+                        message: format!(
+                            "invalid resize overlay: {} (expected always, never, or after-first)",
+                            value
+                        ),
+                    })?;
+            }
+        }
+        "root-indicator" => {
+            if value.is_empty() {
+                config.root_indicator = RootIndicator::default();
+            } else {
+                config.root_indicator =
+                    parse_root_indicator(value).ok_or_else(|| ConfigError::Parse {
+                        line: line_num,
+                        message: format!(
+                            "invalid root indicator: {} (expected off, border, or strip)",
+                            value
+                        ),
+                    })?;
+            }
+        }
+        "process-exit" => {
+            if value.is_empty() {
+                config.process_exit = ProcessExitPolicy::default();
+            } else {
+                config.process_exit =
+                    parse_process_exit(value).ok_or_else(|| ConfigError::Parse {
+                        line: line_num,
+                        message: format!(
+                            "invalid process exit policy: {} (expected wait, close, or restart)",
+                            value
+                        ),
+                    })?;
+            }
+        }
+        "faint-opacity" => {
+            if value.is_empty() {
+                config.faint_opacity = TerminalConfig::default().faint_opacity;
+            } else {
+                let opacity = value.parse::<f32>().map_err(|_| ConfigError::Parse {
+                    line: line_num,
+                    message: format!("invalid faint opacity: {}", value),
+                })?;
+                config.faint_opacity = opacity.clamp(0.0, 1.0);
+            }
+        }
+        "text-blink" => {
+            if value.is_empty() {
+                config.text_blink = true;
+            } else {
+                config.text_blink = parse_bool(value).ok_or_else(|| ConfigError::Parse {
+                    line: line_num,
+                    message: format!("invalid boolean: {} (expected true or false)", value),
+                })?;
+            }
+        }
+        "text-blink-interval" => {
+            if value.is_empty() {
+                config.text_blink_interval_ms = TerminalConfig::default().text_blink_interval_ms;
+            } else {
+                config.text_blink_interval_ms = value
+                    .parse::<u32>()
+                    .ok()
+                    .filter(|ms| *ms > 0)
+                    .ok_or_else(|| ConfigError::Parse {
+                        line: line_num,
+                        message: format!("invalid text blink interval: {}", value),
+                    })?;
+            }
+        }
+        "paste-html-as-text" => {
+            if value.is_empty() {
+                config.paste_html_as_text = false;
+            } else {
+                config.paste_html_as_text =
+                    parse_bool(value).ok_or_else(|| ConfigError::Parse {
+                        line: line_num,
+                        message: format!("invalid boolean: {} (expected true or false)", value),
+                    })?;
+            }
+        }
+        "paste-preserve-ansi" => {
+            if value.is_empty() {
+                config.paste_preserve_ansi = false;
+            } else {
+                config.paste_preserve_ansi =
+                    parse_bool(value).ok_or_else(|| ConfigError::Parse {
+                        line: line_num,
+                        message: format!("invalid boolean: {} (expected true or false)", value),
+                    })?;
+            }
+        }
+        "paste-strip-control" => {
+            if value.is_empty() {
+                config.paste_strip_control = TerminalConfig::default().paste_strip_control;
+            } else {
+                config.paste_strip_control =
+                    parse_bool(value).ok_or_else(|| ConfigError::Parse {
+                        line: line_num,
+                        message: format!("invalid boolean: {} (expected true or false)", value),
+                    })?;
+            }
+        }
+        "clipboard-paste-protection" => {
+            if value.is_empty() {
+                config.paste_protection = TerminalConfig::default().paste_protection;
+            } else {
+                config.paste_protection = parse_bool(value).ok_or_else(|| ConfigError::Parse {
+                    line: line_num,
+                    message: format!("invalid boolean: {} (expected true or false)", value),
+                })?;
+            }
+        }
+        "clipboard-trim-trailing-spaces" => {
+            if value.is_empty() {
+                config.clipboard_trim_trailing_spaces =
+                    TerminalConfig::default().clipboard_trim_trailing_spaces;
+            } else {
+                config.clipboard_trim_trailing_spaces =
+                    parse_bool(value).ok_or_else(|| ConfigError::Parse {
+                        line: line_num,
+                        message: format!("invalid boolean: {} (expected true or false)", value),
+                    })?;
+            }
+        }
         // Unknown keys are silently ignored (matching Ghostty behavior for forward compatibility)
         _ => {}
     }
@@ -756,6 +1139,52 @@ fn apply_config_option(
     Ok(())
 }
 
+/// Parse a `pattern -> url-template` link rule.
+fn parse_link_rule(value: &str, line_num: usize) -> Result<LinkRule, ConfigError> {
+    let (pattern, template) = value.rsplit_once("->").ok_or_else(|| ConfigError::Parse {
+        line: line_num,
+        message: format!("invalid link rule (expected `pattern -> url`): {}", value),
+    })?;
+    let (pattern, template) = (pattern.trim(), template.trim());
+    if pattern.is_empty() || template.is_empty() {
+        return Err(ConfigError::Parse {
+            line: line_num,
+            message: format!("invalid link rule (expected `pattern -> url`): {}", value),
+        });
+    }
+
+    LinkRule::new(pattern, template).map_err(|err| ConfigError::Parse {
+        line: line_num,
+        message: format!("invalid link rule pattern: {}", err),
+    })
+}
+
+/// Parse an `id pattern` smart link, or one of the built-in ids (`file`, `git-sha`) alone.
+fn parse_smart_link(value: &str, line_num: usize) -> Result<LinkRule, ConfigError> {
+    let (id, pattern) = match value.split_once(char::is_whitespace) {
+        Some((id, pattern)) => (id, pattern.trim()),
+        None => (value, ""),
+    };
+    if pattern.is_empty() {
+        return match id {
+            "file" => Ok(LinkRule::file_location()),
+            "git-sha" => Ok(LinkRule::git_sha()),
+            _ => Err(ConfigError::Parse {
+                line: line_num,
+                message: format!(
+                    "invalid smart link (expected `id pattern`, `file` or `git-sha`): {}",
+                    value
+                ),
+            }),
+        };
+    }
+
+    LinkRule::smart(id, pattern).map_err(|err| ConfigError::Parse {
+        line: line_num,
+        message: format!("invalid smart link pattern: {}", err),
+    })
+}
+
 /// Parse a hex color value.
 ///
 /// Supports formats:
@@ -785,6 +1214,84 @@ fn parse_cursor_style(value: &str) -> Option<CursorStyle> {
     }
 }
 
+/// Parse a function key policy value.
+fn parse_function_key_policy(value: &str) -> Option<FunctionKeyPolicy> {
+    match value.to_lowercase().as_str() {
+        "encode" => Some(FunctionKeyPolicy::Encode),
+        "ignore" => Some(FunctionKeyPolicy::Ignore),
+        _ => None,
+    }
+}
+
+/// Parse a focus click policy value.
+fn parse_focus_click_policy(value: &str) -> Option<FocusClickPolicy> {
+    match value.to_lowercase().as_str() {
+        "ignore" => Some(FocusClickPolicy::Ignore),
+        "focus" | "focus-only" => Some(FocusClickPolicy::FocusOnly),
+        "forward" => Some(FocusClickPolicy::Forward),
+        _ => None,
+    }
+}
+
+/// Parse a resize overlay value.
+fn parse_resize_overlay(value: &str) -> Option<ResizeOverlayPolicy> {
+    match value.to_lowercase().as_str() {
+        "always" => Some(ResizeOverlayPolicy::Always),
+        "never" => Some(ResizeOverlayPolicy::Never),
+        "after-first" => Some(ResizeOverlayPolicy::AfterFirst),
+        _ => None,
+    }
+}
+
+/// Parse a root indicator value.
+fn parse_root_indicator(value: &str) -> Option<RootIndicator> {
+    match value.to_lowercase().as_str() {
+        "off" | "false" => Some(RootIndicator::Off),
+        "border" => Some(RootIndicator::Border),
+        "strip" => Some(RootIndicator::Strip),
+        _ => None,
+    }
+}
+
+/// Parse Ghostty's `working-directory`: `home`, `inherit` (the host's directory) or a path,
+/// where a leading `~/` stands for the home directory.
+fn parse_working_directory(value: &str) -> Option<PathBuf> {
+    match value {
+        "" | "inherit" => None,
+        "home" => home_dir(),
+        _ => match value.strip_prefix("~/") {
+            Some(rest) => home_dir().map(|home| home.join(rest)),
+            None => Some(PathBuf::from(value)),
+        },
+    }
+}
+
+/// Parse a process exit policy value.
+fn parse_process_exit(value: &str) -> Option<ProcessExitPolicy> {
+    match value.to_lowercase().as_str() {
+        "wait" => Some(ProcessExitPolicy::Wait),
+        "close" => Some(ProcessExitPolicy::Close),
+        "restart" => Some(ProcessExitPolicy::Restart),
+        _ => None,
+    }
+}
+
+/// Parse Ghostty's `scroll-to-bottom` flag list (e.g. `keystroke,no-output`) into whether
+/// keystrokes scroll to the bottom. The `output` flags are accepted for compatibility; output never
+/// moves a viewport that was scrolled back.
+fn parse_scroll_to_bottom(value: &str) -> Option<bool> {
+    let mut keystroke = TerminalConfig::default().scroll_to_bottom_on_keystroke;
+    for flag in value.split(',').map(str::trim) {
+        match flag.to_lowercase().as_str() {
+            "keystroke" => keystroke = true,
+            "no-keystroke" => keystroke = false,
+            "output" | "no-output" => {}
+            _ => return None,
+        }
+    }
+    Some(keystroke)
+}
+
 /// Parse a boolean value.
 fn parse_bool(value: &str) -> Option<bool> {
     match value.to_lowercase().as_str() {
@@ -934,6 +1441,17 @@ font-size = 14.5
         let config = parse_config(input).unwrap();
         assert_eq!(config.font_family.as_deref(), Some("JetBrains Mono"));
         assert_eq!(config.font_size, Some(14.5));
+        assert!(!config.font_ligatures);
+
+        assert!(
+            parse_config("font-ligatures = true")
+                .unwrap()
+                .font_ligatures
+        );
+        assert!(matches!(
+            parse_config("font-ligatures = some"),
+            Err(ConfigError::Parse { line: 1, .. })
+        ));
     }
 
     #[test]
@@ -1065,6 +1583,27 @@ adjust-cursor-height = 47%
         assert!((config.adjust_cursor_height.unwrap() - 0.47).abs() < 0.001);
     }
 
+    #[test]
+    fn test_parse_config_cursor_thickness() {
+        assert_eq!(parse_config("").unwrap().cursor_thickness, None);
+        assert_eq!(
+            parse_config("cursor-thickness = 3")
+                .unwrap()
+                .cursor_thickness,
+            Some(3.0)
+        );
+        assert_eq!(
+            parse_config("cursor-thickness = 1.5px")
+                .unwrap()
+                .cursor_thickness,
+            Some(1.5)
+        );
+        assert!(matches!(
+            parse_config("cursor-thickness = 0"),
+            Err(ConfigError::Parse { line: 1, .. })
+        ));
+    }
+
     #[test]
     fn test_parse_config_cursor_invert_fg_bg() {
         let input = "cursor-invert-fg-bg = true";
@@ -1406,4 +1945,475 @@ foreground = #f8f8f2
         let config = parse_config("").unwrap();
         assert!((config.background_opacity - 1.0).abs() < 0.001);
     }
+
+    #[test]
+    fn test_parse_config_scroll_to_bottom() {
+        assert!(parse_config("").unwrap().scroll_to_bottom_on_keystroke);
+
+        let config = parse_config("scroll-to-bottom = no-keystroke, no-output").unwrap();
+        assert!(!config.scroll_to_bottom_on_keystroke);
+
+        let config = parse_config("scroll-to-bottom = no-keystroke\nscroll-to-bottom =").unwrap();
+        assert!(config.scroll_to_bottom_on_keystroke);
+
+        assert!(matches!(
+            parse_config("scroll-to-bottom = always"),
+            Err(ConfigError::Parse { line: 1, .. })
+        ));
+    }
+
+    #[test]
+    fn test_parse_config_alternate_scroll_lines() {
+        let config = parse_config("alternate-scroll-lines = 5").unwrap();
+        assert_eq!(config.alternate_scroll_lines, 5);
+
+        let config = parse_config("alternate-scroll-lines = 0\nalternate-scroll-lines =").unwrap();
+        assert_eq!(config.alternate_scroll_lines, 3);
+
+        assert!(matches!(
+            parse_config("alternate-scroll-lines = -1"),
+            Err(ConfigError::Parse { line: 1, .. })
+        ));
+    }
+
+    #[test]
+    fn test_parse_config_failed_command_gutter() {
+        assert!(!parse_config("").unwrap().failed_command_gutter);
+        assert!(
+            parse_config("failed-command-gutter = true")
+                .unwrap()
+                .failed_command_gutter
+        );
+        assert!(matches!(
+            parse_config("failed-command-gutter = maybe"),
+            Err(ConfigError::Parse { line: 1, .. })
+        ));
+    }
+
+    #[test]
+    fn test_parse_config_word_separators() {
+        let config = parse_config("word-separators = /.-").unwrap();
+        assert_eq!(config.word_separators, "/.-");
+
+        let config = parse_config("word-separators = /\nword-separators =").unwrap();
+        assert_eq!(config.word_separators, DEFAULT_WORD_SEPARATORS);
+    }
+
+    #[test]
+    fn test_parse_config_visual_bell() {
+        assert!(!parse_config("").unwrap().visual_bell);
+        assert!(parse_config("visual-bell = true").unwrap().visual_bell);
+        assert!(matches!(
+            parse_config("visual-bell = loud"),
+            Err(ConfigError::Parse { line: 1, .. })
+        ));
+    }
+
+    #[test]
+    fn test_parse_config_scrollback_lines() {
+        assert_eq!(parse_config("").unwrap().scrollback_lines, None);
+        assert_eq!(
+            parse_config("scrollback-lines = 5000")
+                .unwrap()
+                .scrollback_lines,
+            Some(5000)
+        );
+        assert_eq!(
+            parse_config("scrollback-lines = 5000\nscrollback-lines =")
+                .unwrap()
+                .scrollback_lines,
+            None
+        );
+        assert!(matches!(
+            parse_config("scrollback-lines = lots"),
+            Err(ConfigError::Parse { line: 1, .. })
+        ));
+    }
+
+    #[test]
+    fn test_parse_config_word_chars() {
+        let config = parse_config("word-separators = /:-|\nword-chars = :-").unwrap();
+        assert_eq!(config.word_chars, ":-");
+        assert_eq!(config.effective_word_separators(), "/|");
+
+        let config = parse_config("word-chars = :\nword-chars =").unwrap();
+        assert_eq!(config.effective_word_separators(), DEFAULT_WORD_SEPARATORS);
+    }
+
+    #[test]
+    fn test_parse_config_function_key_policy() {
+        assert_eq!(
+            parse_config("").unwrap().function_key_policy,
+            FunctionKeyPolicy::Encode
+        );
+        assert_eq!(
+            parse_config("function-key-policy = Ignore")
+                .unwrap()
+                .function_key_policy,
+            FunctionKeyPolicy::Ignore
+        );
+        assert!(matches!(
+            parse_config("function-key-policy = media"),
+            Err(ConfigError::Parse { line: 1, .. })
+        ));
+    }
+
+    #[test]
+    fn test_parse_config_scrollbar() {
+        assert!(parse_config("").unwrap().scrollbar);
+        assert!(!parse_config("scrollbar = false").unwrap().scrollbar);
+        assert!(!parse_config("scrollbar = never").unwrap().scrollbar);
+        assert!(
+            parse_config("scrollbar = never\nscrollbar =")
+                .unwrap()
+                .scrollbar
+        );
+        assert!(matches!(
+            parse_config("scrollbar = sometimes"),
+            Err(ConfigError::Parse { line: 1, .. })
+        ));
+    }
+
+    #[test]
+    fn test_parse_config_root_indicator() {
+        assert_eq!(
+            parse_config("").unwrap().root_indicator,
+            RootIndicator::Strip
+        );
+        assert_eq!(
+            parse_config("root-indicator = Border")
+                .unwrap()
+                .root_indicator,
+            RootIndicator::Border
+        );
+        assert_eq!(
+            parse_config("root-indicator = off\nroot-indicator =")
+                .unwrap()
+                .root_indicator,
+            RootIndicator::Strip
+        );
+        assert!(matches!(
+            parse_config("root-indicator = flash"),
+            Err(ConfigError::Parse { line: 1, .. })
+        ));
+    }
+
+    #[test]
+    fn test_parse_config_working_directory() {
+        assert_eq!(parse_config("").unwrap().working_directory, None);
+        assert_eq!(
+            parse_config("working-directory = /srv/app")
+                .unwrap()
+                .working_directory,
+            Some(PathBuf::from("/srv/app"))
+        );
+        assert_eq!(
+            parse_config("working-directory = /srv\nworking-directory = inherit")
+                .unwrap()
+                .working_directory,
+            None
+        );
+        assert_eq!(
+            parse_config("working-directory = home")
+                .unwrap()
+                .working_directory,
+            home_dir()
+        );
+    }
+
+    #[test]
+    fn test_parse_config_process_exit() {
+        assert_eq!(
+            parse_config("").unwrap().process_exit,
+            ProcessExitPolicy::Wait
+        );
+        assert_eq!(
+            parse_config("process-exit = Restart").unwrap().process_exit,
+            ProcessExitPolicy::Restart
+        );
+        assert_eq!(
+            parse_config("process-exit = close\nprocess-exit =")
+                .unwrap()
+                .process_exit,
+            ProcessExitPolicy::Wait
+        );
+        assert!(matches!(
+            parse_config("process-exit = linger"),
+            Err(ConfigError::Parse { line: 1, .. })
+        ));
+    }
+
+    #[test]
+    fn test_parse_config_focus_click_policy() {
+        assert_eq!(
+            parse_config("").unwrap().focus_click_policy,
+            FocusClickPolicy::FocusOnly
+        );
+        assert_eq!(
+            parse_config("focus-click = Forward")
+                .unwrap()
+                .focus_click_policy,
+            FocusClickPolicy::Forward
+        );
+        assert_eq!(
+            parse_config("focus-click = ignore\nfocus-click =")
+                .unwrap()
+                .focus_click_policy,
+            FocusClickPolicy::FocusOnly
+        );
+        assert!(matches!(
+            parse_config("focus-click = always"),
+            Err(ConfigError::Parse { line: 1, .. })
+        ));
+    }
+
+    #[test]
+    fn test_parse_config_resize_overlay() {
+        assert_eq!(
+            parse_config("").unwrap().resize_overlay,
+            ResizeOverlayPolicy::AfterFirst
+        );
+        assert_eq!(
+            parse_config("resize-overlay = Never")
+                .unwrap()
+                .resize_overlay,
+            ResizeOverlayPolicy::Never
+        );
+        assert_eq!(
+            parse_config("resize-overlay = always\nresize-overlay =")
+                .unwrap()
+                .resize_overlay,
+            ResizeOverlayPolicy::AfterFirst
+        );
+        assert!(matches!(
+            parse_config("resize-overlay = sometimes"),
+            Err(ConfigError::Parse { line: 1, .. })
+        ));
+    }
+
+    #[test]
+    fn test_parse_config_faint_opacity() {
+        assert_eq!(parse_config("").unwrap().faint_opacity, 0.65);
+        assert_eq!(
+            parse_config("faint-opacity = 0.4").unwrap().faint_opacity,
+            0.4
+        );
+        assert_eq!(
+            parse_config("faint-opacity = 3").unwrap().faint_opacity,
+            1.0
+        );
+        assert!(matches!(
+            parse_config("faint-opacity = dim"),
+            Err(ConfigError::Parse { line: 1, .. })
+        ));
+    }
+
+    #[test]
+    fn test_parse_config_text_blink() {
+        let config = parse_config("").unwrap();
+        assert!(config.text_blink);
+        assert_eq!(config.text_blink_interval_ms, 500);
+
+        let config = parse_config("text-blink = false\ntext-blink-interval = 750").unwrap();
+        assert!(!config.text_blink);
+        assert_eq!(config.text_blink_interval_ms, 750);
+
+        assert!(matches!(
+            parse_config("text-blink = sometimes"),
+            Err(ConfigError::Parse { line: 1, .. })
+        ));
+        assert!(matches!(
+            parse_config("text-blink-interval = 0"),
+            Err(ConfigError::Parse { line: 1, .. })
+        ));
+    }
+
+    #[test]
+    fn test_parse_config_clipboard_trim_trailing_spaces() {
+        assert!(parse_config("").unwrap().clipboard_trim_trailing_spaces);
+        assert!(
+            !parse_config("clipboard-trim-trailing-spaces = false")
+                .unwrap()
+                .clipboard_trim_trailing_spaces
+        );
+        assert!(
+            parse_config(
+                "clipboard-trim-trailing-spaces = false
+clipboard-trim-trailing-spaces ="
+            )
+            .unwrap()
+            .clipboard_trim_trailing_spaces
+        );
+        assert!(matches!(
+            parse_config("clipboard-trim-trailing-spaces = sometimes"),
+            Err(ConfigError::Parse { line: 1, .. })
+        ));
+    }
+
+    #[test]
+    fn test_parse_config_paste_options() {
+        let config = parse_config("").unwrap();
+        assert!(!config.paste_html_as_text);
+        assert!(!config.paste_preserve_ansi);
+        assert!(config.paste_strip_control);
+        assert!(config.paste_protection);
+
+        let config = parse_config("paste-html-as-text = true\npaste-preserve-ansi = true").unwrap();
+        assert!(config.paste_html_as_text);
+        assert!(config.paste_preserve_ansi);
+
+        let config = parse_config(
+            "paste-strip-control = false\nclipboard-paste-protection = false\n\
+             paste-strip-control =",
+        )
+        .unwrap();
+        assert!(config.paste_strip_control);
+        assert!(!config.paste_protection);
+
+        assert!(matches!(
+            parse_config("paste-preserve-ansi = maybe"),
+            Err(ConfigError::Parse { line: 1, .. })
+        ));
+    }
+
+    #[test]
+    fn test_parse_config_background_blur_and_cells() {
+        let config = parse_config("").unwrap();
+        assert!(config.background_blur);
+        assert!(!config.background_opacity_cells);
+
+        let config =
+            parse_config("background-blur = false\nbackground-opacity-cells = true").unwrap();
+        assert!(!config.background_blur);
+        assert!(config.background_opacity_cells);
+
+        assert!(!parse_config("background-blur = 0").unwrap().background_blur);
+        assert!(
+            parse_config("background-blur = 20")
+                .unwrap()
+                .background_blur
+        );
+        assert!(matches!(
+            parse_config("background-blur = heavy"),
+            Err(ConfigError::Parse { line: 1, .. })
+        ));
+    }
+
+    #[test]
+    fn test_parse_config_cursor_animation() {
+        assert!(!parse_config("").unwrap().cursor_animation);
+        assert!(
+            parse_config("cursor-animation = true")
+                .unwrap()
+                .cursor_animation
+        );
+        assert!(
+            !parse_config("cursor-animation = true\ncursor-animation =")
+                .unwrap()
+                .cursor_animation
+        );
+        assert!(matches!(
+            parse_config("cursor-animation = smooth"),
+            Err(ConfigError::Parse { line: 1, .. })
+        ));
+    }
+
+    #[test]
+    fn test_parse_config_link_rules() {
+        let input = r#"
+link-rule = JIRA-\d+ -> https://jira.example.com/browse/$0
+link-rule = #(\d+) -> https://github.com/org/repo/pull/$1
+"#;
+        let config = parse_config(input).unwrap();
+        assert_eq!(config.link_rules.len(), 2);
+        assert_eq!(config.link_rules[0].pattern(), r"JIRA-\d+");
+
+        let links = config.link_rules[1].find_links("see #42 and #7");
+        assert_eq!(
+            links,
+            vec![
+                (4..7, "https://github.com/org/repo/pull/42".to_string()),
+                (12..14, "https://github.com/org/repo/pull/7".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_parse_config_link_rule_reset() {
+        let input = "link-rule = a+ -> https://a\nlink-rule =";
+        let config = parse_config(input).unwrap();
+        assert!(config.link_rules.is_empty());
+    }
+
+    #[test]
+    fn test_parse_config_smart_links() {
+        let input = r"
+link-rule = JIRA-\d+ -> https://jira.example.com/browse/$0
+smart-link = file
+smart-link = ticket T-\d+
+";
+        let config = parse_config(input).unwrap();
+        assert_eq!(config.link_rules.len(), 3);
+        assert_eq!(config.link_rules[1].id(), Some("file"));
+        assert_eq!(config.link_rules[2].id(), Some("ticket"));
+        assert_eq!(config.link_rules[2].pattern(), r"T-\d+");
+        assert_eq!(config.link_rules[2].url_template(), "");
+
+        let config = parse_config(&format!("{input}smart-link =")).unwrap();
+        assert_eq!(config.link_rules.len(), 1);
+        assert_eq!(config.link_rules[0].id(), None);
+
+        assert!(matches!(
+            parse_config("smart-link = nope"),
+            Err(ConfigError::Parse { line: 1, .. })
+        ));
+        assert!(matches!(
+            parse_config("smart-link = x ("),
+            Err(ConfigError::Parse { line: 1, .. })
+        ));
+    }
+
+    #[test]
+    fn test_parse_config_link_rule_invalid() {
+        assert!(matches!(
+            parse_config("link-rule = JIRA-\\d+"),
+            Err(ConfigError::Parse { line: 1, .. })
+        ));
+        assert!(matches!(
+            parse_config("link-rule = ( -> https://x"),
+            Err(ConfigError::Parse { line: 1, .. })
+        ));
+    }
+
+    #[test]
+    fn test_config_validate_grid_size() {
+        assert!(TerminalConfig::default().validate().is_ok());
+
+        for (cols, rows) in [(0, 0), (80, 0), (60_000, 24)] {
+            let config = TerminalConfig {
+                cols,
+                rows,
+                ..TerminalConfig::default()
+            };
+            assert!(matches!(config.validate(), Err(ConfigError::Invalid(_))));
+        }
+
+        let config = TerminalConfig {
+            max_cols: 0,
+            ..TerminalConfig::default()
+        };
+        assert!(matches!(config.validate(), Err(ConfigError::Invalid(_))));
+    }
+
+    #[test]
+    fn test_config_clamp_grid_size() {
+        let config = TerminalConfig {
+            max_cols: 200,
+            max_rows: 100,
+            ..TerminalConfig::default()
+        };
+        assert_eq!(config.clamp_grid_size(0, 0), (1, 1));
+        assert_eq!(config.clamp_grid_size(60_000, 24), (200, 24));
+        assert_eq!(config.clamp_grid_size(80, 500), (80, 100));
+    }
 }
@@ -0,0 +1,104 @@
+//! Hot reload of the config file while the app is running.
+
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+use futures::StreamExt;
+use futures::channel::mpsc;
+use gpui::{App, Task, WeakEntity};
+use notify::{EventKind, RecursiveMode, Watcher};
+
+use crate::config_file::{load_config_from_path, watched_config_path};
+use crate::view::TerminalView;
+
+/// Editors often save in several steps (truncate, write, rename); wait this long after the
+/// first event so a burst turns into one reload of the finished file.
+const RELOAD_DEBOUNCE: Duration = Duration::from_millis(100);
+
+/// Watch `~/.config/Job/terminal/config` (or `$XDG_CONFIG_HOME/Job/terminal/config`) and apply
+/// it to running views whenever it changes.
+///
+/// `views` is asked for the live views on every reload, so panes opened after the watcher
+/// started pick up later edits too; dropped views are skipped. Each view gets the reloaded
+/// config through [`TerminalView::apply_config`]. A file that fails to parse is reported on
+/// stderr and leaves the views untouched.
+///
+/// The watch stops when the returned task is dropped, so detach it or keep it with the app.
+pub fn watch_config<F>(cx: &mut App, views: F) -> Task<()>
+where
+    F: FnMut(&mut App) -> Vec<WeakEntity<TerminalView>> + 'static,
+{
+    let Some(path) = watched_config_path() else {
+        return Task::ready(());
+    };
+    watch_config_path(cx, path, views)
+}
+
+/// [`watch_config`] for a config file at an explicit path.
+pub fn watch_config_path<F>(cx: &mut App, path: PathBuf, mut views: F) -> Task<()>
+where
+    F: FnMut(&mut App) -> Vec<WeakEntity<TerminalView>> + 'static,
+{
+    let (tx, mut rx) = mpsc::unbounded();
+    let watched = path.clone();
+    let watcher = notify::recommended_watcher(move |event: notify::Result<notify::Event>| {
+        if let Ok(event) = event
+            && is_config_change(&event, &watched)
+        {
+            let _ = tx.unbounded_send(());
+        }
+    });
+    let mut watcher = match watcher {
+        Ok(watcher) => watcher,
+        Err(err) => {
+            eprintln!("[config] Failed to watch {}: {}", path.display(), err);
+            return Task::ready(());
+        }
+    };
+    // Watch the directory rather than the file: atomic saves replace the file's inode, and the
+    // file may not exist yet.
+    let dir = path.parent().unwrap_or(Path::new("."));
+    if let Err(err) = watcher.watch(dir, RecursiveMode::NonRecursive) {
+        eprintln!("[config] Failed to watch {}: {}", dir.display(), err);
+        return Task::ready(());
+    }
+
+    cx.spawn(async move |cx| {
+        // Keep the watcher alive for as long as the task runs.
+        let _watcher = watcher;
+        while rx.next().await.is_some() {
+            cx.background_executor().timer(RELOAD_DEBOUNCE).await;
+            while let Ok(Some(())) = rx.try_next() {}
+
+            let config = match load_config_from_path(&path) {
+                Ok(config) => config,
+                Err(err) => {
+                    eprintln!("[config] Failed to reload {}: {}", path.display(), err);
+                    continue;
+                }
+            };
+            let updated = cx.update(|cx| {
+                for view in views(cx) {
+                    view.update(cx, |view, cx| view.apply_config(config.clone(), cx))
+                        .ok();
+                }
+            });
+            if updated.is_err() {
+                break;
+            }
+        }
+    })
+}
+
+/// Whether a watcher event touched the config file with a content change (not just an access).
+pub(crate) fn is_config_change(event: &notify::Event, path: &Path) -> bool {
+    let relevant = matches!(
+        event.kind,
+        EventKind::Create(_) | EventKind::Modify(_) | EventKind::Any
+    );
+    relevant
+        && event
+            .paths
+            .iter()
+            .any(|p| p.file_name() == path.file_name())
+}
@@ -67,11 +67,19 @@ pub fn terminal_font(config: &TerminalConfig) -> gpui::Font {
     font
 }
 
+/// Font features for terminal text with ligatures disabled.
 pub fn default_terminal_font_features() -> gpui::FontFeatures {
+    terminal_font_features(false)
+}
+
+/// Font features for terminal text. Kerning is always off so glyphs stay on the cell grid;
+/// `ligatures` turns on contextual alternates and standard ligatures (`calt`, `liga`).
+pub fn terminal_font_features(ligatures: bool) -> gpui::FontFeatures {
     use std::sync::Arc;
+    let ligatures = u32::from(ligatures);
     gpui::FontFeatures(Arc::new(vec![
-        ("calt".to_string(), 0),
-        ("liga".to_string(), 0),
+        ("calt".to_string(), ligatures),
+        ("liga".to_string(), ligatures),
         ("kern".to_string(), 0),
     ]))
 }
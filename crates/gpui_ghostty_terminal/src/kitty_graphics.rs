@@ -0,0 +1,732 @@
+//! Kitty graphics protocol support.
+//!
+//! Graphics commands arrive as APC strings (`ESC _ G <control> ; <payload> ESC \`). The VT core
+//! ignores APC, so the session scans for them itself, decodes transmitted images and records
+//! placements keyed by the screen cell they are anchored to. The view paints placements that
+//! intersect the viewport.
+//!
+//! Only direct transmission (`t=d`) of uncompressed PNG, RGB or RGBA data is supported.
+
+use std::collections::{BTreeMap, HashMap};
+use std::ops::Range;
+use std::sync::Arc;
+
+/// Longest single APC command body kept in memory. Clients chunk transmissions into 4 KiB pieces,
+/// so anything larger is almost certainly garbage.
+const MAX_COMMAND_BYTES: usize = 1024 * 1024;
+/// Largest base64 payload accepted for one (possibly chunked) transmission.
+const MAX_TRANSMISSION_BYTES: usize = 64 * 1024 * 1024;
+/// Total decoded pixel bytes kept before the oldest images are evicted.
+const MAX_STORED_IMAGE_BYTES: usize = 256 * 1024 * 1024;
+
+/// A decoded image transmitted by the application.
+#[derive(Debug)]
+pub struct KittyImage {
+    pub id: u32,
+    pub width: u32,
+    pub height: u32,
+    /// Pixel data, 4 bytes per pixel in RGBA order.
+    pub rgba: Vec<u8>,
+    serial: u64,
+}
+
+impl KittyImage {
+    /// Monotonic identifier that changes whenever an image id is re-transmitted.
+    ///
+    /// Renderers can use it as a cache key for uploaded textures.
+    pub fn serial(&self) -> u64 {
+        self.serial
+    }
+}
+
+/// An image displayed at a cell position.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct KittyPlacement {
+    pub image_id: u32,
+    pub placement_id: u32,
+    /// 0-based column of the top-left cell.
+    pub col: u16,
+    /// Screen-absolute row of the top-left cell (see `ghostty_vt::Terminal::active_top_row`).
+    pub row: u32,
+    /// Number of columns covered.
+    pub cols: u16,
+    /// Number of rows covered.
+    pub rows: u16,
+    /// Pixel offset inside the top-left cell.
+    pub x_offset: u32,
+    pub y_offset: u32,
+    /// Placements with a negative z-index are drawn below text.
+    pub z_index: i32,
+    /// True when the application requested an explicit cell size, in which case the image is
+    /// scaled to fill `cols` x `rows`. Otherwise it is drawn at its native pixel size.
+    pub fit_to_cells: bool,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+struct PlacementKey {
+    row: u32,
+    col: u16,
+    serial: u64,
+}
+
+/// Cursor and cell geometry needed to execute a command.
+#[derive(Clone, Copy, Debug)]
+pub(crate) struct KittyCommandContext {
+    /// 0-based cursor column.
+    pub cursor_col: u16,
+    /// Screen-absolute cursor row.
+    pub cursor_row: u32,
+    /// Screen-absolute row of the active area's top row.
+    pub active_top_row: u32,
+    pub cell_width_px: u32,
+    pub cell_height_px: u32,
+}
+
+/// Side effects the session must apply after a command.
+#[derive(Debug, Default, PartialEq, Eq)]
+pub(crate) struct KittyCommandOutcome {
+    /// Reply to write back to the pty.
+    pub response: Option<Vec<u8>>,
+    /// Columns and rows the cursor should move past the new placement.
+    pub cursor_advance: Option<(u16, u16)>,
+}
+
+#[derive(Clone, Copy, Debug, Default)]
+enum ApcScanState {
+    #[default]
+    Idle,
+    Esc,
+    Body,
+    BodyEsc,
+    Skip,
+    SkipEsc,
+}
+
+/// Byte-level scanner that extracts kitty graphics command bodies (the bytes after `ESC _ G`).
+#[derive(Debug, Default)]
+pub(crate) struct KittyApcScanner {
+    state: ApcScanState,
+    body: Vec<u8>,
+}
+
+impl KittyApcScanner {
+    /// Feeds one output byte; returns the command body once its terminator is seen.
+    pub(crate) fn advance(&mut self, b: u8) -> Option<Vec<u8>> {
+        use ApcScanState::*;
+
+        match (self.state, b) {
+            (Idle, 0x1b) => self.state = Esc,
+            (Idle, _) => {}
+            (Esc, b'_') => {
+                self.body.clear();
+                self.state = Body;
+            }
+            (Esc, 0x1b) => {}
+            (Esc, _) => self.state = Idle,
+            (Body, 0x1b) => self.state = BodyEsc,
+            (Body, 0x18 | 0x1a) => self.state = Idle,
+            (Body, _) => {
+                if self.body.is_empty() && b != b'G' {
+                    self.state = Skip;
+                } else if self.body.len() >= MAX_COMMAND_BYTES {
+                    self.body = Vec::new();
+                    self.state = Skip;
+                } else {
+                    self.body.push(b);
+                }
+            }
+            (BodyEsc, b'\\') => {
+                self.state = Idle;
+                let body = std::mem::take(&mut self.body);
+                return body.get(1..).map(<[u8]>::to_vec);
+            }
+            (BodyEsc, b'_') => {
+                self.body.clear();
+                self.state = Body;
+            }
+            (BodyEsc, _) => self.state = Idle,
+            (Skip, 0x1b) => self.state = SkipEsc,
+            (Skip, 0x18 | 0x1a) => self.state = Idle,
+            (Skip, _) => {}
+            (SkipEsc, b'_') => {
+                self.body.clear();
+                self.state = Body;
+            }
+            (SkipEsc, _) => self.state = Idle,
+        }
+
+        None
+    }
+}
+
+#[derive(Clone, Debug, Default)]
+struct Control {
+    action: u8,
+    format: u32,
+    medium: u8,
+    compression: u8,
+    image_id: u32,
+    image_number: u32,
+    placement_id: u32,
+    more: bool,
+    width: u32,
+    height: u32,
+    cols: u16,
+    rows: u16,
+    x_offset: u32,
+    y_offset: u32,
+    z_index: i32,
+    cursor_movement: u32,
+    quiet: u32,
+    delete: u8,
+    x: u32,
+    y: u32,
+}
+
+fn parse_control(bytes: &[u8]) -> Control {
+    let mut control = Control {
+        action: b't',
+        format: 32,
+        medium: b'd',
+        delete: b'a',
+        ..Control::default()
+    };
+
+    for pair in bytes.split(|b| *b == b',') {
+        let [key, b'=', value @ ..] = pair else {
+            continue;
+        };
+        let number = || {
+            std::str::from_utf8(value)
+                .ok()
+                .and_then(|v| v.parse::<i64>().ok())
+                .unwrap_or(0)
+        };
+        let unsigned = || number().clamp(0, u32::MAX as i64) as u32;
+        let first = value.first().copied().unwrap_or(0);
+
+        match key {
+            b'a' => control.action = first,
+            b'f' => control.format = unsigned(),
+            b't' => control.medium = first,
+            b'o' => control.compression = first,
+            b'i' => control.image_id = unsigned(),
+            b'I' => control.image_number = unsigned(),
+            b'p' => control.placement_id = unsigned(),
+            b'm' => control.more = unsigned() == 1,
+            b's' => control.width = unsigned(),
+            b'v' => control.height = unsigned(),
+            b'c' => control.cols = unsigned().min(u16::MAX as u32) as u16,
+            b'r' => control.rows = unsigned().min(u16::MAX as u32) as u16,
+            b'X' => control.x_offset = unsigned(),
+            b'Y' => control.y_offset = unsigned(),
+            b'z' => control.z_index = number().clamp(i32::MIN as i64, i32::MAX as i64) as i32,
+            b'C' => control.cursor_movement = unsigned(),
+            b'q' => control.quiet = unsigned(),
+            b'd' => control.delete = first,
+            b'x' => control.x = unsigned(),
+            b'y' => control.y = unsigned(),
+            _ => {}
+        }
+    }
+
+    control
+}
+
+#[derive(Debug)]
+struct PendingTransmission {
+    control: Control,
+    payload: Vec<u8>,
+}
+
+/// Image and placement store for one terminal session.
+#[derive(Debug, Default)]
+pub(crate) struct KittyGraphicsState {
+    images: HashMap<u32, Arc<KittyImage>>,
+    image_numbers: HashMap<u32, u32>,
+    placements: BTreeMap<PlacementKey, KittyPlacement>,
+    pending: Option<PendingTransmission>,
+    next_image_id: u32,
+    next_serial: u64,
+    stored_bytes: usize,
+}
+
+impl KittyGraphicsState {
+    pub(crate) fn image(&self, id: u32) -> Option<&Arc<KittyImage>> {
+        self.images.get(&id)
+    }
+
+    /// Placements that cover at least one row in `rows` (screen-absolute).
+    pub(crate) fn placements_in_rows(
+        &self,
+        rows: Range<u32>,
+    ) -> impl Iterator<Item = &KittyPlacement> + '_ {
+        let upper = PlacementKey {
+            row: rows.end,
+            col: 0,
+            serial: 0,
+        };
+        self.placements
+            .range(..upper)
+            .map(|(_, placement)| placement)
+            .filter(move |p| p.row.saturating_add(u32::from(p.rows)) > rows.start)
+    }
+
+    /// Drops placements that start in the first `rows` screen rows and moves the rest up, after
+    /// those rows were removed from the top of the screen.
+    pub(crate) fn remove_top_rows(&mut self, rows: u32) {
+        self.placements = std::mem::take(&mut self.placements)
+            .into_iter()
+            .filter(|(key, _)| key.row >= rows)
+            .map(|(mut key, mut placement)| {
+                key.row -= rows;
+                placement.row -= rows;
+                (key, placement)
+            })
+            .collect();
+    }
+
+    pub(crate) fn clear(&mut self) {
+        *self = Self {
+            next_image_id: self.next_image_id,
+            next_serial: self.next_serial,
+            ..Self::default()
+        };
+    }
+
+    pub(crate) fn handle_command(
+        &mut self,
+        body: &[u8],
+        ctx: KittyCommandContext,
+    ) -> KittyCommandOutcome {
+        let (control_bytes, payload) = match body.iter().position(|b| *b == b';') {
+            Some(idx) => (&body[..idx], &body[idx + 1..]),
+            None => (body, &[][..]),
+        };
+        let control = parse_control(control_bytes);
+
+        // Continuation chunks only carry `m` (and optionally `q`); everything else comes from the
+        // first chunk.
+        if let Some(mut pending) = self.pending.take() {
+            if pending.payload.len() + payload.len() > MAX_TRANSMISSION_BYTES {
+                return self.reply(&pending.control, Err("EFBIG:transmission too large"));
+            }
+            pending.payload.extend_from_slice(payload);
+            if control.more {
+                self.pending = Some(pending);
+                return KittyCommandOutcome::default();
+            }
+            return self.finish_transmission(pending.control, &pending.payload, ctx);
+        }
+
+        match control.action {
+            b't' | b'T' => {
+                if control.more {
+                    self.pending = Some(PendingTransmission {
+                        control,
+                        payload: payload.to_vec(),
+                    });
+                    return KittyCommandOutcome::default();
+                }
+                self.finish_transmission(control, payload, ctx)
+            }
+            b'p' => {
+                let Some(id) = self.resolve_image_id(&control) else {
+                    return self.reply(&control, Err("ENOENT:image not found"));
+                };
+                let mut outcome = self.place(id, &control, ctx);
+                outcome.response = self.reply(&control, Ok(())).response;
+                outcome
+            }
+            b'q' => {
+                let result = decode_image(&control, payload).map(|_| ());
+                self.reply(&control, result)
+            }
+            b'd' => {
+                self.delete(&control, ctx);
+                KittyCommandOutcome::default()
+            }
+            _ => self.reply(&control, Err("EINVAL:unsupported action")),
+        }
+    }
+
+    fn finish_transmission(
+        &mut self,
+        mut control: Control,
+        payload: &[u8],
+        ctx: KittyCommandContext,
+    ) -> KittyCommandOutcome {
+        let (width, height, rgba) = match decode_image(&control, payload) {
+            Ok(decoded) => decoded,
+            Err(err) => return self.reply(&control, Err(err)),
+        };
+
+        let id = if control.image_id != 0 {
+            control.image_id
+        } else {
+            self.allocate_image_id()
+        };
+        if control.image_number != 0 {
+            self.image_numbers.insert(control.image_number, id);
+            // Replies to `I=` transmissions carry the id the terminal picked.
+            control.image_id = id;
+        }
+
+        self.next_serial += 1;
+        let image = Arc::new(KittyImage {
+            id,
+            width,
+            height,
+            rgba,
+            serial: self.next_serial,
+        });
+        self.stored_bytes += image.rgba.len();
+        if let Some(old) = self.images.insert(id, image) {
+            self.stored_bytes -= old.rgba.len();
+        }
+        self.evict_oldest_images();
+
+        let mut outcome = if control.action == b'T' {
+            self.place(id, &control, ctx)
+        } else {
+            KittyCommandOutcome::default()
+        };
+        outcome.response = self.reply(&control, Ok(())).response;
+        outcome
+    }
+
+    fn place(
+        &mut self,
+        image_id: u32,
+        control: &Control,
+        ctx: KittyCommandContext,
+    ) -> KittyCommandOutcome {
+        let Some(image) = self.images.get(&image_id) else {
+            return KittyCommandOutcome::default();
+        };
+
+        let cell_w = ctx.cell_width_px.max(1);
+        let cell_h = ctx.cell_height_px.max(1);
+        let fit_to_cells = control.cols != 0 || control.rows != 0;
+        let cols = if control.cols != 0 {
+            control.cols
+        } else {
+            (image.width + control.x_offset)
+                .div_ceil(cell_w)
+                .min(u16::MAX as u32) as u16
+        };
+        let rows = if control.rows != 0 {
+            control.rows
+        } else {
+            (image.height + control.y_offset)
+                .div_ceil(cell_h)
+                .min(u16::MAX as u32) as u16
+        };
+
+        if control.placement_id != 0 {
+            self.placements
+                .retain(|_, p| !(p.image_id == image_id && p.placement_id == control.placement_id));
+        }
+
+        self.next_serial += 1;
+        let key = PlacementKey {
+            row: ctx.cursor_row,
+            col: ctx.cursor_col,
+            serial: self.next_serial,
+        };
+        self.placements.insert(
+            key,
+            KittyPlacement {
+                image_id,
+                placement_id: control.placement_id,
+                col: ctx.cursor_col,
+                row: ctx.cursor_row,
+                cols: cols.max(1),
+                rows: rows.max(1),
+                x_offset: control.x_offset,
+                y_offset: control.y_offset,
+                z_index: control.z_index,
+                fit_to_cells,
+            },
+        );
+
+        KittyCommandOutcome {
+            response: None,
+            cursor_advance: (control.cursor_movement != 1).then_some((cols.max(1), rows.max(1))),
+        }
+    }
+
+    fn delete(&mut self, control: &Control, ctx: KittyCommandContext) {
+        let free_images = control.delete.is_ascii_uppercase();
+        let cell_hit = |p: &KittyPlacement, col: u16, row: u32| {
+            col >= p.col
+                && u32::from(col) < u32::from(p.col) + u32::from(p.cols)
+                && row >= p.row
+                && u64::from(row) < u64::from(p.row) + u64::from(p.rows)
+        };
+        // `x`/`y` in delete commands are 1-based cell coordinates within the active area.
+        let col_arg = control.x.saturating_sub(1).min(u16::MAX as u32) as u16;
+        let row_arg = ctx
+            .active_top_row
+            .saturating_add(control.y.saturating_sub(1));
+
+        let target_image = match control.delete.to_ascii_lowercase() {
+            b'i' => Some(control.image_id),
+            b'n' => self.image_numbers.get(&control.image_number).copied(),
+            _ => None,
+        };
+
+        let mut touched: Vec<u32> = Vec::new();
+        self.placements.retain(|_, p| {
+            let remove = match control.delete.to_ascii_lowercase() {
+                b'a' => true,
+                b'i' | b'n' => {
+                    Some(p.image_id) == target_image
+                        && (control.placement_id == 0 || p.placement_id == control.placement_id)
+                }
+                b'c' => cell_hit(p, ctx.cursor_col, ctx.cursor_row),
+                b'p' => cell_hit(p, col_arg, row_arg),
+                b'x' => {
+                    col_arg >= p.col && u32::from(col_arg) < u32::from(p.col) + u32::from(p.cols)
+                }
+                b'y' => {
+                    row_arg >= p.row && u64::from(row_arg) < u64::from(p.row) + u64::from(p.rows)
+                }
+                b'z' => p.z_index == control.z_index,
+                _ => false,
+            };
+            if remove {
+                touched.push(p.image_id);
+            }
+            !remove
+        });
+
+        if !free_images {
+            return;
+        }
+        if let Some(id) = target_image {
+            touched.push(id);
+        }
+        for id in touched {
+            if self.placements.values().any(|p| p.image_id == id) {
+                continue;
+            }
+            if let Some(image) = self.images.remove(&id) {
+                self.stored_bytes -= image.rgba.len();
+            }
+            self.image_numbers.retain(|_, v| *v != id);
+        }
+    }
+
+    fn resolve_image_id(&self, control: &Control) -> Option<u32> {
+        let id = if control.image_id != 0 {
+            control.image_id
+        } else {
+            *self.image_numbers.get(&control.image_number)?
+        };
+        self.images.contains_key(&id).then_some(id)
+    }
+
+    fn allocate_image_id(&mut self) -> u32 {
+        // Terminal-chosen ids count down from the top so they don't collide with
+        // application-chosen ones, which tend to be small.
+        loop {
+            self.next_image_id = self.next_image_id.wrapping_add(1);
+            let id = u32::MAX - self.next_image_id;
+            if id != 0 && !self.images.contains_key(&id) {
+                return id;
+            }
+        }
+    }
+
+    fn evict_oldest_images(&mut self) {
+        while self.stored_bytes > MAX_STORED_IMAGE_BYTES && self.images.len() > 1 {
+            let Some(oldest) = self
+                .images
+                .values()
+                .min_by_key(|image| image.serial)
+                .map(|image| image.id)
+            else {
+                break;
+            };
+            if let Some(image) = self.images.remove(&oldest) {
+                self.stored_bytes -= image.rgba.len();
+            }
+            self.placements.retain(|_, p| p.image_id != oldest);
+            self.image_numbers.retain(|_, v| *v != oldest);
+        }
+    }
+
+    fn reply(&self, control: &Control, result: Result<(), &str>) -> KittyCommandOutcome {
+        let quiet = match result {
+            Ok(()) => control.quiet >= 1,
+            Err(_) => control.quiet >= 2,
+        };
+        // Commands without an id never get a reply.
+        if quiet || (control.image_id == 0 && control.image_number == 0) {
+            return KittyCommandOutcome::default();
+        }
+
+        let mut keys = Vec::new();
+        if control.image_id != 0 {
+            keys.push(format!("i={}", control.image_id));
+        }
+        if control.image_number != 0 {
+            keys.push(format!("I={}", control.image_number));
+        }
+        if control.placement_id != 0 {
+            keys.push(format!("p={}", control.placement_id));
+        }
+        let message = result.err().unwrap_or("OK");
+        let response = format!("\x1b_G{};{}\x1b\\", keys.join(","), message);
+
+        KittyCommandOutcome {
+            response: Some(response.into_bytes()),
+            cursor_advance: None,
+        }
+    }
+}
+
+fn decode_image(control: &Control, payload: &[u8]) -> Result<(u32, u32, Vec<u8>), &'static str> {
+    use base64::Engine as _;
+    use base64::engine::DecodePaddingMode;
+    use base64::engine::general_purpose::{GeneralPurpose, GeneralPurposeConfig};
+
+    const BASE64: GeneralPurpose = GeneralPurpose::new(
+        &base64::alphabet::STANDARD,
+        GeneralPurposeConfig::new().with_decode_padding_mode(DecodePaddingMode::Indifferent),
+    );
+
+    if control.medium != b'd' {
+        return Err("EINVAL:only direct transmission is supported");
+    }
+    if control.compression != 0 {
+        return Err("EINVAL:compressed data is not supported");
+    }
+
+    let data = BASE64
+        .decode(payload)
+        .map_err(|_| "EINVAL:invalid base64 payload")?;
+
+    match control.format {
+        100 => {
+            let image = image::load_from_memory_with_format(&data, image::ImageFormat::Png)
+                .map_err(|_| "EBADPNG:failed to decode PNG")?
+                .into_rgba8();
+            let (width, height) = image.dimensions();
+            Ok((width, height, image.into_raw()))
+        }
+        24 | 32 => {
+            let (width, height) = (control.width, control.height);
+            let channels = if control.format == 24 { 3 } else { 4 };
+            let expected = (width as usize)
+                .checked_mul(height as usize)
+                .and_then(|n| n.checked_mul(channels))
+                .ok_or("EINVAL:image too large")?;
+            if width == 0 || height == 0 || data.len() != expected {
+                return Err("ENODATA:pixel data does not match image size");
+            }
+            let rgba = if channels == 4 {
+                data
+            } else {
+                data.chunks_exact(3)
+                    .flat_map(|px| [px[0], px[1], px[2], 0xff])
+                    .collect()
+            };
+            Ok((width, height, rgba))
+        }
+        _ => Err("EINVAL:unsupported format"),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn ctx() -> KittyCommandContext {
+        KittyCommandContext {
+            cursor_col: 2,
+            cursor_row: 5,
+            active_top_row: 0,
+            cell_width_px: 10,
+            cell_height_px: 20,
+        }
+    }
+
+    fn scan(bytes: &[u8]) -> Vec<Vec<u8>> {
+        let mut scanner = KittyApcScanner::default();
+        bytes.iter().filter_map(|b| scanner.advance(*b)).collect()
+    }
+
+    #[test]
+    fn scanner_extracts_graphics_commands_only() {
+        let commands = scan(b"a\x1b_Ga=q,i=1;AAAA\x1b\\b\x1b_Xignored\x1b\\\x1b_Gi=2\x1b\\");
+        assert_eq!(commands, vec![b"a=q,i=1;AAAA".to_vec(), b"i=2".to_vec()]);
+    }
+
+    #[test]
+    fn scanner_survives_split_terminator() {
+        let mut scanner = KittyApcScanner::default();
+        let mut out = Vec::new();
+        for chunk in [&b"\x1b_Gi=1"[..], b"\x1b", b"\\"] {
+            out.extend(chunk.iter().filter_map(|b| scanner.advance(*b)));
+        }
+        assert_eq!(out, vec![b"i=1".to_vec()]);
+    }
+
+    #[test]
+    fn rgba_transmit_and_display_places_at_cursor() {
+        let mut state = KittyGraphicsState::default();
+        // 2x1 RGBA image: base64 of 8 bytes.
+        let outcome = state.handle_command(b"a=T,f=32,s=2,v=1,i=7;/wAA//8AAP8=", ctx());
+        assert_eq!(
+            outcome.response.as_deref(),
+            Some(&b"\x1b_Gi=7;OK\x1b\\"[..])
+        );
+        assert_eq!(outcome.cursor_advance, Some((1, 1)));
+
+        let image = state.image(7).unwrap();
+        assert_eq!((image.width, image.height), (2, 1));
+
+        let placements: Vec<_> = state.placements_in_rows(0..10).copied().collect();
+        assert_eq!(placements.len(), 1);
+        assert_eq!((placements[0].col, placements[0].row), (2, 5));
+        assert!(state.placements_in_rows(6..10).next().is_none());
+    }
+
+    #[test]
+    fn chunked_transmission_is_reassembled() {
+        let mut state = KittyGraphicsState::default();
+        let first = state.handle_command(b"a=t,f=24,s=1,v=1,i=3,m=1;AA", ctx());
+        assert_eq!(first, KittyCommandOutcome::default());
+        let last = state.handle_command(b"m=0;AA", ctx());
+        assert!(last.response.is_some());
+        assert_eq!(state.image(3).unwrap().rgba, vec![0, 0, 0, 0xff]);
+    }
+
+    #[test]
+    fn quiet_and_errors() {
+        let mut state = KittyGraphicsState::default();
+        let outcome = state.handle_command(b"a=p,i=99", ctx());
+        assert_eq!(
+            outcome.response.as_deref(),
+            Some(&b"\x1b_Gi=99;ENOENT:image not found\x1b\\"[..])
+        );
+
+        let outcome = state.handle_command(b"a=T,f=24,s=1,v=1,i=4,q=1;AAAA", ctx());
+        assert_eq!(outcome.response, None);
+    }
+
+    #[test]
+    fn delete_by_id_frees_image_when_uppercase() {
+        let mut state = KittyGraphicsState::default();
+        state.handle_command(b"a=T,f=24,s=1,v=1,i=5,q=2;AAAA", ctx());
+        state.handle_command(b"a=d,d=i,i=5", ctx());
+        assert!(state.placements_in_rows(0..100).next().is_none());
+        assert!(state.image(5).is_some());
+
+        state.handle_command(b"a=p,i=5,C=1,q=2", ctx());
+        state.handle_command(b"a=d,d=I,i=5", ctx());
+        assert!(state.image(5).is_none());
+    }
+}
@@ -1,21 +1,65 @@
 pub mod config;
 pub mod config_file;
+mod config_watch;
 mod font;
+mod kitty_graphics;
+mod panel;
+pub mod prelude;
+mod process;
+mod recorder;
 mod session;
+mod session_handle;
+mod shell_integration;
+mod spawn;
+pub mod testing;
 pub mod themes;
+#[cfg(feature = "unstable-remote")]
+mod transport;
+pub mod unstable;
 
 pub mod view;
 
-pub use config::{CursorColor, DEFAULT_PALETTE, TerminalConfig};
+pub use config::{
+    CursorColor, DEFAULT_PALETTE, DEFAULT_WORD_SEPARATORS, FocusClickPolicy, FunctionKeyPolicy,
+    LinkRule, ProcessExitPolicy, ResizeOverlayPolicy, RootIndicator, TerminalConfig,
+};
 pub use config_file::{
-    ConfigError, load_config, load_config_from_path, reload_theme_for_appearance,
-    save_theme_to_config,
-};
-pub use font::{default_terminal_font, default_terminal_font_features, terminal_font};
-pub use ghostty_vt::{CursorStyle, Rgb};
-pub use session::TerminalSession;
-pub use themes::{get_embedded_theme, list_embedded_themes};
-pub use view::{Copy, CopyLastOutput, Paste, SelectAll};
+    ConfigError, apply_theme, import_theme_file, load_config, load_config_from_path,
+    reload_theme_for_appearance, save_theme_to_config,
+};
+pub use config_watch::{watch_config, watch_config_path};
+pub use font::{
+    default_terminal_font, default_terminal_font_features, terminal_font, terminal_font_features,
+};
+pub use ghostty_vt::{
+    AbsolutePoint, Cell, CursorStyle, KeyModes, Rgb, SearchMatch, StyleRun, Underline,
+    ViewportPoint,
+};
+pub use panel::{
+    ClosePane, ClosePolicy, FocusNextPane, FocusPreviousPane, NewTab, NextTab, PendingClose,
+    PreviousTab, SplitAxis, SplitDown, SplitRight, TerminalPanel, TerminalPanelEvent,
+};
+pub use process::{has_running_child, process_cwd, process_name, process_runs_as_root};
+pub use recorder::RecordingOptions;
+pub use session::{
+    ClipboardAccess, ClipboardAccessPolicy, ClipboardEntry, MouseEncoding, SearchDirection,
+    TerminalEvent, TerminalSession, ViewportDiff, ViewportRowDiff,
+};
+pub use session_handle::{TerminalSessionHandle, TerminalSessionId, TerminalSessionRegistry};
+pub use shell_integration::PromptMark;
+pub use spawn::SpawnOptions;
+pub use themes::{
+    ThemeInfo, ThemeRegistry, ThemeSource, default_theme_dirs, get_embedded_theme,
+    import_itermcolors, import_windows_terminal_schemes, list_embedded_themes,
+};
+pub use view::{
+    AddSelectionToNotes, ClearScrollback, Copy, CopyLastOutput, JumpToNextPrompt,
+    JumpToPreviousPrompt, LockInput, PaneSearchMatch, Paste, PasteConfirmCallback, PastePolicy,
+    PasteWarning, Search, SearchNext, SearchPrevious, SelectAll, SelectLastCommandOutput,
+    SelectWord, ShowClipboardHistory, SmartLinkHandler, SmartLinkMatch, TerminalEventCallback,
+    TerminalInput, TerminalOverlay, TerminalResizeCallback, TerminalView, ToggleNotes, UrlOpener,
+    ZoomIn, ZoomOut, ZoomReset, search_all_panes,
+};
 
 /// Fish shell integration script that emits OSC 133 markers.
 ///
@@ -29,18 +73,27 @@ pub const GHOSTTY_FISH_INTEGRATION_SCRIPT: &str = include_str!(
 
 use gpui::{WindowBackgroundAppearance, WindowOptions};
 
-/// Build `WindowOptions` with the appropriate background appearance for the given config.
+/// Window background appearance for the given config.
 ///
-/// When `background_opacity < 1.0`, uses `WindowBackgroundAppearance::Blurred` to enable
-/// a frosted-glass effect on macOS. Otherwise uses `Opaque`.
-pub fn window_options_for_config(config: &TerminalConfig) -> WindowOptions {
-    let background = if config.background_opacity < 1.0 {
+/// When `background_opacity < 1.0`, returns `Blurred` for a frosted-glass effect on macOS, or
+/// `Transparent` when `background_blur` is off. Otherwise returns `Opaque`. Hosts that change
+/// the opacity at runtime can pass this to `Window::set_background_appearance`.
+pub fn window_background_appearance(config: &TerminalConfig) -> WindowBackgroundAppearance {
+    if config.background_opacity >= 1.0 {
+        WindowBackgroundAppearance::Opaque
+    } else if config.background_blur {
         WindowBackgroundAppearance::Blurred
     } else {
-        WindowBackgroundAppearance::Opaque
-    };
+        WindowBackgroundAppearance::Transparent
+    }
+}
+
+/// Build `WindowOptions` with the appropriate background appearance for the given config.
+///
+/// See [`window_background_appearance`].
+pub fn window_options_for_config(config: &TerminalConfig) -> WindowOptions {
     WindowOptions {
-        window_background: background,
+        window_background: window_background_appearance(config),
         ..Default::default()
     }
 }
@@ -0,0 +1,839 @@
+//! `TerminalPanel`: tabs of recursively split terminal panes.
+
+use std::collections::HashMap;
+use std::rc::Rc;
+use std::sync::Once;
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+use gpui::{
+    AnyElement, App, Bounds, Context, DragMoveEvent, Entity, EntityId, EventEmitter, FocusHandle,
+    Focusable, KeyBinding, Pixels, Point, PromptLevel, Subscription, WeakEntity, Window, actions,
+    div, prelude::*, px, relative, rgba,
+};
+
+use crate::view::TerminalView;
+
+actions!(
+    terminal_panel,
+    [
+        ClosePane,
+        FocusNextPane,
+        FocusPreviousPane,
+        NewTab,
+        NextTab,
+        PreviousTab,
+        SplitDown,
+        SplitRight
+    ]
+);
+
+const KEY_CONTEXT: &str = "TerminalPanel";
+const DEFAULT_PANE_TITLE: &str = "Terminal";
+const DIVIDER_WIDTH: f32 = 1.0;
+/// Smallest width or height a divider drag leaves a pane.
+const MIN_PANE_EXTENT: f32 = 48.0;
+
+static KEY_BINDINGS: Once = Once::new();
+
+fn ensure_key_bindings(cx: &mut App) {
+    KEY_BINDINGS.call_once(|| {
+        cx.bind_keys([
+            KeyBinding::new("cmd-d", SplitRight, Some(KEY_CONTEXT)),
+            KeyBinding::new("cmd-shift-d", SplitDown, Some(KEY_CONTEXT)),
+            KeyBinding::new("cmd-t", NewTab, Some(KEY_CONTEXT)),
+            KeyBinding::new("cmd-w", ClosePane, Some(KEY_CONTEXT)),
+            KeyBinding::new("ctrl-tab", FocusNextPane, Some(KEY_CONTEXT)),
+            KeyBinding::new("ctrl-shift-tab", FocusPreviousPane, Some(KEY_CONTEXT)),
+            KeyBinding::new("cmd-shift-]", NextTab, Some(KEY_CONTEXT)),
+            KeyBinding::new("cmd-shift-[", PreviousTab, Some(KEY_CONTEXT)),
+        ]);
+    });
+}
+
+type NewPaneFn = dyn Fn(&mut Window, &mut App) -> Entity<TerminalView>;
+
+/// Direction panes are laid out in a split.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum SplitAxis {
+    /// Side by side.
+    Horizontal,
+    /// Stacked.
+    Vertical,
+}
+
+/// Events emitted by a [`TerminalPanel`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum TerminalPanelEvent {
+    /// The last pane was closed; hosts typically close the window.
+    Empty,
+}
+
+type BusyPaneFn = dyn Fn(&Entity<TerminalView>, &App) -> Option<String>;
+type ConfirmCloseFn = dyn Fn(PendingClose, &mut Window, &mut App);
+
+/// Whether `cmd-w` closes a pane right away or asks first while a program runs in it.
+#[derive(Clone, Default)]
+pub enum ClosePolicy {
+    /// Close without asking.
+    #[default]
+    Immediate,
+    /// `busy` names the program running in a pane, or returns `None` when the pane is idle
+    /// (hosts typically check [`has_running_child`](crate::has_running_child) on the pane's
+    /// shell and [`process_name`](crate::process_name) on the PTY's foreground process). Busy
+    /// panes are handed to `confirm` instead of closing.
+    Confirm {
+        busy: Rc<BusyPaneFn>,
+        confirm: Rc<ConfirmCloseFn>,
+    },
+}
+
+impl ClosePolicy {
+    pub fn confirm(
+        busy: impl Fn(&Entity<TerminalView>, &App) -> Option<String> + 'static,
+        confirm: impl Fn(PendingClose, &mut Window, &mut App) + 'static,
+    ) -> Self {
+        Self::Confirm {
+            busy: Rc::new(busy),
+            confirm: Rc::new(confirm),
+        }
+    }
+
+    /// [`Self::confirm`] with the platform's modal prompt.
+    pub fn prompt(busy: impl Fn(&Entity<TerminalView>, &App) -> Option<String> + 'static) -> Self {
+        Self::confirm(busy, |pending, window, cx| {
+            let message = format!("Close the pane running {}?", pending.process_name());
+            let answer = window.prompt(
+                PromptLevel::Warning,
+                &message,
+                Some("The process will be terminated."),
+                &["Close", "Cancel"],
+                cx,
+            );
+            cx.spawn(async move |cx| {
+                if answer.await == Ok(0) {
+                    cx.update(|cx| pending.confirm(cx)).ok();
+                }
+            })
+            .detach();
+        })
+    }
+}
+
+/// A pane close waiting on the user, handed to a [`ClosePolicy::Confirm`] callback. Dropping it
+/// keeps the pane open.
+pub struct PendingClose {
+    panel: WeakEntity<TerminalPanel>,
+    pane: EntityId,
+    process_name: String,
+}
+
+impl PendingClose {
+    /// The pane's view.
+    pub fn pane(&self) -> EntityId {
+        self.pane
+    }
+
+    /// What the pane is running, as `busy` reported it.
+    pub fn process_name(&self) -> &str {
+        &self.process_name
+    }
+
+    /// Close the pane after all.
+    pub fn confirm(self, cx: &mut App) {
+        self.panel
+            .update(cx, |panel, cx| panel.close_pane(self.pane, cx))
+            .ok();
+    }
+}
+
+/// A split: a single pane, or several laid out along an axis.
+enum PaneNode {
+    Pane(Entity<TerminalView>),
+    Split {
+        /// Identifies the split while its divider is dragged.
+        id: usize,
+        axis: SplitAxis,
+        children: Vec<PaneNode>,
+        /// Share of the split's extent per child, summing to 1.
+        sizes: Vec<f32>,
+    },
+}
+
+fn next_split_id() -> usize {
+    static NEXT_SPLIT_ID: AtomicUsize = AtomicUsize::new(0);
+    NEXT_SPLIT_ID.fetch_add(1, Ordering::Relaxed)
+}
+
+impl PaneNode {
+    fn panes(&self, out: &mut Vec<Entity<TerminalView>>) {
+        match self {
+            PaneNode::Pane(view) => out.push(view.clone()),
+            PaneNode::Split { children, .. } => {
+                for child in children {
+                    child.panes(out);
+                }
+            }
+        }
+    }
+
+    /// Put `new` next to `target` along `axis`. Returns `false` if `target` is not in the tree.
+    fn split(&mut self, target: EntityId, axis: SplitAxis, new: Entity<TerminalView>) -> bool {
+        match self {
+            PaneNode::Pane(view) if view.entity_id() == target => {
+                let existing = PaneNode::Pane(view.clone());
+                *self = PaneNode::Split {
+                    id: next_split_id(),
+                    axis,
+                    children: vec![existing, PaneNode::Pane(new)],
+                    sizes: vec![0.5, 0.5],
+                };
+                true
+            }
+            PaneNode::Pane(_) => false,
+            PaneNode::Split {
+                axis: split_axis,
+                children,
+                sizes,
+                ..
+            } => {
+                // Splitting along the same axis adds a sibling that takes half of the pane's
+                // space, instead of nesting a split.
+                if *split_axis == axis
+                    && let Some(ix) = children.iter().position(
+                        |child| matches!(child, PaneNode::Pane(view) if view.entity_id() == target),
+                    )
+                {
+                    children.insert(ix + 1, PaneNode::Pane(new));
+                    sizes[ix] /= 2.0;
+                    sizes.insert(ix + 1, sizes[ix]);
+                    return true;
+                }
+                children
+                    .iter_mut()
+                    .any(|child| child.split(target, axis, new.clone()))
+            }
+        }
+    }
+
+    /// Remove the pane `target`, collapsing splits left with one child. Returns `None` when the
+    /// whole node goes away.
+    fn remove(self, target: EntityId) -> Option<PaneNode> {
+        match self {
+            PaneNode::Pane(view) if view.entity_id() == target => None,
+            PaneNode::Pane(view) => Some(PaneNode::Pane(view)),
+            PaneNode::Split {
+                id,
+                axis,
+                children,
+                sizes,
+            } => {
+                let (mut children, mut sizes): (Vec<PaneNode>, Vec<f32>) = children
+                    .into_iter()
+                    .zip(sizes)
+                    .filter_map(|(child, size)| Some((child.remove(target)?, size)))
+                    .unzip();
+                match children.len() {
+                    0 => None,
+                    1 => children.pop(),
+                    _ => {
+                        // The remaining panes share the freed space in proportion.
+                        let total: f32 = sizes.iter().sum();
+                        sizes.iter_mut().for_each(|size| *size /= total);
+                        Some(PaneNode::Split {
+                            id,
+                            axis,
+                            children,
+                            sizes,
+                        })
+                    }
+                }
+            }
+        }
+    }
+
+    /// The sizes and axis of split `id`.
+    fn split_sizes_mut(&mut self, id: usize) -> Option<(&mut Vec<f32>, SplitAxis)> {
+        match self {
+            PaneNode::Pane(_) => None,
+            PaneNode::Split {
+                id: split_id,
+                axis,
+                children,
+                sizes,
+            } => {
+                if *split_id == id {
+                    return Some((sizes, *axis));
+                }
+                children
+                    .iter_mut()
+                    .find_map(|child| child.split_sizes_mut(id))
+            }
+        }
+    }
+}
+
+/// Move the divider before child `index` of a split with `sizes` to `at` (a fraction of the
+/// split's extent), keeping both neighbors at least `min` and the other children unchanged.
+pub(crate) fn drag_divider(sizes: &mut [f32], index: usize, at: f32, min: f32) {
+    if index == 0 || index >= sizes.len() {
+        return;
+    }
+    let before: f32 = sizes[..index - 1].iter().sum();
+    let pair = sizes[index - 1] + sizes[index];
+    let min = min.min(pair / 2.0);
+    let first = (at - before).clamp(min, pair - min);
+    sizes[index - 1] = first;
+    sizes[index] = pair - first;
+}
+
+/// Drag payload of a split divider: the split and the index of the child after the divider.
+#[derive(Clone, Copy)]
+struct DraggedDivider {
+    split: usize,
+    index: usize,
+}
+
+impl Render for DraggedDivider {
+    fn render(&mut self, _: &mut Window, _: &mut Context<Self>) -> impl IntoElement {
+        // Dividers move in place; nothing follows the mouse.
+        div()
+    }
+}
+
+struct PanelTab {
+    root: PaneNode,
+    active: EntityId,
+}
+
+impl PanelTab {
+    fn panes(&self) -> Vec<Entity<TerminalView>> {
+        let mut panes = Vec::new();
+        self.root.panes(&mut panes);
+        panes
+    }
+
+    fn active_pane(&self) -> Option<Entity<TerminalView>> {
+        let panes = self.panes();
+        panes
+            .iter()
+            .find(|view| view.entity_id() == self.active)
+            .or(panes.first())
+            .cloned()
+    }
+}
+
+/// Tabs of terminal panes split horizontally and vertically to any depth, with the plumbing
+/// hosts would otherwise write themselves:
+///
+/// - `cmd-d` / `cmd-shift-d` split the focused pane right / down, `cmd-t` opens a tab and `cmd-w`
+///   closes the focused pane (and its tab once empty), asking first per the [`ClosePolicy`]
+/// - `ctrl-tab` / `ctrl-shift-tab` cycle focus through the panes of the tab,
+///   `cmd-shift-]` / `cmd-shift-[` switch tabs
+/// - a pane closes itself once its view asks to, after its process exited (see
+///   [`TerminalView::process_exited`] and [`Self::set_close_on_exit`])
+/// - dragging the divider between panes resizes them (each pane's grid and PTY follow)
+/// - each pane of a split tab shows its title (OSC 0/2) above it, the tab bar shows the focused
+///   pane's title, and the window title follows the focused pane
+///
+/// Panes come from the `new_pane` callback, which wires each [`TerminalView`] to its PTY or
+/// other transport. When the last pane closes the panel emits [`TerminalPanelEvent::Empty`].
+pub struct TerminalPanel {
+    new_pane: Rc<NewPaneFn>,
+    tabs: Vec<PanelTab>,
+    active_tab: usize,
+    close_on_exit: bool,
+    close_policy: ClosePolicy,
+    focus_handle: FocusHandle,
+    /// Focus the active pane on the next render (after a close, where no window is at hand).
+    pending_focus: bool,
+    subscriptions: HashMap<EntityId, Subscription>,
+}
+
+impl EventEmitter<TerminalPanelEvent> for TerminalPanel {}
+
+impl Focusable for TerminalPanel {
+    fn focus_handle(&self, _: &App) -> FocusHandle {
+        self.focus_handle.clone()
+    }
+}
+
+impl TerminalPanel {
+    /// A panel with one tab holding one pane from `new_pane`.
+    pub fn new(
+        new_pane: impl Fn(&mut Window, &mut App) -> Entity<TerminalView> + 'static,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) -> Self {
+        ensure_key_bindings(cx);
+        let mut panel = Self {
+            new_pane: Rc::new(new_pane),
+            tabs: Vec::new(),
+            active_tab: 0,
+            close_on_exit: true,
+            close_policy: ClosePolicy::default(),
+            focus_handle: cx.focus_handle(),
+            pending_focus: true,
+            subscriptions: HashMap::new(),
+        };
+        panel.new_tab(window, cx);
+        panel
+    }
+
+    /// Close panes whose view asks to be closed ([`TerminalView::is_close_requested`]), e.g. on
+    /// Enter over the "process exited" overlay. On by default; turn it off to close panes only
+    /// with `cmd-w`.
+    pub fn set_close_on_exit(&mut self, close_on_exit: bool) {
+        self.close_on_exit = close_on_exit;
+    }
+
+    /// Whether `cmd-w` asks before closing a pane with a program running.
+    /// [`ClosePolicy::Immediate`] by default.
+    pub fn set_close_policy(&mut self, policy: ClosePolicy) {
+        self.close_policy = policy;
+    }
+
+    pub fn tab_count(&self) -> usize {
+        self.tabs.len()
+    }
+
+    pub fn active_tab(&self) -> usize {
+        self.active_tab
+    }
+
+    /// Panes of the active tab, in layout order.
+    pub fn panes(&self) -> Vec<Entity<TerminalView>> {
+        self.tabs
+            .get(self.active_tab)
+            .map(PanelTab::panes)
+            .unwrap_or_default()
+    }
+
+    /// The focused pane of the active tab.
+    pub fn active_pane(&self) -> Option<Entity<TerminalView>> {
+        self.tabs.get(self.active_tab)?.active_pane()
+    }
+
+    /// Panes of every tab, e.g. for [`search_all_panes`](crate::search_all_panes) or
+    /// [`watch_config`](crate::watch_config).
+    pub fn all_panes(&self) -> Vec<Entity<TerminalView>> {
+        self.tabs.iter().flat_map(PanelTab::panes).collect()
+    }
+
+    /// Open a tab with a fresh pane and focus it.
+    pub fn new_tab(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        let view = self.create_pane(window, cx);
+        self.tabs.push(PanelTab {
+            active: view.entity_id(),
+            root: PaneNode::Pane(view),
+        });
+        self.active_tab = self.tabs.len() - 1;
+        self.focus_active(window, cx);
+    }
+
+    /// Split the focused pane, putting a fresh pane to its right (`Horizontal`) or below it
+    /// (`Vertical`), and focus the new pane.
+    pub fn split(&mut self, axis: SplitAxis, window: &mut Window, cx: &mut Context<Self>) {
+        let Some(active) = self.active_pane() else {
+            self.new_tab(window, cx);
+            return;
+        };
+        let view = self.create_pane(window, cx);
+        let id = view.entity_id();
+        let tab = &mut self.tabs[self.active_tab];
+        if tab.root.split(active.entity_id(), axis, view) {
+            tab.active = id;
+        }
+        self.focus_active(window, cx);
+    }
+
+    /// Close `pane` wherever it is, dropping its tab once empty.
+    pub fn close_pane(&mut self, pane: EntityId, cx: &mut Context<Self>) {
+        let Some(tab_ix) = self
+            .tabs
+            .iter()
+            .position(|tab| tab.panes().iter().any(|view| view.entity_id() == pane))
+        else {
+            return;
+        };
+        self.subscriptions.remove(&pane);
+
+        let tab = &mut self.tabs[tab_ix];
+        let panes = tab.panes();
+        let closed_ix = panes.iter().position(|view| view.entity_id() == pane);
+        let placeholder = PaneNode::Split {
+            id: 0,
+            axis: SplitAxis::Horizontal,
+            children: Vec::new(),
+            sizes: Vec::new(),
+        };
+        match std::mem::replace(&mut tab.root, placeholder).remove(pane) {
+            Some(root) => {
+                tab.root = root;
+                if tab.active == pane {
+                    // Focus the pane before the closed one, like closing a tab.
+                    let remaining = tab.panes();
+                    let ix = closed_ix.unwrap_or(0).saturating_sub(1);
+                    if let Some(view) = remaining.get(ix.min(remaining.len() - 1)) {
+                        tab.active = view.entity_id();
+                    }
+                }
+            }
+            None => {
+                self.tabs.remove(tab_ix);
+                if self.active_tab > tab_ix || self.active_tab >= self.tabs.len() {
+                    self.active_tab = self.active_tab.saturating_sub(1);
+                }
+            }
+        }
+
+        if self.tabs.is_empty() {
+            cx.emit(TerminalPanelEvent::Empty);
+        }
+        self.pending_focus = true;
+        cx.notify();
+    }
+
+    /// Close `pane` as `cmd-w` does: right away, or through the [`ClosePolicy`]'s confirmation
+    /// while a program runs in it.
+    pub fn request_close_pane(
+        &mut self,
+        pane: EntityId,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let ClosePolicy::Confirm { busy, confirm } = self.close_policy.clone() else {
+            self.close_pane(pane, cx);
+            return;
+        };
+        let Some(view) = self
+            .all_panes()
+            .into_iter()
+            .find(|view| view.entity_id() == pane)
+        else {
+            return;
+        };
+        let Some(process_name) = busy(&view, cx) else {
+            self.close_pane(pane, cx);
+            return;
+        };
+        let pending = PendingClose {
+            panel: cx.weak_entity(),
+            pane,
+            process_name,
+        };
+        cx.defer_in(window, move |_, window, cx| confirm(pending, window, cx));
+    }
+
+    /// Move focus `delta` panes along the active tab, wrapping around.
+    pub fn cycle_focus(&mut self, delta: isize, window: &mut Window, cx: &mut Context<Self>) {
+        let Some(tab) = self.tabs.get_mut(self.active_tab) else {
+            return;
+        };
+        let panes = tab.panes();
+        let Some(current) = panes.iter().position(|view| view.entity_id() == tab.active) else {
+            return;
+        };
+        let next = (current as isize + delta).rem_euclid(panes.len() as isize) as usize;
+        tab.active = panes[next].entity_id();
+        self.focus_active(window, cx);
+    }
+
+    /// Switch to tab `ix` and focus its active pane.
+    pub fn activate_tab(&mut self, ix: usize, window: &mut Window, cx: &mut Context<Self>) {
+        if ix < self.tabs.len() {
+            self.active_tab = ix;
+            self.focus_active(window, cx);
+        }
+    }
+
+    fn create_pane(&mut self, window: &mut Window, cx: &mut Context<Self>) -> Entity<TerminalView> {
+        let view = (self.new_pane)(window, cx);
+        // The panel sets the window title from the focused pane instead.
+        view.update(cx, |view, _| {
+            view.session_mut().config_mut().update_window_title = false;
+        });
+        let subscription = cx.observe(&view, |panel, view, cx| {
+            if panel.close_on_exit && view.read(cx).is_close_requested() {
+                panel.close_pane(view.entity_id(), cx);
+            } else {
+                // Titles may have changed.
+                cx.notify();
+            }
+        });
+        self.subscriptions.insert(view.entity_id(), subscription);
+        view
+    }
+
+    fn focus_active(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        if let Some(view) = self.active_pane() {
+            let focus_handle = view.read(cx).focus_handle().clone();
+            focus_handle.focus(window, cx);
+        }
+        self.pending_focus = false;
+        cx.notify();
+    }
+
+    /// Make the pane holding focus the active one, e.g. after a click.
+    fn sync_active_with_focus(&mut self, window: &Window, cx: &App) {
+        let Some(tab) = self.tabs.get_mut(self.active_tab) else {
+            return;
+        };
+        if let Some(view) = tab
+            .panes()
+            .into_iter()
+            .find(|view| view.read(cx).focus_handle().is_focused(window))
+        {
+            tab.active = view.entity_id();
+        }
+    }
+
+    fn on_split_right(&mut self, _: &SplitRight, window: &mut Window, cx: &mut Context<Self>) {
+        self.split(SplitAxis::Horizontal, window, cx);
+    }
+
+    fn on_split_down(&mut self, _: &SplitDown, window: &mut Window, cx: &mut Context<Self>) {
+        self.split(SplitAxis::Vertical, window, cx);
+    }
+
+    fn on_new_tab(&mut self, _: &NewTab, window: &mut Window, cx: &mut Context<Self>) {
+        self.new_tab(window, cx);
+    }
+
+    fn on_close_pane(&mut self, _: &ClosePane, window: &mut Window, cx: &mut Context<Self>) {
+        if let Some(view) = self.active_pane() {
+            self.request_close_pane(view.entity_id(), window, cx);
+        }
+    }
+
+    fn on_focus_next_pane(
+        &mut self,
+        _: &FocusNextPane,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        self.cycle_focus(1, window, cx);
+    }
+
+    fn on_focus_previous_pane(
+        &mut self,
+        _: &FocusPreviousPane,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        self.cycle_focus(-1, window, cx);
+    }
+
+    fn on_next_tab(&mut self, _: &NextTab, window: &mut Window, cx: &mut Context<Self>) {
+        if !self.tabs.is_empty() {
+            self.activate_tab((self.active_tab + 1) % self.tabs.len(), window, cx);
+        }
+    }
+
+    fn on_previous_tab(&mut self, _: &PreviousTab, window: &mut Window, cx: &mut Context<Self>) {
+        if !self.tabs.is_empty() {
+            let len = self.tabs.len();
+            self.activate_tab((self.active_tab + len - 1) % len, window, cx);
+        }
+    }
+
+    /// Follow a dragged divider; each pane refits its grid (and resizes its PTY) on the next
+    /// layout.
+    fn drag_divider(
+        &mut self,
+        drag: DraggedDivider,
+        position: Point<Pixels>,
+        bounds: Bounds<Pixels>,
+        cx: &mut Context<Self>,
+    ) {
+        let Some(tab) = self.tabs.get_mut(self.active_tab) else {
+            return;
+        };
+        let Some((sizes, axis)) = tab.root.split_sizes_mut(drag.split) else {
+            return;
+        };
+        let (offset, extent) = match axis {
+            SplitAxis::Horizontal => (position.x - bounds.origin.x, bounds.size.width),
+            SplitAxis::Vertical => (position.y - bounds.origin.y, bounds.size.height),
+        };
+        if extent <= px(0.) {
+            return;
+        }
+        drag_divider(
+            sizes,
+            drag.index,
+            offset / extent,
+            px(MIN_PANE_EXTENT) / extent,
+        );
+        cx.notify();
+    }
+
+    fn render_node(&self, node: &PaneNode, show_titles: bool, cx: &Context<Self>) -> AnyElement {
+        match node {
+            PaneNode::Pane(view) => {
+                let title = show_titles.then(|| {
+                    div()
+                        .flex_none()
+                        .px(px(8.))
+                        .py(px(2.))
+                        .bg(rgba(0x252526ff))
+                        .text_color(rgba(0xb0b0b0ff))
+                        .text_size(px(12.))
+                        .overflow_hidden()
+                        .child(pane_title(view, cx))
+                });
+                div()
+                    .flex()
+                    .flex_col()
+                    .flex_1()
+                    .size_full()
+                    .overflow_hidden()
+                    .children(title)
+                    .child(div().flex_1().size_full().child(view.clone()))
+                    .into_any_element()
+            }
+            PaneNode::Split {
+                id,
+                axis,
+                children,
+                sizes,
+            } => {
+                let split_id = *id;
+                let mut split = div()
+                    .id(("pane-split", split_id))
+                    .flex()
+                    .flex_1()
+                    .size_full()
+                    .on_drag_move(cx.listener(
+                        move |panel, event: &DragMoveEvent<DraggedDivider>, _, cx| {
+                            let drag = *event.drag(cx);
+                            if drag.split == split_id {
+                                panel.drag_divider(drag, event.event.position, event.bounds, cx);
+                            }
+                        },
+                    ));
+                split = match axis {
+                    SplitAxis::Horizontal => split.flex_row(),
+                    SplitAxis::Vertical => split.flex_col(),
+                };
+                for (ix, (child, size)) in children.iter().zip(sizes).enumerate() {
+                    if ix > 0 {
+                        let divider = match axis {
+                            SplitAxis::Horizontal => div().w(px(DIVIDER_WIDTH)).h_full(),
+                            SplitAxis::Vertical => div().h(px(DIVIDER_WIDTH)).w_full(),
+                        };
+                        let divider = divider
+                            .id(ix)
+                            .flex_none()
+                            .bg(rgba(0x404040ff))
+                            .hover(|divider| divider.bg(rgba(0x5a5a5aff)))
+                            .on_drag(
+                                DraggedDivider {
+                                    split: split_id,
+                                    index: ix,
+                                },
+                                |divider, _, _, cx| cx.new(|_| *divider),
+                            );
+                        split = split.child(match axis {
+                            SplitAxis::Horizontal => divider.cursor_col_resize(),
+                            SplitAxis::Vertical => divider.cursor_row_resize(),
+                        });
+                    }
+                    split = split.child(
+                        div()
+                            .flex()
+                            .flex_basis(relative(*size))
+                            .flex_shrink()
+                            .min_w(px(0.))
+                            .min_h(px(0.))
+                            .overflow_hidden()
+                            .child(self.render_node(child, show_titles, cx)),
+                    );
+                }
+                split.into_any_element()
+            }
+        }
+    }
+}
+
+/// The title a pane's application set, or a placeholder.
+fn pane_title(view: &Entity<TerminalView>, cx: &App) -> String {
+    view.read(cx)
+        .session()
+        .title()
+        .unwrap_or(DEFAULT_PANE_TITLE)
+        .to_string()
+}
+
+impl Render for TerminalPanel {
+    fn render(&mut self, window: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        if self.pending_focus {
+            self.focus_active(window, cx);
+        } else {
+            self.sync_active_with_focus(window, cx);
+        }
+
+        let active_pane = self.active_pane();
+        if let Some(view) = &active_pane {
+            window.set_window_title(&pane_title(view, cx));
+        }
+
+        let tab_bar = (self.tabs.len() > 1).then(|| {
+            div()
+                .flex()
+                .flex_row()
+                .flex_none()
+                .w_full()
+                .bg(rgba(0x1e1e1eff))
+                .border_b_1()
+                .border_color(rgba(0x404040ff))
+                .children(self.tabs.iter().enumerate().map(|(ix, tab)| {
+                    let title = tab
+                        .active_pane()
+                        .map(|view| pane_title(&view, cx))
+                        .unwrap_or_default();
+                    let active = ix == self.active_tab;
+                    div()
+                        .id(ix)
+                        .flex_1()
+                        .px(px(12.))
+                        .py(px(4.))
+                        .overflow_hidden()
+                        .cursor_pointer()
+                        .text_size(px(12.))
+                        .text_color(if active {
+                            rgba(0xe0e0e0ff)
+                        } else {
+                            rgba(0x909090ff)
+                        })
+                        .when(active, |tab| tab.bg(rgba(0x2d2d2dff)))
+                        .hover(|tab| tab.bg(rgba(0x2a2d2eff)))
+                        .child(title)
+                        .on_click(cx.listener(move |panel, _, window, cx| {
+                            panel.activate_tab(ix, window, cx)
+                        }))
+                }))
+        });
+
+        let content = self.tabs.get(self.active_tab).map(|tab| {
+            let show_titles = matches!(tab.root, PaneNode::Split { .. });
+            self.render_node(&tab.root, show_titles, cx)
+        });
+
+        div()
+            .size_full()
+            .flex()
+            .flex_col()
+            .track_focus(&self.focus_handle)
+            .key_context(KEY_CONTEXT)
+            .on_action(cx.listener(Self::on_split_right))
+            .on_action(cx.listener(Self::on_split_down))
+            .on_action(cx.listener(Self::on_new_tab))
+            .on_action(cx.listener(Self::on_close_pane))
+            .on_action(cx.listener(Self::on_focus_next_pane))
+            .on_action(cx.listener(Self::on_focus_previous_pane))
+            .on_action(cx.listener(Self::on_next_tab))
+            .on_action(cx.listener(Self::on_previous_tab))
+            .children(tab_bar)
+            .child(div().flex().flex_1().size_full().children(content))
+    }
+}
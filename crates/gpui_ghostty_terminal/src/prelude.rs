@@ -0,0 +1,33 @@
+//! Items most embedders need, for a single glob import:
+//!
+//! ```ignore
+//! use gpui_ghostty_terminal::prelude::*;
+//! ```
+//!
+//! Actions are namespaced under [`actions`] (e.g. `actions::Copy`) so the glob does not shadow
+//! std names like the `Copy` marker trait.
+
+pub use crate::{
+    AbsolutePoint, Cell, ClipboardAccess, ClipboardAccessPolicy, ClipboardEntry, CursorColor,
+    CursorStyle, FocusClickPolicy, FunctionKeyPolicy, LinkRule, MouseEncoding, PaneSearchMatch,
+    PasteConfirmCallback, PastePolicy, PasteWarning, ProcessExitPolicy, PromptMark,
+    RecordingOptions, ResizeOverlayPolicy, Rgb, RootIndicator, SearchDirection, SearchMatch,
+    SmartLinkHandler, SmartLinkMatch, SpawnOptions, TerminalConfig, TerminalEvent,
+    TerminalEventCallback, TerminalInput, TerminalOverlay, TerminalPanel, TerminalResizeCallback,
+    TerminalSession, TerminalSessionHandle, TerminalSessionRegistry, TerminalView, ThemeRegistry,
+    UrlOpener, ViewportPoint, default_terminal_font, default_terminal_font_features,
+    get_embedded_theme, has_running_child, list_embedded_themes, load_config, process_cwd,
+    process_name, process_runs_as_root, reload_theme_for_appearance, search_all_panes,
+    terminal_font, terminal_font_features, watch_config, window_background_appearance,
+    window_options_for_config,
+};
+
+/// The view's actions, for key bindings and `on_action` handlers.
+pub mod actions {
+    pub use crate::{
+        AddSelectionToNotes, ClearScrollback, Copy, CopyLastOutput, JumpToNextPrompt,
+        JumpToPreviousPrompt, LockInput, Paste, Search, SearchNext, SearchPrevious, SelectAll,
+        SelectLastCommandOutput, SelectWord, ShowClipboardHistory, ToggleNotes, ZoomIn, ZoomOut,
+        ZoomReset,
+    };
+}
@@ -0,0 +1,221 @@
+//! Inspection of the processes running behind a PTY.
+//!
+//! Hosts pass the PTY's foreground process group leader (e.g.
+//! `MasterPty::process_group_leader`) or the pid of the spawned shell. Linux reads procfs; other
+//! Unix platforms ask `ps`, `pgrep` and `lsof`.
+
+use std::path::PathBuf;
+
+/// Whether the process `pid` runs with an effective user id of 0, for hosts that feed
+/// [`TerminalSession::set_foreground_root`](crate::TerminalSession::set_foreground_root) from the
+/// PTY's foreground process group leader (e.g. `MasterPty::process_group_leader`).
+///
+/// Returns `None` when the process is gone or cannot be inspected on this platform.
+pub fn process_runs_as_root(pid: u32) -> Option<bool> {
+    effective_uid(pid).map(|uid| uid == 0)
+}
+
+/// The command name of process `pid` (`vim`, `ssh`, ...), e.g. the foreground process group
+/// leader's for a tab label.
+///
+/// Returns `None` when the process is gone or cannot be inspected on this platform.
+pub fn process_name(pid: u32) -> Option<String> {
+    let name = command_name(pid)?;
+    let name = name.trim();
+    // `ps` reports the executable path on some platforms.
+    let name = name.rsplit('/').next().unwrap_or(name);
+    (!name.is_empty()).then(|| name.to_string())
+}
+
+/// The working directory of process `pid`, for hosts that want the directory of the foreground
+/// program rather than the last one the shell reported with OSC 7.
+pub fn process_cwd(pid: u32) -> Option<PathBuf> {
+    working_directory(pid)
+}
+
+/// Whether process `pid` (typically the shell spawned on the PTY) has child processes still
+/// running, so closing the pane would kill them; hosts can ask for confirmation first.
+pub fn has_running_child(pid: u32) -> Option<bool> {
+    child_pids(pid).map(|children| !children.is_empty())
+}
+
+#[cfg(target_os = "linux")]
+fn command_name(pid: u32) -> Option<String> {
+    std::fs::read_to_string(format!("/proc/{pid}/comm")).ok()
+}
+
+#[cfg(all(unix, not(target_os = "linux")))]
+fn command_name(pid: u32) -> Option<String> {
+    ps_field(pid, "comm=")
+}
+
+#[cfg(not(unix))]
+fn command_name(_pid: u32) -> Option<String> {
+    None
+}
+
+#[cfg(target_os = "linux")]
+fn working_directory(pid: u32) -> Option<PathBuf> {
+    std::fs::read_link(format!("/proc/{pid}/cwd")).ok()
+}
+
+#[cfg(all(unix, not(target_os = "linux")))]
+fn working_directory(pid: u32) -> Option<PathBuf> {
+    let output = std::process::Command::new("lsof")
+        .args(["-a", "-d", "cwd", "-Fn", "-p", &pid.to_string()])
+        .output()
+        .ok()?;
+    if !output.status.success() {
+        return None;
+    }
+    parse_lsof_cwd(&String::from_utf8_lossy(&output.stdout))
+}
+
+#[cfg(not(unix))]
+fn working_directory(_pid: u32) -> Option<PathBuf> {
+    None
+}
+
+#[cfg(target_os = "linux")]
+fn child_pids(pid: u32) -> Option<Vec<u32>> {
+    let tasks = std::fs::read_dir(format!("/proc/{pid}/task")).ok()?;
+    let mut children = Vec::new();
+    for task in tasks.flatten() {
+        if let Ok(list) = std::fs::read_to_string(task.path().join("children")) {
+            children.extend(parse_pid_list(&list));
+        }
+    }
+    Some(children)
+}
+
+#[cfg(all(unix, not(target_os = "linux")))]
+fn child_pids(pid: u32) -> Option<Vec<u32>> {
+    let output = std::process::Command::new("pgrep")
+        .args(["-P", &pid.to_string()])
+        .output()
+        .ok()?;
+    // `pgrep` exits with 1 when nothing matched.
+    match output.status.code() {
+        Some(0) | Some(1) => Some(parse_pid_list(&String::from_utf8_lossy(&output.stdout))),
+        _ => None,
+    }
+}
+
+#[cfg(not(unix))]
+fn child_pids(_pid: u32) -> Option<Vec<u32>> {
+    None
+}
+
+#[cfg(all(unix, not(target_os = "linux")))]
+fn ps_field(pid: u32, field: &str) -> Option<String> {
+    let output = std::process::Command::new("ps")
+        .args(["-o", field, "-p", &pid.to_string()])
+        .output()
+        .ok()?;
+    if !output.status.success() {
+        return None;
+    }
+    Some(String::from_utf8_lossy(&output.stdout).into_owned())
+}
+
+/// Pids separated by whitespace, as in `/proc/<pid>/task/<tid>/children` or `pgrep` output.
+#[cfg_attr(not(unix), allow(dead_code))]
+fn parse_pid_list(list: &str) -> Vec<u32> {
+    list.split_whitespace()
+        .filter_map(|pid| pid.parse().ok())
+        .collect()
+}
+
+/// The directory from `lsof -Fn` output, whose `n` field holds the file name.
+#[cfg_attr(target_os = "linux", allow(dead_code))]
+fn parse_lsof_cwd(output: &str) -> Option<PathBuf> {
+    output
+        .lines()
+        .find_map(|line| line.strip_prefix('n'))
+        .map(PathBuf::from)
+}
+
+#[cfg(target_os = "linux")]
+fn effective_uid(pid: u32) -> Option<u32> {
+    let status = std::fs::read_to_string(format!("/proc/{pid}/status")).ok()?;
+    parse_status_effective_uid(&status)
+}
+
+#[cfg(all(unix, not(target_os = "linux")))]
+fn effective_uid(pid: u32) -> Option<u32> {
+    let output = std::process::Command::new("ps")
+        .args(["-o", "uid=", "-p", &pid.to_string()])
+        .output()
+        .ok()?;
+    if !output.status.success() {
+        return None;
+    }
+    String::from_utf8_lossy(&output.stdout).trim().parse().ok()
+}
+
+#[cfg(not(unix))]
+fn effective_uid(_pid: u32) -> Option<u32> {
+    None
+}
+
+/// Reads the effective uid from a `/proc/<pid>/status` dump, whose `Uid:` line lists the real,
+/// effective, saved and filesystem ids.
+#[cfg_attr(not(target_os = "linux"), allow(dead_code))]
+fn parse_status_effective_uid(status: &str) -> Option<u32> {
+    status
+        .lines()
+        .find_map(|line| line.strip_prefix("Uid:"))?
+        .split_whitespace()
+        .nth(1)?
+        .parse()
+        .ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn effective_uid_comes_from_the_second_uid_column() {
+        let status =
+            "Name:\tsudo\nUmask:\t0022\nUid:\t1000\t0\t0\t0\nGid:\t1000\t1000\t1000\t1000\n";
+        assert_eq!(parse_status_effective_uid(status), Some(0));
+        assert_eq!(parse_status_effective_uid("Name:\tbash\n"), None);
+    }
+
+    #[test]
+    fn pid_lists_and_lsof_output_are_parsed() {
+        assert_eq!(parse_pid_list("123 456 \n"), vec![123, 456]);
+        assert_eq!(parse_pid_list(""), Vec::<u32>::new());
+        assert_eq!(
+            parse_lsof_cwd("p4242\nfcwd\nn/Users/me/src\n"),
+            Some(PathBuf::from("/Users/me/src"))
+        );
+        assert_eq!(parse_lsof_cwd("p4242\n"), None);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn own_process_can_be_inspected() {
+        assert!(process_runs_as_root(std::process::id()).is_some());
+        assert_eq!(process_runs_as_root(u32::MAX), None);
+        assert!(process_name(std::process::id()).is_some());
+        assert_eq!(
+            process_cwd(std::process::id()),
+            std::env::current_dir().ok()
+        );
+        assert_eq!(process_name(u32::MAX), None);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn running_children_are_detected() {
+        let mut child = std::process::Command::new("sleep")
+            .arg("5")
+            .spawn()
+            .unwrap();
+        assert_eq!(has_running_child(std::process::id()), Some(true));
+        child.kill().unwrap();
+        child.wait().unwrap();
+    }
+}
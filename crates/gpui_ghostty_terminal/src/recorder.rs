@@ -0,0 +1,118 @@
+//! asciinema (`.cast` v2) recording of a session's output.
+//!
+//! The format is newline-delimited JSON: a header object with the terminal size, then one
+//! `[seconds, code, data]` event per line, where `code` is `"o"` for output, `"i"` for input and
+//! `"r"` for a resize to `"COLSxROWS"`. See <https://docs.asciinema.org/manual/asciicast/v2/>.
+
+use std::io::{self, Write};
+use std::time::{Instant, SystemTime, UNIX_EPOCH};
+
+/// What a recording captures beyond the terminal output.
+#[derive(Clone, Debug, Default)]
+pub struct RecordingOptions {
+    /// Also record what is typed or pasted into the terminal, as `"i"` events. Off by default,
+    /// since input may hold passwords.
+    pub record_input: bool,
+    /// Title stored in the header, shown by players.
+    pub title: Option<String>,
+}
+
+/// An active recording, owned by a [`TerminalSession`](crate::TerminalSession).
+pub(crate) struct Recorder {
+    writer: Box<dyn Write>,
+    start: Instant,
+    record_input: bool,
+    /// Trailing bytes of an output chunk that end mid UTF-8 sequence, held for the next chunk.
+    output_tail: Vec<u8>,
+    input_tail: Vec<u8>,
+}
+
+impl Recorder {
+    /// Start a recording by writing the header for a `cols`×`rows` terminal.
+    pub(crate) fn start(
+        mut writer: Box<dyn Write>,
+        cols: u16,
+        rows: u16,
+        options: RecordingOptions,
+    ) -> io::Result<Self> {
+        let timestamp = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|since| since.as_secs())
+            .unwrap_or(0);
+        let mut header = serde_json::json!({
+            "version": 2,
+            "width": cols,
+            "height": rows,
+            "timestamp": timestamp,
+        });
+        if let Some(title) = options.title {
+            header["title"] = title.into();
+        }
+        writeln!(writer, "{}", header)?;
+        Ok(Self {
+            writer,
+            start: Instant::now(),
+            record_input: options.record_input,
+            output_tail: Vec::new(),
+            input_tail: Vec::new(),
+        })
+    }
+
+    pub(crate) fn output(&mut self, bytes: &[u8]) -> io::Result<()> {
+        let text = take_utf8(&mut self.output_tail, bytes);
+        self.event("o", &text)
+    }
+
+    pub(crate) fn input(&mut self, bytes: &[u8]) -> io::Result<()> {
+        if !self.record_input {
+            return Ok(());
+        }
+        let text = take_utf8(&mut self.input_tail, bytes);
+        self.event("i", &text)
+    }
+
+    pub(crate) fn resize(&mut self, cols: u16, rows: u16) -> io::Result<()> {
+        self.event("r", &format!("{}x{}", cols, rows))
+    }
+
+    pub(crate) fn finish(mut self) -> io::Result<()> {
+        self.writer.flush()
+    }
+
+    fn event(&mut self, code: &str, data: &str) -> io::Result<()> {
+        if data.is_empty() {
+            return Ok(());
+        }
+        let time = self.start.elapsed().as_secs_f64();
+        let event = serde_json::json!([(time * 1e6).round() / 1e6, code, data]);
+        writeln!(self.writer, "{}", event)
+    }
+}
+
+/// Decode `tail` followed by `bytes`, keeping an incomplete UTF-8 sequence at the end in `tail`
+/// for the next call. Invalid bytes become U+FFFD.
+fn take_utf8(tail: &mut Vec<u8>, bytes: &[u8]) -> String {
+    tail.extend_from_slice(bytes);
+    let complete = tail.len() - incomplete_utf8_suffix(tail);
+    let text = String::from_utf8_lossy(&tail[..complete]).into_owned();
+    tail.drain(..complete);
+    text
+}
+
+/// Length of a UTF-8 sequence cut off at the end of `bytes`, or 0.
+fn incomplete_utf8_suffix(bytes: &[u8]) -> usize {
+    for back in 1..=bytes.len().min(3) {
+        let byte = bytes[bytes.len() - back];
+        if byte & 0xC0 == 0x80 {
+            continue;
+        }
+        let len = match byte {
+            0xC0..=0xDF => 2,
+            0xE0..=0xEF => 3,
+            0xF0..=0xF7 => 4,
+            _ => return 0,
+        };
+        return if back < len { back } else { 0 };
+    }
+    0
+}
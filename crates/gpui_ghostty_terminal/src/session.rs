@@ -1,52 +1,355 @@
-use ghostty_vt::{CursorStyle, Error, Rgb, Terminal};
+use std::collections::BTreeMap;
+use std::io::{self, Write};
+use std::ops::Range;
+use std::time::SystemTime;
+
+use ghostty_vt::{
+    AbsolutePoint, CursorStyle, Error, KeyModes, Rgb, SearchMatch, Terminal, ViewportPoint,
+};
+
+use crate::kitty_graphics::{KittyApcScanner, KittyCommandContext, KittyGraphicsState};
+use crate::recorder::{Recorder, RecordingOptions};
+use crate::shell_integration::{Osc133ScanState, PromptMark, PromptMarks};
+use crate::{DEFAULT_PALETTE, TerminalConfig};
+
+/// Number of OSC 52 clipboard writes kept by [`TerminalSession::clipboard_history`].
+const CLIPBOARD_HISTORY_LIMIT: usize = 50;
+
+/// An application clipboard write (OSC 52) recorded by the session.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ClipboardEntry {
+    pub text: String,
+    pub timestamp: SystemTime,
+}
+
+/// Number of undelivered [`TerminalEvent`]s kept; the oldest are dropped first.
+const EVENT_QUEUE_LIMIT: usize = 64;
+
+/// A state change caused by terminal output.
+///
+/// Drain queued events with [`TerminalSession::take_events`], or receive them as they happen
+/// with [`TerminalSession::subscribe`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum TerminalEvent {
+    /// The window title changed (OSC 0/2).
+    TitleChanged(String),
+    /// The shell reported a new working directory (OSC 7), as a local path.
+    CwdChanged(String),
+    /// An application wrote to the clipboard (OSC 52).
+    ClipboardWritten(String),
+    /// An application switched to the alternate screen.
+    AltScreenEntered,
+    /// An application switched back to the primary screen.
+    AltScreenExited,
+    /// A command finished (OSC 133;D).
+    CommandFinished(PromptMark),
+    /// The application rang the bell (BEL). Several bells in one chunk of output are reported
+    /// once.
+    Bell,
+    /// A desktop notification requested with OSC 9 or OSC 777;notify.
+    Notification { title: Option<String>, body: String },
+    /// A shell set a user variable with OSC 1337;SetUserVar (e.g. the git branch or active
+    /// virtualenv), read back with [`TerminalSession::user_var`].
+    UserVarChanged { name: String, value: String },
+    /// The host reported that the process behind the session exited (see
+    /// [`TerminalSession::process_exited`]), with its exit code when known.
+    ProcessExited { exit_code: Option<i32> },
+}
+
+type TerminalEventSubscriber = Box<dyn FnMut(&TerminalEvent)>;
+type GenerationObserver = Box<dyn FnMut(u64)>;
+
+/// Cheap-to-read terminal state compared after each mutation; together with the dirty-row
+/// flags it tells whether the content generation has to move.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+struct ChangeFingerprint {
+    cols: u16,
+    rows: u16,
+    viewport_top_row: u32,
+    active_top_row: u32,
+    cursor: Option<ViewportPoint>,
+    cursor_visible: bool,
+    cursor_style: CursorStyle,
+}
+
+/// Outcome of a [`ClipboardAccessPolicy`] check for an OSC 52 clipboard read.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum ClipboardAccess {
+    /// Reply to the application with these clipboard contents.
+    Allow(String),
+    /// Refuse the read; the application gets no reply.
+    Deny,
+    /// Ask the user first, then answer with [`TerminalSession::answer_clipboard_read`].
+    Prompt,
+}
+
+/// Host hook deciding whether applications may read the clipboard through OSC 52
+/// (`ESC ] 52 ; c ; ? ST`).
+///
+/// Reads are denied while no policy is set. Closures returning [`ClipboardAccess`] implement
+/// this trait.
+pub trait ClipboardAccessPolicy {
+    fn clipboard_read(&mut self) -> ClipboardAccess;
+}
 
-use crate::TerminalConfig;
+impl<F: FnMut() -> ClipboardAccess> ClipboardAccessPolicy for F {
+    fn clipboard_read(&mut self) -> ClipboardAccess {
+        self()
+    }
+}
+
+/// Direction for [`TerminalSession::search`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum SearchDirection {
+    /// Towards newer output (down).
+    Forward,
+    /// Towards older output (up).
+    Backward,
+}
+
+/// How mouse reports are encoded, chosen by the application with DEC private modes.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum MouseEncoding {
+    /// Legacy `CSI M Cb Cx Cy` with each value sent as one byte offset by 32; coordinates past
+    /// 223 cannot be reported.
+    #[default]
+    X10,
+    /// Like [`MouseEncoding::X10`] but with values past 95 sent as UTF-8 (mode 1005).
+    Utf8,
+    /// `CSI Cb ; Cx ; Cy M` with decimal values (mode 1015).
+    Urxvt,
+    /// `CSI < Cb ; Cx ; Cy M/m` with decimal values and distinct releases (mode 1006).
+    Sgr,
+    /// [`MouseEncoding::Sgr`] with 1-based pixel coordinates instead of cells (mode 1016).
+    SgrPixels,
+}
+
+/// Viewport rows that changed since a generation, from [`TerminalSession::diff_since`].
+///
+/// Apply `changed_rows` to a mirror that is `cols`x`rows`, then pass `generation` to the next
+/// `diff_since` call to receive only later changes.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ViewportDiff {
+    /// The [`TerminalSession::generation`] this diff brings a mirror up to.
+    pub generation: u64,
+    pub cols: u16,
+    pub rows: u16,
+    /// Cursor position, or `None` when the cursor is hidden.
+    pub cursor: Option<ViewportPoint>,
+    /// Changed rows in top-to-bottom order.
+    pub changed_rows: Vec<ViewportRowDiff>,
+}
+
+/// The contents of one changed viewport row in a [`ViewportDiff`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ViewportRowDiff {
+    /// 0-based viewport row.
+    pub row: u16,
+    pub text: String,
+    pub style_runs: Vec<ghostty_vt::StyleRun>,
+}
+
+/// Last viewport contents reported by [`TerminalSession::diff_since`], with the session
+/// generation at which each row was last seen to change.
+#[derive(Debug, Default)]
+struct ViewportSnapshot {
+    cols: u16,
+    rows: Vec<(u64, ViewportRowDiff)>,
+    /// Session generation the rows were last compared at.
+    compared_at: Option<u64>,
+}
 
 pub struct TerminalSession {
     config: TerminalConfig,
     terminal: Terminal,
     bracketed_paste_enabled: bool,
+    focus_reporting_enabled: bool,
     mouse_x10_enabled: bool,
     mouse_button_event_enabled: bool,
     mouse_any_event_enabled: bool,
     mouse_sgr_enabled: bool,
+    mouse_sgr_pixels_enabled: bool,
+    mouse_utf8_enabled: bool,
+    mouse_urxvt_enabled: bool,
+    /// xterm modifyOtherKeys level (0-2) set with `CSI > 4 ; Pv m`.
+    modify_other_keys: u8,
+    alt_screen_enabled: bool,
+    /// Rows the primary screen was scrolled back when the alternate screen was entered.
+    primary_scroll_offset: Option<u32>,
+    /// Scroll-back offset to restore once the pending output has left the alternate screen.
+    pending_scroll_restore: Option<u32>,
+    /// Default foreground, background and cursor colors set by OSC 10/11/12, overriding the
+    /// config until reset by OSC 110/111/112.
+    osc_foreground: Option<Rgb>,
+    osc_background: Option<Rgb>,
+    osc_cursor_color: Option<Rgb>,
+    default_colors_changed: bool,
     title: Option<String>,
+    /// Titles saved by XTWINOPS 22 (push), most recent last; restored by XTWINOPS 23 (pop).
+    title_stack: Vec<Option<String>>,
+    cwd: Option<String>,
+    remote_host: Option<String>,
+    user_vars: BTreeMap<String, String>,
+    badge_format: Option<String>,
+    notes: String,
+    foreground_root: bool,
     clipboard_write: Option<String>,
+    clipboard_history: Vec<ClipboardEntry>,
+    events: Vec<TerminalEvent>,
+    event_subscribers: Vec<TerminalEventSubscriber>,
+    /// Bells and notifications found by the output scan, in byte order, raised once the
+    /// terminal has parsed the chunk.
+    scanned_events: Vec<TerminalEvent>,
+    clipboard_access_policy: Option<Box<dyn ClipboardAccessPolicy>>,
+    pending_clipboard_read: Option<u8>,
     parse_tail: Vec<u8>,
     dsr_state: DsrScanState,
     osc_query_state: OscQueryScanState,
+    osc52_read_state: Osc52ReadScanState,
+    xtgettcap_scan: XtGetTcapScanner,
     osc133_state: Osc133ScanState,
-    osc133_output_start_row: Option<u16>,
+    prompt_marks: PromptMarks,
     last_command_output: Option<String>,
+    kitty_scan: KittyApcScanner,
+    kitty_graphics: KittyGraphicsState,
+    cell_pixel_size: (u32, u32),
+    search_pattern: String,
+    search_current: Option<SearchMatch>,
+    snapshot: ViewportSnapshot,
+    generation: u64,
+    generation_fingerprint: ChangeFingerprint,
+    generation_observers: Vec<GenerationObserver>,
+    recorder: Option<Recorder>,
+    /// Write error that ended the last recording, returned by `stop_recording`.
+    recording_error: Option<io::Error>,
 }
 
 impl TerminalSession {
-    pub fn new(config: TerminalConfig) -> Result<Self, Error> {
+    pub fn new(mut config: TerminalConfig) -> Result<Self, Error> {
+        (config.cols, config.rows) = config.clamp_grid_size(config.cols, config.rows);
         let mut terminal = Terminal::new(config.cols, config.rows)?;
         terminal.set_default_colors(config.default_fg, config.default_bg);
+        terminal.set_default_cursor_style(config.cursor_style);
 
         // Apply custom palette if configured
         if let Some(ref palette) = config.palette {
             terminal.set_default_palette(palette);
         }
 
-        Ok(Self {
+        if let Some(lines) = config.scrollback_lines {
+            terminal.set_max_scrollback(lines);
+        }
+
+        let mut session = Self {
             config,
             terminal,
             bracketed_paste_enabled: false,
+            focus_reporting_enabled: false,
             mouse_x10_enabled: false,
             mouse_button_event_enabled: false,
             mouse_any_event_enabled: false,
             mouse_sgr_enabled: false,
+            mouse_sgr_pixels_enabled: false,
+            mouse_utf8_enabled: false,
+            mouse_urxvt_enabled: false,
+            modify_other_keys: 0,
+            alt_screen_enabled: false,
+            primary_scroll_offset: None,
+            pending_scroll_restore: None,
+            osc_foreground: None,
+            osc_background: None,
+            osc_cursor_color: None,
+            default_colors_changed: false,
             title: None,
+            title_stack: Vec::new(),
+            cwd: None,
+            remote_host: None,
+            user_vars: BTreeMap::new(),
+            badge_format: None,
+            notes: String::new(),
+            foreground_root: false,
             clipboard_write: None,
+            clipboard_history: Vec::new(),
+            events: Vec::new(),
+            event_subscribers: Vec::new(),
+            scanned_events: Vec::new(),
+            clipboard_access_policy: None,
+            pending_clipboard_read: None,
             parse_tail: Vec::new(),
             dsr_state: DsrScanState::default(),
             osc_query_state: OscQueryScanState::default(),
+            osc52_read_state: Osc52ReadScanState::default(),
+            xtgettcap_scan: XtGetTcapScanner::default(),
             osc133_state: Osc133ScanState::default(),
-            osc133_output_start_row: None,
+            prompt_marks: PromptMarks::default(),
             last_command_output: None,
-        })
+            kitty_scan: KittyApcScanner::default(),
+            kitty_graphics: KittyGraphicsState::default(),
+            cell_pixel_size: (8, 16),
+            search_pattern: String::new(),
+            search_current: None,
+            snapshot: ViewportSnapshot::default(),
+            generation: 0,
+            generation_fingerprint: ChangeFingerprint::default(),
+            generation_observers: Vec::new(),
+            recorder: None,
+            recording_error: None,
+        };
+        session.generation_fingerprint = session.change_fingerprint();
+        Ok(session)
+    }
+
+    /// Content generation: starts at 0 and increases whenever output, scrolling, resizing, a
+    /// reset or a theme change alters what the terminal shows. Compare it with a remembered
+    /// value for a cheap "has anything changed" check, e.g. for background tabs.
+    ///
+    /// Output is judged by Ghostty's dirty rows, which stay set until the view takes them, so a
+    /// no-op write can still count as a change while earlier output is waiting to be drawn.
+    pub fn generation(&self) -> u64 {
+        self.generation
+    }
+
+    /// Returns true if the content changed after `generation`, then stores the current one in
+    /// it, for polling loops that keep their own last-seen value.
+    pub fn observe_generation(&self, generation: &mut u64) -> bool {
+        let changed = self.generation > *generation;
+        *generation = self.generation;
+        changed
+    }
+
+    /// Calls `callback` with the new generation every time it increases.
+    pub fn subscribe_generation(&mut self, callback: impl FnMut(u64) + 'static) {
+        self.generation_observers.push(Box::new(callback));
+    }
+
+    fn change_fingerprint(&self) -> ChangeFingerprint {
+        ChangeFingerprint {
+            cols: self.config.cols,
+            rows: self.config.rows,
+            viewport_top_row: self.terminal.viewport_top_row(),
+            active_top_row: self.terminal.active_top_row(),
+            cursor: self.terminal.cursor_position(),
+            cursor_visible: self.terminal.cursor_visible(),
+            cursor_style: self.terminal.cursor_style(),
+        }
+    }
+
+    /// Moves the generation forward if anything visible changed since the last check.
+    fn note_changes(&mut self) {
+        let fingerprint = self.change_fingerprint();
+        if fingerprint != self.generation_fingerprint
+            || self.terminal.has_dirty_viewport_rows(self.config.rows)
+        {
+            self.generation_fingerprint = fingerprint;
+            self.bump_generation();
+        }
+    }
+
+    fn bump_generation(&mut self) {
+        self.generation += 1;
+        for observer in &mut self.generation_observers {
+            observer(self.generation);
+        }
     }
 
     pub fn cols(&self) -> u16 {
@@ -57,12 +360,38 @@ impl TerminalSession {
         self.config.rows
     }
 
+    /// Default text color: the one set by OSC 10, if any, else the configured one.
     pub fn default_foreground(&self) -> Rgb {
-        self.config.default_fg
+        self.osc_foreground.unwrap_or(self.config.default_fg)
     }
 
+    /// Default background color: the one set by OSC 11, if any, else the configured one.
     pub fn default_background(&self) -> Rgb {
-        self.config.default_bg
+        self.osc_background.unwrap_or(self.config.default_bg)
+    }
+
+    /// Cursor color set by the application with OSC 12, until reset with OSC 112.
+    pub fn cursor_color(&self) -> Option<Rgb> {
+        self.osc_cursor_color
+    }
+
+    /// Unsolicited OSC 10 and OSC 11 reports of the current default colors, in the format of
+    /// query replies, for telling the application that the colors changed.
+    pub fn default_color_reports(&self) -> String {
+        let fg = self.default_foreground();
+        let bg = self.default_background();
+        let mut reports = osc_color_query_response(OscQuery::ForegroundColor, (fg.r, fg.g, fg.b));
+        reports.push_str(&osc_color_query_response(
+            OscQuery::BackgroundColor,
+            (bg.r, bg.g, bg.b),
+        ));
+        reports
+    }
+
+    /// Returns true once after OSC 10/11/12 (or their resets) changed the default colors, so
+    /// rows already on screen can be redrawn with them.
+    pub fn take_default_colors_changed(&mut self) -> bool {
+        std::mem::take(&mut self.default_colors_changed)
     }
 
     pub fn background_opacity(&self) -> f32 {
@@ -73,6 +402,11 @@ impl TerminalSession {
         self.bracketed_paste_enabled
     }
 
+    /// Returns true while the application wants focus in/out reports (DEC mode 1004).
+    pub fn focus_reporting_enabled(&self) -> bool {
+        self.focus_reporting_enabled
+    }
+
     pub fn mouse_reporting_enabled(&self) -> bool {
         self.mouse_x10_enabled || self.mouse_button_event_enabled || self.mouse_any_event_enabled
     }
@@ -81,6 +415,22 @@ impl TerminalSession {
         self.mouse_sgr_enabled
     }
 
+    /// Encoding for mouse reports. When several encoding modes are set, SGR-Pixels wins over
+    /// SGR, which wins over urxvt, which wins over UTF-8.
+    pub fn mouse_encoding(&self) -> MouseEncoding {
+        if self.mouse_sgr_pixels_enabled {
+            MouseEncoding::SgrPixels
+        } else if self.mouse_sgr_enabled {
+            MouseEncoding::Sgr
+        } else if self.mouse_urxvt_enabled {
+            MouseEncoding::Urxvt
+        } else if self.mouse_utf8_enabled {
+            MouseEncoding::Utf8
+        } else {
+            MouseEncoding::X10
+        }
+    }
+
     pub fn mouse_button_event_enabled(&self) -> bool {
         self.mouse_button_event_enabled
     }
@@ -89,48 +439,314 @@ impl TerminalSession {
         self.mouse_any_event_enabled
     }
 
+    /// Returns true while the alternate screen (DEC modes 47/1047/1049) is active.
+    pub fn is_alt_screen(&self) -> bool {
+        self.alt_screen_enabled
+    }
+
     pub fn title(&self) -> Option<&str> {
         self.title.as_deref()
     }
 
+    /// Returns the working directory last reported by the shell via OSC 7 (or iTerm2's
+    /// OSC 1337;CurrentDir).
+    pub fn current_working_directory(&self) -> Option<&str> {
+        self.cwd.as_deref()
+    }
+
+    /// Returns the `user@host` last reported with OSC 1337;RemoteHost.
+    pub fn remote_host(&self) -> Option<&str> {
+        self.remote_host.as_deref()
+    }
+
+    /// Returns a user variable set with OSC 1337;SetUserVar.
+    pub fn user_var(&self, name: &str) -> Option<&str> {
+        self.user_vars.get(name).map(String::as_str)
+    }
+
+    /// Returns all user variables set with OSC 1337;SetUserVar, by name.
+    pub fn user_vars(&self) -> &BTreeMap<String, String> {
+        &self.user_vars
+    }
+
+    /// Sets the badge drawn over the top-right of the view; `None` (or an empty string) removes
+    /// it. Applications set it with OSC 1337;SetBadgeFormat.
+    ///
+    /// The format may reference `\(user.NAME)` user variables, `\(session.path)` and
+    /// `\(session.hostname)`, which [`TerminalSession::badge`] fills in.
+    pub fn set_badge_format(&mut self, format: Option<String>) {
+        self.badge_format = format.filter(|format| !format.is_empty());
+    }
+
+    pub fn badge_format(&self) -> Option<&str> {
+        self.badge_format.as_deref()
+    }
+
+    /// Free-form notes kept with this session (commands, snippets), shown in the view's notes
+    /// panel. Hosts that save and restore sessions should store them alongside.
+    pub fn notes(&self) -> &str {
+        &self.notes
+    }
+
+    pub fn set_notes(&mut self, notes: impl Into<String>) {
+        self.notes = notes.into();
+    }
+
+    pub(crate) fn notes_mut(&mut self) -> &mut String {
+        &mut self.notes
+    }
+
+    /// Records whether the foreground process runs as root, e.g. after `sudo -s`. The terminal
+    /// cannot see processes itself, so the host checks the PTY's foreground process (see
+    /// [`process_runs_as_root`](crate::process_runs_as_root)) and reports it here; views then
+    /// show the configured [`RootIndicator`](crate::RootIndicator).
+    pub fn set_foreground_root(&mut self, root: bool) {
+        self.foreground_root = root;
+    }
+
+    pub fn is_foreground_root(&self) -> bool {
+        self.foreground_root
+    }
+
+    /// Returns the badge text with its `\(...)` references filled in, or `None` when there is
+    /// no badge or it expands to nothing.
+    pub fn badge(&self) -> Option<String> {
+        let format = self.badge_format.as_deref()?;
+        let mut out = String::new();
+        let mut rest = format;
+        while let Some(start) = rest.find("\\(") {
+            out.push_str(&rest[..start]);
+            let Some(len) = rest[start + 2..].find(')') else {
+                rest = &rest[start..];
+                break;
+            };
+            let name = &rest[start + 2..start + 2 + len];
+            let value = match name {
+                "session.path" => self.cwd.as_deref(),
+                "session.hostname" => self
+                    .remote_host
+                    .as_deref()
+                    .map(|host| host.rsplit('@').next().unwrap_or(host)),
+                _ => name
+                    .strip_prefix("user.")
+                    .and_then(|var| self.user_var(var)),
+            };
+            out.push_str(value.unwrap_or_default());
+            rest = &rest[start + 3 + len..];
+        }
+        out.push_str(rest);
+        (!out.trim().is_empty()).then_some(out)
+    }
+
     pub(crate) fn window_title_updates_enabled(&self) -> bool {
         self.config.update_window_title
     }
 
-    pub fn hyperlink_at(&self, col: u16, row: u16) -> Option<String> {
-        self.terminal.hyperlink_at(col, row)
+    pub fn hyperlink_at(&self, point: ViewportPoint) -> Option<String> {
+        self.terminal.hyperlink_at(point)
     }
 
     pub fn take_clipboard_write(&mut self) -> Option<String> {
         self.clipboard_write.take()
     }
 
+    /// Returns and clears the events raised by output since the last call, oldest first.
+    ///
+    /// Only the most recent events are kept between calls.
+    pub fn take_events(&mut self) -> Vec<TerminalEvent> {
+        std::mem::take(&mut self.events)
+    }
+
+    /// Calls `callback` with every event as it is raised, while output is being fed.
+    ///
+    /// Subscribers see events independently of [`take_events`](Self::take_events).
+    pub fn subscribe(&mut self, callback: impl FnMut(&TerminalEvent) + 'static) {
+        self.event_subscribers.push(Box::new(callback));
+    }
+
+    /// Records that the process writing to this session exited, raising
+    /// [`TerminalEvent::ProcessExited`]. Hosts call this when the PTY child is reaped; it is not
+    /// something the output stream can tell.
+    pub fn process_exited(&mut self, exit_code: Option<i32>) {
+        self.push_event(TerminalEvent::ProcessExited { exit_code });
+    }
+
+    fn push_event(&mut self, event: TerminalEvent) {
+        for subscriber in &mut self.event_subscribers {
+            subscriber(&event);
+        }
+        if self.events.len() >= EVENT_QUEUE_LIMIT {
+            self.events.remove(0);
+        }
+        self.events.push(event);
+    }
+
+    /// Raises the bells and notifications scanned from the last chunk in the order they were
+    /// written. The terminal's bell count is authoritative: a scanned BEL it did not ring (the
+    /// end of an OSC that started before the scan tail) is dropped, and a bell outside the scan
+    /// tail is raised last.
+    fn raise_scanned_events(&mut self) {
+        let rang = self.terminal.take_bell_count() > 0;
+        let mut events = std::mem::take(&mut self.scanned_events);
+        if !rang {
+            events.retain(|event| *event != TerminalEvent::Bell);
+        } else if !events.contains(&TerminalEvent::Bell) {
+            events.push(TerminalEvent::Bell);
+        }
+        for event in events {
+            self.push_event(event);
+        }
+    }
+
+    /// Clipboard writes made by applications via OSC 52, oldest first.
+    ///
+    /// Keeps the most recent writes so an entry overwritten by a TUI can be recovered.
+    pub fn clipboard_history(&self) -> &[ClipboardEntry] {
+        &self.clipboard_history
+    }
+
+    pub fn clear_clipboard_history(&mut self) {
+        self.clipboard_history.clear();
+    }
+
+    /// Sets the policy consulted when an application asks to read the clipboard via OSC 52.
+    pub fn set_clipboard_access_policy(&mut self, policy: impl ClipboardAccessPolicy + 'static) {
+        self.clipboard_access_policy = Some(Box::new(policy));
+    }
+
+    /// Returns true while a clipboard read deferred by [`ClipboardAccess::Prompt`] awaits an
+    /// answer.
+    pub fn has_pending_clipboard_read(&self) -> bool {
+        self.pending_clipboard_read.is_some()
+    }
+
+    /// Answers the pending clipboard read: `Some(contents)` allows it, `None` denies it.
+    ///
+    /// Returns the reply to write to the PTY, or `None` when nothing should be sent.
+    pub fn answer_clipboard_read(&mut self, contents: Option<&str>) -> Option<Vec<u8>> {
+        let selection = self.pending_clipboard_read.take()?;
+        contents.map(|text| osc_52_read_response(selection, text).into_bytes())
+    }
+
     /// Returns the rendered text of the last completed command's output, captured via OSC 133
     /// shell integration markers. Returns `None` if no command has completed since the last call
     /// or if the shell does not emit OSC 133 sequences.
     ///
-    /// Only output visible in the terminal viewport at the time the command completed is captured.
+    /// The text is captured when the command completes, so later screen changes do not affect it.
     pub fn take_last_command_output(&mut self) -> Option<String> {
         self.last_command_output.take()
     }
 
-    /// Dump viewport rows [first_row_0indexed, last_row_0indexed] inclusive, strip trailing
-    /// whitespace per line, and join with newlines.  Returns `None` when the range is empty.
-    fn collect_output_rows(
-        &self,
-        first_row_0indexed: u16,
-        last_row_0indexed: u16,
-    ) -> Option<String> {
-        if last_row_0indexed < first_row_0indexed {
-            return None;
+    /// Returns the current text of the most recently finished command's output, read from the
+    /// screen (including scrollback) without consuming it.
+    pub fn last_command_output(&self) -> Option<String> {
+        self.collect_output_rows(self.last_finished_prompt_mark()?.output_rows()?)
+    }
+
+    /// Returns the prompt mark of the most recently finished command.
+    pub fn last_finished_prompt_mark(&self) -> Option<&PromptMark> {
+        self.prompt_marks
+            .marks()
+            .iter()
+            .rev()
+            .find(|m| m.is_finished())
+    }
+
+    /// Prompt marks recorded from OSC 133 shell integration, oldest first.
+    pub fn prompt_marks(&self) -> &[PromptMark] {
+        self.prompt_marks.marks()
+    }
+
+    /// Returns the last prompt mark whose prompt row is above `row` (screen-absolute).
+    pub fn previous_prompt_mark(&self, row: u32) -> Option<&PromptMark> {
+        self.prompt_marks
+            .marks()
+            .iter()
+            .rev()
+            .find(|mark| mark.prompt_row < row)
+    }
+
+    /// Returns the first prompt mark whose prompt row is below `row` (screen-absolute).
+    pub fn next_prompt_mark(&self, row: u32) -> Option<&PromptMark> {
+        self.prompt_marks
+            .marks()
+            .iter()
+            .find(|mark| mark.prompt_row > row)
+    }
+
+    pub fn clear_prompt_marks(&mut self) {
+        self.prompt_marks.clear();
+    }
+
+    /// Start recording output to `writer` in asciinema `.cast` v2 format, replacing any
+    /// recording in progress. Only output is recorded; see [`Self::start_recording_with`] for
+    /// input and a title.
+    pub fn start_recording(&mut self, writer: impl Write + 'static) -> io::Result<()> {
+        self.start_recording_with(writer, RecordingOptions::default())
+    }
+
+    /// [`Self::start_recording`] with options. Input is recorded as the view sends it (see
+    /// [`Self::record_input`]).
+    pub fn start_recording_with(
+        &mut self,
+        writer: impl Write + 'static,
+        options: RecordingOptions,
+    ) -> io::Result<()> {
+        self.stop_recording().ok();
+        let recorder = Recorder::start(
+            Box::new(writer),
+            self.config.cols,
+            self.config.rows,
+            options,
+        )?;
+        self.recorder = Some(recorder);
+        Ok(())
+    }
+
+    /// Finish the recording and flush its writer. Returns the write error that cut the
+    /// recording short, if any.
+    pub fn stop_recording(&mut self) -> io::Result<()> {
+        if let Some(err) = self.recording_error.take() {
+            self.recorder = None;
+            return Err(err);
         }
+        match self.recorder.take() {
+            Some(recorder) => recorder.finish(),
+            None => Ok(()),
+        }
+    }
+
+    pub fn is_recording(&self) -> bool {
+        self.recorder.is_some()
+    }
 
-        let mut lines: Vec<String> = Vec::new();
-        for row in first_row_0indexed..=last_row_0indexed {
-            let line = self.terminal.dump_viewport_row(row).unwrap_or_default();
-            lines.push(line.trim_end().to_string());
+    /// Record bytes sent to the application, when the recording was started with
+    /// [`RecordingOptions::record_input`]. [`TerminalView`](crate::TerminalView) calls this for
+    /// typed and pasted input; hosts that write to the PTY themselves can call it too.
+    pub fn record_input(&mut self, bytes: &[u8]) {
+        self.record(|recorder| recorder.input(bytes));
+    }
+
+    /// Run a recorder write, ending the recording if it fails.
+    fn record(&mut self, write: impl FnOnce(&mut Recorder) -> io::Result<()>) {
+        if let Some(recorder) = self.recorder.as_mut()
+            && let Err(err) = write(recorder)
+        {
+            self.recorder = None;
+            self.recording_error = Some(err);
+        }
+    }
+
+    /// Dump screen-absolute `rows`, strip trailing whitespace per line, and join with newlines.
+    /// Returns `None` when nothing but whitespace remains.
+    fn collect_output_rows(&self, rows: Range<u32>) -> Option<String> {
+        if rows.is_empty() {
+            return None;
         }
 
+        let text = self.terminal.dump_scrollback(rows).unwrap_or_default();
+        let mut lines: Vec<String> = text.lines().map(|l| l.trim_end().to_string()).collect();
+
         // Drop trailing empty lines
         while lines.last().map(|l: &String| l.is_empty()).unwrap_or(false) {
             lines.pop();
@@ -143,6 +759,67 @@ impl TerminalSession {
         }
     }
 
+    /// Applies default color sets and resets (OSC 10-12, 110-112) in order; `None` restores
+    /// the configured color.
+    fn apply_osc_color_sets(&mut self, sets: Vec<(u32, Option<Rgb>)>) {
+        let before = (
+            self.osc_foreground,
+            self.osc_background,
+            self.osc_cursor_color,
+        );
+        for (target, rgb) in sets {
+            match target {
+                10 => self.osc_foreground = rgb,
+                11 => self.osc_background = rgb,
+                12 => self.osc_cursor_color = rgb,
+                _ => {}
+            }
+        }
+        if (
+            self.osc_foreground,
+            self.osc_background,
+            self.osc_cursor_color,
+        ) == before
+        {
+            return;
+        }
+        if (self.osc_foreground, self.osc_background) != (before.0, before.1) {
+            self.terminal
+                .set_default_colors(self.default_foreground(), self.default_background());
+        }
+        self.default_colors_changed = true;
+        self.bump_generation();
+    }
+
+    /// Applies title sets (OSC 0/2) and title stack pushes and pops (XTWINOPS 22/23) in the
+    /// order they appeared in the output.
+    fn apply_title_ops(&mut self, mut ops: Vec<(usize, TitleOp)>) {
+        const TITLE_STACK_LIMIT: usize = 10;
+
+        ops.sort_by_key(|(position, _)| *position);
+        let previous = self.title.clone();
+        for (_, op) in ops {
+            match op {
+                TitleOp::Set(title) => self.title = Some(title),
+                TitleOp::Push => {
+                    if self.title_stack.len() == TITLE_STACK_LIMIT {
+                        self.title_stack.remove(0);
+                    }
+                    self.title_stack.push(self.title.clone());
+                }
+                TitleOp::Pop => {
+                    if let Some(title) = self.title_stack.pop() {
+                        self.title = title;
+                    }
+                }
+            }
+        }
+        if self.title != previous {
+            let title = self.title.clone().unwrap_or_default();
+            self.push_event(TerminalEvent::TitleChanged(title));
+        }
+    }
+
     fn update_state_from_output(&mut self, bytes: &[u8]) {
         const TAIL_LIMIT: usize = 2048;
 
@@ -151,16 +828,28 @@ impl TerminalSession {
             let drop_len = self.parse_tail.len() - TAIL_LIMIT;
             self.parse_tail.drain(0..drop_len);
         }
+        // Sequences are re-scanned while they remain in the tail; only those terminated by the
+        // new bytes count as fresh clipboard writes.
+        let new_start = self.parse_tail.len().saturating_sub(bytes.len());
         let buf = self.parse_tail.as_slice();
 
+        let mut alt_screen_switches: Vec<bool> = Vec::new();
+        let mut title_ops: Vec<(usize, TitleOp)> = Vec::new();
         let mut i = 0usize;
         while i + 2 < buf.len() {
-            if buf[i] != 0x1b || buf[i + 1] != b'[' || buf[i + 2] != b'?' {
+            // `CSI ? Ps h/l` sets DEC private modes; `CSI > 4 ; Pv m` sets modifyOtherKeys;
+            // `CSI 22/23 ; Ps t` pushes and pops the window title.
+            if buf[i] != 0x1b || buf[i + 1] != b'[' {
+                i += 1;
+                continue;
+            }
+            let private = buf[i + 2];
+            if !matches!(private, b'?' | b'>' | b'2') {
                 i += 1;
                 continue;
             }
 
-            let mut k = i + 3;
+            let mut k = if private == b'2' { i + 2 } else { i + 3 };
             let mut nums: Vec<u32> = Vec::new();
             let mut num: u32 = 0;
             let mut saw_digit = false;
@@ -185,7 +874,40 @@ impl TerminalSession {
                     continue;
                 }
 
-                if b == b'h' || b == b'l' {
+                if private == b'>' && b == b'm' {
+                    if saw_digit {
+                        nums.push(num);
+                    }
+                    // A bare `CSI > 4 m` resets the resource to its default, off.
+                    if nums.first() == Some(&4) {
+                        self.modify_other_keys = nums.get(1).copied().unwrap_or(0).min(2) as u8;
+                    }
+
+                    i = k + 1;
+                    consumed = true;
+                    break;
+                }
+
+                if private == b'2' && b == b't' {
+                    if saw_digit {
+                        nums.push(num);
+                    }
+                    // Only the window title (Ps 0 or 2) is kept; icon titles are not tracked.
+                    let which = nums.get(1).copied().unwrap_or(0);
+                    if k >= new_start && matches!(which, 0 | 2) {
+                        match nums.first() {
+                            Some(22) => title_ops.push((k, TitleOp::Push)),
+                            Some(23) => title_ops.push((k, TitleOp::Pop)),
+                            _ => {}
+                        }
+                    }
+
+                    i = k + 1;
+                    consumed = true;
+                    break;
+                }
+
+                if private == b'?' && (b == b'h' || b == b'l') {
                     if saw_digit {
                         nums.push(num);
                     }
@@ -195,9 +917,19 @@ impl TerminalSession {
                         match ps {
                             2004 => self.bracketed_paste_enabled = enabled,
                             1000 => self.mouse_x10_enabled = enabled,
+                            1004 => self.focus_reporting_enabled = enabled,
                             1002 => self.mouse_button_event_enabled = enabled,
                             1003 => self.mouse_any_event_enabled = enabled,
+                            1005 => self.mouse_utf8_enabled = enabled,
                             1006 => self.mouse_sgr_enabled = enabled,
+                            1015 => self.mouse_urxvt_enabled = enabled,
+                            1016 => self.mouse_sgr_pixels_enabled = enabled,
+                            47 | 1047 | 1049 => {
+                                if k >= new_start && enabled != self.alt_screen_enabled {
+                                    alt_screen_switches.push(enabled);
+                                }
+                                self.alt_screen_enabled = enabled;
+                            }
                             _ => {}
                         }
                     }
@@ -223,11 +955,22 @@ impl TerminalSession {
             i += 1;
         }
 
-        let mut last_title: Option<String> = None;
-        let mut last_clipboard: Option<String> = None;
+        let mut last_cwd: Option<String> = None;
+        let mut clipboard_writes: Vec<String> = Vec::new();
+        let mut bell_scanned = false;
+        let mut color_sets: Vec<(u32, Option<Rgb>)> = Vec::new();
+        let mut iterm_reports: Vec<Osc1337> = Vec::new();
         let mut j = 0usize;
-        while j + 1 < buf.len() {
-            if buf[j] != 0x1b || buf[j + 1] != b']' {
+        while j < buf.len() {
+            if buf[j] == 0x07 {
+                if j >= new_start && !bell_scanned {
+                    bell_scanned = true;
+                    self.scanned_events.push(TerminalEvent::Bell);
+                }
+                j += 1;
+                continue;
+            }
+            if j + 1 >= buf.len() || buf[j] != 0x1b || buf[j + 1] != b']' {
                 j += 1;
                 continue;
             }
@@ -259,20 +1002,62 @@ impl TerminalSession {
                 match buf[k] {
                     0x07 => {
                         if ps == 0 || ps == 2 {
-                            last_title =
-                                Some(String::from_utf8_lossy(&buf[title_start..k]).into_owned());
-                        } else if ps == 52 {
-                            last_clipboard = decode_osc_52(&buf[title_start..k]);
+                            if k >= new_start {
+                                let title = String::from_utf8_lossy(&buf[title_start..k]);
+                                title_ops.push((k, TitleOp::Set(title.into_owned())));
+                            }
+                        } else if ps == 7 {
+                            last_cwd = parse_osc_7(&buf[title_start..k]).or(last_cwd);
+                        } else if ps == 52
+                            && k >= new_start
+                            && let Some(text) = decode_osc_52(&buf[title_start..k])
+                        {
+                            clipboard_writes.push(text);
+                        } else if ps == 1337 {
+                            match parse_osc_1337(&buf[title_start..k]) {
+                                Some(Osc1337::CurrentDir(dir)) => last_cwd = Some(dir),
+                                Some(report) if k >= new_start => iterm_reports.push(report),
+                                _ => {}
+                            }
+                        } else if matches!(ps, 10..=12 | 110..=112) {
+                            if k >= new_start {
+                                color_sets.extend(parse_osc_color_sets(ps, &buf[title_start..k]));
+                            }
+                        } else if k >= new_start
+                            && let Some(event) = parse_osc_notification(ps, &buf[title_start..k])
+                        {
+                            self.scanned_events.push(event);
                         }
                         k += 1;
                         break;
                     }
                     0x1b if k + 1 < buf.len() && buf[k + 1] == b'\\' => {
                         if ps == 0 || ps == 2 {
-                            last_title =
-                                Some(String::from_utf8_lossy(&buf[title_start..k]).into_owned());
-                        } else if ps == 52 {
-                            last_clipboard = decode_osc_52(&buf[title_start..k]);
+                            if k + 1 >= new_start {
+                                let title = String::from_utf8_lossy(&buf[title_start..k]);
+                                title_ops.push((k + 1, TitleOp::Set(title.into_owned())));
+                            }
+                        } else if ps == 7 {
+                            last_cwd = parse_osc_7(&buf[title_start..k]).or(last_cwd);
+                        } else if ps == 52
+                            && k + 1 >= new_start
+                            && let Some(text) = decode_osc_52(&buf[title_start..k])
+                        {
+                            clipboard_writes.push(text);
+                        } else if ps == 1337 {
+                            match parse_osc_1337(&buf[title_start..k]) {
+                                Some(Osc1337::CurrentDir(dir)) => last_cwd = Some(dir),
+                                Some(report) if k + 1 >= new_start => iterm_reports.push(report),
+                                _ => {}
+                            }
+                        } else if matches!(ps, 10..=12 | 110..=112) {
+                            if k + 1 >= new_start {
+                                color_sets.extend(parse_osc_color_sets(ps, &buf[title_start..k]));
+                            }
+                        } else if k + 1 >= new_start
+                            && let Some(event) = parse_osc_notification(ps, &buf[title_start..k])
+                        {
+                            self.scanned_events.push(event);
                         }
                         k += 2;
                         break;
@@ -284,17 +1069,102 @@ impl TerminalSession {
             j = k.max(j + 1);
         }
 
-        if let Some(title) = last_title {
-            self.title = Some(title);
+        if !title_ops.is_empty() {
+            self.apply_title_ops(title_ops);
+        }
+        if let Some(cwd) = last_cwd
+            && self.cwd.as_ref() != Some(&cwd)
+        {
+            self.cwd = Some(cwd.clone());
+            self.push_event(TerminalEvent::CwdChanged(cwd));
+        }
+        if !color_sets.is_empty() {
+            self.apply_osc_color_sets(color_sets);
+        }
+        for entering in alt_screen_switches {
+            self.on_alt_screen_switch(entering);
+        }
+        for text in clipboard_writes {
+            self.record_clipboard_write(text);
         }
-        if let Some(clipboard) = last_clipboard {
-            self.clipboard_write = Some(clipboard);
+        for report in iterm_reports {
+            match report {
+                Osc1337::SetUserVar { name, value } => {
+                    if self.user_vars.get(&name) != Some(&value) {
+                        self.user_vars.insert(name.clone(), value.clone());
+                        self.push_event(TerminalEvent::UserVarChanged { name, value });
+                    }
+                }
+                Osc1337::RemoteHost(host) => self.remote_host = Some(host),
+                Osc1337::SetBadgeFormat(format) => self.set_badge_format(Some(format)),
+                Osc1337::CurrentDir(_) => {}
+            }
+        }
+    }
+
+    /// Remembers how far the primary screen was scrolled back on entering the alternate screen,
+    /// and schedules restoring it on leaving, so quitting `less` keeps the user's place.
+    fn on_alt_screen_switch(&mut self, entering: bool) {
+        self.push_event(if entering {
+            TerminalEvent::AltScreenEntered
+        } else {
+            TerminalEvent::AltScreenExited
+        });
+        if entering {
+            self.primary_scroll_offset = Some(self.scroll_offset());
+            self.pending_scroll_restore = None;
+        } else {
+            self.pending_scroll_restore = self.primary_scroll_offset.take();
+        }
+    }
+
+    /// Scrolls the primary screen back to where it was before the alternate screen was entered.
+    fn restore_primary_scroll(&mut self) -> Result<(), Error> {
+        let Some(offset) = self.pending_scroll_restore.take() else {
+            return Ok(());
+        };
+        if self.alt_screen_enabled || offset == 0 {
+            return Ok(());
+        }
+        self.terminal.scroll_viewport_bottom()?;
+        self.terminal
+            .scroll_viewport(-(offset.min(i32::MAX as u32) as i32))
+    }
+
+    fn record_clipboard_write(&mut self, text: String) {
+        if self.clipboard_history.len() >= CLIPBOARD_HISTORY_LIMIT {
+            self.clipboard_history.remove(0);
         }
+        self.clipboard_history.push(ClipboardEntry {
+            text: text.clone(),
+            timestamp: SystemTime::now(),
+        });
+        self.clipboard_write = Some(text.clone());
+        self.push_event(TerminalEvent::ClipboardWritten(text));
     }
 
     pub fn feed(&mut self, bytes: &[u8]) -> Result<(), Error> {
+        self.record(|recorder| recorder.output(bytes));
         self.update_state_from_output(bytes);
-        self.terminal.feed(bytes)
+
+        let mut seg_start = 0usize;
+        for (i, &b) in bytes.iter().enumerate() {
+            let Some(command) = self.kitty_scan.advance(b) else {
+                continue;
+            };
+            self.terminal.feed(&bytes[seg_start..=i])?;
+            seg_start = i + 1;
+            self.handle_kitty_graphics_command(&command, &mut |_| {})?;
+        }
+
+        if seg_start < bytes.len() {
+            self.terminal.feed(&bytes[seg_start..])?;
+        }
+        self.raise_scanned_events();
+        self.restore_primary_scroll()?;
+        self.note_changes();
+
+        Ok(())
     }
 
     pub fn feed_with_pty_responses(
@@ -302,26 +1172,42 @@ impl TerminalSession {
         bytes: &[u8],
         mut send: impl FnMut(&[u8]),
     ) -> Result<(), Error> {
+        self.record(|recorder| recorder.output(bytes));
         self.update_state_from_output(bytes);
 
         let mut seg_start = 0usize;
         for (i, &b) in bytes.iter().enumerate() {
             let dsr = self.dsr_state.advance(b);
             let osc = self.osc_query_state.advance(b);
+            let clipboard_read = self.osc52_read_state.advance(b);
             let osc133 = self.osc133_state.advance(b);
-            if dsr.is_none() && osc.is_none() && osc133.is_none() {
+            let kitty = self.kitty_scan.advance(b);
+            let tcap_query = self.xtgettcap_scan.advance(b);
+            if dsr.is_none()
+                && osc.is_none()
+                && clipboard_read.is_none()
+                && osc133.is_none()
+                && kitty.is_none()
+                && tcap_query.is_none()
+            {
                 continue;
             }
 
             self.terminal.feed(&bytes[seg_start..=i])?;
             seg_start = i + 1;
 
+            if let Some(command) = kitty {
+                self.handle_kitty_graphics_command(&command, &mut send)?;
+            }
+
             if let Some(query) = dsr {
                 match query {
                     TerminalQuery::DeviceStatus => send(b"\x1b[0n"),
                     TerminalQuery::CursorPosition => {
-                        let (col, row) = self.cursor_position().unwrap_or((1, 1));
-                        let resp = format!("\x1b[{};{}R", row, col);
+                        let cursor = self
+                            .cursor_position()
+                            .unwrap_or(ViewportPoint { col: 1, row: 1 });
+                        let resp = format!("\x1b[{};{}R", cursor.row, cursor.col);
                         send(resp.as_bytes());
                     }
                     TerminalQuery::PrimaryDeviceAttributes => {
@@ -331,17 +1217,38 @@ impl TerminalSession {
                         // 7 = DRCS, 8 = UDK, 9 = national replacement
                         send(b"\x1b[?62;1;2;6;7;8;9c");
                     }
+                    TerminalQuery::TextAreaPixels => {
+                        let (cell_w, cell_h) = self.cell_pixel_size;
+                        let resp = format!(
+                            "\x1b[4;{};{}t",
+                            cell_h * u32::from(self.rows()),
+                            cell_w * u32::from(self.cols())
+                        );
+                        send(resp.as_bytes());
+                    }
+                    TerminalQuery::CellPixels => {
+                        let (cell_w, cell_h) = self.cell_pixel_size;
+                        send(format!("\x1b[6;{cell_h};{cell_w}t").as_bytes());
+                    }
+                    TerminalQuery::TextAreaChars => {
+                        let resp = format!("\x1b[8;{};{}t", self.rows(), self.cols());
+                        send(resp.as_bytes());
+                    }
                 }
             }
 
+            if let Some(names) = tcap_query {
+                send(&xtgettcap_response(&names));
+            }
+
             if let Some(query) = osc {
                 let rgb = match query {
                     OscQuery::ForegroundColor => {
-                        let fg = self.config.default_fg;
+                        let fg = self.default_foreground();
                         (fg.r, fg.g, fg.b)
                     }
                     OscQuery::BackgroundColor => {
-                        let bg = self.config.default_bg;
+                        let bg = self.default_background();
                         (bg.r, bg.g, bg.b)
                     }
                 };
@@ -349,56 +1256,352 @@ impl TerminalSession {
                 send(resp.as_bytes());
             }
 
-            if let Some(event) = osc133 {
-                match event {
-                    Osc133Event::OutputStart => {
-                        // Record the cursor row (1-indexed) at the command line.
-                        // Output will begin on the next row.
-                        self.osc133_output_start_row =
-                            self.terminal.cursor_position().map(|(_, row)| row);
-                    }
-                    Osc133Event::OutputEnd => {
-                        if let Some(start_row) = self.osc133_output_start_row.take() {
-                            // cursor_position() returns 1-indexed rows.
-                            // dump_viewport_row() takes 0-indexed rows.
-                            //
-                            // At OutputStart (C): cursor is at the first output line (shell
-                            // already moved cursor past the command with \r\n before emitting C).
-                            // At OutputEnd (D): cursor is on the line AFTER the last output line.
-                            //
-                            // Output rows (1-indexed): start_row ..= end_row-1
-                            // Output rows (0-indexed): (start_row-1) ..= (end_row-2)
-                            let end_row = self
-                                .terminal
-                                .cursor_position()
-                                .map(|(_, row)| row)
-                                .unwrap_or(start_row);
-                            if end_row > start_row {
-                                let first = start_row.saturating_sub(1); // 1-indexed → 0-indexed
-                                let last = end_row.saturating_sub(2); // (end_row-1) → 0-indexed
-                                self.last_command_output = self.collect_output_rows(first, last);
-                            }
-                        }
+            if let Some(selection) = clipboard_read {
+                let access = match self.clipboard_access_policy.as_mut() {
+                    Some(policy) => policy.clipboard_read(),
+                    None => ClipboardAccess::Deny,
+                };
+                match access {
+                    ClipboardAccess::Allow(text) => {
+                        send(osc_52_read_response(selection, &text).as_bytes());
                     }
+                    ClipboardAccess::Deny => {}
+                    ClipboardAccess::Prompt => self.pending_clipboard_read = Some(selection),
                 }
             }
+
+            if let Some(event) = osc133
+                && let Some(cursor) = self.terminal.cursor_position_absolute()
+                && let Some(mark) = self.prompt_marks.handle(event, cursor)
+            {
+                if let Some(rows) = mark.output_rows() {
+                    // At OutputStart (C) the cursor is on the first output line (the shell
+                    // already moved past the command line); at OutputEnd (D) it is on the line
+                    // after the last output line.
+                    self.last_command_output = self.collect_output_rows(rows);
+                }
+                self.push_event(TerminalEvent::CommandFinished(mark));
+            }
         }
 
         if seg_start < bytes.len() {
             self.terminal.feed(&bytes[seg_start..])?;
         }
+        self.raise_scanned_events();
+        self.restore_primary_scroll()?;
+        self.note_changes();
+
+        Ok(())
+    }
+
+    fn handle_kitty_graphics_command(
+        &mut self,
+        command: &[u8],
+        send: &mut impl FnMut(&[u8]),
+    ) -> Result<(), Error> {
+        let active_top_row = self.terminal.active_top_row();
+        let cursor = self
+            .terminal
+            .cursor_position_absolute()
+            .unwrap_or(AbsolutePoint {
+                row: active_top_row,
+                col: 1,
+            });
+        let ctx = KittyCommandContext {
+            cursor_col: cursor.col.saturating_sub(1),
+            cursor_row: cursor.row,
+            active_top_row,
+            cell_width_px: self.cell_pixel_size.0,
+            cell_height_px: self.cell_pixel_size.1,
+        };
+
+        let outcome = self.kitty_graphics.handle_command(command, ctx);
+        if let Some(response) = outcome.response {
+            send(&response);
+        }
+        if let Some((cols, rows)) = outcome.cursor_advance {
+            // Leave the cursor on the image's last row, just past its last column.
+            let mut movement = "\n".repeat(usize::from(rows.saturating_sub(1)));
+            movement.push_str(&format!("\x1b[{}C", cols));
+            self.terminal.feed(movement.as_bytes())?;
+        }
 
         Ok(())
     }
 
+    /// Sets the size of one cell in device pixels.
+    ///
+    /// Used to work out how many cells a kitty graphics image covers when the application does
+    /// not specify a size, and to answer XTWINOPS pixel size queries (`CSI 14 t`, `CSI 16 t`).
+    /// The view keeps this in sync with the font metrics.
+    pub fn set_cell_pixel_size(&mut self, width: u32, height: u32) {
+        self.cell_pixel_size = (width.max(1), height.max(1));
+    }
+
+    /// Returns the size of one cell in device pixels.
+    pub fn cell_pixel_size(&self) -> (u32, u32) {
+        self.cell_pixel_size
+    }
+
+    /// Limits scrollback to roughly `lines` rows, overriding
+    /// [`TerminalConfig::scrollback_lines`]. Older rows are dropped as new output arrives.
+    pub fn set_scrollback_lines(&mut self, lines: u32) {
+        self.config.scrollback_lines = Some(lines);
+        self.terminal.set_max_scrollback(lines);
+    }
+
+    /// Returns the bytes of terminal page memory in use, including scrollback, so hosts
+    /// embedding many panes can report or budget memory.
+    pub fn scrollback_memory_bytes(&self) -> usize {
+        self.terminal.memory_bytes()
+    }
+
+    /// Returns the screen-absolute row currently shown at the top of the viewport.
+    pub fn viewport_top_row(&self) -> u32 {
+        self.terminal.viewport_top_row()
+    }
+
+    pub(crate) fn kitty_graphics(&self) -> &KittyGraphicsState {
+        &self.kitty_graphics
+    }
+
+    /// Removes all kitty graphics images and placements.
+    pub fn clear_kitty_graphics(&mut self) {
+        self.kitty_graphics.clear();
+    }
+
+    /// Returns every match of `pattern` in scrollback and the active area, top to bottom.
+    ///
+    /// Matching is case-insensitive unless the pattern contains an uppercase letter.
+    pub fn search_all(&self, pattern: &str) -> Vec<SearchMatch> {
+        let case_sensitive = pattern.chars().any(char::is_uppercase);
+        self.terminal
+            .search(pattern, case_sensitive)
+            .unwrap_or_default()
+    }
+
+    /// Moves to the next match of `pattern` in `direction`, wrapping around at either end.
+    ///
+    /// The first search for a pattern starts from the viewport: `Forward` picks the first match
+    /// at or below its top row, `Backward` the last match at or above its bottom row. Later calls
+    /// continue from the previous result. Returns `None` when there are no matches.
+    pub fn search(&mut self, pattern: &str, direction: SearchDirection) -> Option<SearchMatch> {
+        let matches = self.search_all(pattern);
+        self.step_search_matches(pattern, direction, &matches)
+    }
+
+    /// Like [`search`](Self::search), but steps through `matches`, the result of
+    /// [`search_all`](Self::search_all) for `pattern`, instead of scanning the scrollback again.
+    pub(crate) fn step_search_matches(
+        &mut self,
+        pattern: &str,
+        direction: SearchDirection,
+        matches: &[SearchMatch],
+    ) -> Option<SearchMatch> {
+        if pattern != self.search_pattern {
+            self.search_pattern = pattern.to_string();
+            self.search_current = None;
+        }
+
+        let next = match (self.search_current, direction) {
+            (Some(current), SearchDirection::Forward) => {
+                matches.iter().find(|m| **m > current).or(matches.first())
+            }
+            (Some(current), SearchDirection::Backward) => matches
+                .iter()
+                .rev()
+                .find(|m| **m < current)
+                .or(matches.last()),
+            (None, SearchDirection::Forward) => {
+                let top = self.viewport_top_row();
+                matches.iter().find(|m| m.row >= top).or(matches.first())
+            }
+            (None, SearchDirection::Backward) => {
+                let bottom = self.viewport_top_row() + u32::from(self.rows());
+                matches
+                    .iter()
+                    .rev()
+                    .find(|m| m.row < bottom)
+                    .or(matches.last())
+            }
+        }
+        .copied();
+
+        self.search_current = next;
+        next
+    }
+
+    /// Returns the match selected by the last call to [`search`](Self::search).
+    pub fn current_search_match(&self) -> Option<SearchMatch> {
+        self.search_current
+    }
+
+    /// Makes `current` the current match for `pattern`, so the next [`search`](Self::search)
+    /// continues from it, e.g. after it was picked from a result list.
+    pub fn set_current_search_match(&mut self, pattern: &str, current: SearchMatch) {
+        self.search_pattern = pattern.to_string();
+        self.search_current = Some(current);
+    }
+
+    /// Forgets the current search position.
+    pub fn clear_search(&mut self) {
+        self.search_pattern.clear();
+        self.search_current = None;
+    }
+
+    /// Scrolls the viewport the minimum amount needed to show the given screen-absolute row.
+    pub fn scroll_to_row(&mut self, row: u32) -> Result<(), Error> {
+        let top = i64::from(self.viewport_top_row());
+        let rows = i64::from(self.rows());
+        let row = i64::from(row);
+
+        let delta = if row < top {
+            row - top
+        } else if row >= top + rows {
+            row - (top + rows - 1)
+        } else {
+            return Ok(());
+        };
+
+        self.scroll_viewport(delta.clamp(i64::from(i32::MIN), i64::from(i32::MAX)) as i32)
+    }
+
+    /// Translates a point in the (possibly scrolled) viewport to the active screen, the
+    /// coordinates applications use for mouse reports. Returns `None` for rows above the active
+    /// screen, i.e. in scrollback.
+    pub fn viewport_point_to_active(&self, point: ViewportPoint) -> Option<ViewportPoint> {
+        point
+            .to_absolute(self.viewport_top_row())
+            .to_viewport(self.terminal.active_top_row(), self.rows())
+    }
+
+    /// Scrolls the viewport so that screen-absolute `row` is its top row (as far as scrollback
+    /// allows).
+    pub fn scroll_row_to_top(&mut self, row: u32) -> Result<(), Error> {
+        let delta = i64::from(row) - i64::from(self.viewport_top_row());
+        self.scroll_viewport(delta.clamp(i64::from(i32::MIN), i64::from(i32::MAX)) as i32)
+    }
+
     pub fn dump_viewport(&self) -> Result<String, Error> {
         self.terminal.dump_viewport()
     }
 
+    /// Replaces `out` with the viewport's UTF-8 text, reusing its allocation.
+    pub fn dump_viewport_into(&self, out: &mut Vec<u8>) -> Result<(), Error> {
+        self.terminal.dump_viewport_into(out)
+    }
+
+    /// Returns the number of rows of history above the active area.
+    pub fn scrollback_len(&self) -> u32 {
+        self.terminal.scrollback_len()
+    }
+
+    /// Rows the viewport is scrolled back from the bottom; 0 while it follows the output.
+    pub fn scroll_offset(&self) -> u32 {
+        self.terminal
+            .active_top_row()
+            .saturating_sub(self.terminal.viewport_top_row())
+    }
+
+    /// Scrollback plus the active area: every row a scrollbar spans.
+    pub fn total_lines(&self) -> u32 {
+        self.scrollback_len() + u32::from(self.rows())
+    }
+
+    /// Dumps screen-absolute rows in `range` (scrollback first, then the active area),
+    /// independent of where the viewport is scrolled.
+    pub fn dump_scrollback(&self, range: Range<u32>) -> Result<String, Error> {
+        self.terminal.dump_scrollback(range)
+    }
+
+    /// Dumps screen-absolute rows in `range` as logical lines, joining soft-wrapped rows.
+    pub fn dump_logical_lines(&self, range: Range<u32>) -> Result<Vec<String>, Error> {
+        self.terminal.dump_logical_lines(range)
+    }
+
+    /// Whether each viewport row soft-wraps into the next one.
+    pub fn viewport_row_wraps(&self) -> Result<Vec<bool>, Error> {
+        let top = self.terminal.viewport_top_row();
+        self.terminal
+            .row_wraps(top..top.saturating_add(u32::from(self.rows())))
+    }
+
+    /// Returns the text of the screen-absolute `row`, without a trailing newline.
+    pub fn dump_screen_row(&self, row: u32) -> Result<String, Error> {
+        let mut text = self.terminal.dump_scrollback(row..row.saturating_add(1))?;
+        while text.ends_with(['\n', '\r']) {
+            text.pop();
+        }
+        Ok(text)
+    }
+
     pub fn dump_viewport_row(&self, row: u16) -> Result<String, Error> {
         self.terminal.dump_viewport_row(row)
     }
 
+    /// Replaces `out` with a viewport row's UTF-8 text, reusing its allocation.
+    pub fn dump_viewport_row_into(&self, row: u16, out: &mut Vec<u8>) -> Result<(), Error> {
+        self.terminal.dump_viewport_row_into(row, out)
+    }
+
+    /// Returns the viewport rows that changed after `generation`, for mirroring the terminal
+    /// elsewhere (a web view, a screen share) without resending unchanged rows.
+    ///
+    /// `generation` is a [`generation`](Self::generation), usually the one returned by the
+    /// previous diff; pass `0` to get every row. Rows are compared with the contents seen by the
+    /// previous call, so changes from scrolling, resizing, or theme updates are reported like
+    /// output. A resize reports every row.
+    pub fn diff_since(&mut self, generation: u64) -> Result<ViewportDiff, Error> {
+        let rows = self.config.rows;
+        if self.snapshot.cols != self.config.cols || self.snapshot.rows.len() != rows as usize {
+            self.snapshot.cols = self.config.cols;
+            self.snapshot.rows.clear();
+            self.snapshot.compared_at = None;
+        }
+
+        // Nothing changed since the last comparison: skip dumping every row.
+        let rescan = self.snapshot.compared_at != Some(self.generation);
+        self.snapshot.compared_at = Some(self.generation);
+
+        let changed_at = self.generation;
+        if rescan {
+            let mut lines = Vec::with_capacity(rows as usize);
+            let mut row_style_runs = Vec::with_capacity(rows as usize);
+            self.terminal
+                .dump_viewport_rows_into(0..rows, &mut lines, &mut row_style_runs)?;
+            for ((row, text), style_runs) in (0..rows).zip(lines).zip(row_style_runs) {
+                let current = ViewportRowDiff {
+                    row,
+                    text,
+                    style_runs,
+                };
+                match self.snapshot.rows.get_mut(row as usize) {
+                    Some((_, previous)) if *previous == current => {}
+                    Some(slot) => *slot = (changed_at, current),
+                    None => self.snapshot.rows.push((changed_at, current)),
+                }
+            }
+        }
+
+        Ok(ViewportDiff {
+            generation: self.generation,
+            cols: self.config.cols,
+            rows,
+            cursor: self
+                .terminal
+                .cursor_visible()
+                .then(|| self.terminal.cursor_position())
+                .flatten(),
+            changed_rows: self
+                .snapshot
+                .rows
+                .iter()
+                .filter(|(row_generation, _)| generation == 0 || *row_generation > generation)
+                .map(|(_, row)| row.clone())
+                .collect(),
+        })
+    }
+
     pub fn dump_viewport_row_cell_styles(
         &self,
         row: u16,
@@ -413,10 +1616,51 @@ impl TerminalSession {
         self.terminal.dump_viewport_row_style_runs(row)
     }
 
-    pub fn cursor_position(&self) -> Option<(u16, u16)> {
+    /// Dumps the text (without a trailing newline) and style runs of the viewport rows in `rows`
+    /// with one FFI call, refilling `lines` and `style_runs` in place.
+    pub fn dump_viewport_rows_into(
+        &self,
+        rows: Range<u16>,
+        lines: &mut Vec<String>,
+        style_runs: &mut Vec<Vec<ghostty_vt::StyleRun>>,
+    ) -> Result<(), Error> {
+        self.terminal
+            .dump_viewport_rows_into(rows, lines, style_runs)
+    }
+
+    /// Replaces `out` with the style runs of a viewport row, reusing its allocation.
+    pub fn dump_viewport_row_style_runs_into(
+        &self,
+        row: u16,
+        out: &mut Vec<ghostty_vt::StyleRun>,
+    ) -> Result<(), Error> {
+        self.terminal.dump_viewport_row_style_runs_into(row, out)
+    }
+
+    /// Returns the style runs of a screen-absolute row, which may be in scrollback.
+    pub fn dump_screen_row_style_runs(&self, row: u32) -> Result<Vec<ghostty_vt::StyleRun>, Error> {
+        self.terminal.dump_screen_row_style_runs(row)
+    }
+
+    /// Replaces `out` with the style runs of a screen-absolute row, reusing its allocation.
+    pub fn dump_screen_row_style_runs_into(
+        &self,
+        row: u32,
+        out: &mut Vec<ghostty_vt::StyleRun>,
+    ) -> Result<(), Error> {
+        self.terminal.dump_screen_row_style_runs_into(row, out)
+    }
+
+    pub fn cursor_position(&self) -> Option<ViewportPoint> {
         self.terminal.cursor_position()
     }
 
+    /// Returns the cursor in screen-absolute coordinates, so it can be related to scrollback rows
+    /// (search matches, prompt marks) regardless of the viewport scroll position.
+    pub fn cursor_position_absolute(&self) -> Option<AbsolutePoint> {
+        self.terminal.cursor_position_absolute()
+    }
+
     /// Returns the current cursor style (may be changed by DECSCUSR escape sequences).
     pub fn cursor_style(&self) -> CursorStyle {
         self.terminal.cursor_style()
@@ -427,11 +1671,31 @@ impl TerminalSession {
         self.terminal.cursor_blink()
     }
 
+    /// Returns true if the cursor should blink: `cursor_style_blink` when configured, otherwise
+    /// the blink state set by the application (DEC mode 12 / DECSCUSR).
+    pub fn cursor_should_blink(&self) -> bool {
+        self.config
+            .cursor_style_blink
+            .unwrap_or_else(|| self.terminal.cursor_blink())
+    }
+
     /// Returns true if cursor is visible.
     pub fn cursor_visible(&self) -> bool {
         self.terminal.cursor_visible()
     }
 
+    /// xterm modifyOtherKeys level requested with `CSI > 4 ; Pv m`: 0 off, 1 for keys that have
+    /// no distinct traditional encoding (ctrl+digit, ctrl+shift+letter), 2 for every modified key.
+    pub fn modify_other_keys(&self) -> u8 {
+        self.modify_other_keys
+    }
+
+    /// Returns the application cursor keys (DECCKM, mode 1) and application keypad (DECKPAM,
+    /// mode 66) state that key encoding has to follow.
+    pub fn key_modes(&self) -> KeyModes {
+        self.terminal.key_modes()
+    }
+
     /// Returns the configured default cursor style.
     pub fn default_cursor_style(&self) -> CursorStyle {
         self.config.cursor_style
@@ -450,35 +1714,130 @@ impl TerminalSession {
         &mut self.config
     }
 
+    /// Replaces the config with `config`, e.g. one reloaded from the config file, keeping the
+    /// live grid size, and applies the settings the terminal holds itself: colors, the default
+    /// cursor style and the scrollback limit. New `max_cols`/`max_rows` bounds apply from the
+    /// next resize, and a config without `scrollback_lines` keeps the current limit.
+    pub fn apply_config(&mut self, mut config: TerminalConfig) {
+        (config.cols, config.rows) = (self.config.cols, self.config.rows);
+        self.terminal.set_default_cursor_style(config.cursor_style);
+        if let Some(lines) = config.scrollback_lines {
+            self.terminal.set_max_scrollback(lines);
+        }
+        self.config = config;
+        self.apply_theme_colors();
+    }
+
     /// Apply the current config's theme colors to the terminal.
     ///
     /// Call this after modifying the config via `config_mut()` to update
     /// the terminal's default colors and palette.
     pub fn apply_theme_colors(&mut self) {
         self.terminal
-            .set_default_colors(self.config.default_fg, self.config.default_bg);
+            .set_default_colors(self.default_foreground(), self.default_background());
 
-        if let Some(ref palette) = self.config.palette {
-            self.terminal.set_default_palette(palette);
+        // A theme without a palette must not keep the previous theme's colors.
+        let palette = self.config.palette.unwrap_or(DEFAULT_PALETTE);
+        self.terminal.set_default_palette(&palette);
+        self.generation_fingerprint = self.change_fingerprint();
+        self.bump_generation();
+    }
+
+    /// Hard reset, as for a "Reset Terminal" menu item: clears the screen and scrollback and
+    /// restores every mode, color and the cursor, without touching the PTY or the process behind
+    /// it. Prompt marks, kitty graphics and the search position go too, and leaving the alternate
+    /// screen this way reports [`TerminalEvent::AltScreenExited`].
+    pub fn reset(&mut self) -> Result<(), Error> {
+        self.terminal.reset()?;
+        self.bracketed_paste_enabled = false;
+        self.focus_reporting_enabled = false;
+        self.mouse_x10_enabled = false;
+        self.mouse_button_event_enabled = false;
+        self.mouse_any_event_enabled = false;
+        self.mouse_sgr_enabled = false;
+        self.mouse_sgr_pixels_enabled = false;
+        self.mouse_utf8_enabled = false;
+        self.mouse_urxvt_enabled = false;
+        self.modify_other_keys = 0;
+        self.title_stack.clear();
+        if self.osc_foreground.is_some()
+            || self.osc_background.is_some()
+            || self.osc_cursor_color.is_some()
+        {
+            self.apply_osc_color_sets(vec![(10, None), (11, None), (12, None)]);
         }
+        if std::mem::take(&mut self.alt_screen_enabled) {
+            self.push_event(TerminalEvent::AltScreenExited);
+        }
+        self.primary_scroll_offset = None;
+        self.pending_scroll_restore = None;
+        self.prompt_marks.clear();
+        self.last_command_output = None;
+        self.kitty_graphics.clear();
+        self.clear_search();
+        self.note_changes();
+        Ok(())
+    }
+
+    /// Drops all scrollback while keeping the current screen (the usual cmd-k behavior). Prompt
+    /// marks and kitty graphics placements in the dropped rows go with it; the rest keep pointing
+    /// at the same text.
+    pub fn clear_scrollback(&mut self) -> Result<(), Error> {
+        let removed = self.terminal.scrollback_len();
+        self.terminal.clear_scrollback()?;
+        self.prompt_marks.remove_top_rows(removed);
+        self.kitty_graphics.remove_top_rows(removed);
+        self.clear_search();
+        self.note_changes();
+        Ok(())
+    }
+
+    /// Soft reset (DECSTR): restores modes, scroll margins, charsets and text attributes but
+    /// keeps the screen contents and scrollback, e.g. to recover from a program that exited
+    /// without undoing its terminal settings.
+    pub fn reset_soft(&mut self) -> Result<(), Error> {
+        self.terminal.reset_soft()?;
+        self.note_changes();
+        Ok(())
+    }
+
+    /// Draws host text into the grid at a 1-based active-area cell without touching the
+    /// application's cursor (see [`Terminal::write_text_at`]), e.g. for a status line.
+    pub fn write_text_at(&mut self, col: u16, row: u16, styled_text: &[u8]) -> Result<(), Error> {
+        self.terminal.write_text_at(col, row, styled_text)?;
+        self.note_changes();
+        Ok(())
     }
 
     pub fn scroll_viewport(&mut self, delta_lines: i32) -> Result<(), Error> {
-        self.terminal.scroll_viewport(delta_lines)
+        self.terminal.scroll_viewport(delta_lines)?;
+        self.note_changes();
+        Ok(())
     }
 
     pub fn scroll_viewport_top(&mut self) -> Result<(), Error> {
-        self.terminal.scroll_viewport_top()
+        self.terminal.scroll_viewport_top()?;
+        self.note_changes();
+        Ok(())
     }
 
     pub fn scroll_viewport_bottom(&mut self) -> Result<(), Error> {
-        self.terminal.scroll_viewport_bottom()
+        self.terminal.scroll_viewport_bottom()?;
+        self.note_changes();
+        Ok(())
     }
 
+    /// Resizes the grid, clamped to the config's `max_cols`/`max_rows` (and at least 1x1).
     pub fn resize(&mut self, cols: u16, rows: u16) -> Result<(), Error> {
+        let (cols, rows) = self.config.clamp_grid_size(cols, rows);
+        self.terminal.resize(cols, rows)?;
+        if (cols, rows) != (self.config.cols, self.config.rows) {
+            self.record(|recorder| recorder.resize(cols, rows));
+        }
         self.config.cols = cols;
         self.config.rows = rows;
-        self.terminal.resize(cols, rows)
+        self.note_changes();
+        Ok(())
     }
 
     pub(crate) fn take_dirty_viewport_rows(&mut self) -> Vec<u16> {
@@ -487,16 +1846,37 @@ impl TerminalSession {
             .unwrap_or_default()
     }
 
+    pub(crate) fn take_dirty_viewport_rows_into(&mut self, out: &mut Vec<u16>) {
+        let _ = self
+            .terminal
+            .take_dirty_viewport_rows_into(self.config.rows, out);
+    }
+
     pub(crate) fn take_viewport_scroll_delta(&mut self) -> i32 {
         self.terminal.take_viewport_scroll_delta()
     }
 }
 
+/// A window title change found in the output, applied by
+/// [`TerminalSession::apply_title_ops`].
+#[derive(Debug)]
+enum TitleOp {
+    Set(String),
+    Push,
+    Pop,
+}
+
 #[derive(Clone, Copy, Debug)]
 enum TerminalQuery {
     DeviceStatus,
     CursorPosition,
     PrimaryDeviceAttributes,
+    /// XTWINOPS 14: text area size in pixels.
+    TextAreaPixels,
+    /// XTWINOPS 16: cell size in pixels.
+    CellPixels,
+    /// XTWINOPS 18: text area size in characters.
+    TextAreaChars,
 }
 
 #[derive(Clone, Copy, Debug, PartialEq, Eq)]
@@ -518,6 +1898,171 @@ fn osc_color_query_response(query: OscQuery, (r, g, b): (u8, u8, u8)) -> String
     format!("\x1b]{};rgb:{:04x}/{:04x}/{:04x}\x1b\\", ps, r16, g16, b16)
 }
 
+fn osc_52_read_response(selection: u8, text: &str) -> String {
+    use base64::Engine as _;
+    use base64::engine::general_purpose::STANDARD;
+
+    format!(
+        "\x1b]52;{};{}\x1b\\",
+        char::from(selection),
+        STANDARD.encode(text)
+    )
+}
+
+/// Capabilities reported to XTGETTCAP queries, as terminfo name and value (`None` for booleans).
+const XTGETTCAP_CAPABILITIES: &[(&str, Option<&str>)] = &[
+    ("Co", Some("256")),
+    ("colors", Some("256")),
+    ("RGB", Some("8/8/8")),
+    ("Tc", None),
+    ("Ms", Some("\\E]52;%p1%s;%p2%s\\007")),
+    ("Ss", Some("\\E[%p1%d q")),
+    ("Se", Some("\\E[0 q")),
+    ("Smulx", Some("\\E[4:%p1%dm")),
+    ("setrgbf", Some("\\E[38;2;%p1%d;%p2%d;%p3%dm")),
+    ("setrgbb", Some("\\E[48;2;%p1%d;%p2%d;%p3%dm")),
+];
+
+/// Replies to an XTGETTCAP query (`DCS + q <hex name> ; ... ST`) with one
+/// `DCS 1 + r <hex name> = <hex value> ST` per known capability and `DCS 0 + r <hex name> ST`
+/// per unknown one, so applications can tell what is actually supported.
+fn xtgettcap_response(names: &[u8]) -> Vec<u8> {
+    let mut out = Vec::new();
+    for hex_name in names.split(|&b| b == b';').filter(|name| !name.is_empty()) {
+        let known = hex_decode(hex_name).and_then(|name| {
+            XTGETTCAP_CAPABILITIES
+                .iter()
+                .find(|(cap, _)| cap.as_bytes() == name.as_slice())
+        });
+        match known {
+            Some((_, value)) => {
+                out.extend_from_slice(b"\x1bP1+r");
+                out.extend_from_slice(hex_name);
+                if let Some(value) = value {
+                    out.push(b'=');
+                    out.extend_from_slice(hex_encode(value.as_bytes()).as_bytes());
+                }
+            }
+            None => {
+                out.extend_from_slice(b"\x1bP0+r");
+                out.extend_from_slice(hex_name);
+            }
+        }
+        out.extend_from_slice(b"\x1b\\");
+    }
+    out
+}
+
+fn hex_decode(hex: &[u8]) -> Option<Vec<u8>> {
+    if !hex.len().is_multiple_of(2) {
+        return None;
+    }
+    hex.chunks(2)
+        .map(|pair| u8::from_str_radix(std::str::from_utf8(pair).ok()?, 16).ok())
+        .collect()
+}
+
+fn hex_encode(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{b:02X}")).collect()
+}
+
+/// Collects the hex-encoded names of XTGETTCAP queries: `ESC P + q <names> ST`.
+#[derive(Debug, Default)]
+struct XtGetTcapScanner {
+    state: XtGetTcapScanState,
+    names: Vec<u8>,
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+enum XtGetTcapScanState {
+    #[default]
+    Idle,
+    Esc,
+    Dcs,
+    DcsPlus,
+    Names,
+    StEscape,
+}
+
+impl XtGetTcapScanner {
+    /// Longest name list kept; longer queries are dropped rather than buffered without bound.
+    const NAMES_LIMIT: usize = 1024;
+
+    fn advance(&mut self, b: u8) -> Option<Vec<u8>> {
+        use XtGetTcapScanState::*;
+
+        self.state = match (self.state, b) {
+            (StEscape, b'\\') => {
+                self.state = Idle;
+                return Some(std::mem::take(&mut self.names));
+            }
+            (Names, 0x1b) => StEscape,
+            (_, 0x1b) => Esc,
+            (Esc, b'P') => Dcs,
+            (Dcs, b'+') => DcsPlus,
+            (DcsPlus, b'q') => {
+                self.names.clear();
+                Names
+            }
+            (Names, b)
+                if self.names.len() < Self::NAMES_LIMIT && (b.is_ascii_hexdigit() || b == b';') =>
+            {
+                self.names.push(b);
+                Names
+            }
+            _ => Idle,
+        };
+        None
+    }
+}
+
+/// Detects OSC 52 clipboard reads: `ESC ] 52 ; <selection> ; ? ST`.
+///
+/// Reports the first selection character (`c` when none is given) so the reply can echo it.
+#[derive(Clone, Copy, Debug, Default)]
+enum Osc52ReadScanState {
+    #[default]
+    Idle,
+    Esc,
+    Osc,
+    Osc5,
+    Osc52,
+    Selection(Option<u8>),
+    AfterSelection(Option<u8>),
+    Query(Option<u8>),
+    StEscape(Option<u8>),
+}
+
+impl Osc52ReadScanState {
+    fn advance(&mut self, b: u8) -> Option<u8> {
+        use Osc52ReadScanState::*;
+
+        let matched = match (*self, b) {
+            (Query(selection), 0x07) | (StEscape(selection), b'\\') => {
+                Some(selection.unwrap_or(b'c'))
+            }
+            _ => None,
+        };
+
+        *self = match (*self, b) {
+            (Query(selection), 0x1b) => StEscape(selection),
+            (_, 0x1b) => Esc,
+            (Esc, b']') => Osc,
+            (Osc, b'5') => Osc5,
+            (Osc5, b'2') => Osc52,
+            (Osc52, b';') => Selection(None),
+            (Selection(selection), b';') => AfterSelection(selection),
+            (Selection(selection), c) if c.is_ascii_alphanumeric() => {
+                Selection(selection.or(Some(c)))
+            }
+            (AfterSelection(selection), b'?') => Query(selection),
+            _ => Idle,
+        };
+
+        matched
+    }
+}
+
 #[derive(Clone, Copy, Debug, Default)]
 enum DsrScanState {
     #[default]
@@ -530,6 +2075,10 @@ enum DsrScanState {
     Csi6,
     CsiQ6,
     Csi0, // For DA1: ESC [ 0 c
+    Csi1,
+    Csi14,
+    Csi16,
+    Csi18,
 }
 
 impl DsrScanState {
@@ -541,6 +2090,10 @@ impl DsrScanState {
             (Csi6, b'n') | (CsiQ6, b'n') => Some(TerminalQuery::CursorPosition),
             // DA1: ESC [ c or ESC [ 0 c
             (Csi, b'c') | (Csi0, b'c') => Some(TerminalQuery::PrimaryDeviceAttributes),
+            // XTWINOPS size reports: ESC [ 14 t, ESC [ 16 t, ESC [ 18 t
+            (Csi14, b't') => Some(TerminalQuery::TextAreaPixels),
+            (Csi16, b't') => Some(TerminalQuery::CellPixels),
+            (Csi18, b't') => Some(TerminalQuery::TextAreaChars),
             _ => None,
         };
 
@@ -553,6 +2106,10 @@ impl DsrScanState {
             (Csi, b'6') => Csi6,
             (CsiQ, b'6') => CsiQ6,
             (Csi, b'0') => Csi0,
+            (Csi, b'1') => Csi1,
+            (Csi1, b'4') => Csi14,
+            (Csi1, b'6') => Csi16,
+            (Csi1, b'8') => Csi18,
             (Csi5, b'n') => Idle,
             (CsiQ5, b'n') => Idle,
             (Csi6, b'n') => Idle,
@@ -637,75 +2194,165 @@ fn value_to_after_semicolon_state(ps: u32) -> OscQueryScanState {
     }
 }
 
-#[derive(Clone, Copy, Debug)]
-enum Osc133Event {
-    OutputStart,
-    OutputEnd,
+/// Parses an OSC 7 working directory report (`file://host/path`, or kitty's
+/// `kitty-shell-cwd://host/path`) into a local path. The host part is ignored.
+fn parse_osc_7(payload: &[u8]) -> Option<String> {
+    let uri = std::str::from_utf8(payload).ok()?;
+    let (scheme, rest) = uri.split_once("://")?;
+    if !matches!(scheme, "file" | "kitty-shell-cwd") {
+        return None;
+    }
+    let path = &rest[rest.find('/')?..];
+    percent_decode(path)
 }
 
-/// Byte-level state machine that detects OSC 133;C and OSC 133;D sequences.
-///
-/// OSC 133;C ST — command output start (shell integration)
-/// OSC 133;D [;exit_code] ST — command output end
-///
-/// ST is either BEL (0x07) or ESC \ (0x1b 0x5c).
-#[derive(Clone, Copy, Debug, Default)]
-enum Osc133ScanState {
-    #[default]
-    Idle,
-    Esc,
-    OscOpen,
-    Osc1,
-    Osc13,
-    Osc133,
-    Osc133Semi,
-    WaitTerm(Osc133Event),
-    SkipToTerm(Osc133Event),
-    StEsc(Osc133Event),
-}
-
-impl Osc133ScanState {
-    fn advance(&mut self, b: u8) -> Option<Osc133Event> {
-        use Osc133Event::*;
-        use Osc133ScanState::*;
-
-        let (next, event): (Self, Option<Osc133Event>) = match (*self, b) {
-            // ESC in terminal states: may start ST (ESC \)
-            (WaitTerm(ev), 0x1b) | (SkipToTerm(ev), 0x1b) => (StEsc(ev), None),
-
-            // ESC always (re)starts an OSC sequence
-            (_, 0x1b) => (Esc, None),
-
-            // BEL terminates — fire event
-            (WaitTerm(ev), 0x07) | (SkipToTerm(ev), 0x07) => (Idle, Some(ev)),
-
-            // ST second byte (\ after ESC) — fire event
-            (StEsc(ev), b'\\') => (Idle, Some(ev)),
-
-            // Normal sequence progression
-            (Esc, b']') => (OscOpen, None),
-            (OscOpen, b'1') => (Osc1, None),
-            (Osc1, b'3') => (Osc13, None),
-            (Osc13, b'3') => (Osc133, None),
-            (Osc133, b';') => (Osc133Semi, None),
-            (Osc133Semi, b'C') => (WaitTerm(OutputStart), None),
-            (Osc133Semi, b'D') => (WaitTerm(OutputEnd), None),
-
-            // OSC 133;D may be followed by ;exit_code — skip until terminator
-            (WaitTerm(OutputEnd), b';') => (SkipToTerm(OutputEnd), None),
-
-            // In SkipToTerm: consume all bytes until terminator (handled above)
-            (SkipToTerm(ev), _) => (SkipToTerm(ev), None),
-
-            // StEsc followed by anything other than '\\' resets
-            (StEsc(_), _) => (Idle, None),
-
-            // Any other byte resets
-            _ => (Idle, None),
-        };
+fn percent_decode(text: &str) -> Option<String> {
+    let bytes = text.as_bytes();
+    let mut out = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        if bytes[i] == b'%' {
+            let hex = std::str::from_utf8(bytes.get(i + 1..i + 3)?).ok()?;
+            out.push(u8::from_str_radix(hex, 16).ok()?);
+            i += 3;
+        } else {
+            out.push(bytes[i]);
+            i += 1;
+        }
+    }
+    String::from_utf8(out).ok()
+}
+
+/// An iTerm2 OSC 1337 report understood by the session.
+#[derive(Clone, Debug, PartialEq, Eq)]
+enum Osc1337 {
+    /// `SetUserVar=name=<base64 value>`
+    SetUserVar { name: String, value: String },
+    /// `CurrentDir=/path`, an alternative to OSC 7.
+    CurrentDir(String),
+    /// `RemoteHost=user@host`
+    RemoteHost(String),
+    /// `SetBadgeFormat=<base64 format>`; an empty format removes the badge.
+    SetBadgeFormat(String),
+}
 
-        *self = next;
-        event
+/// Parses the OSC 1337 keys used by shell integrations. Other keys (inline images, marks) are
+/// ignored.
+fn parse_osc_1337(payload: &[u8]) -> Option<Osc1337> {
+    use base64::Engine as _;
+    use base64::engine::general_purpose::STANDARD;
+
+    let payload = std::str::from_utf8(payload).ok()?;
+    let (key, value) = payload.split_once('=')?;
+    match key {
+        "SetUserVar" => {
+            let (name, encoded) = value.split_once('=')?;
+            if name.is_empty() {
+                return None;
+            }
+            let decoded = STANDARD.decode(encoded).ok()?;
+            Some(Osc1337::SetUserVar {
+                name: name.to_string(),
+                value: String::from_utf8(decoded).ok()?,
+            })
+        }
+        "SetBadgeFormat" => {
+            let decoded = STANDARD.decode(value).ok()?;
+            Some(Osc1337::SetBadgeFormat(String::from_utf8(decoded).ok()?))
+        }
+        "CurrentDir" if value.starts_with('/') => Some(Osc1337::CurrentDir(value.to_string())),
+        "RemoteHost" if !value.is_empty() => Some(Osc1337::RemoteHost(value.to_string())),
+        _ => None,
+    }
+}
+
+/// Parses OSC 10/11/12 color sets into `(target, color)` pairs, and OSC 110/111/112 into
+/// `(target, None)` resets. Like xterm, each extra `;`-separated color moves on to the next
+/// target, so `OSC 10 ; fg ; bg` sets both. Queries (`?`) are answered elsewhere.
+fn parse_osc_color_sets(ps: u32, payload: &[u8]) -> Vec<(u32, Option<Rgb>)> {
+    if ps >= 110 {
+        return vec![(ps - 100, None)];
+    }
+    let payload = String::from_utf8_lossy(payload);
+    payload
+        .split(';')
+        .zip(ps..=12)
+        .filter(|(spec, _)| *spec != "?")
+        .filter_map(|(spec, target)| Some((target, Some(parse_x_color(spec)?))))
+        .collect()
+}
+
+/// Parses an X11 color spec as used by OSC color sequences: `rgb:r/g/b` with 1-4 hex digits
+/// per channel, or `#rgb`, `#rrggbb`, `#rrrgggbbb` and `#rrrrggggbbbb`.
+fn parse_x_color(spec: &str) -> Option<Rgb> {
+    fn channel(hex: &str) -> Option<u8> {
+        if hex.is_empty() || hex.len() > 4 {
+            return None;
+        }
+        let value = u32::from_str_radix(hex, 16).ok()?;
+        let max = (1u32 << (4 * hex.len())) - 1;
+        Some((value * 255 / max) as u8)
+    }
+
+    if let Some(rest) = spec.strip_prefix("rgb:") {
+        let mut parts = rest.split('/');
+        let (r, g, b) = (parts.next()?, parts.next()?, parts.next()?);
+        if parts.next().is_some() {
+            return None;
+        }
+        return Some(Rgb {
+            r: channel(r)?,
+            g: channel(g)?,
+            b: channel(b)?,
+        });
+    }
+
+    let hex = spec.strip_prefix('#')?;
+    if hex.is_empty() || !hex.len().is_multiple_of(3) || hex.len() > 12 || !hex.is_ascii() {
+        return None;
+    }
+    // `#` forms give the most significant bits of each channel.
+    let digits = hex.len() / 3;
+    let high = |i: usize| {
+        let part = &hex[i * digits..(i + 1) * digits];
+        let value = u32::from_str_radix(part, 16).ok()?;
+        Some(if digits == 1 {
+            (value * 0x11) as u8
+        } else {
+            (value >> (4 * (digits - 2))) as u8
+        })
+    };
+    Some(Rgb {
+        r: high(0)?,
+        g: high(1)?,
+        b: high(2)?,
+    })
+}
+
+/// Parses OSC 9 (`body`) and OSC 777 (`notify;title;body`) desktop notifications. OSC 9;4
+/// progress reports are not notifications and are ignored.
+fn parse_osc_notification(ps: u32, payload: &[u8]) -> Option<TerminalEvent> {
+    let payload = String::from_utf8_lossy(payload);
+    match ps {
+        9 if !payload.starts_with("4;") && !payload.is_empty() => {
+            Some(TerminalEvent::Notification {
+                title: None,
+                body: payload.into_owned(),
+            })
+        }
+        777 => {
+            let mut parts = payload.splitn(3, ';');
+            if parts.next()? != "notify" {
+                return None;
+            }
+            let title = parts.next()?.to_string();
+            let body = parts.next().unwrap_or_default().to_string();
+            Some(TerminalEvent::Notification {
+                title: Some(title),
+                body,
+            })
+        }
+        _ => None,
     }
 }
 
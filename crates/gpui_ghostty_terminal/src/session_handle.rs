@@ -0,0 +1,232 @@
+//! Sessions that outlive their views.
+//!
+//! A [`TerminalSessionHandle`] follows one terminal from view to view: while a
+//! [`TerminalView`] shows it, output fed through the handle is queued on the view; once the view
+//! is dropped (its tab closed, say) the session, its PTY input and resize callback move into the
+//! handle and output keeps being parsed there. [`TerminalSessionHandle::attach`] later builds a
+//! new view around the same session, scrollback and all. [`TerminalSessionRegistry`] keeps the
+//! handles of an app so background terminals can be listed and brought back.
+
+use std::cell::RefCell;
+use std::collections::BTreeMap;
+use std::rc::Rc;
+
+use gpui::{App, AppContext, Entity, WeakEntity};
+
+use crate::TerminalSession;
+use crate::view::{TerminalInput, TerminalResizeCallback, TerminalView};
+
+/// Identifies a session in a [`TerminalSessionRegistry`]; stable across reattachment.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct TerminalSessionId(u64);
+
+/// The parts of a view that keep running while no view shows the session.
+pub(crate) struct DetachedSession {
+    pub(crate) session: TerminalSession,
+    pub(crate) input: Option<TerminalInput>,
+    pub(crate) resize_callback: Option<TerminalResizeCallback>,
+}
+
+impl DetachedSession {
+    /// Parse output with no view attached, still answering the application's queries.
+    pub(crate) fn feed(&mut self, bytes: &[u8]) {
+        if let Some(input) = self.input.as_ref() {
+            let _ = self.session.feed_with_pty_responses(bytes, |reply| {
+                input.send(reply);
+            });
+        } else {
+            let _ = self.session.feed(bytes);
+        }
+    }
+}
+
+enum HandleState {
+    Attached(WeakEntity<TerminalView>),
+    Detached(Box<DetachedSession>),
+    /// The view was dropped before its session could be taken, or the handle was closed.
+    Closed,
+}
+
+/// A terminal session that can be detached from its view and reattached to a new one.
+///
+/// Feed PTY output through [`Self::feed_output`] instead of the view, so it reaches the session
+/// whether or not a view is attached. Cloning gives another handle to the same session.
+#[derive(Clone)]
+pub struct TerminalSessionHandle {
+    id: TerminalSessionId,
+    state: Rc<RefCell<HandleState>>,
+}
+
+impl TerminalSessionHandle {
+    fn new(id: TerminalSessionId, view: &Entity<TerminalView>, cx: &mut App) -> Self {
+        let handle = Self {
+            id,
+            state: Rc::new(RefCell::new(HandleState::Closed)),
+        };
+        handle.track(view, cx);
+        handle
+    }
+
+    /// Follow `view`, taking its session when the view is released.
+    fn track(&self, view: &Entity<TerminalView>, cx: &mut App) {
+        *self.state.borrow_mut() = HandleState::Attached(view.downgrade());
+        let state = Rc::downgrade(&self.state);
+        let view_id = view.entity_id();
+        cx.observe_release(view, move |view, _cx| {
+            let Some(state) = state.upgrade() else {
+                return;
+            };
+            let mut state = state.borrow_mut();
+            let tracked =
+                matches!(&*state, HandleState::Attached(weak) if weak.entity_id() == view_id);
+            if tracked {
+                *state = match view.take_session() {
+                    Some(detached) => HandleState::Detached(Box::new(detached)),
+                    None => HandleState::Closed,
+                };
+            }
+        })
+        .detach();
+    }
+
+    pub fn id(&self) -> TerminalSessionId {
+        self.id
+    }
+
+    /// The view showing the session, if one is attached.
+    pub fn view(&self) -> Option<Entity<TerminalView>> {
+        match &*self.state.borrow() {
+            HandleState::Attached(view) => view.upgrade(),
+            _ => None,
+        }
+    }
+
+    pub fn is_attached(&self) -> bool {
+        self.view().is_some()
+    }
+
+    pub fn is_detached(&self) -> bool {
+        matches!(&*self.state.borrow(), HandleState::Detached(_))
+    }
+
+    /// Whether the session is gone for good.
+    pub fn is_closed(&self) -> bool {
+        match &*self.state.borrow() {
+            HandleState::Attached(view) => view.upgrade().is_none(),
+            HandleState::Detached(_) => false,
+            HandleState::Closed => true,
+        }
+    }
+
+    /// The session's title (OSC 0/2), attached or not.
+    pub fn title(&self, cx: &App) -> Option<String> {
+        match &*self.state.borrow() {
+            HandleState::Attached(view) => view
+                .upgrade()
+                .and_then(|view| view.read(cx).session().title().map(str::to_string)),
+            HandleState::Detached(detached) => detached.session.title().map(str::to_string),
+            HandleState::Closed => None,
+        }
+    }
+
+    /// Deliver PTY output: queued on the attached view, or parsed straight into the session
+    /// while detached. Dropped once the session is closed.
+    pub fn feed_output(&self, bytes: &[u8], cx: &mut App) {
+        let view = match &mut *self.state.borrow_mut() {
+            HandleState::Attached(view) => view.clone(),
+            HandleState::Detached(detached) => {
+                detached.feed(bytes);
+                return;
+            }
+            HandleState::Closed => return,
+        };
+        view.update(cx, |view, cx| view.queue_output_bytes(bytes, cx))
+            .ok();
+    }
+
+    /// Take the session out of its view now, without waiting for the view to be dropped. The
+    /// view is left showing an empty terminal. Returns `false` if no view was attached.
+    pub fn detach(&self, cx: &mut App) -> bool {
+        let Some(view) = self.view() else {
+            return false;
+        };
+        let detached = view.update(cx, |view, cx| {
+            let detached = view.take_session();
+            cx.notify();
+            detached
+        });
+        *self.state.borrow_mut() = match detached {
+            Some(detached) => HandleState::Detached(Box::new(detached)),
+            None => HandleState::Closed,
+        };
+        true
+    }
+
+    /// Show a detached session in a new view. Returns the attached view unchanged if there is
+    /// one, and `None` once the session is closed.
+    pub fn attach(&self, cx: &mut App) -> Option<Entity<TerminalView>> {
+        if let Some(view) = self.view() {
+            return Some(view);
+        }
+        let state = std::mem::replace(&mut *self.state.borrow_mut(), HandleState::Closed);
+        let HandleState::Detached(detached) = state else {
+            return None;
+        };
+        let view = cx.new(|cx| TerminalView::from_detached(*detached, cx.focus_handle()));
+        self.track(&view, cx);
+        Some(view)
+    }
+
+    /// Drop the session (when detached) and stop following its view.
+    pub fn close(&self) {
+        *self.state.borrow_mut() = HandleState::Closed;
+    }
+}
+
+/// The sessions of an app, attached or running in the background.
+#[derive(Default)]
+pub struct TerminalSessionRegistry {
+    next_id: u64,
+    sessions: BTreeMap<TerminalSessionId, TerminalSessionHandle>,
+}
+
+impl TerminalSessionRegistry {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Start tracking the session shown by `view`; dropping the view detaches it.
+    pub fn register(&mut self, view: &Entity<TerminalView>, cx: &mut App) -> TerminalSessionHandle {
+        self.next_id += 1;
+        let id = TerminalSessionId(self.next_id);
+        let handle = TerminalSessionHandle::new(id, view, cx);
+        self.sessions.insert(id, handle.clone());
+        handle
+    }
+
+    pub fn get(&self, id: TerminalSessionId) -> Option<&TerminalSessionHandle> {
+        self.sessions.get(&id)
+    }
+
+    /// Every live session, in registration order.
+    pub fn sessions(&self) -> impl Iterator<Item = &TerminalSessionHandle> {
+        self.sessions.values().filter(|handle| !handle.is_closed())
+    }
+
+    /// Sessions running with no view attached.
+    pub fn detached(&self) -> impl Iterator<Item = &TerminalSessionHandle> {
+        self.sessions.values().filter(|handle| handle.is_detached())
+    }
+
+    /// Close a session and forget it.
+    pub fn remove(&mut self, id: TerminalSessionId) -> Option<TerminalSessionHandle> {
+        let handle = self.sessions.remove(&id)?;
+        handle.close();
+        Some(handle)
+    }
+
+    /// Forget sessions that were closed or lost their view without being detached.
+    pub fn prune(&mut self) {
+        self.sessions.retain(|_, handle| !handle.is_closed());
+    }
+}
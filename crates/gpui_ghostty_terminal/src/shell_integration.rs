@@ -0,0 +1,294 @@
+use std::ops::Range;
+
+use ghostty_vt::AbsolutePoint;
+
+/// Maximum number of prompt marks kept; the oldest are dropped first.
+const MAX_PROMPT_MARKS: usize = 1000;
+
+/// One prompt/command cycle recorded from OSC 133 shell integration markers.
+///
+/// Rows are screen-absolute (0 = oldest scrollback row).
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct PromptMark {
+    /// Row the prompt was drawn on (OSC 133;A).
+    pub prompt_row: u32,
+    /// Where command input begins, right after the prompt (OSC 133;B).
+    pub command_start: Option<AbsolutePoint>,
+    /// First row of command output (OSC 133;C).
+    pub output_start_row: Option<u32>,
+    /// Row the cursor was on when the command finished (OSC 133;D); output ends above it.
+    pub output_end_row: Option<u32>,
+    /// Exit status reported with OSC 133;D, if the shell sent one.
+    pub exit_code: Option<i32>,
+}
+
+impl PromptMark {
+    fn new(prompt_row: u32) -> Self {
+        Self {
+            prompt_row,
+            command_start: None,
+            output_start_row: None,
+            output_end_row: None,
+            exit_code: None,
+        }
+    }
+
+    pub fn is_finished(&self) -> bool {
+        self.output_end_row.is_some()
+    }
+
+    /// Returns true if the command finished with a non-zero exit status.
+    pub fn failed(&self) -> bool {
+        matches!(self.exit_code, Some(code) if code != 0)
+    }
+
+    /// Rows holding the command's output, once it has finished.
+    pub fn output_rows(&self) -> Option<Range<u32>> {
+        let start = self.output_start_row?;
+        let end = self.output_end_row?;
+        Some(start..end.max(start))
+    }
+
+    /// Rows from the prompt through the end of the output (or just the prompt row while the
+    /// command is still running).
+    pub fn rows(&self) -> Range<u32> {
+        let end = self.output_end_row.unwrap_or(self.prompt_row + 1);
+        self.prompt_row..end.max(self.prompt_row + 1)
+    }
+}
+
+/// Prompt marks in the order the shell emitted them.
+#[derive(Default)]
+pub(crate) struct PromptMarks {
+    marks: Vec<PromptMark>,
+}
+
+impl PromptMarks {
+    pub(crate) fn marks(&self) -> &[PromptMark] {
+        &self.marks
+    }
+
+    pub(crate) fn clear(&mut self) {
+        self.marks.clear();
+    }
+
+    /// Forgets marks whose prompt was in the first `rows` screen rows and moves the rest up, after
+    /// those rows were removed from the top of the screen.
+    pub(crate) fn remove_top_rows(&mut self, rows: u32) {
+        self.marks.retain(|mark| mark.prompt_row >= rows);
+        for mark in &mut self.marks {
+            mark.prompt_row -= rows;
+            if let Some(start) = mark.command_start.as_mut() {
+                start.row = start.row.saturating_sub(rows);
+            }
+            for row in [&mut mark.output_start_row, &mut mark.output_end_row]
+                .into_iter()
+                .flatten()
+            {
+                *row = row.saturating_sub(rows);
+            }
+        }
+    }
+
+    /// Records `event` at the cursor position. Returns the mark when a command finishes.
+    pub(crate) fn handle(
+        &mut self,
+        event: Osc133Event,
+        cursor: AbsolutePoint,
+    ) -> Option<PromptMark> {
+        // Shells may skip markers (e.g. no A before C); start a new mark whenever the current
+        // one has already passed the stage being reported.
+        match event {
+            Osc133Event::PromptStart => self.push(PromptMark::new(cursor.row)),
+            Osc133Event::CommandStart => match self.open_mark() {
+                Some(mark) if mark.command_start.is_none() && mark.output_start_row.is_none() => {
+                    mark.command_start = Some(cursor);
+                }
+                _ => {
+                    let mut mark = PromptMark::new(cursor.row);
+                    mark.command_start = Some(cursor);
+                    self.push(mark);
+                }
+            },
+            Osc133Event::OutputStart => match self.open_mark() {
+                Some(mark) if mark.output_start_row.is_none() => {
+                    mark.output_start_row = Some(cursor.row);
+                }
+                _ => {
+                    let mut mark = PromptMark::new(cursor.row);
+                    mark.output_start_row = Some(cursor.row);
+                    self.push(mark);
+                }
+            },
+            Osc133Event::OutputEnd(exit_code) => {
+                let mark = self.open_mark()?;
+                mark.output_end_row = Some(cursor.row);
+                mark.exit_code = exit_code;
+                return Some(*mark);
+            }
+        }
+        None
+    }
+
+    fn open_mark(&mut self) -> Option<&mut PromptMark> {
+        self.marks.last_mut().filter(|mark| !mark.is_finished())
+    }
+
+    fn push(&mut self, mark: PromptMark) {
+        if self.marks.len() >= MAX_PROMPT_MARKS {
+            self.marks.remove(0);
+        }
+        self.marks.push(mark);
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) enum Osc133Event {
+    PromptStart,
+    CommandStart,
+    OutputStart,
+    OutputEnd(Option<i32>),
+}
+
+/// Byte-level state machine that detects OSC 133 shell integration sequences.
+///
+/// OSC 133;A ST — prompt start
+/// OSC 133;B ST — command input start (end of prompt)
+/// OSC 133;C ST — command output start
+/// OSC 133;D [;exit_code] ST — command output end
+///
+/// ST is either BEL (0x07) or ESC \ (0x1b 0x5c). Any further `;key=value` options are ignored.
+#[derive(Clone, Copy, Debug, Default)]
+pub(crate) enum Osc133ScanState {
+    #[default]
+    Idle,
+    Esc,
+    OscOpen,
+    Osc1,
+    Osc13,
+    Osc133,
+    Osc133Semi,
+    WaitTerm(Osc133Event),
+    ExitCode(Option<i32>),
+    SkipToTerm(Osc133Event),
+    StEsc(Osc133Event),
+}
+
+impl Osc133ScanState {
+    pub(crate) fn advance(&mut self, b: u8) -> Option<Osc133Event> {
+        use Osc133Event::*;
+        use Osc133ScanState::*;
+
+        let (next, event): (Self, Option<Osc133Event>) = match (*self, b) {
+            // ESC in terminal states: may start ST (ESC \)
+            (WaitTerm(ev), 0x1b) | (SkipToTerm(ev), 0x1b) => (StEsc(ev), None),
+            (ExitCode(code), 0x1b) => (StEsc(OutputEnd(code)), None),
+
+            // ESC always (re)starts an OSC sequence
+            (_, 0x1b) => (Esc, None),
+
+            // BEL terminates — fire event
+            (WaitTerm(ev), 0x07) | (SkipToTerm(ev), 0x07) => (Idle, Some(ev)),
+            (ExitCode(code), 0x07) => (Idle, Some(OutputEnd(code))),
+
+            // ST second byte (\ after ESC) — fire event
+            (StEsc(ev), b'\\') => (Idle, Some(ev)),
+
+            // Normal sequence progression
+            (Esc, b']') => (OscOpen, None),
+            (OscOpen, b'1') => (Osc1, None),
+            (Osc1, b'3') => (Osc13, None),
+            (Osc13, b'3') => (Osc133, None),
+            (Osc133, b';') => (Osc133Semi, None),
+            (Osc133Semi, b'A') => (WaitTerm(PromptStart), None),
+            (Osc133Semi, b'B') => (WaitTerm(CommandStart), None),
+            (Osc133Semi, b'C') => (WaitTerm(OutputStart), None),
+            (Osc133Semi, b'D') => (WaitTerm(OutputEnd(None)), None),
+
+            // OSC 133;D may be followed by ;exit_code
+            (WaitTerm(OutputEnd(_)), b';') => (ExitCode(None), None),
+            (ExitCode(code), b'0'..=b'9') => {
+                let digit = i32::from(b - b'0');
+                let code = code.unwrap_or(0).saturating_mul(10).saturating_add(digit);
+                (ExitCode(Some(code)), None)
+            }
+            (ExitCode(code), _) => (SkipToTerm(OutputEnd(code)), None),
+
+            // Options (e.g. `;aid=…`) — skip until terminator
+            (WaitTerm(ev), b';') => (SkipToTerm(ev), None),
+
+            // In SkipToTerm: consume all bytes until terminator (handled above)
+            (SkipToTerm(ev), _) => (SkipToTerm(ev), None),
+
+            // StEsc followed by anything other than '\\' resets
+            (StEsc(_), _) => (Idle, None),
+
+            // Any other byte resets
+            _ => (Idle, None),
+        };
+
+        *self = next;
+        event
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn scan(bytes: &[u8]) -> Vec<Osc133Event> {
+        let mut state = Osc133ScanState::default();
+        bytes.iter().filter_map(|&b| state.advance(b)).collect()
+    }
+
+    #[test]
+    fn scanner_reports_all_markers_and_exit_codes() {
+        assert_eq!(
+            scan(
+                b"\x1b]133;A\x07$ \x1b]133;B;aid=1\x1b\\ls\r\n\x1b]133;C\x07out\x1b]133;D;127\x07"
+            ),
+            [
+                Osc133Event::PromptStart,
+                Osc133Event::CommandStart,
+                Osc133Event::OutputStart,
+                Osc133Event::OutputEnd(Some(127)),
+            ]
+        );
+        assert_eq!(
+            scan(b"\x1b]133;D\x07\x1b]133;D;0;aid=1\x07"),
+            [
+                Osc133Event::OutputEnd(None),
+                Osc133Event::OutputEnd(Some(0))
+            ]
+        );
+    }
+
+    #[test]
+    fn marks_follow_prompt_command_output_cycle() {
+        let at = |row| AbsolutePoint { row, col: 1 };
+        let mut marks = PromptMarks::default();
+
+        assert_eq!(marks.handle(Osc133Event::PromptStart, at(0)), None);
+        marks.handle(Osc133Event::CommandStart, AbsolutePoint { row: 0, col: 3 });
+        marks.handle(Osc133Event::OutputStart, at(1));
+        let finished = marks
+            .handle(Osc133Event::OutputEnd(Some(1)), at(4))
+            .unwrap();
+
+        assert_eq!(finished.prompt_row, 0);
+        assert_eq!(
+            finished.command_start,
+            Some(AbsolutePoint { row: 0, col: 3 })
+        );
+        assert_eq!(finished.output_rows(), Some(1..4));
+        assert!(finished.failed());
+        assert_eq!(finished.rows(), 0..4);
+
+        // A stray D with no open mark is ignored; C without A starts a new mark.
+        assert_eq!(marks.handle(Osc133Event::OutputEnd(Some(0)), at(5)), None);
+        marks.handle(Osc133Event::OutputStart, at(5));
+        assert_eq!(marks.marks().len(), 2);
+        assert_eq!(marks.marks()[1].prompt_row, 5);
+        assert!(!marks.marks()[1].is_finished());
+    }
+}
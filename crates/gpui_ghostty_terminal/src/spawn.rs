@@ -0,0 +1,206 @@
+//! What to run on a PTY: program, arguments, working directory and environment.
+//!
+//! The crate does not own the PTY; hosts turn a [`SpawnOptions`] into their PTY library's
+//! command (e.g. `portable_pty::CommandBuilder`):
+//!
+//! ```ignore
+//! let options = SpawnOptions::from_config(&config);
+//! let (program, args) = options.program_and_args();
+//! let mut cmd = CommandBuilder::new(program);
+//! cmd.args(args);
+//! if let Some(cwd) = options.working_dir() {
+//!     cmd.cwd(cwd);
+//! }
+//! for (key, value) in options.environment() {
+//!     cmd.env(key, value);
+//! }
+//! ```
+
+use std::path::{Path, PathBuf};
+
+use crate::TerminalConfig;
+
+/// Shell used when neither the options nor `$SHELL` name one.
+#[cfg(target_os = "macos")]
+const FALLBACK_SHELL: &str = "/bin/zsh";
+#[cfg(not(target_os = "macos"))]
+const FALLBACK_SHELL: &str = "/bin/sh";
+
+/// How to start the process behind a terminal.
+///
+/// Built up with chained setters; the accessors resolve defaults (the user's shell, a login
+/// shell flag, terminal environment variables) so every host spawns the same way.
+#[derive(Clone, Debug)]
+pub struct SpawnOptions {
+    program: Option<String>,
+    args: Vec<String>,
+    cwd: Option<PathBuf>,
+    env: Vec<(String, String)>,
+    login_shell: bool,
+}
+
+impl Default for SpawnOptions {
+    fn default() -> Self {
+        Self {
+            program: None,
+            args: Vec::new(),
+            cwd: None,
+            env: Vec::new(),
+            login_shell: true,
+        }
+    }
+}
+
+impl SpawnOptions {
+    /// The user's shell as a login shell, in the host's working directory.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Options for the config's `command` and `working-directory`. A command with arguments
+    /// (`command = nvim -u NONE`) runs as written; a bare program is started as a login shell.
+    pub fn from_config(config: &TerminalConfig) -> Self {
+        let mut options = Self::new();
+        if let Some(command) = config.command.as_deref() {
+            let mut parts = command.split_whitespace();
+            if let Some(program) = parts.next() {
+                options = options.program(program).args(parts);
+            }
+        }
+        if let Some(cwd) = config.working_directory.clone() {
+            options = options.cwd(cwd);
+        }
+        options
+    }
+
+    /// Run `program` instead of the user's shell.
+    pub fn program(mut self, program: impl Into<String>) -> Self {
+        self.program = Some(program.into());
+        self
+    }
+
+    pub fn arg(mut self, arg: impl Into<String>) -> Self {
+        self.args.push(arg.into());
+        self
+    }
+
+    pub fn args<I, S>(mut self, args: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        self.args.extend(args.into_iter().map(Into::into));
+        self
+    }
+
+    /// Start in `cwd` instead of the host's working directory.
+    pub fn cwd(mut self, cwd: impl Into<PathBuf>) -> Self {
+        self.cwd = Some(cwd.into());
+        self
+    }
+
+    /// Set an environment variable, overriding the defaults from [`Self::environment`].
+    pub fn env(mut self, key: impl Into<String>, value: impl Into<String>) -> Self {
+        let key = key.into();
+        self.env.retain(|(existing, _)| *existing != key);
+        self.env.push((key, value.into()));
+        self
+    }
+
+    /// Pass `-l` so the shell reads its login profile. On by default; only applies when no
+    /// arguments were given.
+    pub fn login_shell(mut self, login_shell: bool) -> Self {
+        self.login_shell = login_shell;
+        self
+    }
+
+    /// The program to run and its arguments: the configured program or `$SHELL`, with `-l` for
+    /// a login shell.
+    pub fn program_and_args(&self) -> (String, Vec<String>) {
+        let program = self.program.clone().unwrap_or_else(|| {
+            std::env::var("SHELL")
+                .ok()
+                .filter(|shell| !shell.is_empty())
+                .unwrap_or_else(|| FALLBACK_SHELL.to_string())
+        });
+        let mut args = self.args.clone();
+        if args.is_empty() && self.login_shell {
+            args.push("-l".to_string());
+        }
+        (program, args)
+    }
+
+    /// The directory to start in, if not the host's.
+    pub fn working_dir(&self) -> Option<&Path> {
+        self.cwd.as_deref()
+    }
+
+    /// Variables to set on top of the inherited environment. `TERM`, `COLORTERM` and
+    /// `TERM_PROGRAM` describe this terminal rather than whatever the host was started from;
+    /// [`Self::env`] entries come last and win.
+    pub fn environment(&self) -> Vec<(String, String)> {
+        let mut env: Vec<(String, String)> = [
+            ("TERM", "xterm-256color"),
+            ("COLORTERM", "truecolor"),
+            ("TERM_PROGRAM", "gpui-ghostty"),
+            ("TERM_PROGRAM_VERSION", env!("CARGO_PKG_VERSION")),
+        ]
+        .into_iter()
+        .filter(|(key, _)| !self.env.iter().any(|(set, _)| set == key))
+        .map(|(key, value)| (key.to_string(), value.to_string()))
+        .collect();
+        env.extend(self.env.iter().cloned());
+        env
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn bare_programs_start_as_login_shells() {
+        let (program, args) = SpawnOptions::new().program("/bin/fish").program_and_args();
+        assert_eq!(program, "/bin/fish");
+        assert_eq!(args, ["-l"]);
+
+        let options = SpawnOptions::new().program("/bin/bash").login_shell(false);
+        assert!(options.program_and_args().1.is_empty());
+    }
+
+    #[test]
+    fn config_command_and_working_directory_are_used() {
+        let config = TerminalConfig {
+            command: Some("nvim -u NONE".to_string()),
+            working_directory: Some(PathBuf::from("/tmp")),
+            ..TerminalConfig::default()
+        };
+        let options = SpawnOptions::from_config(&config);
+        assert_eq!(
+            options.program_and_args(),
+            (
+                "nvim".to_string(),
+                vec!["-u".to_string(), "NONE".to_string()]
+            )
+        );
+        assert_eq!(options.working_dir(), Some(Path::new("/tmp")));
+    }
+
+    #[test]
+    fn terminal_variables_default_but_can_be_overridden() {
+        let env = SpawnOptions::new()
+            .env("TERM", "xterm-ghostty")
+            .env("EDITOR", "vi")
+            .environment();
+        let get = |key: &str| {
+            env.iter()
+                .find(|(set, _)| set == key)
+                .map(|(_, value)| value.as_str())
+        };
+        assert_eq!(get("TERM"), Some("xterm-ghostty"));
+        assert_eq!(get("COLORTERM"), Some("truecolor"));
+        assert_eq!(get("TERM_PROGRAM"), Some("gpui-ghostty"));
+        assert_eq!(get("EDITOR"), Some("vi"));
+        assert_eq!(env.iter().filter(|(key, _)| key == "TERM").count(), 1);
+    }
+}
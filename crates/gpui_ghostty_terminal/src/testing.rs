@@ -0,0 +1,312 @@
+//! Headless golden testing of terminal output, without GPUI windows.
+//!
+//! [`TerminalHarness`] wraps a [`TerminalSession`] with a virtual clock: feed it the bytes a TUI
+//! writes, advance time to step blink phases, and compare [`Snapshot`]s (text grid plus per-cell
+//! attributes) against checked-in expectations:
+//!
+//! ```ignore
+//! let mut harness = TerminalHarness::new(20, 3);
+//! harness.feed(b"\x1b[1mbold\x1b[m plain");
+//! assert_eq!(
+//!     harness.snapshot().to_string(),
+//!     "bold plain\n\n\n--\ncursor 11,1 block\n1 1-4 bold\n"
+//! );
+//! ```
+
+use std::fmt;
+use std::time::Duration;
+
+use ghostty_vt::{CursorStyle, Error, Rgb, StyleRun, Underline, ViewportPoint};
+
+use crate::view::CURSOR_BLINK_INTERVAL;
+use crate::{TerminalConfig, TerminalSession};
+
+const FLAG_INVERSE: u8 = 0x01;
+const FLAG_BOLD: u8 = 0x02;
+const FLAG_ITALIC: u8 = 0x04;
+const FLAG_FAINT: u8 = 0x10;
+const FLAG_INVISIBLE: u8 = 0x20;
+const FLAG_STRIKETHROUGH: u8 = 0x40;
+const FLAG_BLINK: u8 = 0x80;
+
+/// A terminal session driven by hand for tests: bytes in, snapshots out.
+pub struct TerminalHarness {
+    session: TerminalSession,
+    elapsed: Duration,
+    responses: Vec<u8>,
+}
+
+impl TerminalHarness {
+    /// A `cols`×`rows` terminal with the default config.
+    pub fn new(cols: u16, rows: u16) -> Self {
+        Self::with_config(TerminalConfig::default(), cols, rows).expect("terminal init")
+    }
+
+    /// A `cols`×`rows` terminal with the given config (its own grid size is ignored).
+    pub fn with_config(config: TerminalConfig, cols: u16, rows: u16) -> Result<Self, Error> {
+        let config = TerminalConfig {
+            cols,
+            rows,
+            ..config
+        };
+        Ok(Self {
+            session: TerminalSession::new(config)?,
+            elapsed: Duration::ZERO,
+            responses: Vec::new(),
+        })
+    }
+
+    /// Feed output as if the application had written it to the PTY. Replies the terminal sends
+    /// back (cursor position reports, color queries, ...) are kept for
+    /// [`Self::take_responses`].
+    pub fn feed(&mut self, bytes: &[u8]) -> &mut Self {
+        let responses = &mut self.responses;
+        self.session
+            .feed_with_pty_responses(bytes, |reply| responses.extend_from_slice(reply))
+            .expect("feed terminal output");
+        self
+    }
+
+    /// [`Self::feed`] for text; `\n` is sent as `\r\n`, like a PTY in cooked mode.
+    pub fn feed_str(&mut self, text: &str) -> &mut Self {
+        self.feed(text.replace('\n', "\r\n").as_bytes())
+    }
+
+    /// The bytes the terminal replied with since the last call.
+    pub fn take_responses(&mut self) -> Vec<u8> {
+        std::mem::take(&mut self.responses)
+    }
+
+    /// Move the virtual clock forward, stepping the cursor and `SGR 5` text blink phases.
+    pub fn advance(&mut self, duration: Duration) -> &mut Self {
+        self.elapsed += duration;
+        self
+    }
+
+    /// Time passed on the virtual clock.
+    pub fn elapsed(&self) -> Duration {
+        self.elapsed
+    }
+
+    /// Resize the grid, as a window resize would.
+    pub fn resize(&mut self, cols: u16, rows: u16) -> &mut Self {
+        self.session.resize(cols, rows).expect("resize terminal");
+        self
+    }
+
+    pub fn session(&self) -> &TerminalSession {
+        &self.session
+    }
+
+    pub fn session_mut(&mut self) -> &mut TerminalSession {
+        &mut self.session
+    }
+
+    /// Capture the viewport as it would be drawn at the current virtual time.
+    pub fn snapshot(&self) -> Snapshot {
+        let session = &self.session;
+        let cols = session.cols();
+        let rows = session.rows();
+        let mut lines = Vec::new();
+        let mut style_runs = Vec::new();
+        session
+            .dump_viewport_rows_into(0..rows, &mut lines, &mut style_runs)
+            .expect("dump viewport");
+
+        let default_attrs =
+            CellAttrs::plain(session.default_foreground(), session.default_background());
+        let cells = style_runs
+            .iter()
+            .map(|runs| row_cells(runs, cols, default_attrs))
+            .collect();
+        let text = lines
+            .into_iter()
+            .map(|line| line.trim_end().to_string())
+            .collect();
+
+        let cursor_blink_on = !session.cursor_should_blink()
+            || (self.elapsed.as_millis() / CURSOR_BLINK_INTERVAL.as_millis()).is_multiple_of(2);
+        let text_blink_interval = u128::from(session.config().text_blink_interval_ms);
+        let text_blink_hidden = session.config().text_blink
+            && text_blink_interval > 0
+            && !(self.elapsed.as_millis() / text_blink_interval).is_multiple_of(2);
+
+        Snapshot {
+            text,
+            cells,
+            default_attrs,
+            cursor: session
+                .cursor_position()
+                .filter(|_| session.cursor_visible() && cursor_blink_on),
+            cursor_style: session.cursor_style(),
+            text_blink_hidden,
+        }
+    }
+}
+
+/// Expand a row's style runs (1-based, inclusive columns) into one entry per cell.
+fn row_cells(runs: &[StyleRun], cols: u16, default_attrs: CellAttrs) -> Vec<CellAttrs> {
+    let mut cells = vec![default_attrs; usize::from(cols)];
+    for run in runs {
+        let attrs = CellAttrs::from_run(run);
+        let start = usize::from(run.start_col.max(1) - 1);
+        let end = usize::from(run.end_col).min(cells.len());
+        if start < end {
+            cells[start..end].fill(attrs);
+        }
+    }
+    cells
+}
+
+/// The look of one cell.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct CellAttrs {
+    pub fg: Rgb,
+    pub bg: Rgb,
+    pub bold: bool,
+    pub italic: bool,
+    pub faint: bool,
+    pub inverse: bool,
+    pub invisible: bool,
+    pub strikethrough: bool,
+    pub blink: bool,
+    pub underline: Underline,
+    pub underline_color: Option<Rgb>,
+    pub hyperlink: bool,
+}
+
+impl CellAttrs {
+    fn plain(fg: Rgb, bg: Rgb) -> Self {
+        Self {
+            fg,
+            bg,
+            bold: false,
+            italic: false,
+            faint: false,
+            inverse: false,
+            invisible: false,
+            strikethrough: false,
+            blink: false,
+            underline: Underline::None,
+            underline_color: None,
+            hyperlink: false,
+        }
+    }
+
+    fn from_run(run: &StyleRun) -> Self {
+        Self {
+            fg: run.fg,
+            bg: run.bg,
+            bold: run.flags & FLAG_BOLD != 0,
+            italic: run.flags & FLAG_ITALIC != 0,
+            faint: run.flags & FLAG_FAINT != 0,
+            inverse: run.flags & FLAG_INVERSE != 0,
+            invisible: run.flags & FLAG_INVISIBLE != 0,
+            strikethrough: run.flags & FLAG_STRIKETHROUGH != 0,
+            blink: run.flags & FLAG_BLINK != 0,
+            underline: run.underline,
+            underline_color: run.underline_color,
+            hyperlink: run.hyperlink_id.is_some(),
+        }
+    }
+
+    /// Attribute list for the snapshot text, relative to the default colors.
+    fn describe(&self, default_attrs: &CellAttrs) -> String {
+        let mut parts: Vec<String> = Vec::new();
+        let flags = [
+            (self.bold, "bold"),
+            (self.italic, "italic"),
+            (self.faint, "faint"),
+            (self.inverse, "inverse"),
+            (self.invisible, "invisible"),
+            (self.strikethrough, "strikethrough"),
+            (self.blink, "blink"),
+            (self.hyperlink, "link"),
+        ];
+        parts.extend(
+            flags
+                .iter()
+                .filter(|(on, _)| *on)
+                .map(|(_, name)| name.to_string()),
+        );
+        if self.underline != Underline::None {
+            parts.push(format!("underline={:?}", self.underline).to_lowercase());
+        }
+        if let Some(color) = self.underline_color {
+            parts.push(format!("underline-color={}", hex(color)));
+        }
+        if self.fg != default_attrs.fg {
+            parts.push(format!("fg={}", hex(self.fg)));
+        }
+        if self.bg != default_attrs.bg {
+            parts.push(format!("bg={}", hex(self.bg)));
+        }
+        parts.join(" ")
+    }
+}
+
+fn hex(color: Rgb) -> String {
+    format!("#{:02x}{:02x}{:02x}", color.r, color.g, color.b)
+}
+
+/// The viewport at one moment, for golden comparisons.
+///
+/// `Display` renders a stable text form: the rows (trailing blanks trimmed), a `--` line, the
+/// cursor (`cursor col,row style`, or `cursor hidden`), then one line per run of non-default
+/// cells as `row start-end attrs`. Rows and columns are 1-based, column ranges inclusive.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Snapshot {
+    /// One string per viewport row, trailing blanks trimmed.
+    pub text: Vec<String>,
+    /// One entry per cell, `cells[row][col]`.
+    pub cells: Vec<Vec<CellAttrs>>,
+    /// Attributes of a blank cell.
+    pub default_attrs: CellAttrs,
+    /// The 1-based cursor position, or `None` when hidden (by the application or the blink
+    /// phase).
+    pub cursor: Option<ViewportPoint>,
+    pub cursor_style: CursorStyle,
+    /// Whether `SGR 5` blinking text is in its hidden phase.
+    pub text_blink_hidden: bool,
+}
+
+impl Snapshot {
+    /// The text rows joined with `\n`.
+    pub fn text(&self) -> String {
+        self.text.join("\n")
+    }
+}
+
+impl fmt::Display for Snapshot {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for line in &self.text {
+            writeln!(f, "{}", line)?;
+        }
+        writeln!(f, "--")?;
+        match self.cursor {
+            Some(point) => {
+                let style = format!("{:?}", self.cursor_style).to_lowercase();
+                writeln!(f, "cursor {},{} {}", point.col, point.row, style)?;
+            }
+            None => writeln!(f, "cursor hidden")?,
+        }
+        if self.text_blink_hidden {
+            writeln!(f, "blink hidden")?;
+        }
+        for (row, cells) in self.cells.iter().enumerate() {
+            let mut col = 0;
+            while col < cells.len() {
+                let attrs = cells[col];
+                let start = col;
+                while col < cells.len() && cells[col] == attrs {
+                    col += 1;
+                }
+                if attrs != self.default_attrs {
+                    let description = attrs.describe(&self.default_attrs);
+                    writeln!(f, "{} {}-{} {}", row + 1, start + 1, col, description)?;
+                }
+            }
+        }
+        Ok(())
+    }
+}
@@ -1,7 +1,10 @@
 use gpui::{KeyBinding, KeyContext, Keymap, Keystroke, actions};
 use std::any::TypeId;
 
-use crate::{TerminalConfig, TerminalSession};
+use crate::{
+    ClipboardAccess, KeyModes, MouseEncoding, Rgb, SearchDirection, TerminalConfig, TerminalEvent,
+    TerminalSession, ViewportPoint,
+};
 
 actions!(tab_shadow_test, [RootTab, TerminalTab]);
 
@@ -112,6 +115,126 @@ fn tracks_mouse_reporting_mode_from_output() {
     assert!(!session.mouse_sgr_enabled());
 }
 
+#[test]
+fn tracks_focus_reporting_mode_from_output() {
+    let mut session = TerminalSession::new(TerminalConfig::default()).unwrap();
+    assert!(!session.focus_reporting_enabled());
+
+    session.feed(b"\x1b[?1004h").unwrap();
+    assert!(session.focus_reporting_enabled());
+
+    session.feed(b"\x1b[?1004l").unwrap();
+    assert!(!session.focus_reporting_enabled());
+
+    session.feed(b"\x1b[?1004h").unwrap();
+    session.reset().unwrap();
+    assert!(!session.focus_reporting_enabled());
+}
+
+#[test]
+fn tracks_application_cursor_and_keypad_modes_from_output() {
+    let mut session = TerminalSession::new(TerminalConfig::default()).unwrap();
+    assert_eq!(session.key_modes(), KeyModes::default());
+
+    session.feed(b"\x1b[?1h").unwrap();
+    assert!(session.key_modes().application_cursor_keys);
+    assert!(!session.key_modes().application_keypad);
+
+    session.feed(b"\x1b=").unwrap();
+    assert!(session.key_modes().application_keypad);
+    session.feed(b"\x1b>").unwrap();
+    assert!(!session.key_modes().application_keypad);
+
+    session.feed(b"\x1b[?66h").unwrap();
+    assert!(session.key_modes().application_keypad);
+
+    session.feed(b"\x1b[?1;66l").unwrap();
+    assert_eq!(session.key_modes(), KeyModes::default());
+}
+
+#[test]
+fn tracks_mouse_encoding_modes_from_output() {
+    let mut session = TerminalSession::new(TerminalConfig::default()).unwrap();
+    assert_eq!(session.mouse_encoding(), MouseEncoding::X10);
+
+    session.feed(b"\x1b[?1005h").unwrap();
+    assert_eq!(session.mouse_encoding(), MouseEncoding::Utf8);
+
+    session.feed(b"\x1b[?1015h").unwrap();
+    assert_eq!(session.mouse_encoding(), MouseEncoding::Urxvt);
+
+    session.feed(b"\x1b[?1006h").unwrap();
+    assert_eq!(session.mouse_encoding(), MouseEncoding::Sgr);
+
+    session.feed(b"\x1b[?1016h").unwrap();
+    assert_eq!(session.mouse_encoding(), MouseEncoding::SgrPixels);
+
+    session.feed(b"\x1b[?1016;1006;1015l").unwrap();
+    assert_eq!(session.mouse_encoding(), MouseEncoding::Utf8);
+
+    session.reset().unwrap();
+    assert_eq!(session.mouse_encoding(), MouseEncoding::X10);
+}
+
+#[test]
+fn tracks_alt_screen_mode_from_output() {
+    let mut session = TerminalSession::new(TerminalConfig::default()).unwrap();
+    assert!(!session.is_alt_screen());
+
+    session.feed(b"\x1b[?1049h").unwrap();
+    assert!(session.is_alt_screen());
+
+    session.feed(b"\x1b[?1049l").unwrap();
+    assert!(!session.is_alt_screen());
+
+    session.feed(b"\x1b[?47h").unwrap();
+    assert!(session.is_alt_screen());
+}
+
+#[test]
+fn clamps_grid_size_to_configured_bounds() {
+    let mut session = TerminalSession::new(TerminalConfig {
+        cols: 0,
+        rows: 0,
+        max_cols: 200,
+        max_rows: 100,
+        ..TerminalConfig::default()
+    })
+    .unwrap();
+    assert_eq!((session.cols(), session.rows()), (1, 1));
+
+    session.resize(60_000, 50).unwrap();
+    assert_eq!((session.cols(), session.rows()), (200, 50));
+
+    session.resize(80, 0).unwrap();
+    assert_eq!((session.cols(), session.rows()), (80, 1));
+}
+
+#[test]
+fn restores_primary_scroll_position_after_leaving_alt_screen() {
+    let mut session = TerminalSession::new(TerminalConfig {
+        cols: 20,
+        rows: 5,
+        ..TerminalConfig::default()
+    })
+    .unwrap();
+    for i in 0..30 {
+        session.feed(format!("line {i}\r\n").as_bytes()).unwrap();
+    }
+    let bottom = session.viewport_top_row();
+    session.scroll_viewport(-10).unwrap();
+    assert_eq!(session.viewport_top_row(), bottom - 10);
+
+    session.feed(b"\x1b[?1049hpager\x1b[?1049l").unwrap();
+    assert!(!session.is_alt_screen());
+    assert_eq!(session.viewport_top_row(), bottom - 10);
+
+    // Old switches left in the scan buffer do not trigger another restore.
+    session.scroll_viewport_bottom().unwrap();
+    session.feed(b"x").unwrap();
+    assert_eq!(session.viewport_top_row(), bottom);
+}
+
 #[test]
 fn viewport_index_maps_row_and_column_to_byte_index() {
     let viewport = "abc\ndef";
@@ -142,6 +265,42 @@ fn tracks_modes_across_chunk_boundaries() {
     assert!(session.mouse_sgr_enabled());
 }
 
+#[test]
+fn title_stack_restores_titles_on_pop() {
+    let mut session = TerminalSession::new(TerminalConfig::default()).unwrap();
+    session.feed(b"\x1b]2;shell\x07").unwrap();
+
+    // vim saves the title, sets its own, and restores the saved one on exit.
+    session.feed(b"\x1b[22;0t\x1b]2;vim\x07").unwrap();
+    assert_eq!(session.title(), Some("vim"));
+    session.feed(b"\x1b[23;0t").unwrap();
+    assert_eq!(session.title(), Some("shell"));
+
+    // Later output must not re-apply titles still in the scan tail.
+    session.feed(b"more output").unwrap();
+    assert_eq!(session.title(), Some("shell"));
+
+    // Set, push and pop in one chunk apply in order.
+    session
+        .feed(b"\x1b[22;2t\x1b]0;less\x07\x1b[23;2t")
+        .unwrap();
+    assert_eq!(session.title(), Some("shell"));
+
+    // Popping an empty stack leaves the title alone.
+    session.feed(b"\x1b[23;0t").unwrap();
+    assert_eq!(session.title(), Some("shell"));
+
+    let titles: Vec<_> = session
+        .take_events()
+        .into_iter()
+        .filter_map(|event| match event {
+            TerminalEvent::TitleChanged(title) => Some(title),
+            _ => None,
+        })
+        .collect();
+    assert_eq!(titles, ["shell", "vim", "shell"]);
+}
+
 #[test]
 fn tracks_osc_title_across_chunk_boundaries() {
     let mut session = TerminalSession::new(TerminalConfig::default()).unwrap();
@@ -162,6 +321,214 @@ fn tracks_osc_52_clipboard_across_chunk_boundaries() {
     assert_eq!(session.take_clipboard_write().as_deref(), Some("hi"));
 }
 
+#[test]
+fn records_osc_52_writes_in_clipboard_history_once() {
+    let mut session = TerminalSession::new(TerminalConfig::default()).unwrap();
+    session
+        .feed(b"\x1b]52;c;aGk=\x07\x1b]52;c;Ynll\x1b\\")
+        .unwrap();
+    assert_eq!(session.take_clipboard_write().as_deref(), Some("bye"));
+
+    // Later output must not replay writes that are still in the scan buffer.
+    session.feed(b"more output").unwrap();
+    assert!(session.take_clipboard_write().is_none());
+
+    let history: Vec<&str> = session
+        .clipboard_history()
+        .iter()
+        .map(|entry| entry.text.as_str())
+        .collect();
+    assert_eq!(history, ["hi", "bye"]);
+}
+
+#[test]
+fn queues_bell_and_notification_events() {
+    let mut session = TerminalSession::new(TerminalConfig::default()).unwrap();
+    session
+        .feed(b"\x07\x07\x1b]9;build done\x07\x1b]9;4;1;50\x07\x1b]777;notify;make;ok\x1b\\")
+        .unwrap();
+    assert_eq!(
+        session.take_events(),
+        [
+            TerminalEvent::Bell,
+            TerminalEvent::Notification {
+                title: None,
+                body: "build done".to_string(),
+            },
+            TerminalEvent::Notification {
+                title: Some("make".to_string()),
+                body: "ok".to_string(),
+            },
+        ]
+    );
+
+    // Notifications still in the scan buffer are not reported again.
+    session.feed(b"more").unwrap();
+    assert!(session.take_events().is_empty());
+
+    // Events come out in the order they were written.
+    session.feed(b"\x1b]9;first\x07\x07").unwrap();
+    assert_eq!(
+        session.take_events(),
+        [
+            TerminalEvent::Notification {
+                title: None,
+                body: "first".to_string(),
+            },
+            TerminalEvent::Bell,
+        ]
+    );
+}
+
+#[test]
+fn reports_state_changes_as_events_and_to_subscribers() {
+    use std::cell::RefCell;
+    use std::rc::Rc;
+
+    let mut session = TerminalSession::new(TerminalConfig::default()).unwrap();
+    let seen = Rc::new(RefCell::new(Vec::new()));
+    let sink = seen.clone();
+    session.subscribe(move |event| sink.borrow_mut().push(event.clone()));
+
+    session
+        .feed(b"\x1b]2;vim\x07\x1b]7;file://host/tmp/my%20dir\x1b\\\x1b[?1049h")
+        .unwrap();
+    session.feed(b"\x1b]2;vim\x07\x1b[?1049l").unwrap();
+
+    let expected = [
+        TerminalEvent::TitleChanged("vim".to_string()),
+        TerminalEvent::CwdChanged("/tmp/my dir".to_string()),
+        TerminalEvent::AltScreenEntered,
+        TerminalEvent::AltScreenExited,
+    ];
+    assert_eq!(session.take_events(), expected);
+    assert_eq!(*seen.borrow(), expected);
+    assert_eq!(session.current_working_directory(), Some("/tmp/my dir"));
+}
+
+#[test]
+fn tracks_osc_7_working_directory() {
+    let mut session = TerminalSession::new(TerminalConfig::default()).unwrap();
+    assert_eq!(session.current_working_directory(), None);
+
+    session.feed(b"\x1b]7;file:///home/me\x07").unwrap();
+    assert_eq!(session.current_working_directory(), Some("/home/me"));
+
+    session
+        .feed(b"\x1b]7;kitty-shell-cwd://box/srv/%C3%A9t%C3%A9\x07")
+        .unwrap();
+    assert_eq!(session.current_working_directory(), Some("/srv/été"));
+
+    // Other schemes, missing paths, and bad escapes are ignored.
+    session
+        .feed(b"\x1b]7;https://example.com/x\x07\x1b]7;file://box\x07\x1b]7;file:///a%zz\x07")
+        .unwrap();
+    assert_eq!(session.current_working_directory(), Some("/srv/été"));
+}
+
+#[test]
+fn tracks_osc_1337_user_vars_and_shell_integration_keys() {
+    let mut session = TerminalSession::new(TerminalConfig::default()).unwrap();
+    let _ = session.take_events();
+
+    session
+        .feed(
+            b"\x1b]1337;SetUserVar=git_branch=bWFpbg==\x07\x1b]1337;SetUserVar=venv=dmVudg==\x1b\\",
+        )
+        .unwrap();
+    assert_eq!(session.user_var("git_branch"), Some("main"));
+    assert_eq!(session.user_vars().len(), 2);
+    assert_eq!(
+        session.take_events(),
+        [
+            TerminalEvent::UserVarChanged {
+                name: "git_branch".to_string(),
+                value: "main".to_string(),
+            },
+            TerminalEvent::UserVarChanged {
+                name: "venv".to_string(),
+                value: "venv".to_string(),
+            },
+        ]
+    );
+
+    // Re-sending the same value is not a change; malformed values are ignored.
+    session
+        .feed(b"\x1b]1337;SetUserVar=git_branch=bWFpbg==\x07\x1b]1337;SetUserVar=x=%%%\x07")
+        .unwrap();
+    assert!(session.take_events().is_empty());
+    assert_eq!(session.user_var("x"), None);
+
+    session
+        .feed(b"\x1b]1337;CurrentDir=/srv/app\x07\x1b]1337;RemoteHost=me@box\x07")
+        .unwrap();
+    assert_eq!(session.current_working_directory(), Some("/srv/app"));
+    assert_eq!(session.remote_host(), Some("me@box"));
+}
+
+#[test]
+fn badge_format_expands_user_vars_and_host() {
+    let mut session = TerminalSession::new(TerminalConfig::default()).unwrap();
+    assert_eq!(session.badge(), None);
+
+    // SetBadgeFormat carries `\(user.env) @ \(session.hostname)` in base64.
+    session
+        .feed(b"\x1b]1337;SetBadgeFormat=XCh1c2VyLmVudikgQCBcKHNlc3Npb24uaG9zdG5hbWUp\x07")
+        .unwrap();
+    session
+        .feed(b"\x1b]1337;SetUserVar=env=cHJvZA==\x07\x1b]1337;RemoteHost=me@db1\x07")
+        .unwrap();
+    assert_eq!(session.badge().as_deref(), Some("prod @ db1"));
+
+    session.set_badge_format(Some("staging".to_string()));
+    assert_eq!(session.badge().as_deref(), Some("staging"));
+
+    session.feed(b"\x1b]1337;SetBadgeFormat=\x07").unwrap();
+    assert_eq!(session.badge_format(), None);
+    assert_eq!(session.badge(), None);
+}
+
+#[test]
+fn answers_osc_52_clipboard_reads_through_the_access_policy() {
+    let mut session = TerminalSession::new(TerminalConfig::default()).unwrap();
+    let mut response = Vec::new();
+
+    // Denied while no policy is set.
+    session
+        .feed_with_pty_responses(b"\x1b]52;c;?\x07", |bytes| {
+            response.extend_from_slice(bytes)
+        })
+        .unwrap();
+    assert!(response.is_empty());
+
+    session.set_clipboard_access_policy(|| ClipboardAccess::Allow("hi".to_string()));
+    session
+        .feed_with_pty_responses(b"\x1b]52;c;", |bytes| response.extend_from_slice(bytes))
+        .unwrap();
+    session
+        .feed_with_pty_responses(b"?\x1b\\", |bytes| response.extend_from_slice(bytes))
+        .unwrap();
+    assert_eq!(response, b"\x1b]52;c;aGk=\x1b\\");
+    // A read is not a write.
+    assert!(session.take_clipboard_write().is_none());
+
+    response.clear();
+    session.set_clipboard_access_policy(|| ClipboardAccess::Prompt);
+    session
+        .feed_with_pty_responses(b"\x1b]52;p;?\x07", |bytes| {
+            response.extend_from_slice(bytes)
+        })
+        .unwrap();
+    assert!(response.is_empty());
+    assert!(session.has_pending_clipboard_read());
+    assert_eq!(
+        session.answer_clipboard_read(Some("bye")).as_deref(),
+        Some(&b"\x1b]52;p;Ynll\x1b\\"[..])
+    );
+    assert!(!session.has_pending_clipboard_read());
+    assert_eq!(session.answer_clipboard_read(Some("bye")), None);
+}
+
 #[test]
 fn responds_to_csi_6n_cursor_position_request() {
     let mut session = TerminalSession::new(TerminalConfig::default()).unwrap();
@@ -176,6 +543,181 @@ fn responds_to_csi_6n_cursor_position_request() {
     assert_eq!(response, b"\x1b[1;3R");
 }
 
+#[test]
+fn osc_color_sets_override_and_reset_default_colors() {
+    use ghostty_vt::Rgb;
+
+    let mut session = TerminalSession::new(TerminalConfig::default()).unwrap();
+    let config_fg = session.default_foreground();
+    let config_bg = session.default_background();
+    assert_eq!(session.cursor_color(), None);
+
+    session
+        .feed(b"\x1b]10;rgb:ff/80/00\x07\x1b]11;#102030\x1b\\\x1b]12;rgb:f/0/ffff\x07")
+        .unwrap();
+    assert_eq!(
+        session.default_foreground(),
+        Rgb {
+            r: 0xff,
+            g: 0x80,
+            b: 0x00
+        }
+    );
+    assert_eq!(
+        session.default_background(),
+        Rgb {
+            r: 0x10,
+            g: 0x20,
+            b: 0x30
+        }
+    );
+    assert_eq!(
+        session.cursor_color(),
+        Some(Rgb {
+            r: 0xff,
+            g: 0x00,
+            b: 0xff
+        })
+    );
+    assert!(session.take_default_colors_changed());
+    assert!(!session.take_default_colors_changed());
+
+    // Queries report the color in effect.
+    let mut response = Vec::new();
+    session
+        .feed_with_pty_responses(b"\x1b]11;?\x07", |bytes| response.extend_from_slice(bytes))
+        .unwrap();
+    assert_eq!(
+        response,
+        osc_color_response(11, (0x10, 0x20, 0x30)).as_bytes()
+    );
+
+    session.feed(b"\x1b]110\x07\x1b]111\x1b\\").unwrap();
+    assert_eq!(session.default_foreground(), config_fg);
+    assert_eq!(session.default_background(), config_bg);
+    assert!(session.take_default_colors_changed());
+
+    // Extra colors move on to the next target; unparsable specs are ignored.
+    session.feed(b"\x1b]10;#abc;#123456\x07").unwrap();
+    assert_eq!(
+        session.default_foreground(),
+        Rgb {
+            r: 0xaa,
+            g: 0xbb,
+            b: 0xcc
+        }
+    );
+    assert_eq!(
+        session.default_background(),
+        Rgb {
+            r: 0x12,
+            g: 0x34,
+            b: 0x56
+        }
+    );
+    session.feed(b"\x1b]11;not-a-color\x07").unwrap();
+    assert_eq!(
+        session.default_background(),
+        Rgb {
+            r: 0x12,
+            g: 0x34,
+            b: 0x56
+        }
+    );
+
+    session.reset().unwrap();
+    assert_eq!(session.default_background(), config_bg);
+    assert_eq!(session.cursor_color(), None);
+}
+
+#[test]
+fn apply_theme_replaces_theme_colors_and_keeps_config_on_error() {
+    let mut config = TerminalConfig {
+        selection_background: Some(Rgb { r: 1, g: 2, b: 3 }),
+        ..TerminalConfig::default()
+    };
+    crate::apply_theme(&mut config, "catppuccin-latte").unwrap();
+    assert_eq!(config.theme_spec.as_deref(), Some("catppuccin-latte"));
+    assert!(config.palette.is_some());
+    assert_ne!(config.selection_background, Some(Rgb { r: 1, g: 2, b: 3 }));
+
+    let before = config.clone();
+    assert!(crate::apply_theme(&mut config, "no-such-theme-anywhere").is_err());
+    assert_eq!(config.default_bg, before.default_bg);
+    assert_eq!(config.palette, before.palette);
+    assert_eq!(config.theme_spec, before.theme_spec);
+}
+
+#[test]
+fn default_color_reports_follow_theme_reloads() {
+    let mut config = TerminalConfig::default();
+    config.theme_spec = Some("dark:dracula,light:catppuccin-latte".to_string());
+    let mut session = TerminalSession::new(config).unwrap();
+
+    assert!(crate::reload_theme_for_appearance(
+        session.config_mut(),
+        false
+    ));
+    session.apply_theme_colors();
+    let bg = session.default_background();
+    let fg = session.default_foreground();
+    assert_eq!(
+        session.default_color_reports(),
+        format!(
+            "{}{}",
+            osc_color_response(10, (fg.r, fg.g, fg.b)),
+            osc_color_response(11, (bg.r, bg.g, bg.b))
+        )
+    );
+}
+
+#[test]
+fn responds_to_xtwinops_size_queries() {
+    let config = TerminalConfig {
+        cols: 80,
+        rows: 24,
+        ..TerminalConfig::default()
+    };
+    let mut session = TerminalSession::new(config).unwrap();
+    session.set_cell_pixel_size(9, 18);
+
+    let mut respond = |query: &[u8]| {
+        let mut response = Vec::new();
+        session
+            .feed_with_pty_responses(query, |bytes| response.extend_from_slice(bytes))
+            .unwrap();
+        response
+    };
+    assert_eq!(respond(b"\x1b[14t"), b"\x1b[4;432;720t");
+    assert_eq!(respond(b"\x1b[16t"), b"\x1b[6;18;9t");
+    assert_eq!(respond(b"\x1b[18t"), b"\x1b[8;24;80t");
+    assert!(respond(b"\x1b[1t").is_empty());
+}
+
+#[test]
+fn responds_to_xtgettcap_queries() {
+    let mut session = TerminalSession::new(TerminalConfig::default()).unwrap();
+    let mut response = Vec::new();
+
+    // "RGB" and "Tc" are known; "fullkbd" (kitty keyboard) is not. Split across chunks.
+    session
+        .feed_with_pty_responses(b"\x1bP+q524742;5463;66", |bytes| {
+            response.extend_from_slice(bytes)
+        })
+        .unwrap();
+    assert!(response.is_empty());
+    session
+        .feed_with_pty_responses(b"756c6c6b6264\x1b\\", |bytes| {
+            response.extend_from_slice(bytes)
+        })
+        .unwrap();
+
+    assert_eq!(
+        String::from_utf8(response).unwrap(),
+        "\x1bP1+r524742=382F382F38\x1b\\\x1bP1+r5463\x1b\\\x1bP0+r66756c6c6b6264\x1b\\"
+    );
+}
+
 #[test]
 fn responds_to_csi_6n_across_chunk_boundaries() {
     let mut session = TerminalSession::new(TerminalConfig::default()).unwrap();
@@ -342,21 +884,121 @@ fn sgr_mouse_encoding_helpers_match_expected_format() {
         2 + 32 + 8 + 16
     );
     assert_eq!(
-        crate::view::sgr_mouse_sequence(0, 1, 1, true),
+        crate::view::sgr_mouse_sequence(0, ViewportPoint { col: 1, row: 1 }, true),
         "\u{1b}[<0;1;1M"
     );
     assert_eq!(
-        crate::view::sgr_mouse_sequence(0, 1, 1, false),
+        crate::view::sgr_mouse_sequence(0, ViewportPoint { col: 1, row: 1 }, false),
         "\u{1b}[<0;1;1m"
     );
 }
 
+#[test]
+fn legacy_mouse_encodings_match_expected_format() {
+    use crate::view::mouse_sequence;
+
+    let point = ViewportPoint { col: 1, row: 2 };
+    assert_eq!(
+        mouse_sequence(MouseEncoding::X10, 0, point, true).unwrap(),
+        b"\x1b[M !\""
+    );
+    // Releases report button 3 but keep the modifier bits.
+    assert_eq!(
+        mouse_sequence(MouseEncoding::X10, 2 + 16, point, false).unwrap(),
+        b"\x1b[M3!\""
+    );
+    assert_eq!(
+        mouse_sequence(MouseEncoding::Urxvt, 0, point, true).unwrap(),
+        b"\x1b[32;1;2M"
+    );
+    assert_eq!(
+        mouse_sequence(MouseEncoding::Sgr, 0, point, false).unwrap(),
+        b"\x1b[<0;1;2m"
+    );
+    // SGR-Pixels shares the SGR format; the point already holds pixels.
+    assert_eq!(
+        mouse_sequence(
+            MouseEncoding::SgrPixels,
+            0,
+            ViewportPoint { col: 640, row: 33 },
+            true
+        )
+        .unwrap(),
+        b"\x1b[<0;640;33M"
+    );
+
+    let far = ViewportPoint { col: 300, row: 2 };
+    assert_eq!(mouse_sequence(MouseEncoding::X10, 0, far, true), None);
+    assert_eq!(
+        mouse_sequence(MouseEncoding::Utf8, 0, far, true).unwrap(),
+        "\x1b[M \u{14c}\"".as_bytes()
+    );
+}
+
+#[test]
+fn mouse_points_translate_to_active_screen_when_scrolled_back() {
+    let config = TerminalConfig {
+        cols: 10,
+        rows: 3,
+        ..TerminalConfig::default()
+    };
+    let mut session = TerminalSession::new(config).unwrap();
+    session.feed(b"1\r\n2\r\n3\r\n4\r\n5").unwrap();
+
+    let at = |row| ViewportPoint { col: 2, row };
+    assert_eq!(session.viewport_point_to_active(at(2)), Some(at(2)));
+
+    // Scrolled up one row: the top viewport row is history, the rest shift down by one.
+    session.scroll_viewport(-1).unwrap();
+    assert_eq!(session.viewport_point_to_active(at(1)), None);
+    assert_eq!(session.viewport_point_to_active(at(2)), Some(at(1)));
+    assert_eq!(session.viewport_point_to_active(at(3)), Some(at(2)));
+}
+
 #[test]
 fn ctrl_c_encodes_to_etx_even_without_key_char() {
     let ctrl_c = Keystroke::parse("ctrl-c").unwrap();
     assert_eq!(crate::view::ctrl_byte_for_keystroke(&ctrl_c), Some(0x03));
 }
 
+#[test]
+fn modify_other_keys_encodes_ambiguous_keys() {
+    use crate::view::modify_other_keys_sequence;
+
+    let key = |text: &str| Keystroke::parse(text).unwrap();
+    let encode = |level: u8, text: &str| modify_other_keys_sequence(level, &key(text));
+
+    assert_eq!(encode(0, "ctrl-shift-a"), None);
+
+    // Level 1: only keys without a distinct traditional encoding.
+    assert_eq!(encode(1, "ctrl-a"), None);
+    assert_eq!(encode(1, "alt-a"), None);
+    assert_eq!(encode(1, "ctrl-shift-a").as_deref(), Some("\x1b[27;6;65~"));
+    assert_eq!(encode(1, "ctrl-1").as_deref(), Some("\x1b[27;5;49~"));
+    assert_eq!(encode(1, "ctrl-enter").as_deref(), Some("\x1b[27;5;13~"));
+
+    // Level 2: every ctrl/alt combination.
+    assert_eq!(encode(2, "ctrl-a").as_deref(), Some("\x1b[27;5;97~"));
+    assert_eq!(encode(2, "alt-a").as_deref(), Some("\x1b[27;3;97~"));
+    assert_eq!(encode(2, "a"), None);
+    assert_eq!(encode(2, "ctrl-up"), None);
+}
+
+#[test]
+fn tracks_modify_other_keys_level_from_output() {
+    let mut session = TerminalSession::new(TerminalConfig::default()).unwrap();
+    assert_eq!(session.modify_other_keys(), 0);
+
+    session.feed(b"\x1b[>4;2m").unwrap();
+    assert_eq!(session.modify_other_keys(), 2);
+
+    session.feed(b"\x1b[>4;1m").unwrap();
+    assert_eq!(session.modify_other_keys(), 1);
+
+    session.feed(b"\x1b[>4m").unwrap();
+    assert_eq!(session.modify_other_keys(), 0);
+}
+
 #[test]
 fn does_not_skip_enter_key_when_ime_in_progress() {
     let enter = Keystroke::parse("enter").unwrap();
@@ -372,6 +1014,36 @@ fn does_not_skip_enter_key_when_ime_in_progress() {
     assert!(!crate::view::should_skip_key_down_for_ime(true, &committed));
 }
 
+#[test]
+fn word_range_in_line_respects_separators() {
+    use crate::view::word_range_in_line;
+
+    let line = "cd ~/src/gpui-ghostty.rs";
+    assert_eq!(word_range_in_line(line, 0, ""), Some(0..2));
+    assert_eq!(word_range_in_line(line, 2, ""), None);
+    assert_eq!(word_range_in_line(line, 10, ""), Some(3..24));
+    assert_eq!(word_range_in_line(line, 10, "/"), Some(9..24));
+    assert_eq!(word_range_in_line(line, 10, "/-."), Some(9..13));
+    assert_eq!(word_range_in_line(line, 8, "/"), Some(8..9));
+    assert_eq!(word_range_in_line(line, 99, ""), None);
+}
+
+#[test]
+fn queued_input_preview_shows_printable_text_only() {
+    use crate::view::queued_input_preview;
+
+    assert_eq!(queued_input_preview(b"ls -la\r").as_deref(), Some("ls -la"));
+    assert_eq!(
+        queued_input_preview(b"gti\x7f\x7fit").as_deref(),
+        Some("git")
+    );
+    assert_eq!(
+        queued_input_preview(b"a\x1b[1;5Cb\x1bOPc\x1bxd").as_deref(),
+        Some("abcd")
+    );
+    assert_eq!(queued_input_preview(b"\x03\x1b[A").as_deref(), None);
+}
+
 #[test]
 fn byte_index_for_column_in_line_handles_wide_characters() {
     assert_eq!(crate::view::byte_index_for_column_in_line("Ｗa", 1), 0);
@@ -497,3 +1169,768 @@ fn osc133_returns_none_when_no_output() {
 
     assert!(session.take_last_command_output().is_none());
 }
+
+#[test]
+fn osc133_records_prompt_marks_and_reads_output_from_scrollback() {
+    let config = TerminalConfig {
+        cols: 20,
+        rows: 3,
+        ..TerminalConfig::default()
+    };
+    let mut session = TerminalSession::new(config).unwrap();
+
+    let mut noop = |_: &[u8]| {};
+    session
+        .feed_with_pty_responses(
+            b"\x1b]133;A\x07$ \x1b]133;B\x07false\r\n\x1b]133;C\x07a\r\nb\r\nc\r\nd\r\n\x1b]133;D;1\x07",
+            &mut noop,
+        )
+        .unwrap();
+    session
+        .feed_with_pty_responses(b"\x1b]133;A\x07$ ", &mut noop)
+        .unwrap();
+
+    let marks = session.prompt_marks();
+    assert_eq!(marks.len(), 2);
+    assert_eq!(marks[0].prompt_row, 0);
+    assert_eq!(marks[0].command_start.map(|p| p.col), Some(3));
+    assert_eq!(marks[0].output_rows(), Some(1..5));
+    assert_eq!(marks[0].exit_code, Some(1));
+    assert!(marks[0].failed());
+    assert_eq!(marks[1].prompt_row, 5);
+    assert!(!marks[1].is_finished());
+
+    // The output has scrolled out of the 3-row viewport but is still readable.
+    assert_eq!(session.last_command_output().as_deref(), Some("a\nb\nc\nd"));
+    assert_eq!(
+        session.take_last_command_output().as_deref(),
+        Some("a\nb\nc\nd")
+    );
+
+    assert_eq!(
+        session.previous_prompt_mark(5).map(|m| m.prompt_row),
+        Some(0)
+    );
+    assert_eq!(session.next_prompt_mark(0).map(|m| m.prompt_row), Some(5));
+    session.scroll_row_to_top(0).unwrap();
+    assert_eq!(session.viewport_top_row(), 0);
+}
+
+#[test]
+fn kitty_graphics_transmit_and_display_places_image_and_moves_cursor() {
+    let mut session = TerminalSession::new(TerminalConfig::default()).unwrap();
+    session.set_cell_pixel_size(1, 1);
+
+    let mut response = Vec::new();
+    session
+        .feed_with_pty_responses(
+            b"ab\x1b_Ga=T,f=24,s=2,v=2,i=9;AAAAAAAAAAAAAAAA\x1b\\",
+            |b| response.extend_from_slice(b),
+        )
+        .unwrap();
+
+    assert_eq!(response, b"\x1b_Gi=9;OK\x1b\\");
+    let placements: Vec<_> = session
+        .kitty_graphics()
+        .placements_in_rows(0..u32::MAX)
+        .copied()
+        .collect();
+    assert_eq!(placements.len(), 1);
+    assert_eq!((placements[0].col, placements[0].row), (2, 0));
+    assert_eq!((placements[0].cols, placements[0].rows), (2, 2));
+    assert_eq!(
+        session.cursor_position(),
+        Some(ViewportPoint { col: 5, row: 2 })
+    );
+    assert!(session.dump_viewport().unwrap().starts_with("ab"));
+}
+
+#[test]
+fn search_walks_matches_and_wraps() {
+    let config = TerminalConfig {
+        cols: 20,
+        rows: 2,
+        ..TerminalConfig::default()
+    };
+    let mut session = TerminalSession::new(config).unwrap();
+    session.feed(b"foo 1\r\nbar\r\nfoo 2\r\nfoo 3").unwrap();
+
+    assert_eq!(session.search_all("FOO").len(), 0);
+    assert_eq!(session.search_all("foo").len(), 3);
+
+    let rows = |m: Option<crate::SearchMatch>| m.map(|m| m.row);
+    assert_eq!(
+        rows(session.search("foo", SearchDirection::Backward)),
+        Some(3)
+    );
+    assert_eq!(
+        rows(session.search("foo", SearchDirection::Backward)),
+        Some(2)
+    );
+    assert_eq!(
+        rows(session.search("foo", SearchDirection::Backward)),
+        Some(0)
+    );
+    assert_eq!(
+        rows(session.search("foo", SearchDirection::Backward)),
+        Some(3)
+    );
+    assert_eq!(
+        rows(session.search("foo", SearchDirection::Forward)),
+        Some(0)
+    );
+
+    session.scroll_to_row(0).unwrap();
+    assert_eq!(session.viewport_top_row(), 0);
+    session.scroll_to_row(3).unwrap();
+    assert_eq!(session.viewport_top_row(), 2);
+
+    assert_eq!(session.search("nope", SearchDirection::Forward), None);
+}
+
+#[test]
+fn picked_search_match_becomes_current_and_row_text_is_dumped() {
+    let config = TerminalConfig {
+        cols: 20,
+        rows: 2,
+        ..TerminalConfig::default()
+    };
+    let mut session = TerminalSession::new(config).unwrap();
+    session.feed(b"foo 1\r\nbar\r\nfoo 2\r\nfoo 3").unwrap();
+
+    let picked = session.search_all("foo")[1];
+    assert_eq!(session.dump_screen_row(picked.row).unwrap(), "foo 2");
+
+    session.set_current_search_match("foo", picked);
+    assert_eq!(session.current_search_match(), Some(picked));
+    assert_eq!(
+        session
+            .search("foo", SearchDirection::Backward)
+            .map(|m| m.row),
+        Some(0)
+    );
+}
+
+#[test]
+fn hard_reset_clears_screen_and_tracked_modes_but_soft_reset_keeps_text() {
+    let config = TerminalConfig {
+        cols: 10,
+        rows: 2,
+        ..TerminalConfig::default()
+    };
+    let mut session = TerminalSession::new(config).unwrap();
+    session.feed(b"kept[?2004h[?1002h").unwrap();
+
+    session.reset_soft().unwrap();
+    assert_eq!(session.dump_viewport().unwrap().trim(), "kept");
+
+    session
+        .feed(
+            b"
+more
+lines[?1049h",
+        )
+        .unwrap();
+    session.take_events();
+    session.search("more", SearchDirection::Forward);
+    session.reset().unwrap();
+
+    assert_eq!(session.dump_viewport().unwrap().trim(), "");
+    assert_eq!(session.scrollback_len(), 0);
+    assert!(!session.bracketed_paste_enabled());
+    assert!(!session.mouse_button_event_enabled());
+    assert!(!session.is_alt_screen());
+    assert_eq!(session.current_search_match(), None);
+    assert_eq!(session.take_events(), vec![TerminalEvent::AltScreenExited]);
+}
+
+#[test]
+fn clear_scrollback_keeps_screen_and_moves_prompt_marks_up() {
+    let config = TerminalConfig {
+        cols: 10,
+        rows: 2,
+        ..TerminalConfig::default()
+    };
+    let mut session = TerminalSession::new(config).unwrap();
+    session
+        .feed(b"\x1b]133;A\x07$ a\r\nout\r\n\x1b]133;A\x07$ b")
+        .unwrap();
+    assert_eq!(session.scrollback_len(), 1);
+    assert_eq!(
+        session
+            .prompt_marks()
+            .iter()
+            .map(|mark| mark.prompt_row)
+            .collect::<Vec<_>>(),
+        [0, 2]
+    );
+
+    session.clear_scrollback().unwrap();
+
+    assert_eq!(session.scrollback_len(), 0);
+    assert_eq!(session.dump_viewport().unwrap().trim_end(), "out\n$ b");
+    assert_eq!(
+        session
+            .prompt_marks()
+            .iter()
+            .map(|mark| mark.prompt_row)
+            .collect::<Vec<_>>(),
+        [1]
+    );
+}
+
+#[test]
+fn scroll_offset_and_total_lines_describe_the_scrollbar() {
+    let config = TerminalConfig {
+        cols: 10,
+        rows: 2,
+        ..TerminalConfig::default()
+    };
+    let mut session = TerminalSession::new(config).unwrap();
+    session.feed(b"a\r\nb\r\nc\r\nd\r\ne").unwrap();
+    assert_eq!(session.total_lines(), 5);
+    assert_eq!(session.scroll_offset(), 0);
+
+    session.scroll_viewport(-2).unwrap();
+    assert_eq!(session.scroll_offset(), 2);
+    session.scroll_viewport_top().unwrap();
+    assert_eq!(session.scroll_offset(), 3);
+}
+
+#[test]
+fn session_dumps_scrollback_rows() {
+    let config = TerminalConfig {
+        cols: 10,
+        rows: 2,
+        ..TerminalConfig::default()
+    };
+    let mut session = TerminalSession::new(config).unwrap();
+    session.feed(b"a\r\nb\r\nc\r\nd").unwrap();
+
+    assert_eq!(session.scrollback_len(), 2);
+    let history = session
+        .dump_scrollback(0..session.scrollback_len())
+        .unwrap();
+    let history: Vec<&str> = history.lines().map(str::trim_end).collect();
+    assert_eq!(history, ["a", "b"]);
+}
+
+#[test]
+fn viewport_row_wraps_mark_soft_wrapped_rows() {
+    let config = TerminalConfig {
+        cols: 10,
+        rows: 3,
+        ..TerminalConfig::default()
+    };
+    let mut session = TerminalSession::new(config).unwrap();
+    session.feed(b"git commit -m wip\r\nok").unwrap();
+
+    assert_eq!(session.viewport_row_wraps().unwrap(), [true, false, false]);
+    assert_eq!(
+        session.dump_logical_lines(0..2).unwrap(),
+        ["git commit -m wip"]
+    );
+}
+
+#[test]
+fn configured_cursor_style_is_default_and_blink_override_wins() {
+    let config = TerminalConfig {
+        cursor_style: ghostty_vt::CursorStyle::Bar,
+        ..TerminalConfig::default()
+    };
+    let mut session = TerminalSession::new(config).unwrap();
+    assert_eq!(session.cursor_style(), ghostty_vt::CursorStyle::Bar);
+
+    session.feed(b"\x1b[1 q").unwrap();
+    assert_eq!(session.cursor_style(), ghostty_vt::CursorStyle::Block);
+    assert!(session.cursor_should_blink());
+    session.feed(b"\x1b[0 q").unwrap();
+    assert_eq!(session.cursor_style(), ghostty_vt::CursorStyle::Bar);
+
+    session.config_mut().cursor_style_blink = Some(false);
+    assert!(!session.cursor_should_blink());
+}
+
+#[test]
+fn reloading_the_config_keeps_the_live_grid_size() {
+    let mut session = TerminalSession::new(TerminalConfig::default()).unwrap();
+    session.resize(120, 40).unwrap();
+
+    session.apply_config(TerminalConfig {
+        cursor_style: ghostty_vt::CursorStyle::Bar,
+        scrollback_lines: Some(100),
+        ..TerminalConfig::default()
+    });
+    assert_eq!((session.cols(), session.rows()), (120, 40));
+    assert_eq!(session.cursor_style(), ghostty_vt::CursorStyle::Bar);
+    assert_eq!(session.config().scrollback_lines, Some(100));
+
+    // The grid still matches the reported size.
+    session.feed(&[b'x'; 120]).unwrap();
+    assert_eq!(session.dump_screen_row(0).unwrap(), "x".repeat(120));
+    assert_eq!(session.dump_screen_row(1).unwrap(), "");
+}
+
+#[test]
+fn diff_since_reports_only_rows_changed_after_generation() {
+    let config = TerminalConfig {
+        cols: 10,
+        rows: 3,
+        ..TerminalConfig::default()
+    };
+    let mut session = TerminalSession::new(config).unwrap();
+    session.feed(b"one\r\ntwo").unwrap();
+
+    let full = session.diff_since(0).unwrap();
+    assert_eq!(full.generation, session.generation());
+    assert_eq!((full.cols, full.rows), (10, 3));
+    assert_eq!(full.changed_rows.len(), 3);
+    assert_eq!(full.changed_rows[0].text.trim_end(), "one");
+    assert_eq!(full.cursor, Some(ViewportPoint { col: 4, row: 2 }));
+
+    let unchanged = session.diff_since(full.generation).unwrap();
+    assert_eq!(unchanged.generation, full.generation);
+    assert!(unchanged.changed_rows.is_empty());
+
+    session.feed(b"\x1b[31mX").unwrap();
+    let diff = session.diff_since(full.generation).unwrap();
+    assert!(diff.generation > full.generation);
+    assert_eq!(diff.changed_rows.len(), 1);
+    assert_eq!(diff.changed_rows[0].row, 1);
+    assert_eq!(diff.changed_rows[0].text.trim_end(), "twoX");
+    assert!(
+        diff.changed_rows[0]
+            .style_runs
+            .iter()
+            .any(|run| run.start_col == 4)
+    );
+
+    session.resize(12, 3).unwrap();
+    let resized = session.diff_since(diff.generation).unwrap();
+    assert_eq!(resized.cols, 12);
+    assert_eq!(resized.changed_rows.len(), 3);
+}
+
+#[test]
+fn content_generation_moves_only_on_visible_changes() {
+    use std::cell::RefCell;
+    use std::rc::Rc;
+
+    let mut session = TerminalSession::new(TerminalConfig {
+        cols: 10,
+        rows: 3,
+        ..TerminalConfig::default()
+    })
+    .unwrap();
+    let seen = Rc::new(RefCell::new(Vec::new()));
+    session.subscribe_generation({
+        let seen = seen.clone();
+        move |generation| seen.borrow_mut().push(generation)
+    });
+
+    let mut last_seen = session.generation();
+    session.feed(b"hello").unwrap();
+    assert!(session.observe_generation(&mut last_seen));
+    assert!(!session.observe_generation(&mut last_seen));
+
+    // Once the rows are drawn, output that changes nothing leaves the generation alone.
+    session.take_dirty_viewport_rows();
+    session.feed(b"\x1b[0m").unwrap();
+    assert_eq!(session.generation(), last_seen);
+
+    session.resize(12, 3).unwrap();
+    assert_eq!(*seen.borrow(), vec![1, 2]);
+    assert_eq!(session.generation(), 2);
+}
+
+#[test]
+fn window_background_follows_opacity_and_blur() {
+    use gpui::WindowBackgroundAppearance;
+
+    let mut config = TerminalConfig::default();
+    assert_eq!(
+        crate::window_background_appearance(&config),
+        WindowBackgroundAppearance::Opaque
+    );
+
+    config.background_opacity = 0.8;
+    assert_eq!(
+        crate::window_background_appearance(&config),
+        WindowBackgroundAppearance::Blurred
+    );
+
+    config.background_blur = false;
+    assert_eq!(
+        crate::window_options_for_config(&config).window_background,
+        WindowBackgroundAppearance::Transparent
+    );
+}
+
+#[test]
+fn ligature_toggle_switches_calt_and_liga_but_not_kerning() {
+    let off = crate::default_terminal_font_features();
+    assert!(off.0.iter().all(|(_, value)| *value == 0));
+
+    let on = crate::terminal_font_features(true);
+    let value = |tag: &str| on.0.iter().find(|(t, _)| t == tag).map(|(_, v)| *v);
+    assert_eq!(value("calt"), Some(1));
+    assert_eq!(value("liga"), Some(1));
+    assert_eq!(value("kern"), Some(0));
+}
+
+#[test]
+fn config_watch_reacts_to_writes_of_the_config_file_only() {
+    use crate::config_watch::is_config_change;
+    use notify::event::{AccessKind, CreateKind, DataChange, EventKind, ModifyKind};
+    use std::path::{Path, PathBuf};
+
+    let path = Path::new("/home/u/.config/Job/terminal/config");
+    let event = |kind: EventKind, file: &str| {
+        notify::Event::new(kind).add_path(PathBuf::from("/home/u/.config/Job/terminal").join(file))
+    };
+
+    let write = EventKind::Modify(ModifyKind::Data(DataChange::Content));
+    assert!(is_config_change(&event(write, "config"), path));
+    assert!(is_config_change(
+        &event(EventKind::Create(CreateKind::File), "config"),
+        path
+    ));
+    assert!(!is_config_change(&event(write, "config.swp"), path));
+    assert!(!is_config_change(
+        &event(EventKind::Access(AccessKind::Read), "config"),
+        path
+    ));
+}
+
+#[test]
+fn theme_registry_scans_directories_and_fuzzy_searches() {
+    use crate::{ThemeRegistry, ThemeSource};
+
+    let dir = std::env::temp_dir().join(format!("gpui-ghostty-themes-{}", std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+    std::fs::write(
+        dir.join("Paper Mint"),
+        "background = #f4f8f0\nforeground = #203020\npalette = 1=#c03030\n",
+    )
+    .unwrap();
+    // Embedded themes take precedence over files of the same name.
+    std::fs::write(dir.join("dracula"), "background = #ffffff\n").unwrap();
+
+    let registry = ThemeRegistry::new([dir.clone()]);
+    std::fs::remove_dir_all(&dir).unwrap();
+
+    let mint = registry.get("paper mint").unwrap();
+    assert_eq!(mint.source, ThemeSource::File(dir.join("Paper Mint")));
+    assert!(!mint.is_dark);
+    assert_eq!(
+        mint.foreground,
+        Rgb {
+            r: 0x20,
+            g: 0x30,
+            b: 0x20
+        }
+    );
+
+    let dracula = registry.get("Dracula").unwrap();
+    assert_eq!(dracula.source, ThemeSource::Embedded);
+    assert!(dracula.is_dark);
+    assert_eq!(
+        registry.get("Catppuccin Mocha").unwrap().name,
+        "catppuccin-mocha"
+    );
+
+    let names: Vec<&str> = registry
+        .search("cat moc")
+        .iter()
+        .map(|t| t.name.as_str())
+        .collect();
+    assert_eq!(names.first(), Some(&"catppuccin-mocha"));
+    assert_eq!(registry.search("paper")[0].name, "Paper Mint");
+    assert!(registry.search("zzzz").is_empty());
+
+    let mut config = TerminalConfig::default();
+    registry.apply(&mut config, "paper mint").unwrap();
+    assert_eq!(
+        config.default_bg,
+        Rgb {
+            r: 0xf4,
+            g: 0xf8,
+            b: 0xf0
+        }
+    );
+    assert_eq!(
+        config.palette.unwrap()[1],
+        Rgb {
+            r: 0xc0,
+            g: 0x30,
+            b: 0x30
+        }
+    );
+}
+
+#[test]
+fn imports_itermcolors_presets() {
+    let plist = br#"<?xml version="1.0" encoding="UTF-8"?>
+<!DOCTYPE plist PUBLIC "-//Apple//DTD PLIST 1.0//EN" "http://www.apple.com/DTDs/PropertyList-1.0.dtd">
+<plist version="1.0">
+<dict>
+    <key>Ansi 1 Color</key>
+    <dict>
+        <key>Blue Component</key><real>0.0</real>
+        <key>Green Component</key><real>0.0</real>
+        <key>Red Component</key><real>1</real>
+    </dict>
+    <key>Background Color</key>
+    <dict>
+        <key>Blue Component</key><real>0.2</real>
+        <key>Color Space</key><string>sRGB</string>
+        <key>Green Component</key><real>0.1</real>
+        <key>Red Component</key><real>0.0</real>
+    </dict>
+</dict>
+</plist>"#;
+
+    let theme = crate::import_itermcolors(plist).unwrap();
+    assert_eq!(theme, "palette = 1=#ff0000\nbackground = #001a33\n");
+    assert!(crate::import_itermcolors(b"not a plist").is_err());
+}
+
+#[test]
+fn imports_windows_terminal_schemes() {
+    let json = r##"{
+        "schemes": [
+            {
+                "name": "Campbell",
+                "background": "#0C0C0C",
+                "foreground": "#CCCCCC",
+                "cursorColor": "#FFFFFF",
+                "selectionBackground": "#FFFFFF",
+                "black": "#0C0C0C",
+                "brightWhite": "#F2F2F2"
+            },
+            { "background": "#000000" }
+        ]
+    }"##;
+
+    let schemes = crate::import_windows_terminal_schemes(json).unwrap();
+    assert_eq!(schemes.len(), 1);
+    let (name, contents) = &schemes[0];
+    assert_eq!(name, "Campbell");
+
+    let mut config = TerminalConfig::default();
+    crate::config_file::apply_theme_with_contents(&mut config, name, contents).unwrap();
+    assert_eq!(
+        config.default_bg,
+        Rgb {
+            r: 0x0c,
+            g: 0x0c,
+            b: 0x0c
+        }
+    );
+    assert_eq!(
+        config.palette.unwrap()[15],
+        Rgb {
+            r: 0xf2,
+            g: 0xf2,
+            b: 0xf2
+        }
+    );
+    assert_eq!(
+        config.selection_background,
+        Some(Rgb {
+            r: 0xff,
+            g: 0xff,
+            b: 0xff
+        })
+    );
+    assert_eq!(config.theme_spec.as_deref(), Some("Campbell"));
+
+    assert!(crate::import_windows_terminal_schemes("[]").is_err());
+}
+
+#[test]
+fn terminal_harness_snapshots_text_styles_and_blink_phases() {
+    use crate::testing::TerminalHarness;
+    use std::time::Duration;
+
+    let mut harness = TerminalHarness::new(12, 3);
+    harness.feed(b"\x1b[1mbold\x1b[m plain\r\n\x1b[5mblink\x1b[m\x1b[6n");
+    assert_eq!(harness.take_responses(), b"\x1b[2;6R");
+
+    let snapshot = harness.snapshot();
+    assert_eq!(snapshot.text(), "bold plain\nblink\n");
+    assert!(snapshot.cells[0][..4].iter().all(|cell| cell.bold));
+    assert!(!snapshot.cells[0][4].bold);
+    assert!(snapshot.cells[1][0].blink);
+    assert_eq!(snapshot.cursor, Some(ViewportPoint { col: 6, row: 2 }));
+    assert!(!snapshot.text_blink_hidden);
+    let golden = snapshot.to_string();
+    assert!(golden.starts_with("bold plain\nblink\n\n--\ncursor 6,2 "));
+    assert!(golden.contains("\n1 1-4 bold"));
+    assert!(golden.contains("\n2 1-5 blink"));
+
+    harness.advance(Duration::from_millis(500));
+    assert!(harness.snapshot().text_blink_hidden);
+    harness.advance(Duration::from_millis(500));
+    assert!(!harness.snapshot().text_blink_hidden);
+    assert_eq!(harness.snapshot(), harness.snapshot());
+}
+
+#[test]
+fn records_output_input_and_resizes_as_asciicast_v2() {
+    use crate::RecordingOptions;
+    use std::cell::RefCell;
+    use std::io::Write;
+    use std::rc::Rc;
+
+    #[derive(Clone, Default)]
+    struct Shared(Rc<RefCell<Vec<u8>>>);
+    impl Write for Shared {
+        fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+            self.0.borrow_mut().extend_from_slice(buf);
+            Ok(buf.len())
+        }
+        fn flush(&mut self) -> std::io::Result<()> {
+            Ok(())
+        }
+    }
+
+    let config = TerminalConfig {
+        cols: 20,
+        rows: 5,
+        ..TerminalConfig::default()
+    };
+    let mut session = TerminalSession::new(config).unwrap();
+    let cast = Shared::default();
+    session
+        .start_recording_with(
+            cast.clone(),
+            RecordingOptions {
+                record_input: true,
+                title: Some("demo".to_string()),
+            },
+        )
+        .unwrap();
+    assert!(session.is_recording());
+
+    session.feed(b"hi \xe2\x82").unwrap();
+    session.feed(b"\xac\x1b[1m\"").unwrap();
+    session.record_input(b"ls\r");
+    session.resize(30, 6).unwrap();
+    session.stop_recording().unwrap();
+    assert!(!session.is_recording());
+    session.feed(b"after").unwrap();
+
+    let text = String::from_utf8(cast.0.borrow().clone()).unwrap();
+    let lines: Vec<serde_json::Value> = text
+        .lines()
+        .map(|line| serde_json::from_str(line).unwrap())
+        .collect();
+    assert_eq!(lines.len(), 5);
+    assert_eq!(lines[0]["version"], 2);
+    assert_eq!(lines[0]["width"], 20);
+    assert_eq!(lines[0]["height"], 5);
+    assert_eq!(lines[0]["title"], "demo");
+    let events: Vec<(&str, &str)> = lines[1..]
+        .iter()
+        .map(|event| (event[1].as_str().unwrap(), event[2].as_str().unwrap()))
+        .collect();
+    assert_eq!(
+        events,
+        [
+            ("o", "hi "),
+            ("o", "\u{20ac}\x1b[1m\""),
+            ("i", "ls\r"),
+            ("r", "30x6"),
+        ]
+    );
+    assert!(lines[1][0].as_f64().unwrap() >= 0.0);
+}
+
+#[test]
+fn dragging_a_split_divider_moves_only_its_neighbors() {
+    use crate::panel::drag_divider;
+
+    fn assert_sizes(sizes: &[f32], expected: &[f32]) {
+        assert_eq!(sizes.len(), expected.len());
+        for (size, expected) in sizes.iter().zip(expected) {
+            assert!((size - expected).abs() < 1e-5, "{sizes:?} != {expected:?}");
+        }
+    }
+
+    let mut sizes = vec![0.25, 0.25, 0.5];
+    drag_divider(&mut sizes, 2, 0.6, 0.05);
+    assert_sizes(&sizes, &[0.25, 0.35, 0.4]);
+
+    // Clamped so neither neighbor drops below the minimum.
+    drag_divider(&mut sizes, 1, 0.0, 0.05);
+    assert_sizes(&sizes, &[0.05, 0.55, 0.4]);
+    drag_divider(&mut sizes, 1, 1.0, 0.05);
+    assert_sizes(&sizes, &[0.55, 0.05, 0.4]);
+
+    // Out-of-range dividers are ignored.
+    drag_divider(&mut sizes, 0, 0.5, 0.05);
+    drag_divider(&mut sizes, 3, 0.5, 0.05);
+    assert_sizes(&sizes, &[0.55, 0.05, 0.4]);
+}
+
+#[test]
+fn detached_session_keeps_parsing_and_answering_queries() {
+    use crate::TerminalInput;
+    use crate::session_handle::DetachedSession;
+    use std::sync::{Arc, Mutex};
+
+    let replies = Arc::new(Mutex::new(Vec::new()));
+    let sink = replies.clone();
+    let mut detached = DetachedSession {
+        session: TerminalSession::new(TerminalConfig::default()).unwrap(),
+        input: Some(TerminalInput::new(move |bytes| {
+            sink.lock().unwrap().extend_from_slice(bytes)
+        })),
+        resize_callback: None,
+    };
+
+    detached.feed(b"background\x1b[6n");
+
+    assert!(
+        detached
+            .session
+            .dump_viewport()
+            .unwrap()
+            .starts_with("background")
+    );
+    assert_eq!(*replies.lock().unwrap(), b"\x1b[1;11R");
+}
+
+#[test]
+fn process_exit_is_reported_as_an_event() {
+    let mut session = TerminalSession::new(TerminalConfig::default()).unwrap();
+
+    session.process_exited(Some(1));
+
+    assert_eq!(
+        session.take_events(),
+        vec![TerminalEvent::ProcessExited { exit_code: Some(1) }]
+    );
+}
+
+#[cfg(feature = "unstable-remote")]
+#[test]
+fn transport_output_is_batched_up_to_the_exit() {
+    use crate::transport::{TerminalIoEvent, coalesce_events};
+
+    let (bytes, exited) = coalesce_events(vec![
+        TerminalIoEvent::Output(b"one ".to_vec()),
+        TerminalIoEvent::Output(b"two".to_vec()),
+    ]);
+    assert_eq!(bytes, b"one two");
+    assert_eq!(exited, None);
+
+    let (bytes, exited) = coalesce_events(vec![
+        TerminalIoEvent::Output(b"bye".to_vec()),
+        TerminalIoEvent::Exited(Some(3)),
+        TerminalIoEvent::Output(b"late".to_vec()),
+    ]);
+    assert_eq!(bytes, b"bye");
+    assert_eq!(exited, Some(Some(3)));
+}
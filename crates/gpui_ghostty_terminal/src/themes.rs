@@ -5,10 +5,23 @@
 //! installed or any external theme files.
 //!
 //! Theme format follows Ghostty's key-value syntax.
+//!
+//! [`ThemeRegistry`] adds user theme packs: any number of directories of Ghostty theme files,
+//! scanned at runtime and searchable for a theme picker. iTerm2 `.itermcolors` presets and
+//! Windows Terminal scheme JSON are imported alongside Ghostty theme files.
 
+use std::borrow::Cow;
 use std::collections::HashMap;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
 use std::sync::LazyLock;
 
+use ghostty_vt::Rgb;
+
+use crate::TerminalConfig;
+use crate::config_file::{ConfigError, parse_color};
+
 /// A map of theme name (lowercase, normalized) to theme contents.
 static EMBEDDED_THEMES: LazyLock<HashMap<&'static str, &'static str>> = LazyLock::new(|| {
     let mut m = HashMap::new();
@@ -138,6 +151,453 @@ pub fn list_embedded_themes() -> Vec<&'static str> {
     ]
 }
 
+/// The directories Ghostty theme files are looked up in, in search order:
+///
+/// 1. `$XDG_CONFIG_HOME/ghostty/themes` (if `XDG_CONFIG_HOME` is set)
+/// 2. `~/.config/ghostty/themes`
+/// 3. `/Applications/Ghostty.app/Contents/Resources/ghostty/themes` (macOS)
+/// 4. `/usr/share/ghostty/themes` (Linux system-wide)
+pub fn default_theme_dirs() -> Vec<PathBuf> {
+    let mut dirs = Vec::new();
+    if let Ok(xdg_config) = std::env::var("XDG_CONFIG_HOME") {
+        dirs.push(PathBuf::from(xdg_config).join("ghostty/themes"));
+    }
+    if let Some(home) = crate::config_file::home_dir() {
+        dirs.push(home.join(".config/ghostty/themes"));
+    }
+    #[cfg(target_os = "macos")]
+    dirs.push(PathBuf::from(
+        "/Applications/Ghostty.app/Contents/Resources/ghostty/themes",
+    ));
+    #[cfg(target_os = "linux")]
+    dirs.push(PathBuf::from("/usr/share/ghostty/themes"));
+    dirs
+}
+
+/// Where a [`ThemeInfo`] was loaded from.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum ThemeSource {
+    /// Bundled in the binary (see [`list_embedded_themes`]).
+    Embedded,
+    /// A Ghostty theme file in one of the registry's directories.
+    File(PathBuf),
+}
+
+/// A theme known to a [`ThemeRegistry`], with what a theme picker shows about it.
+#[derive(Clone, Debug)]
+pub struct ThemeInfo {
+    /// The name to pass to [`ThemeRegistry::apply`] or the `theme` config option.
+    pub name: String,
+    pub source: ThemeSource,
+    /// Default background, or black when the theme does not set one.
+    pub background: Rgb,
+    /// Default foreground, or white when the theme does not set one.
+    pub foreground: Rgb,
+    /// Whether the background is dark, for grouping themes into dark and light.
+    pub is_dark: bool,
+}
+
+/// Themes from the embedded set plus any number of theme directories (user theme packs), scanned
+/// at runtime.
+///
+/// Directories are scanned by [`Self::rescan`]. Besides Ghostty theme files they may hold
+/// `.itermcolors` presets and Windows Terminal `.json` schemes, converted on the way in. The
+/// registry keeps each theme's contents, so applying a theme does not touch the filesystem
+/// again. When a name exists more than once, the embedded theme wins, then the earliest
+/// directory, matching the `theme` config option.
+#[derive(Clone, Debug, Default)]
+pub struct ThemeRegistry {
+    dirs: Vec<PathBuf>,
+    themes: Vec<ThemeEntry>,
+}
+
+#[derive(Clone, Debug)]
+struct ThemeEntry {
+    info: ThemeInfo,
+    contents: Cow<'static, str>,
+}
+
+impl ThemeRegistry {
+    /// A registry of the embedded themes and the given directories, scanned right away.
+    pub fn new(dirs: impl IntoIterator<Item = PathBuf>) -> Self {
+        let mut registry = Self {
+            dirs: dirs.into_iter().collect(),
+            themes: Vec::new(),
+        };
+        registry.rescan();
+        registry
+    }
+
+    /// A registry of the embedded themes and [`default_theme_dirs`].
+    pub fn with_default_dirs() -> Self {
+        Self::new(default_theme_dirs())
+    }
+
+    /// The scanned directories, in precedence order.
+    pub fn dirs(&self) -> &[PathBuf] {
+        &self.dirs
+    }
+
+    /// Add a directory (after the existing ones) and scan it.
+    pub fn add_dir(&mut self, dir: impl Into<PathBuf>) {
+        self.dirs.push(dir.into());
+        self.rescan();
+    }
+
+    /// Re-read every directory, picking up added, changed and removed theme files. Missing or
+    /// unreadable directories and files are skipped.
+    pub fn rescan(&mut self) {
+        let mut themes: Vec<ThemeEntry> = list_embedded_themes()
+            .into_iter()
+            .filter_map(|name| {
+                let contents = get_embedded_theme(name)?;
+                Some(ThemeEntry::new(
+                    name.to_string(),
+                    ThemeSource::Embedded,
+                    Cow::Borrowed(contents),
+                ))
+            })
+            .collect();
+
+        for dir in &self.dirs {
+            let Ok(entries) = fs::read_dir(dir) else {
+                continue;
+            };
+            let mut files: Vec<PathBuf> = entries
+                .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+                .filter(|path| path.is_file())
+                .collect();
+            files.sort();
+            for path in files {
+                if path
+                    .file_name()
+                    .and_then(|name| name.to_str())
+                    .is_none_or(|name| name.starts_with('.'))
+                {
+                    continue;
+                }
+                for (name, contents) in read_theme_file(&path) {
+                    if get_embedded_theme(&name).is_some()
+                        || themes
+                            .iter()
+                            .any(|t| t.info.name.eq_ignore_ascii_case(&name))
+                    {
+                        continue;
+                    }
+                    themes.push(ThemeEntry::new(
+                        name,
+                        ThemeSource::File(path.clone()),
+                        Cow::Owned(contents),
+                    ));
+                }
+            }
+        }
+
+        themes.sort_by_cached_key(|t| t.info.name.to_lowercase());
+        self.themes = themes;
+    }
+
+    /// Every theme, sorted by name.
+    pub fn themes(&self) -> impl Iterator<Item = &ThemeInfo> {
+        self.themes.iter().map(|t| &t.info)
+    }
+
+    /// Look up a theme by name, ignoring case (embedded aliases like `Catppuccin Mocha` work too).
+    pub fn get(&self, name: &str) -> Option<&ThemeInfo> {
+        self.entry(name).map(|t| &t.info)
+    }
+
+    /// The theme file contents, in Ghostty's `key = value` syntax.
+    pub fn contents(&self, name: &str) -> Option<&str> {
+        self.entry(name).map(|t| t.contents.as_ref())
+    }
+
+    /// Themes whose name matches `query` as a fuzzy subsequence (`"cmoc"` finds
+    /// `catppuccin-mocha`), best match first: exact names, then prefixes, then substrings, then
+    /// tighter subsequences. An empty query lists every theme.
+    pub fn search(&self, query: &str) -> Vec<&ThemeInfo> {
+        let query = normalize_theme_name(query);
+        let mut matches: Vec<(u32, &ThemeInfo)> = self
+            .themes
+            .iter()
+            .filter_map(|t| {
+                Some((
+                    fuzzy_score(&query, &normalize_theme_name(&t.info.name))?,
+                    &t.info,
+                ))
+            })
+            .collect();
+        matches.sort_by_key(|(score, _)| *score);
+        matches.into_iter().map(|(_, info)| info).collect()
+    }
+
+    /// Apply the named theme to `config`, like [`apply_theme`](crate::apply_theme) but from the
+    /// registry's cached contents.
+    pub fn apply(&self, config: &mut TerminalConfig, name: &str) -> Result<(), ConfigError> {
+        let entry = self.entry(name).ok_or(ConfigError::NotFound)?;
+        crate::config_file::apply_theme_with_contents(config, &entry.info.name, &entry.contents)
+    }
+
+    fn entry(&self, name: &str) -> Option<&ThemeEntry> {
+        if let Some(entry) = self
+            .themes
+            .iter()
+            .find(|t| t.info.name.eq_ignore_ascii_case(name))
+        {
+            return Some(entry);
+        }
+        // Embedded aliases ("tokyo night" for "tokyonight").
+        let contents = get_embedded_theme(name)?;
+        self.themes.iter().find(|t| {
+            t.info.source == ThemeSource::Embedded && std::ptr::eq(t.contents.as_ref(), contents)
+        })
+    }
+}
+
+impl ThemeEntry {
+    fn new(name: String, source: ThemeSource, contents: Cow<'static, str>) -> Self {
+        let mut background = Rgb { r: 0, g: 0, b: 0 };
+        let mut foreground = Rgb {
+            r: 0xFF,
+            g: 0xFF,
+            b: 0xFF,
+        };
+        for line in contents.lines() {
+            let Some((key, value)) = line.split_once('=') else {
+                continue;
+            };
+            let color = || parse_color(value.trim().trim_matches('"'));
+            match key.trim() {
+                "background" => background = color().unwrap_or(background),
+                "foreground" => foreground = color().unwrap_or(foreground),
+                _ => {}
+            }
+        }
+        Self {
+            info: ThemeInfo {
+                name,
+                source,
+                background,
+                foreground,
+                is_dark: relative_luminance(background) < 0.5,
+            },
+            contents,
+        }
+    }
+}
+
+/// Read the themes in a file as `(name, Ghostty theme contents)`: one for a Ghostty theme or an
+/// `.itermcolors` file (named after the file), any number for a Windows Terminal `.json`. Files
+/// that cannot be read or converted yield nothing.
+fn read_theme_file(path: &Path) -> Vec<(String, String)> {
+    let extension = path
+        .extension()
+        .and_then(|ext| ext.to_str())
+        .map(str::to_ascii_lowercase);
+    match extension.as_deref() {
+        Some("itermcolors") => {
+            let Some(name) = path.file_stem().and_then(|stem| stem.to_str()) else {
+                return Vec::new();
+            };
+            fs::read(path)
+                .ok()
+                .and_then(|bytes| import_itermcolors(&bytes).ok())
+                .map(|contents| vec![(name.to_string(), contents)])
+                .unwrap_or_default()
+        }
+        Some("json") => fs::read_to_string(path)
+            .ok()
+            .and_then(|json| import_windows_terminal_schemes(&json).ok())
+            .unwrap_or_default(),
+        _ => {
+            let Some(name) = path.file_name().and_then(|name| name.to_str()) else {
+                return Vec::new();
+            };
+            fs::read_to_string(path)
+                .map(|contents| vec![(name.to_string(), contents)])
+                .unwrap_or_default()
+        }
+    }
+}
+
+/// Convert an iTerm2 `.itermcolors` color preset (XML or binary plist) to Ghostty theme syntax,
+/// ready for [`ThemeRegistry`] or [`import_theme_file`](crate::import_theme_file).
+///
+/// Reads the 16 ANSI colors, foreground, background, cursor, cursor text, selection and
+/// selected text colors; entries the preset lacks are left out.
+pub fn import_itermcolors(bytes: &[u8]) -> Result<String, ConfigError> {
+    let value = plist::Value::from_reader(io::Cursor::new(bytes))
+        .map_err(|e| ConfigError::Invalid(format!("invalid .itermcolors plist: {}", e)))?;
+    let dict = value
+        .as_dictionary()
+        .ok_or_else(|| ConfigError::Invalid(".itermcolors is not a dictionary".to_string()))?;
+    let color = |key: &str| -> Option<Rgb> {
+        let color = dict.get(key)?.as_dictionary()?;
+        let component = |name: &str| -> Option<u8> {
+            let value = color.get(name)?;
+            let value = value
+                .as_real()
+                .or_else(|| value.as_signed_integer().map(|v| v as f64))?;
+            Some((value.clamp(0.0, 1.0) * 255.0).round() as u8)
+        };
+        Some(Rgb {
+            r: component("Red Component")?,
+            g: component("Green Component")?,
+            b: component("Blue Component")?,
+        })
+    };
+
+    let mut theme = ThemeWriter::default();
+    for index in 0..16 {
+        theme.palette(index, color(&format!("Ansi {} Color", index)));
+    }
+    theme.color("background", color("Background Color"));
+    theme.color("foreground", color("Foreground Color"));
+    theme.color("cursor-color", color("Cursor Color"));
+    theme.color("cursor-text", color("Cursor Text Color"));
+    theme.color("selection-background", color("Selection Color"));
+    theme.color("selection-foreground", color("Selected Text Color"));
+    theme.finish()
+}
+
+/// Windows Terminal scheme keys for palette entries 0-15.
+const WINDOWS_TERMINAL_PALETTE_KEYS: [&str; 16] = [
+    "black",
+    "red",
+    "green",
+    "yellow",
+    "blue",
+    "purple",
+    "cyan",
+    "white",
+    "brightBlack",
+    "brightRed",
+    "brightGreen",
+    "brightYellow",
+    "brightBlue",
+    "brightPurple",
+    "brightCyan",
+    "brightWhite",
+];
+
+/// Convert Windows Terminal color schemes to Ghostty theme syntax, as `(name, contents)` pairs.
+///
+/// Accepts a single scheme object, an array of schemes, or a settings file or fragment with a
+/// `schemes` array. Schemes without a `name` are skipped.
+pub fn import_windows_terminal_schemes(json: &str) -> Result<Vec<(String, String)>, ConfigError> {
+    let value: serde_json::Value = serde_json::from_str(json)
+        .map_err(|e| ConfigError::Invalid(format!("invalid Windows Terminal JSON: {}", e)))?;
+    let schemes = match &value {
+        serde_json::Value::Array(schemes) => schemes.as_slice(),
+        serde_json::Value::Object(object) => match object.get("schemes") {
+            Some(serde_json::Value::Array(schemes)) => schemes.as_slice(),
+            _ => std::slice::from_ref(&value),
+        },
+        _ => &[],
+    };
+
+    let mut imported = Vec::new();
+    for scheme in schemes {
+        let Some(name) = scheme.get("name").and_then(|name| name.as_str()) else {
+            continue;
+        };
+        let color = |key: &str| scheme.get(key)?.as_str().and_then(parse_color);
+
+        let mut theme = ThemeWriter::default();
+        for (index, key) in WINDOWS_TERMINAL_PALETTE_KEYS.iter().enumerate() {
+            theme.palette(index, color(key));
+        }
+        theme.color("background", color("background"));
+        theme.color("foreground", color("foreground"));
+        theme.color("cursor-color", color("cursorColor"));
+        theme.color("selection-background", color("selectionBackground"));
+        imported.push((name.to_string(), theme.finish()?));
+    }
+    if imported.is_empty() {
+        return Err(ConfigError::Invalid(
+            "no named Windows Terminal color scheme found".to_string(),
+        ));
+    }
+    Ok(imported)
+}
+
+/// Builds Ghostty theme contents from imported colors.
+#[derive(Default)]
+struct ThemeWriter {
+    contents: String,
+}
+
+impl ThemeWriter {
+    fn palette(&mut self, index: usize, color: Option<Rgb>) {
+        if let Some(Rgb { r, g, b }) = color {
+            self.contents.push_str(&format!(
+                "palette = {}=#{:02x}{:02x}{:02x}\n",
+                index, r, g, b
+            ));
+        }
+    }
+
+    fn color(&mut self, key: &str, color: Option<Rgb>) {
+        if let Some(Rgb { r, g, b }) = color {
+            self.contents
+                .push_str(&format!("{} = #{:02x}{:02x}{:02x}\n", key, r, g, b));
+        }
+    }
+
+    fn finish(self) -> Result<String, ConfigError> {
+        if self.contents.is_empty() {
+            return Err(ConfigError::Invalid(
+                "color scheme has no colors".to_string(),
+            ));
+        }
+        Ok(self.contents)
+    }
+}
+
+/// Perceived brightness in `0.0..=1.0` (Rec. 709 weights on the encoded channels).
+fn relative_luminance(color: Rgb) -> f32 {
+    (0.2126 * color.r as f32 + 0.7152 * color.g as f32 + 0.0722 * color.b as f32) / 255.0
+}
+
+fn normalize_theme_name(name: &str) -> String {
+    name.trim()
+        .chars()
+        .map(|ch| match ch {
+            '-' | '_' => ' ',
+            ch => ch.to_ascii_lowercase(),
+        })
+        .collect()
+}
+
+/// Lower is better; `None` when `query` is not a subsequence of `name`.
+fn fuzzy_score(query: &str, name: &str) -> Option<u32> {
+    if query.is_empty() {
+        return Some(0);
+    }
+    if name == query {
+        return Some(0);
+    }
+    if name.starts_with(query) {
+        return Some(1);
+    }
+    if name.contains(query) {
+        return Some(2);
+    }
+    // Subsequence: penalize the characters skipped between matches.
+    let mut gaps = 0u32;
+    let mut name_chars = name.chars();
+    for q in query.chars().filter(|ch| *ch != ' ') {
+        loop {
+            let ch = name_chars.next()?;
+            if ch == q {
+                break;
+            }
+            gaps += 1;
+        }
+    }
+    Some(3 + gaps)
+}
+
 // =============================================================================
 // Embedded Theme Contents
 // =============================================================================
@@ -0,0 +1,103 @@
+//! Byte transports behind a [`TerminalView`]: a local PTY, an SSH channel, a WebSocket.
+//!
+//! A [`TerminalIo`] carries input and size changes to the other end and streams its output
+//! back; [`connect_io`] wires one to a view, so the view does not care where the bytes come from.
+
+use std::sync::Arc;
+
+use futures::StreamExt;
+use futures::stream::BoxStream;
+use gpui::{App, Entity, Task};
+
+use crate::view::{TerminalInput, TerminalResizeCallback, TerminalView};
+
+mod pty;
+#[cfg(feature = "unstable-remote-ssh")]
+mod ssh;
+
+pub use pty::PtyIo;
+#[cfg(feature = "unstable-remote-ssh")]
+pub use ssh::{SshAuth, SshConfig, SshIo};
+
+/// Output chunks coalesced into one update of the view.
+const OUTPUT_BATCH: usize = 64;
+
+/// What the other end of a transport reports.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum TerminalIoEvent {
+    /// Bytes written by the process, to be parsed by the terminal.
+    Output(Vec<u8>),
+    /// The process exited (or the connection closed), with its exit code when known.
+    Exited(Option<i32>),
+}
+
+/// A connection to the process behind a terminal.
+///
+/// `write` and `resize` are called from the UI thread and must not block for long; queue the
+/// bytes to a writer thread or task if the underlying sink can.
+pub trait TerminalIo: Send + Sync + 'static {
+    /// Sends input to the process. Returns `false` when the bytes could not be delivered, so the
+    /// view holds typed input back (see [`TerminalInput::new_fallible`]).
+    fn write(&self, bytes: &[u8]) -> bool;
+
+    /// Tells the other end the grid size changed.
+    fn resize(&self, cols: u16, rows: u16);
+
+    /// The process's output, ending with [`TerminalIoEvent::Exited`]. Called once, by
+    /// [`connect_io`].
+    fn output(&mut self) -> BoxStream<'static, TerminalIoEvent>;
+}
+
+/// Connects `view` to `io`: typed input and grid size changes go to the transport, its output
+/// is queued on the view, and its exit reaches [`TerminalView::process_exited`]. A stream that
+/// ends without an exit event counts as an exit with an unknown code.
+///
+/// The pump stops when the returned task is dropped, so detach it or keep it with the view.
+pub fn connect_io(view: &Entity<TerminalView>, mut io: impl TerminalIo, cx: &mut App) -> Task<()> {
+    let output = io.output();
+    let io = Arc::new(io);
+    let input = {
+        let io = io.clone();
+        TerminalInput::new_fallible(move |bytes| io.write(bytes))
+    };
+    let resize_callback = TerminalResizeCallback::new(move |cols, rows| io.resize(cols, rows));
+    view.update(cx, |view, cx| {
+        view.attach_process(input, resize_callback, cx)
+    });
+
+    let view = view.downgrade();
+    let mut output = output.ready_chunks(OUTPUT_BATCH);
+    cx.spawn(async move |cx| {
+        let mut exit_code = None;
+        while let Some(events) = output.next().await {
+            let (bytes, exited) = coalesce_events(events);
+            let updated = view.update(cx, |view, cx| {
+                if !bytes.is_empty() {
+                    view.queue_output_bytes(&bytes, cx);
+                }
+            });
+            if updated.is_err() {
+                return;
+            }
+            if let Some(code) = exited {
+                exit_code = code;
+                break;
+            }
+        }
+        view.update(cx, |view, cx| view.process_exited(exit_code, cx))
+            .ok();
+    })
+}
+
+/// Joins the output of a batch of events, and reports the exit if one is among them. Events
+/// after an exit are dropped.
+pub(crate) fn coalesce_events(events: Vec<TerminalIoEvent>) -> (Vec<u8>, Option<Option<i32>>) {
+    let mut bytes = Vec::new();
+    for event in events {
+        match event {
+            TerminalIoEvent::Output(chunk) => bytes.extend_from_slice(&chunk),
+            TerminalIoEvent::Exited(code) => return (bytes, Some(code)),
+        }
+    }
+    (bytes, None)
+}
@@ -0,0 +1,125 @@
+//! A local PTY as a [`TerminalIo`], via `portable-pty`.
+
+use std::io::{self, Read, Write};
+use std::sync::{Mutex, mpsc as std_mpsc};
+use std::thread;
+
+use futures::channel::mpsc;
+use futures::stream::{BoxStream, StreamExt};
+use portable_pty::{CommandBuilder, MasterPty, PtySize, native_pty_system};
+
+use super::{TerminalIo, TerminalIoEvent};
+use crate::SpawnOptions;
+
+/// A process running on a local PTY.
+pub struct PtyIo {
+    master: Mutex<Box<dyn MasterPty + Send>>,
+    /// Input goes through a writer thread so a full PTY buffer never blocks the UI.
+    input: std_mpsc::Sender<Vec<u8>>,
+    output: Option<mpsc::UnboundedReceiver<TerminalIoEvent>>,
+}
+
+impl PtyIo {
+    /// Spawns the process described by `options` on a new `cols`×`rows` PTY.
+    pub fn spawn(options: &SpawnOptions, cols: u16, rows: u16) -> io::Result<Self> {
+        let pair = native_pty_system()
+            .openpty(PtySize {
+                rows,
+                cols,
+                pixel_width: 0,
+                pixel_height: 0,
+            })
+            .map_err(io::Error::other)?;
+
+        let (program, args) = options.program_and_args();
+        let mut cmd = CommandBuilder::new(program);
+        cmd.args(args);
+        if let Some(cwd) = options.working_dir() {
+            cmd.cwd(cwd);
+        }
+        for (key, value) in options.environment() {
+            cmd.env(key, value);
+        }
+        let mut child = pair.slave.spawn_command(cmd).map_err(io::Error::other)?;
+        // Only the child may hold the slave, so reads end once it exits.
+        drop(pair.slave);
+
+        let mut reader = pair.master.try_clone_reader().map_err(io::Error::other)?;
+        let mut writer = pair.master.take_writer().map_err(io::Error::other)?;
+
+        let (input, input_rx) = std_mpsc::channel::<Vec<u8>>();
+        thread::spawn(move || {
+            while let Ok(bytes) = input_rx.recv() {
+                if writer
+                    .write_all(&bytes)
+                    .and_then(|()| writer.flush())
+                    .is_err()
+                {
+                    break;
+                }
+            }
+        });
+
+        let (tx, rx) = mpsc::unbounded();
+        thread::spawn(move || {
+            let mut buf = [0u8; 8192];
+            loop {
+                let n = match reader.read(&mut buf) {
+                    Ok(0) | Err(_) => break,
+                    Ok(n) => n,
+                };
+                if tx
+                    .unbounded_send(TerminalIoEvent::Output(buf[..n].to_vec()))
+                    .is_err()
+                {
+                    break;
+                }
+            }
+            let exit_code = child
+                .wait()
+                .ok()
+                .and_then(|status| i32::try_from(status.exit_code()).ok());
+            let _ = tx.unbounded_send(TerminalIoEvent::Exited(exit_code));
+        });
+
+        Ok(Self {
+            master: Mutex::new(pair.master),
+            input,
+            output: Some(rx),
+        })
+    }
+
+    /// The PTY's foreground process group leader, for [`process_name`](crate::process_name) and
+    /// friends.
+    #[cfg(unix)]
+    pub fn foreground_process(&self) -> Option<u32> {
+        let master = self.master.lock().ok()?;
+        master
+            .process_group_leader()
+            .and_then(|pid| u32::try_from(pid).ok())
+    }
+}
+
+impl TerminalIo for PtyIo {
+    fn write(&self, bytes: &[u8]) -> bool {
+        self.input.send(bytes.to_vec()).is_ok()
+    }
+
+    fn resize(&self, cols: u16, rows: u16) {
+        if let Ok(master) = self.master.lock() {
+            let _ = master.resize(PtySize {
+                rows,
+                cols,
+                pixel_width: 0,
+                pixel_height: 0,
+            });
+        }
+    }
+
+    fn output(&mut self) -> BoxStream<'static, TerminalIoEvent> {
+        match self.output.take() {
+            Some(rx) => rx.boxed(),
+            None => futures::stream::empty().boxed(),
+        }
+    }
+}
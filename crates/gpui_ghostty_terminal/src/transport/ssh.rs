@@ -0,0 +1,265 @@
+//! An SSH session as a [`TerminalIo`], via `russh`: a sample remote transport.
+//!
+//! The session runs on its own thread with a single-threaded tokio runtime, since GPUI's
+//! executors do not drive tokio IO.
+
+use std::io;
+use std::path::PathBuf;
+use std::sync::Arc;
+use std::sync::mpsc as std_mpsc;
+use std::thread;
+
+use futures::channel::mpsc;
+use futures::stream::{BoxStream, StreamExt};
+use russh::keys::{PrivateKeyWithHashAlg, load_secret_key, ssh_key};
+use russh::{ChannelMsg, client};
+use tokio::sync::mpsc as tokio_mpsc;
+
+use super::{TerminalIo, TerminalIoEvent};
+
+type HostKeyVerifyFn = dyn Fn(&str) -> bool + Send + Sync;
+
+/// How to log in.
+#[derive(Clone, Debug)]
+pub enum SshAuth {
+    Password(String),
+    /// A private key file (e.g. `~/.ssh/id_ed25519`), with its passphrase if encrypted.
+    KeyFile {
+        path: PathBuf,
+        passphrase: Option<String>,
+    },
+}
+
+/// Where and how to open a remote shell.
+#[derive(Clone)]
+pub struct SshConfig {
+    pub host: String,
+    pub port: u16,
+    pub user: String,
+    pub auth: SshAuth,
+    /// `TERM` requested for the remote PTY.
+    pub term: String,
+    /// Initial grid size; the view sends the real one once laid out.
+    pub cols: u16,
+    pub rows: u16,
+    /// Called with the server key's SHA-256 fingerprint (`SHA256:...`); return whether to trust
+    /// it, e.g. after checking `known_hosts` or asking the user.
+    pub verify_host_key: Arc<HostKeyVerifyFn>,
+}
+
+impl SshConfig {
+    /// Port 22, `xterm-256color`, 80×24.
+    pub fn new(
+        host: impl Into<String>,
+        user: impl Into<String>,
+        auth: SshAuth,
+        verify_host_key: impl Fn(&str) -> bool + Send + Sync + 'static,
+    ) -> Self {
+        Self {
+            host: host.into(),
+            port: 22,
+            user: user.into(),
+            auth,
+            term: "xterm-256color".to_string(),
+            cols: 80,
+            rows: 24,
+            verify_host_key: Arc::new(verify_host_key),
+        }
+    }
+}
+
+enum Command {
+    Data(Vec<u8>),
+    Resize(u16, u16),
+}
+
+/// A shell on a remote host.
+pub struct SshIo {
+    commands: tokio_mpsc::UnboundedSender<Command>,
+    output: Option<mpsc::UnboundedReceiver<TerminalIoEvent>>,
+}
+
+impl SshIo {
+    /// Connects, authenticates and starts a login shell on a remote PTY. Blocks until the shell
+    /// is running or the connection failed, so call it off the UI thread.
+    pub fn connect(config: SshConfig) -> io::Result<Self> {
+        let (ready_tx, ready_rx) = std_mpsc::channel::<io::Result<()>>();
+        let (commands, commands_rx) = tokio_mpsc::unbounded_channel();
+        let (output_tx, output_rx) = mpsc::unbounded();
+
+        thread::spawn(move || {
+            let runtime = match tokio::runtime::Builder::new_current_thread()
+                .enable_all()
+                .build()
+            {
+                Ok(runtime) => runtime,
+                Err(err) => {
+                    let _ = ready_tx.send(Err(err));
+                    return;
+                }
+            };
+            runtime.block_on(async move {
+                let (_session, channel) = match open_shell(&config).await {
+                    Ok(opened) => {
+                        let _ = ready_tx.send(Ok(()));
+                        opened
+                    }
+                    Err(err) => {
+                        let _ = ready_tx.send(Err(err));
+                        return;
+                    }
+                };
+                pump(channel, commands_rx, output_tx).await;
+            });
+        });
+
+        ready_rx
+            .recv()
+            .map_err(|_| io::Error::other("ssh connection thread exited"))??;
+        Ok(Self {
+            commands,
+            output: Some(output_rx),
+        })
+    }
+}
+
+impl TerminalIo for SshIo {
+    fn write(&self, bytes: &[u8]) -> bool {
+        self.commands.send(Command::Data(bytes.to_vec())).is_ok()
+    }
+
+    fn resize(&self, cols: u16, rows: u16) {
+        let _ = self.commands.send(Command::Resize(cols, rows));
+    }
+
+    fn output(&mut self) -> BoxStream<'static, TerminalIoEvent> {
+        match self.output.take() {
+            Some(rx) => rx.boxed(),
+            None => futures::stream::empty().boxed(),
+        }
+    }
+}
+
+struct HostKeyCheck {
+    verify: Arc<HostKeyVerifyFn>,
+}
+
+impl client::Handler for HostKeyCheck {
+    type Error = russh::Error;
+
+    async fn check_server_key(
+        &mut self,
+        server_public_key: &ssh_key::PublicKey,
+    ) -> Result<bool, Self::Error> {
+        let fingerprint = server_public_key.fingerprint(Default::default());
+        Ok((self.verify)(&fingerprint.to_string()))
+    }
+}
+
+async fn open_shell(
+    config: &SshConfig,
+) -> io::Result<(client::Handle<HostKeyCheck>, russh::Channel<client::Msg>)> {
+    let handler = HostKeyCheck {
+        verify: config.verify_host_key.clone(),
+    };
+    let mut session = client::connect(
+        Arc::new(client::Config::default()),
+        (config.host.as_str(), config.port),
+        handler,
+    )
+    .await
+    .map_err(io::Error::other)?;
+
+    let auth = match &config.auth {
+        SshAuth::Password(password) => session.authenticate_password(&config.user, password).await,
+        SshAuth::KeyFile { path, passphrase } => {
+            let key = load_secret_key(path, passphrase.as_deref()).map_err(io::Error::other)?;
+            let hash = session
+                .best_supported_rsa_hash()
+                .await
+                .map_err(io::Error::other)?
+                .flatten();
+            session
+                .authenticate_publickey(
+                    &config.user,
+                    PrivateKeyWithHashAlg::new(Arc::new(key), hash),
+                )
+                .await
+        }
+    }
+    .map_err(io::Error::other)?;
+    if !auth.success() {
+        return Err(io::Error::new(
+            io::ErrorKind::PermissionDenied,
+            format!("ssh authentication as {} failed", config.user),
+        ));
+    }
+
+    let channel = session
+        .channel_open_session()
+        .await
+        .map_err(io::Error::other)?;
+    channel
+        .request_pty(
+            false,
+            &config.term,
+            u32::from(config.cols),
+            u32::from(config.rows),
+            0,
+            0,
+            &[],
+        )
+        .await
+        .map_err(io::Error::other)?;
+    channel
+        .request_shell(true)
+        .await
+        .map_err(io::Error::other)?;
+    Ok((session, channel))
+}
+
+/// Moves input and resizes to the channel and its output to the view until either side ends.
+async fn pump(
+    mut channel: russh::Channel<client::Msg>,
+    mut commands: tokio_mpsc::UnboundedReceiver<Command>,
+    output: mpsc::UnboundedSender<TerminalIoEvent>,
+) {
+    let mut exit_code = None;
+    loop {
+        tokio::select! {
+            command = commands.recv() => match command {
+                Some(Command::Data(bytes)) => {
+                    if channel.data(&bytes[..]).await.is_err() {
+                        break;
+                    }
+                }
+                Some(Command::Resize(cols, rows)) => {
+                    let _ = channel
+                        .window_change(u32::from(cols), u32::from(rows), 0, 0)
+                        .await;
+                }
+                // The `SshIo` was dropped.
+                None => {
+                    let _ = channel.eof().await;
+                    break;
+                }
+            },
+            message = channel.wait() => match message {
+                Some(ChannelMsg::Data { data }) | Some(ChannelMsg::ExtendedData { data, .. }) => {
+                    if output
+                        .unbounded_send(TerminalIoEvent::Output(data.to_vec()))
+                        .is_err()
+                    {
+                        break;
+                    }
+                }
+                Some(ChannelMsg::ExitStatus { exit_status }) => {
+                    exit_code = i32::try_from(exit_status).ok();
+                }
+                Some(_) => {}
+                None => break,
+            },
+        }
+    }
+    let _ = output.unbounded_send(TerminalIoEvent::Exited(exit_code));
+}
@@ -0,0 +1,54 @@
+//! Experimental APIs, each behind an `unstable-*` cargo feature.
+//!
+//! Nothing in this module is covered by semver: items may change or disappear in any minor
+//! release while their subsystem is still landing. Once an item is stable it is re-exported from
+//! the crate root, and its path here is kept as a `#[deprecated]` alias for one minor release.
+//!
+//! | Feature               | Module     | Contents                                        |
+//! |-----------------------|------------|-------------------------------------------------|
+//! | `unstable-graphics`   | `graphics` | Kitty graphics images and placements            |
+//! | `unstable-remote`     | `remote`   | `TerminalIo` transports and a local PTY         |
+//! | `unstable-remote-ssh` | `remote`   | An SSH transport on `russh` (implies the above) |
+//!
+//! Tmux control mode will land here behind `unstable-tmux`.
+
+#[cfg(feature = "unstable-graphics")]
+pub mod graphics {
+    //! Kitty graphics protocol state kept by a [`TerminalSession`].
+
+    use std::ops::Range;
+    use std::sync::Arc;
+
+    use crate::TerminalSession;
+
+    pub use crate::kitty_graphics::{KittyImage, KittyPlacement};
+
+    /// Read access to the kitty graphics a session has received.
+    pub trait KittyGraphics {
+        /// Returns the image transmitted with the given kitty graphics id.
+        fn image(&self, id: u32) -> Option<Arc<KittyImage>>;
+
+        /// Returns placements covering any of the given screen-absolute rows.
+        fn placements(&self, rows: Range<u32>) -> impl Iterator<Item = &KittyPlacement>;
+    }
+
+    impl KittyGraphics for TerminalSession {
+        fn image(&self, id: u32) -> Option<Arc<KittyImage>> {
+            self.kitty_graphics().image(id).cloned()
+        }
+
+        fn placements(&self, rows: Range<u32>) -> impl Iterator<Item = &KittyPlacement> {
+            self.kitty_graphics().placements_in_rows(rows)
+        }
+    }
+}
+
+#[cfg(feature = "unstable-remote")]
+pub mod remote {
+    //! Byte transports behind a [`TerminalView`](crate::TerminalView): a local PTY, an SSH
+    //! channel, or anything else implementing [`TerminalIo`], wired up with [`connect_io`].
+
+    pub use crate::transport::{PtyIo, TerminalIo, TerminalIoEvent, connect_io};
+    #[cfg(feature = "unstable-remote-ssh")]
+    pub use crate::transport::{SshAuth, SshConfig, SshIo};
+}
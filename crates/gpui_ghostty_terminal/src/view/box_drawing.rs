@@ -0,0 +1,663 @@
+use std::f32::consts::{FRAC_PI_2, PI};
+use std::ops::Range;
+
+use gpui::{Bounds, Hsla, PaintQuad, Path, Pixels, fill, point, px};
+
+/// Weight of one arm of a box drawing line: none, light, heavy or double.
+const NO: u8 = 0;
+const LT: u8 = 1;
+const HV: u8 = 2;
+const DB: u8 = 3;
+
+/// Packs the weights of the left, right, up and down arms, two bits each.
+const fn arms(left: u8, right: u8, up: u8, down: u8) -> u8 {
+    left | (right << 2) | (up << 4) | (down << 6)
+}
+
+/// Arms of U+2500..=U+257F. Dashed, arc and diagonal lines are zero here and drawn by
+/// `line_shapes` directly.
+#[rustfmt::skip]
+const LINE_ARMS: [u8; 128] = [
+    arms(LT, LT, NO, NO), // ─
+    arms(HV, HV, NO, NO), // ━
+    arms(NO, NO, LT, LT), // │
+    arms(NO, NO, HV, HV), // ┃
+    0, // ┄ dashed
+    0, // ┅ dashed
+    0, // ┆ dashed
+    0, // ┇ dashed
+    0, // ┈ dashed
+    0, // ┉ dashed
+    0, // ┊ dashed
+    0, // ┋ dashed
+    arms(NO, LT, NO, LT), // ┌
+    arms(NO, HV, NO, LT), // ┍
+    arms(NO, LT, NO, HV), // ┎
+    arms(NO, HV, NO, HV), // ┏
+    arms(LT, NO, NO, LT), // ┐
+    arms(HV, NO, NO, LT), // ┑
+    arms(LT, NO, NO, HV), // ┒
+    arms(HV, NO, NO, HV), // ┓
+    arms(NO, LT, LT, NO), // └
+    arms(NO, HV, LT, NO), // ┕
+    arms(NO, LT, HV, NO), // ┖
+    arms(NO, HV, HV, NO), // ┗
+    arms(LT, NO, LT, NO), // ┘
+    arms(HV, NO, LT, NO), // ┙
+    arms(LT, NO, HV, NO), // ┚
+    arms(HV, NO, HV, NO), // ┛
+    arms(NO, LT, LT, LT), // ├
+    arms(NO, HV, LT, LT), // ┝
+    arms(NO, LT, HV, LT), // ┞
+    arms(NO, LT, LT, HV), // ┟
+    arms(NO, LT, HV, HV), // ┠
+    arms(NO, HV, HV, LT), // ┡
+    arms(NO, HV, LT, HV), // ┢
+    arms(NO, HV, HV, HV), // ┣
+    arms(LT, NO, LT, LT), // ┤
+    arms(HV, NO, LT, LT), // ┥
+    arms(LT, NO, HV, LT), // ┦
+    arms(LT, NO, LT, HV), // ┧
+    arms(LT, NO, HV, HV), // ┨
+    arms(HV, NO, HV, LT), // ┩
+    arms(HV, NO, LT, HV), // ┪
+    arms(HV, NO, HV, HV), // ┫
+    arms(LT, LT, NO, LT), // ┬
+    arms(HV, LT, NO, LT), // ┭
+    arms(LT, HV, NO, LT), // ┮
+    arms(HV, HV, NO, LT), // ┯
+    arms(LT, LT, NO, HV), // ┰
+    arms(HV, LT, NO, HV), // ┱
+    arms(LT, HV, NO, HV), // ┲
+    arms(HV, HV, NO, HV), // ┳
+    arms(LT, LT, LT, NO), // ┴
+    arms(HV, LT, LT, NO), // ┵
+    arms(LT, HV, LT, NO), // ┶
+    arms(HV, HV, LT, NO), // ┷
+    arms(LT, LT, HV, NO), // ┸
+    arms(HV, LT, HV, NO), // ┹
+    arms(LT, HV, HV, NO), // ┺
+    arms(HV, HV, HV, NO), // ┻
+    arms(LT, LT, LT, LT), // ┼
+    arms(HV, LT, LT, LT), // ┽
+    arms(LT, HV, LT, LT), // ┾
+    arms(HV, HV, LT, LT), // ┿
+    arms(LT, LT, HV, LT), // ╀
+    arms(LT, LT, LT, HV), // ╁
+    arms(LT, LT, HV, HV), // ╂
+    arms(HV, LT, HV, LT), // ╃
+    arms(LT, HV, HV, LT), // ╄
+    arms(HV, LT, LT, HV), // ╅
+    arms(LT, HV, LT, HV), // ╆
+    arms(HV, HV, HV, LT), // ╇
+    arms(HV, HV, LT, HV), // ╈
+    arms(HV, LT, HV, HV), // ╉
+    arms(LT, HV, HV, HV), // ╊
+    arms(HV, HV, HV, HV), // ╋
+    0, // ╌ dashed
+    0, // ╍ dashed
+    0, // ╎ dashed
+    0, // ╏ dashed
+    arms(DB, DB, NO, NO), // ═
+    arms(NO, NO, DB, DB), // ║
+    arms(NO, DB, NO, LT), // ╒
+    arms(NO, LT, NO, DB), // ╓
+    arms(NO, DB, NO, DB), // ╔
+    arms(DB, NO, NO, LT), // ╕
+    arms(LT, NO, NO, DB), // ╖
+    arms(DB, NO, NO, DB), // ╗
+    arms(NO, DB, LT, NO), // ╘
+    arms(NO, LT, DB, NO), // ╙
+    arms(NO, DB, DB, NO), // ╚
+    arms(DB, NO, LT, NO), // ╛
+    arms(LT, NO, DB, NO), // ╜
+    arms(DB, NO, DB, NO), // ╝
+    arms(NO, DB, LT, LT), // ╞
+    arms(NO, LT, DB, DB), // ╟
+    arms(NO, DB, DB, DB), // ╠
+    arms(DB, NO, LT, LT), // ╡
+    arms(LT, NO, DB, DB), // ╢
+    arms(DB, NO, DB, DB), // ╣
+    arms(DB, DB, NO, LT), // ╤
+    arms(LT, LT, NO, DB), // ╥
+    arms(DB, DB, NO, DB), // ╦
+    arms(DB, DB, LT, NO), // ╧
+    arms(LT, LT, DB, NO), // ╨
+    arms(DB, DB, DB, NO), // ╩
+    arms(DB, DB, LT, LT), // ╪
+    arms(LT, LT, DB, DB), // ╫
+    arms(DB, DB, DB, DB), // ╬
+    0, // ╭ arc
+    0, // ╮ arc
+    0, // ╯ arc
+    0, // ╰ arc
+    0, // ╱ diagonal
+    0, // ╲ diagonal
+    0, // ╳ diagonal
+    arms(LT, NO, NO, NO), // ╴
+    arms(NO, NO, LT, NO), // ╵
+    arms(NO, LT, NO, NO), // ╶
+    arms(NO, NO, NO, LT), // ╷
+    arms(HV, NO, NO, NO), // ╸
+    arms(NO, NO, HV, NO), // ╹
+    arms(NO, HV, NO, NO), // ╺
+    arms(NO, NO, NO, HV), // ╻
+    arms(LT, HV, NO, NO), // ╼
+    arms(NO, NO, LT, HV), // ╽
+    arms(HV, LT, NO, NO), // ╾
+    arms(NO, NO, HV, LT), // ╿
+];
+
+/// Geometry of a custom-drawn glyph, in pixels relative to the cell's top-left corner.
+#[derive(Clone, Debug, PartialEq)]
+pub(crate) enum GlyphShape {
+    /// Axis-aligned rectangle, filled with the text color at `alpha` of its opacity.
+    Rect {
+        x0: f32,
+        y0: f32,
+        x1: f32,
+        y1: f32,
+        alpha: f32,
+    },
+    /// Convex polygon filled with the text color.
+    Polygon(Vec<(f32, f32)>),
+}
+
+/// Packed arm weights for a box drawing line character, or `None` if `ch` is not one.
+pub(crate) fn box_drawing_mask(ch: char) -> Option<u8> {
+    let index = (ch as u32).checked_sub(0x2500)? as usize;
+    LINE_ARMS.get(index).copied().filter(|mask| *mask != 0)
+}
+
+/// Box drawing (U+2500..=U+257F), block elements (U+2580..=U+259F), braille patterns
+/// (U+2800..=U+28FF) and Powerline separators (U+E0B0..=U+E0BF) are drawn from geometry rather
+/// than the font, so they join up exactly across cells.
+pub(crate) fn is_custom_glyph(ch: char) -> bool {
+    matches!(
+        ch,
+        '\u{2500}'..='\u{259f}' | '\u{2800}'..='\u{28ff}' | '\u{e0b0}'..='\u{e0bf}'
+    )
+}
+
+/// Byte ranges of custom-drawn glyphs in `text`, with adjacent glyphs merged.
+pub(super) fn custom_glyph_ranges(text: &str) -> Vec<Range<usize>> {
+    let mut ranges: Vec<Range<usize>> = Vec::new();
+    for (index, ch) in text.char_indices().filter(|(_, ch)| is_custom_glyph(*ch)) {
+        let end = index + ch.len_utf8();
+        match ranges.last_mut() {
+            Some(last) if last.end == index => last.end = end,
+            _ => ranges.push(index..end),
+        }
+    }
+    ranges
+}
+
+/// Shapes for `ch` in a `width` x `height` cell, or `None` if it is not drawn by hand.
+pub(crate) fn glyph_shapes(ch: char, width: f32, height: f32) -> Option<Vec<GlyphShape>> {
+    let light = (height / 12.0).max(1.0).round();
+    let mut shapes = Vec::new();
+    match ch {
+        '\u{2500}'..='\u{257f}' => line_shapes(ch, width, height, light, &mut shapes),
+        '\u{2580}'..='\u{259f}' => block_shapes(ch, width, height, &mut shapes),
+        '\u{2800}'..='\u{28ff}' => braille_shapes(ch, width, height, &mut shapes),
+        '\u{e0b0}'..='\u{e0bf}' => powerline_shapes(ch, width, height, light, &mut shapes),
+        _ => {}
+    }
+    (!shapes.is_empty()).then_some(shapes)
+}
+
+/// Adds the paint primitives for `ch` in `cell`: quads for rectangles, paths for the rest.
+pub(super) fn paint_custom_glyph(
+    ch: char,
+    cell: Bounds<Pixels>,
+    color: Hsla,
+    quads: &mut Vec<PaintQuad>,
+    paths: &mut Vec<(Path<Pixels>, Hsla)>,
+) {
+    let Some(shapes) = glyph_shapes(ch, f32::from(cell.size.width), f32::from(cell.size.height))
+    else {
+        return;
+    };
+    let at = |(x, y): (f32, f32)| point(cell.left() + px(x), cell.top() + px(y));
+    for shape in shapes {
+        match shape {
+            GlyphShape::Rect {
+                x0,
+                y0,
+                x1,
+                y1,
+                alpha,
+            } => quads.push(fill(
+                Bounds::from_corners(at((x0, y0)), at((x1, y1))),
+                Hsla {
+                    a: color.a * alpha,
+                    ..color
+                },
+            )),
+            GlyphShape::Polygon(points) => {
+                let mut points = points.into_iter().map(at);
+                let Some(start) = points.next() else {
+                    continue;
+                };
+                let mut path = Path::new(start);
+                for to in points {
+                    path.line_to(to);
+                }
+                paths.push((path, color));
+            }
+        }
+    }
+}
+
+fn rect(x0: f32, y0: f32, x1: f32, y1: f32) -> GlyphShape {
+    GlyphShape::Rect {
+        x0,
+        y0,
+        x1,
+        y1,
+        alpha: 1.0,
+    }
+}
+
+/// A straight stroke of `thickness` from `(x0, y0)` to `(x1, y1)`.
+fn stroke(x0: f32, y0: f32, x1: f32, y1: f32, thickness: f32) -> GlyphShape {
+    let length = (x1 - x0).hypot(y1 - y0).max(f32::EPSILON);
+    let nx = -(y1 - y0) / length * thickness / 2.0;
+    let ny = (x1 - x0) / length * thickness / 2.0;
+    GlyphShape::Polygon(vec![
+        (x0 + nx, y0 + ny),
+        (x1 + nx, y1 + ny),
+        (x1 - nx, y1 - ny),
+        (x0 - nx, y0 - ny),
+    ])
+}
+
+/// Elliptical band between the `outer` and `inner` radii around `center`, over `angles`,
+/// split into convex segments.
+fn arc_band(
+    center: (f32, f32),
+    outer: (f32, f32),
+    inner: (f32, f32),
+    angles: Range<f32>,
+    out: &mut Vec<GlyphShape>,
+) {
+    const SEGMENTS: usize = 12;
+    let at = |(rx, ry): (f32, f32), i: usize| {
+        let angle = angles.start + (angles.end - angles.start) * i as f32 / SEGMENTS as f32;
+        (center.0 + rx * angle.cos(), center.1 + ry * angle.sin())
+    };
+    for i in 0..SEGMENTS {
+        out.push(GlyphShape::Polygon(vec![
+            at(outer, i),
+            at(outer, i + 1),
+            at(inner, i + 1),
+            at(inner, i),
+        ]));
+    }
+}
+
+fn line_shapes(ch: char, width: f32, height: f32, light: f32, out: &mut Vec<GlyphShape>) {
+    if let Some(mask) = box_drawing_mask(ch) {
+        arm_shapes(mask, width, height, light, out);
+        return;
+    }
+
+    let (cx, cy) = (width / 2.0, height / 2.0);
+    match ch {
+        '┄' | '┅' | '┆' | '┇' | '┈' | '┉' | '┊' | '┋' | '╌' | '╍' | '╎' | '╏' =>
+        {
+            let dashes = match ch {
+                '╌' | '╍' | '╎' | '╏' => 2,
+                '┄' | '┅' | '┆' | '┇' => 3,
+                _ => 4,
+            };
+            let heavy = matches!(ch, '┅' | '┇' | '┉' | '┋' | '╍' | '╏');
+            let vertical = matches!(ch, '┆' | '┇' | '┊' | '┋' | '╎' | '╏');
+            let half = if heavy { light } else { light / 2.0 };
+            let length = if vertical { height } else { width };
+            let slot = length / dashes as f32;
+            let gap = (slot * 0.3).max(1.0);
+            for dash in 0..dashes {
+                let start = slot * dash as f32 + gap / 2.0;
+                let end = slot * (dash + 1) as f32 - gap / 2.0;
+                out.push(if vertical {
+                    rect(cx - half, start, cx + half, end)
+                } else {
+                    rect(start, cy - half, end, cy + half)
+                });
+            }
+        }
+        '╭' | '╮' | '╯' | '╰' => {
+            // Signs of the horizontal and vertical arms the arc joins.
+            let (sx, sy) = match ch {
+                '╭' => (1.0, 1.0),
+                '╮' => (-1.0, 1.0),
+                '╯' => (-1.0, -1.0),
+                _ => (1.0, -1.0),
+            };
+            let radius = cx.min(cy);
+            let (ox, oy) = (cx + sx * radius, cy + sy * radius);
+            let half = light / 2.0;
+            // The band runs a quarter turn from the vertical arm's end to the horizontal one's.
+            let start = if sx > 0.0 { PI } else { 0.0 };
+            let end = start + sx * sy * FRAC_PI_2;
+            arc_band(
+                (ox, oy),
+                (radius + half, radius + half),
+                (radius - half, radius - half),
+                start..end,
+                out,
+            );
+            let edge_x = if sx > 0.0 { width } else { 0.0 };
+            let edge_y = if sy > 0.0 { height } else { 0.0 };
+            out.push(rect(ox.min(edge_x), cy - half, ox.max(edge_x), cy + half));
+            out.push(rect(cx - half, oy.min(edge_y), cx + half, oy.max(edge_y)));
+        }
+        '╱' => out.push(stroke(width, 0.0, 0.0, height, light)),
+        '╲' => out.push(stroke(0.0, 0.0, width, height, light)),
+        '╳' => {
+            out.push(stroke(width, 0.0, 0.0, height, light));
+            out.push(stroke(0.0, 0.0, width, height, light));
+        }
+        _ => {}
+    }
+}
+
+fn arm_shapes(mask: u8, width: f32, height: f32, light: f32, out: &mut Vec<GlyphShape>) {
+    let weight = |shift: u8| (mask >> shift) & 0b11;
+    let (left, right, up, down) = (weight(0), weight(2), weight(4), weight(6));
+    let (cx, cy) = (width / 2.0, height / 2.0);
+
+    for (arm, edge, opposite) in [(left, 0.0, right), (right, width, left)] {
+        for [a0, a1, c0, c1] in arm_strokes(arm, edge, cx, cy, [up, down], opposite, light) {
+            out.push(rect(a0.min(a1), c0, a0.max(a1), c1));
+        }
+    }
+    for (arm, edge, opposite) in [(up, 0.0, down), (down, height, up)] {
+        for [a0, a1, c0, c1] in arm_strokes(arm, edge, cy, cx, [left, right], opposite, light) {
+            out.push(rect(c0, a0.min(a1), c1, a0.max(a1)));
+        }
+    }
+}
+
+/// Strokes for one arm running from `edge` to the cell center along its axis, as
+/// `[along_from, along_to, across_start, across_end]`. `sides` are the weights of the
+/// perpendicular arms on the lower and higher side of the axis, `opposite` the arm continuing it.
+fn arm_strokes(
+    arm: u8,
+    edge: f32,
+    center: f32,
+    across: f32,
+    sides: [u8; 2],
+    opposite: u8,
+    light: f32,
+) -> Vec<[f32; 4]> {
+    let toward_edge = if edge < center { -1.0 } else { 1.0 };
+    let thickness = |weight: u8| match weight {
+        NO => 0.0,
+        HV => light * 2.0,
+        DB => light * 3.0,
+        _ => light,
+    };
+
+    match arm {
+        NO => Vec::new(),
+        DB => [(-1.0, sides[0], sides[1]), (1.0, sides[1], sides[0])]
+            .into_iter()
+            .map(|(side, same, other)| {
+                // Each stroke of a double arm stops at the nearer stroke of a perpendicular double
+                // arm on its own side, or runs on to the farther one to close an outer corner.
+                let end = if same == DB {
+                    center + toward_edge * light
+                } else if other == DB {
+                    center - toward_edge * light
+                } else {
+                    center
+                };
+                let offset = across + side * light;
+                [
+                    edge,
+                    end - toward_edge * light / 2.0,
+                    offset - light / 2.0,
+                    offset + light / 2.0,
+                ]
+            })
+            .collect(),
+        _ => {
+            let half = thickness(arm) / 2.0;
+            let end = if sides.contains(&DB) && opposite == NO {
+                // Meet the near stroke of the double line rather than crossing its gap.
+                center + toward_edge * light / 2.0
+            } else {
+                center - toward_edge * thickness(sides[0]).max(thickness(sides[1])) / 2.0
+            };
+            vec![[edge, end, across - half, across + half]]
+        }
+    }
+}
+
+fn block_shapes(ch: char, width: f32, height: f32, out: &mut Vec<GlyphShape>) {
+    let code = ch as u32;
+    let eighths = |n: u32| n as f32 / 8.0;
+    let mut part = |x0: f32, y0: f32, x1: f32, y1: f32| {
+        out.push(rect(x0 * width, y0 * height, x1 * width, y1 * height))
+    };
+    match code {
+        0x2580 => part(0.0, 0.0, 1.0, 0.5),
+        0x2581..=0x2588 => part(0.0, 1.0 - eighths(code - 0x2580), 1.0, 1.0),
+        0x2589..=0x258f => part(0.0, 0.0, eighths(0x2590 - code), 1.0),
+        0x2590 => part(0.5, 0.0, 1.0, 1.0),
+        0x2594 => part(0.0, 0.0, 1.0, eighths(1)),
+        0x2595 => part(eighths(7), 0.0, 1.0, 1.0),
+        0x2596..=0x259f => {
+            // Quadrants present, as upper-left, upper-right, lower-left, lower-right bits.
+            let quadrants = match code {
+                0x2596 => 0b0100,
+                0x2597 => 0b1000,
+                0x2598 => 0b0001,
+                0x2599 => 0b1101,
+                0x259a => 0b1001,
+                0x259b => 0b0111,
+                0x259c => 0b1011,
+                0x259d => 0b0010,
+                0x259e => 0b0110,
+                _ => 0b1110,
+            };
+            for (bit, x, y) in [(0, 0.0, 0.0), (1, 0.5, 0.0), (2, 0.0, 0.5), (3, 0.5, 0.5)] {
+                if quadrants & (1 << bit) != 0 {
+                    part(x, y, x + 0.5, y + 0.5);
+                }
+            }
+        }
+        _ => {
+            // ░ ▒ ▓ are drawn as a translucent fill rather than a dither pattern.
+            let shade = (code - 0x2590) as f32 / 4.0;
+            out.push(GlyphShape::Rect {
+                x0: 0.0,
+                y0: 0.0,
+                x1: width,
+                y1: height,
+                alpha: shade,
+            });
+        }
+    }
+}
+
+/// Braille dots on a 2x4 grid, each a square centered in its part of the cell so plots made of
+/// adjacent cells stay evenly spaced.
+fn braille_shapes(ch: char, width: f32, height: f32, out: &mut Vec<GlyphShape>) {
+    // Bit n of the pattern is dot n + 1; dots 1-3 and 4-6 run down the left and right columns,
+    // and dots 7 and 8 sit below them.
+    const DOTS: [(f32, f32); 8] = [
+        (0.0, 0.0),
+        (0.0, 1.0),
+        (0.0, 2.0),
+        (1.0, 0.0),
+        (1.0, 1.0),
+        (1.0, 2.0),
+        (0.0, 3.0),
+        (1.0, 3.0),
+    ];
+    let pattern = (ch as u32 - 0x2800) as u8;
+    let (column, row) = (width / 2.0, height / 4.0);
+    let side = (column * 0.6).min(row * 0.8).round().max(1.0);
+    for (bit, (x, y)) in DOTS.into_iter().enumerate() {
+        if pattern & (1 << bit) != 0 {
+            let x0 = ((x + 0.5) * column - side / 2.0).round();
+            let y0 = ((y + 0.5) * row - side / 2.0).round();
+            out.push(rect(x0, y0, x0 + side, y0 + side));
+        }
+    }
+}
+
+fn powerline_shapes(ch: char, width: f32, height: f32, light: f32, out: &mut Vec<GlyphShape>) {
+    let cy = height / 2.0;
+    let polygon = |points: &[(f32, f32)]| GlyphShape::Polygon(points.to_vec());
+    match ch {
+        '\u{e0b0}' => out.push(polygon(&[(0.0, 0.0), (width, cy), (0.0, height)])),
+        '\u{e0b2}' => out.push(polygon(&[(width, 0.0), (0.0, cy), (width, height)])),
+        '\u{e0b1}' => {
+            out.push(stroke(0.0, 0.0, width, cy, light));
+            out.push(stroke(width, cy, 0.0, height, light));
+        }
+        '\u{e0b3}' => {
+            out.push(stroke(width, 0.0, 0.0, cy, light));
+            out.push(stroke(0.0, cy, width, height, light));
+        }
+        '\u{e0b4}' | '\u{e0b6}' => {
+            // Half ellipse bulging away from the edge it is anchored to.
+            const SEGMENTS: usize = 16;
+            let (ox, sx) = if ch == '\u{e0b4}' {
+                (0.0, 1.0)
+            } else {
+                (width, -1.0)
+            };
+            let points = (0..=SEGMENTS)
+                .map(|i| {
+                    let angle = -FRAC_PI_2 + PI * i as f32 / SEGMENTS as f32;
+                    (ox + sx * width * angle.cos(), cy + cy * angle.sin())
+                })
+                .collect();
+            out.push(GlyphShape::Polygon(points));
+        }
+        '\u{e0b5}' | '\u{e0b7}' => {
+            let (ox, angles) = if ch == '\u{e0b5}' {
+                (0.0, -FRAC_PI_2..FRAC_PI_2)
+            } else {
+                (width, FRAC_PI_2..PI + FRAC_PI_2)
+            };
+            arc_band(
+                (ox, cy),
+                (width, cy),
+                ((width - light).max(0.0), (cy - light).max(0.0)),
+                angles,
+                out,
+            );
+        }
+        '\u{e0b8}' => out.push(polygon(&[(0.0, 0.0), (0.0, height), (width, height)])),
+        '\u{e0ba}' => out.push(polygon(&[(width, 0.0), (width, height), (0.0, height)])),
+        '\u{e0bc}' => out.push(polygon(&[(0.0, 0.0), (width, 0.0), (0.0, height)])),
+        '\u{e0be}' => out.push(polygon(&[(0.0, 0.0), (width, 0.0), (width, height)])),
+        '\u{e0b9}' | '\u{e0bf}' => out.push(stroke(0.0, 0.0, width, height, light)),
+        '\u{e0bb}' | '\u{e0bd}' => out.push(stroke(width, 0.0, 0.0, height, light)),
+        _ => {}
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn every_box_block_and_powerline_glyph_has_shapes() {
+        for ch in ('\u{2500}'..='\u{259f}').chain('\u{e0b0}'..='\u{e0bf}') {
+            assert!(glyph_shapes(ch, 8.0, 16.0).is_some(), "no shapes for {ch}");
+        }
+        assert!(glyph_shapes('X', 8.0, 16.0).is_none());
+        assert!(!is_custom_glyph('X'));
+    }
+
+    #[test]
+    fn line_table_matches_unicode_names() {
+        assert_eq!(box_drawing_mask('┌'), Some(arms(NO, LT, NO, LT)));
+        assert_eq!(box_drawing_mask('┞'), Some(arms(NO, LT, HV, LT)));
+        assert_eq!(box_drawing_mask('╤'), Some(arms(DB, DB, NO, LT)));
+        assert_eq!(box_drawing_mask('╿'), Some(arms(NO, NO, HV, LT)));
+        assert_eq!(box_drawing_mask('┄'), None);
+        assert_eq!(box_drawing_mask('▀'), None);
+    }
+
+    #[test]
+    fn block_elements_cover_their_fraction_of_the_cell() {
+        let rects = |ch| {
+            glyph_shapes(ch, 8.0, 16.0)
+                .unwrap()
+                .into_iter()
+                .map(|shape| match shape {
+                    GlyphShape::Rect {
+                        x0,
+                        y0,
+                        x1,
+                        y1,
+                        alpha,
+                    } => (x0, y0, x1, y1, alpha),
+                    GlyphShape::Polygon(_) => panic!("{ch} should be rectangles"),
+                })
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(rects('█'), [(0.0, 0.0, 8.0, 16.0, 1.0)]);
+        assert_eq!(rects('▄'), [(0.0, 8.0, 8.0, 16.0, 1.0)]);
+        assert_eq!(rects('▌'), [(0.0, 0.0, 4.0, 16.0, 1.0)]);
+        assert_eq!(rects('▁'), [(0.0, 14.0, 8.0, 16.0, 1.0)]);
+        assert_eq!(rects('▒'), [(0.0, 0.0, 8.0, 16.0, 0.5)]);
+        assert_eq!(
+            rects('▚'),
+            [(0.0, 0.0, 4.0, 8.0, 1.0), (4.0, 8.0, 8.0, 16.0, 1.0)]
+        );
+    }
+
+    #[test]
+    fn double_corner_strokes_meet_without_overhang() {
+        // Light thickness is 2px in a 24px-tall cell, so the strokes of a double line sit 2px
+        // either side of the center.
+        let shapes = glyph_shapes('╔', 12.0, 24.0).unwrap();
+        assert_eq!(
+            shapes,
+            [
+                rect(3.0, 9.0, 12.0, 11.0),
+                rect(7.0, 13.0, 12.0, 15.0),
+                rect(3.0, 9.0, 5.0, 24.0),
+                rect(7.0, 13.0, 9.0, 24.0),
+            ]
+        );
+    }
+
+    #[test]
+    fn braille_dots_follow_the_unicode_bit_order() {
+        assert!(glyph_shapes('\u{2800}', 8.0, 16.0).is_none());
+        assert!(is_custom_glyph('\u{2800}'));
+        // ⡁ is dots 1 and 7: top and bottom of the left column.
+        assert_eq!(
+            glyph_shapes('⡁', 8.0, 16.0).unwrap(),
+            [rect(1.0, 1.0, 3.0, 3.0), rect(1.0, 13.0, 3.0, 15.0)]
+        );
+        assert_eq!(glyph_shapes('⣿', 8.0, 16.0).unwrap().len(), 8);
+    }
+
+    #[test]
+    fn powerline_arrows_span_the_cell() {
+        assert_eq!(
+            glyph_shapes('\u{e0b0}', 8.0, 16.0).unwrap(),
+            [GlyphShape::Polygon(vec![
+                (0.0, 0.0),
+                (8.0, 8.0),
+                (0.0, 16.0)
+            ])]
+        );
+    }
+
+    #[test]
+    fn adjacent_custom_glyphs_merge_into_one_range() {
+        assert_eq!(custom_glyph_ranges("a──b│"), [1..7, 8..11]);
+    }
+}
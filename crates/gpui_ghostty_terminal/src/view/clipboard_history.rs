@@ -0,0 +1,104 @@
+use std::time::SystemTime;
+
+use crate::ClipboardEntry;
+
+const PREVIEW_CHARS: usize = 60;
+
+/// State of the clipboard history picker while it is open.
+#[derive(Default)]
+pub(super) struct ClipboardPicker {
+    /// Selected entry, counted from the newest.
+    pub selected: usize,
+}
+
+impl ClipboardPicker {
+    /// Moves the selection by `delta` rows, wrapping around `len` entries.
+    pub fn move_selection(&mut self, delta: isize, len: usize) {
+        if len == 0 {
+            self.selected = 0;
+            return;
+        }
+        let len = len as isize;
+        self.selected = (self.selected as isize + delta).rem_euclid(len) as usize;
+    }
+
+    pub fn selected_entry<'a>(&self, history: &'a [ClipboardEntry]) -> Option<&'a ClipboardEntry> {
+        history.iter().rev().nth(self.selected)
+    }
+
+    /// One line per entry, newest first, paired with whether it is selected.
+    pub fn rows(&self, history: &[ClipboardEntry], now: SystemTime) -> Vec<(bool, String)> {
+        if history.is_empty() {
+            return vec![(false, "Clipboard history is empty".to_string())];
+        }
+        history
+            .iter()
+            .rev()
+            .enumerate()
+            .map(|(i, entry)| {
+                let label = format!("{:>4}  {}", age(now, entry.timestamp), preview(&entry.text));
+                (i == self.selected, label)
+            })
+            .collect()
+    }
+}
+
+/// First line of `text`, shortened to fit the picker.
+fn preview(text: &str) -> String {
+    let mut lines = text.lines();
+    let first = lines.next().unwrap_or_default();
+    let mut out: String = first.chars().take(PREVIEW_CHARS).collect();
+    if first.chars().count() > PREVIEW_CHARS {
+        out.push('…');
+    }
+    let more = lines.count();
+    if more > 0 {
+        out.push_str(&format!("  (+{} lines)", more));
+    }
+    out
+}
+
+fn age(now: SystemTime, then: SystemTime) -> String {
+    let secs = now.duration_since(then).unwrap_or_default().as_secs();
+    match secs {
+        0..60 => format!("{}s", secs),
+        60..3600 => format!("{}m", secs / 60),
+        3600..86400 => format!("{}h", secs / 3600),
+        _ => format!("{}d", secs / 86400),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    #[test]
+    fn rows_are_newest_first_and_selection_wraps() {
+        let now = SystemTime::now();
+        let history = vec![
+            ClipboardEntry {
+                text: "old".into(),
+                timestamp: now - Duration::from_secs(120),
+            },
+            ClipboardEntry {
+                text: "new\nsecond line".into(),
+                timestamp: now - Duration::from_secs(5),
+            },
+        ];
+
+        let mut picker = ClipboardPicker::default();
+        assert_eq!(
+            picker.rows(&history, now),
+            vec![
+                (true, "  5s  new  (+1 lines)".to_string()),
+                (false, "  2m  old".to_string()),
+            ]
+        );
+
+        picker.move_selection(-1, history.len());
+        assert_eq!(picker.selected_entry(&history).unwrap().text, "old");
+        picker.move_selection(1, history.len());
+        assert_eq!(picker.selected, 0);
+    }
+}
@@ -0,0 +1,113 @@
+use std::time::{Duration, Instant};
+
+use gpui::{Pixels, Point, point};
+
+/// How long the cursor takes to glide from one cell to the next.
+const CURSOR_ANIMATION_DURATION: Duration = Duration::from_millis(120);
+/// Fading copies drawn behind the moving cursor, each a little further back along its path.
+const CURSOR_TRAIL_STEPS: usize = 3;
+/// Progress lag between consecutive trail copies.
+const CURSOR_TRAIL_SPACING: f32 = 0.15;
+
+/// Cursor motion for the `cursor-animation` option: when the cursor's cell changes, it glides
+/// there from wherever it was last drawn, leaving a brief trail.
+#[derive(Default)]
+pub(super) struct CursorAnimation {
+    target: Option<Point<Pixels>>,
+    motion: Option<(Point<Pixels>, Instant)>,
+}
+
+impl CursorAnimation {
+    /// Origins to draw the cursor at for a resting place of `target`, each with an opacity
+    /// factor. Trail copies come first; the cursor itself is last with opacity `1.0`.
+    pub fn frame(&mut self, target: Point<Pixels>, now: Instant) -> Vec<(Point<Pixels>, f32)> {
+        if self.target != Some(target) {
+            if let Some(previous) = self.target {
+                self.motion = Some((self.position(previous, now), now));
+            }
+            self.target = Some(target);
+        }
+
+        let Some((from, started)) = self.motion else {
+            return vec![(target, 1.0)];
+        };
+        let progress = motion_progress(started, now);
+        if progress >= 1.0 {
+            self.motion = None;
+            return vec![(target, 1.0)];
+        }
+
+        let mut frame = Vec::with_capacity(CURSOR_TRAIL_STEPS + 1);
+        for step in (1..=CURSOR_TRAIL_STEPS).rev() {
+            let lagged = (progress - step as f32 * CURSOR_TRAIL_SPACING).max(0.0);
+            let opacity = 0.5 * (1.0 - progress) / step as f32;
+            frame.push((lerp(from, target, ease_out(lagged)), opacity));
+        }
+        frame.push((lerp(from, target, ease_out(progress)), 1.0));
+        frame
+    }
+
+    /// Whether a glide started by [`Self::frame`] still has frames left to draw.
+    pub fn is_moving(&self, now: Instant) -> bool {
+        self.motion
+            .is_some_and(|(_, started)| motion_progress(started, now) < 1.0)
+    }
+
+    fn position(&self, target: Point<Pixels>, now: Instant) -> Point<Pixels> {
+        match self.motion {
+            Some((from, started)) => lerp(from, target, ease_out(motion_progress(started, now))),
+            None => target,
+        }
+    }
+}
+
+fn motion_progress(started: Instant, now: Instant) -> f32 {
+    let elapsed = now.saturating_duration_since(started).as_secs_f32();
+    (elapsed / CURSOR_ANIMATION_DURATION.as_secs_f32()).min(1.0)
+}
+
+/// Cubic ease-out: fast off the mark, settling gently into the new cell.
+fn ease_out(t: f32) -> f32 {
+    1.0 - (1.0 - t).powi(3)
+}
+
+fn lerp(from: Point<Pixels>, to: Point<Pixels>, t: f32) -> Point<Pixels> {
+    point(from.x + (to.x - from.x) * t, from.y + (to.y - from.y) * t)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use gpui::px;
+
+    #[test]
+    fn cursor_glides_to_new_cell_then_rests() {
+        let start = Instant::now();
+        let mut animation = CursorAnimation::default();
+        let a = point(px(0.0), px(0.0));
+        let b = point(px(80.0), px(0.0));
+
+        assert_eq!(animation.frame(a, start), vec![(a, 1.0)]);
+        assert!(!animation.is_moving(start));
+
+        let midway = start + CURSOR_ANIMATION_DURATION / 2;
+        let frame = animation.frame(b, start);
+        assert_eq!(frame.len(), CURSOR_TRAIL_STEPS + 1);
+        assert_eq!(frame.last(), Some(&(a, 1.0)));
+        assert!(animation.is_moving(midway));
+
+        let frame = animation.frame(b, midway);
+        let (head, opacity) = *frame.last().unwrap();
+        assert_eq!(opacity, 1.0);
+        assert!(head.x > a.x && head.x < b.x);
+        assert!(
+            frame[..CURSOR_TRAIL_STEPS].iter().all(|(origin, opacity)| {
+                origin.x <= head.x && *opacity > 0.0 && *opacity < 1.0
+            })
+        );
+
+        let done = start + CURSOR_ANIMATION_DURATION;
+        assert_eq!(animation.frame(b, done), vec![(b, 1.0)]);
+        assert!(!animation.is_moving(done));
+    }
+}
@@ -0,0 +1,111 @@
+use std::collections::{HashMap, HashSet};
+use std::sync::Arc;
+
+use gpui::{Bounds, Pixels, RenderImage, Window, point, px, size};
+
+use crate::TerminalSession;
+use crate::kitty_graphics::KittyImage;
+
+/// Kitty graphics placement resolved to window coordinates for painting.
+pub(super) struct KittyImagePaint {
+    pub bounds: Bounds<Pixels>,
+    pub image: Arc<RenderImage>,
+    pub below_text: bool,
+}
+
+/// Uploaded images keyed by `KittyImage::serial`, so re-transmitting an image id replaces it.
+#[derive(Default)]
+pub(super) struct KittyImageCache {
+    images: HashMap<u64, Arc<RenderImage>>,
+}
+
+impl KittyImageCache {
+    fn get_or_create(&mut self, image: &KittyImage) -> Option<Arc<RenderImage>> {
+        if let Some(existing) = self.images.get(&image.serial()) {
+            return Some(existing.clone());
+        }
+
+        // GPUI expects BGRA pixel data.
+        let mut bgra = image.rgba.clone();
+        for px in bgra.chunks_exact_mut(4) {
+            px.swap(0, 2);
+        }
+        let buffer = image::RgbaImage::from_raw(image.width, image.height, bgra)?;
+        let render_image = Arc::new(RenderImage::new(smallvec::smallvec![image::Frame::new(
+            buffer
+        )]));
+        self.images.insert(image.serial(), render_image.clone());
+        Some(render_image)
+    }
+
+    /// Drops uploaded images that are no longer referenced by the session.
+    fn retain_live(&mut self, session: &TerminalSession, window: &mut Window) {
+        if self.images.is_empty() {
+            return;
+        }
+        let graphics = session.kitty_graphics();
+        let live_serials: HashSet<u64> = graphics
+            .placements_in_rows(0..u32::MAX)
+            .filter_map(|p| graphics.image(p.image_id))
+            .map(|image| image.serial())
+            .collect();
+        self.images.retain(|serial, render_image| {
+            let live = live_serials.contains(serial);
+            if !live {
+                let _ = window.drop_image(render_image.clone());
+            }
+            live
+        });
+    }
+}
+
+/// Resolves the kitty graphics placements visible in the viewport.
+pub(super) fn visible_kitty_images(
+    session: &TerminalSession,
+    cache: &mut KittyImageCache,
+    origin: gpui::Point<Pixels>,
+    cell_width: f32,
+    line_height: Pixels,
+    window: &mut Window,
+) -> Vec<KittyImagePaint> {
+    let top = session.viewport_top_row();
+    let bottom = top.saturating_add(u32::from(session.rows()));
+    let scale = window.scale_factor().max(1.0);
+
+    let mut out = Vec::new();
+    let graphics = session.kitty_graphics();
+    for placement in graphics.placements_in_rows(top..bottom) {
+        let Some(image) = graphics.image(placement.image_id).cloned() else {
+            continue;
+        };
+        let Some(render_image) = cache.get_or_create(&image) else {
+            continue;
+        };
+
+        let row_offset = placement.row as f32 - top as f32;
+        let x = origin.x
+            + px(cell_width * f32::from(placement.col))
+            + px(placement.x_offset as f32 / scale);
+        let y = origin.y + line_height * row_offset + px(placement.y_offset as f32 / scale);
+        let image_size = if placement.fit_to_cells {
+            size(
+                px(cell_width * f32::from(placement.cols)),
+                line_height * f32::from(placement.rows),
+            )
+        } else {
+            size(
+                px(image.width as f32 / scale),
+                px(image.height as f32 / scale),
+            )
+        };
+
+        out.push(KittyImagePaint {
+            bounds: Bounds::new(point(x, y), image_size),
+            image: render_image,
+            below_text: placement.z_index < 0,
+        });
+    }
+
+    cache.retain_live(session, window);
+    out
+}
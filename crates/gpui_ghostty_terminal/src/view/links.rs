@@ -0,0 +1,149 @@
+use std::ops::Range;
+
+use crate::LinkRule;
+
+/// A link-rule match within one viewport row.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub(super) struct RowLink {
+    /// Byte range within the row's text.
+    pub range: Range<usize>,
+    /// `None` for rules without a URL template.
+    pub url: Option<String>,
+    pub pattern_id: Option<String>,
+}
+
+/// A `link-rule` or `smart-link` match the user cmd/ctrl-clicked, as passed to a
+/// [`SmartLinkHandler`](super::SmartLinkHandler).
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct SmartLinkMatch {
+    /// The matching rule's [`LinkRule::id`].
+    pub pattern_id: Option<String>,
+    /// The matched text, e.g. `src/main.rs:12:5`.
+    pub text: String,
+    /// The expanded URL template, if the rule has one.
+    pub url: Option<String>,
+}
+
+/// Link-rule matches per viewport row.
+///
+/// Rows are only matched when something asks for them (hover, click), and the result is reused
+/// until the row's text changes.
+#[derive(Default)]
+pub(super) struct RowLinkCache {
+    rows: Vec<Option<(String, Vec<RowLink>)>>,
+}
+
+impl RowLinkCache {
+    pub(super) fn clear(&mut self) {
+        self.rows.clear();
+    }
+
+    pub(super) fn links_for_row(
+        &mut self,
+        row: usize,
+        line: &str,
+        rules: &[LinkRule],
+    ) -> &[RowLink] {
+        if rules.is_empty() {
+            return &[];
+        }
+
+        if self.rows.len() <= row {
+            self.rows.resize_with(row + 1, || None);
+        }
+        let slot = &mut self.rows[row];
+        if !matches!(slot, Some((text, _)) if text == line) {
+            *slot = Some((line.to_string(), find_row_links(line, rules)));
+        }
+
+        slot.as_ref()
+            .map(|(_, links)| links.as_slice())
+            .unwrap_or_default()
+    }
+
+    pub(super) fn link_at(
+        &mut self,
+        row: usize,
+        line: &str,
+        index: usize,
+        rules: &[LinkRule],
+    ) -> Option<RowLink> {
+        self.links_for_row(row, line, rules)
+            .iter()
+            .find(|link| link.range.contains(&index))
+            .cloned()
+    }
+}
+
+fn find_row_links(line: &str, rules: &[LinkRule]) -> Vec<RowLink> {
+    // Earlier rules win where matches overlap.
+    let mut links: Vec<RowLink> = Vec::new();
+    for rule in rules {
+        for (range, url) in rule.find_links(line) {
+            let overlaps = links
+                .iter()
+                .any(|link| link.range.start < range.end && range.start < link.range.end);
+            if !overlaps {
+                links.push(RowLink {
+                    range,
+                    url: (!url.is_empty()).then_some(url),
+                    pattern_id: rule.id().map(str::to_string),
+                });
+            }
+        }
+    }
+    links.sort_by_key(|link| link.range.start);
+    links
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn earlier_rules_win_on_overlap_and_results_are_cached_per_text() {
+        let rules = vec![
+            LinkRule::new(r"JIRA-\d+", "https://jira/$0").unwrap(),
+            LinkRule::new(r"\d+", "https://num/$0").unwrap(),
+        ];
+        let mut cache = RowLinkCache::default();
+
+        let links = cache.links_for_row(0, "JIRA-12 7", &rules).to_vec();
+        assert_eq!(
+            links,
+            vec![
+                RowLink {
+                    range: 0..7,
+                    url: Some("https://jira/JIRA-12".into()),
+                    pattern_id: None,
+                },
+                RowLink {
+                    range: 8..9,
+                    url: Some("https://num/7".into()),
+                    pattern_id: None,
+                },
+            ]
+        );
+
+        assert_eq!(
+            cache.link_at(0, "JIRA-12 7", 3, &rules),
+            Some(links[0].clone())
+        );
+        assert_eq!(cache.link_at(0, "no match", 3, &rules), None);
+    }
+
+    #[test]
+    fn smart_rules_keep_their_id_and_have_no_url() {
+        let rules = vec![LinkRule::file_location(), LinkRule::git_sha()];
+        let mut cache = RowLinkCache::default();
+
+        let line = "error at src/view/mod.rs:12:5 since 3f2a9c1d";
+        let links = cache.links_for_row(0, line, &rules).to_vec();
+        assert_eq!(links.len(), 2);
+        assert_eq!(&line[links[0].range.clone()], "src/view/mod.rs:12:5");
+        assert_eq!(links[0].pattern_id.as_deref(), Some("file"));
+        assert_eq!(links[0].url, None);
+        assert_eq!(&line[links[1].range.clone()], "3f2a9c1d");
+        assert_eq!(links[1].pattern_id.as_deref(), Some("git-sha"));
+    }
+}
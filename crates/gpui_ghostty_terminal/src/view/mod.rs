@@ -1,23 +1,98 @@
-use super::TerminalSession;
-use ghostty_vt::{KeyModifiers, Rgb, StyleRun, encode_key_named};
+mod box_drawing;
+mod clipboard_history;
+mod cursor_animation;
+mod kitty_images;
+mod links;
+mod notes;
+mod paste;
+mod render_waiters;
+mod resize_overlay;
+mod row_cache;
+mod scrollbar;
+mod search;
+
+use super::{
+    ConfigError, DEFAULT_PALETTE, FocusClickPolicy, FunctionKeyPolicy, MouseEncoding,
+    ProcessExitPolicy, RootIndicator, SearchDirection, SearchMatch, TerminalConfig, TerminalEvent,
+    TerminalSession, apply_theme, reload_theme_for_appearance,
+};
+use crate::session_handle::DetachedSession;
+#[cfg(test)]
+pub(crate) use box_drawing::box_drawing_mask;
+use box_drawing::{custom_glyph_ranges, is_custom_glyph, paint_custom_glyph};
+use clipboard_history::ClipboardPicker;
+use cursor_animation::CursorAnimation;
+use ghostty_vt::{
+    Cell, KeyModifiers, Rgb, StyleRun, Underline, ViewportPoint, encode_key_named_with_modes,
+};
 use gpui::{
     App, Bounds, ClipboardItem, Context, Element, ElementId, ElementInputHandler,
     EntityInputHandler, FocusHandle, GlobalElementId, IntoElement, KeyBinding, KeyDownEvent,
-    LayoutId, MouseButton, MouseDownEvent, MouseMoveEvent, MouseUpEvent, PaintQuad, Pixels, Render,
-    ScrollDelta, ScrollWheelEvent, SharedString, Style, TextRun, UTF16Selection, UnderlineStyle,
-    Window, actions, div, fill, hsla, point, prelude::*, px, relative, rgba, size,
+    LayoutId, Modifiers, MouseButton, MouseDownEvent, MouseMoveEvent, MouseUpEvent, PaintQuad,
+    Path, Pixels, Render, ScrollDelta, ScrollWheelEvent, SharedString, Style, Task, TextRun,
+    UTF16Selection, UnderlineStyle, WeakEntity, Window, actions, div, fill, hsla, point,
+    prelude::*, px, relative, rgba, size,
 };
+use kitty_images::{KittyImageCache, KittyImagePaint, visible_kitty_images};
+pub use links::SmartLinkMatch;
+use links::{RowLink, RowLinkCache};
+use notes::NOTES_PANEL_WIDTH;
+pub use paste::{PastePolicy, PasteWarning};
+use render_waiters::RenderWaiters;
+use resize_overlay::{ResizeOverlay, resize_overlay_label};
+use row_cache::{RowBackground, RowCache, ShapedRow, hash_rgb, row_content_hash};
+use scrollbar::{SCROLLBAR_MARGIN, SCROLLBAR_WIDTH, Scrollbar, Thumb, row_for_thumb_top};
+use search::SearchBar;
+pub use search::{PaneSearchMatch, search_all_panes};
+use smallvec::SmallVec;
+use std::hash::{Hash, Hasher};
+use std::num::NonZeroU32;
 use std::ops::Range;
+use std::rc::Rc;
 use std::sync::Once;
+use std::time::{Duration, Instant};
 
 actions!(
     terminal_view,
-    [Copy, CopyLastOutput, Paste, SelectAll, Tab, TabPrev]
+    [
+        AddSelectionToNotes,
+        ClearScrollback,
+        Copy,
+        CopyLastOutput,
+        JumpToNextPrompt,
+        JumpToPreviousPrompt,
+        LockInput,
+        Paste,
+        Search,
+        SearchNext,
+        SearchPrevious,
+        SelectAll,
+        SelectLastCommandOutput,
+        SelectWord,
+        ShowClipboardHistory,
+        Tab,
+        TabPrev,
+        ToggleNotes,
+        ZoomIn,
+        ZoomOut,
+        ZoomReset
+    ]
 );
 
 const KEY_CONTEXT: &str = "Terminal";
 static KEY_BINDINGS: Once = Once::new();
 
+/// Pixels added or removed per `ZoomIn`/`ZoomOut`.
+const ZOOM_STEP: f32 = 1.0;
+const MIN_FONT_SIZE: f32 = 6.0;
+const MAX_FONT_SIZE: f32 = 72.0;
+/// Interval at which queued output is parsed between frames.
+const OUTPUT_FRAME: Duration = Duration::from_millis(16);
+/// Parse time spent on queued output per frame (and per timer tick), keeping frames on time.
+const OUTPUT_FEED_BUDGET: Duration = Duration::from_millis(6);
+/// How long the cursor stays shown, then hidden, while it blinks.
+pub(crate) const CURSOR_BLINK_INTERVAL: Duration = Duration::from_millis(600);
+
 fn ensure_key_bindings(cx: &mut App) {
     KEY_BINDINGS.call_once(|| {
         cx.bind_keys([
@@ -25,20 +100,24 @@ fn ensure_key_bindings(cx: &mut App) {
             KeyBinding::new("cmd-c", Copy, Some(KEY_CONTEXT)),
             KeyBinding::new("cmd-shift-c", CopyLastOutput, Some(KEY_CONTEXT)),
             KeyBinding::new("cmd-v", Paste, Some(KEY_CONTEXT)),
+            KeyBinding::new("cmd-f", Search, Some(KEY_CONTEXT)),
+            KeyBinding::new("cmd-g", SearchNext, Some(KEY_CONTEXT)),
+            KeyBinding::new("cmd-shift-g", SearchPrevious, Some(KEY_CONTEXT)),
+            KeyBinding::new("cmd-up", JumpToPreviousPrompt, Some(KEY_CONTEXT)),
+            KeyBinding::new("cmd-down", JumpToNextPrompt, Some(KEY_CONTEXT)),
+            KeyBinding::new("cmd-shift-h", ShowClipboardHistory, Some(KEY_CONTEXT)),
+            KeyBinding::new("cmd-k", ClearScrollback, Some(KEY_CONTEXT)),
+            KeyBinding::new("cmd-shift-n", ToggleNotes, Some(KEY_CONTEXT)),
+            KeyBinding::new("cmd-=", ZoomIn, Some(KEY_CONTEXT)),
+            KeyBinding::new("cmd-+", ZoomIn, Some(KEY_CONTEXT)),
+            KeyBinding::new("cmd--", ZoomOut, Some(KEY_CONTEXT)),
+            KeyBinding::new("cmd-0", ZoomReset, Some(KEY_CONTEXT)),
             KeyBinding::new("tab", Tab, Some(KEY_CONTEXT)),
             KeyBinding::new("shift-tab", TabPrev, Some(KEY_CONTEXT)),
         ]);
     });
 }
 
-fn split_viewport_lines(viewport: &str) -> Vec<String> {
-    let viewport = viewport.strip_suffix('\n').unwrap_or(viewport);
-    if viewport.is_empty() {
-        return Vec::new();
-    }
-    viewport.split('\n').map(|line| line.to_string()).collect()
-}
-
 pub(crate) fn should_skip_key_down_for_ime(has_input: bool, keystroke: &gpui::Keystroke) -> bool {
     if !has_input || !keystroke.is_ime_in_progress() {
         return false;
@@ -77,6 +156,61 @@ pub(crate) fn ctrl_byte_for_keystroke(keystroke: &gpui::Keystroke) -> Option<u8>
     }
 }
 
+/// xterm modifyOtherKeys encoding (`CSI 27 ; modifiers ; code ~`) of a ctrl/alt keystroke at
+/// `level` (see [`TerminalSession::modify_other_keys`]), or `None` when the key keeps its
+/// traditional encoding.
+///
+/// Level 1 only covers keys that otherwise can't be told apart: ctrl with digits, punctuation,
+/// shifted letters, enter, tab, escape and backspace. Level 2 covers every ctrl/alt combination.
+pub(crate) fn modify_other_keys_sequence(level: u8, keystroke: &gpui::Keystroke) -> Option<String> {
+    let modifiers = &keystroke.modifiers;
+    if level == 0 || !(modifiers.control || modifiers.alt) || modifiers.platform {
+        return None;
+    }
+
+    let (code, special) = match keystroke.key.as_str() {
+        "enter" => (13, true),
+        "tab" => (9, true),
+        "escape" => (27, true),
+        "backspace" => (127, true),
+        "space" => (32, false),
+        key => {
+            let mut chars = key.chars();
+            let ch = chars.next()?;
+            if chars.next().is_some() {
+                return None;
+            }
+            let ch = if modifiers.shift {
+                ch.to_ascii_uppercase()
+            } else {
+                ch
+            };
+            (u32::from(ch), false)
+        }
+    };
+
+    if level < 2 {
+        let ambiguous = special
+            || (modifiers.shift && char::from_u32(code).is_some_and(|ch| ch.is_ascii_alphabetic()))
+            || ctrl_byte_for_keystroke(keystroke).is_none();
+        if !modifiers.control || !ambiguous {
+            return None;
+        }
+    }
+
+    let mut param = 1;
+    if modifiers.shift {
+        param += 1;
+    }
+    if modifiers.alt {
+        param += 2;
+    }
+    if modifiers.control {
+        param += 4;
+    }
+    Some(format!("\x1b[27;{param};{code}~"))
+}
+
 pub(crate) fn sgr_mouse_button_value(
     base_button: u8,
     motion: bool,
@@ -100,6 +234,15 @@ pub(crate) fn sgr_mouse_button_value(
     value
 }
 
+/// Text of the pill shown when output arrives while scrolled back.
+fn new_output_label(lines: u32) -> String {
+    if lines == 1 {
+        "1 new line ↓".to_string()
+    } else {
+        format!("{lines} new lines ↓")
+    }
+}
+
 fn window_position_to_local(
     last_bounds: Option<Bounds<Pixels>>,
     position: gpui::Point<gpui::Pixels>,
@@ -110,9 +253,69 @@ fn window_position_to_local(
     point(position.x - origin.x, position.y - origin.y)
 }
 
-pub(crate) fn sgr_mouse_sequence(button_value: u8, col: u16, row: u16, pressed: bool) -> String {
+pub(crate) fn sgr_mouse_sequence(button_value: u8, point: ViewportPoint, pressed: bool) -> String {
     let suffix = if pressed { 'M' } else { 'm' };
-    format!("\x1b[<{};{};{}{}", button_value, col, row, suffix)
+    format!(
+        "\x1b[<{};{};{}{}",
+        button_value, point.col, point.row, suffix
+    )
+}
+
+/// Encodes a mouse report for `encoding`, or `None` when the point is beyond what the legacy
+/// encodings can address.
+///
+/// Only SGR distinguishes which button was released; the others report a release as button 3,
+/// keeping the modifier and motion bits.
+pub(crate) fn mouse_sequence(
+    encoding: MouseEncoding,
+    button_value: u8,
+    point: ViewportPoint,
+    pressed: bool,
+) -> Option<Vec<u8>> {
+    let legacy_value = if pressed {
+        button_value
+    } else {
+        (button_value & !0b11) | 3
+    };
+    match encoding {
+        MouseEncoding::Sgr | MouseEncoding::SgrPixels => {
+            Some(sgr_mouse_sequence(button_value, point, pressed).into_bytes())
+        }
+        MouseEncoding::Urxvt => Some(
+            format!(
+                "\x1b[{};{};{}M",
+                u32::from(legacy_value) + 32,
+                point.col,
+                point.row
+            )
+            .into_bytes(),
+        ),
+        MouseEncoding::Utf8 => {
+            let mut seq = b"\x1b[M".to_vec();
+            for value in [
+                u32::from(legacy_value),
+                u32::from(point.col),
+                u32::from(point.row),
+            ] {
+                // Two-byte UTF-8 tops out at U+07FF.
+                let ch = char::from_u32(value + 32).filter(|ch| ch.len_utf8() <= 2)?;
+                let mut buf = [0u8; 4];
+                seq.extend_from_slice(ch.encode_utf8(&mut buf).as_bytes());
+            }
+            Some(seq)
+        }
+        MouseEncoding::X10 => {
+            let mut seq = b"\x1b[M".to_vec();
+            for value in [
+                u32::from(legacy_value),
+                u32::from(point.col),
+                u32::from(point.row),
+            ] {
+                seq.push(u8::try_from(value + 32).ok()?);
+            }
+            Some(seq)
+        }
+    }
 }
 
 fn is_url_byte(b: u8) -> bool {
@@ -145,6 +348,12 @@ fn is_url_byte(b: u8) -> bool {
 }
 
 fn url_at_byte_index(text: &str, index: usize) -> Option<String> {
+    url_range_at_byte_index(text, index).map(|range| text[range].to_string())
+}
+
+/// Byte range of the `http(s)://` URL around byte `index` of `text`, without trailing
+/// punctuation.
+fn url_range_at_byte_index(text: &str, index: usize) -> Option<Range<usize>> {
     let bytes = text.as_bytes();
     if bytes.is_empty() {
         return None;
@@ -181,12 +390,87 @@ fn url_at_byte_index(text: &str, index: usize) -> Option<String> {
 
     let candidate = std::str::from_utf8(&bytes[start..end]).ok()?;
     if candidate.starts_with("https://") || candidate.starts_with("http://") {
-        Some(candidate.to_string())
+        Some(start..end)
     } else {
         None
     }
 }
 
+/// Byte range of the word containing byte `index` of `line`.
+///
+/// Whitespace and any char in `separators` end a word; clicking a separator selects just that
+/// char, and clicking whitespace or past the end of the line selects nothing.
+pub(crate) fn word_range_in_line(
+    line: &str,
+    index: usize,
+    separators: &str,
+) -> Option<Range<usize>> {
+    let is_separator = |c: char| c.is_whitespace() || separators.contains(c);
+    let (at, ch) = line
+        .char_indices()
+        .find(|(i, c)| i + c.len_utf8() > index)?;
+    if ch.is_whitespace() {
+        return None;
+    }
+    if is_separator(ch) {
+        return Some(at..at + ch.len_utf8());
+    }
+
+    let start = line[..at]
+        .char_indices()
+        .rev()
+        .find(|(_, c)| is_separator(*c))
+        .map(|(i, c)| i + c.len_utf8())
+        .unwrap_or(0);
+    let end = line[at..]
+        .char_indices()
+        .find(|(_, c)| is_separator(*c))
+        .map(|(i, _)| at + i)
+        .unwrap_or(line.len());
+    Some(start..end)
+}
+
+/// Printable text of input queued for an unavailable transport, for display at the cursor.
+///
+/// Escape sequences (arrows, function keys) are dropped, DEL/BS erase the previous char and
+/// other control bytes are skipped.
+pub(crate) fn queued_input_preview(bytes: &[u8]) -> Option<String> {
+    let mut text = String::new();
+    let decoded = String::from_utf8_lossy(bytes);
+    let mut chars = decoded.chars();
+    while let Some(ch) = chars.next() {
+        match ch {
+            '\x1b' => {
+                if let Some('[' | 'O') = chars.next() {
+                    for ch in chars.by_ref() {
+                        if ('@'..='~').contains(&ch) {
+                            break;
+                        }
+                    }
+                }
+            }
+            '\x7f' | '\x08' => {
+                text.pop();
+            }
+            ch if ch.is_control() => {}
+            ch => text.push(ch),
+        }
+    }
+    (!text.is_empty()).then_some(text)
+}
+
+/// Text of a built-in overlay; a process exit also says what Enter does.
+fn overlay_text(overlay: &TerminalOverlay, can_restart: bool) -> String {
+    let label = overlay.label().unwrap_or_default();
+    match overlay {
+        TerminalOverlay::ProcessExited { .. } if can_restart => {
+            format!("{} — press Enter to restart", label)
+        }
+        TerminalOverlay::ProcessExited { .. } => format!("{} — press Enter to close", label),
+        _ => label,
+    }
+}
+
 fn url_at_column_in_line(line: &str, col: u16) -> Option<String> {
     if line.is_empty() {
         return None;
@@ -196,8 +480,14 @@ fn url_at_column_in_line(line: &str, col: u16) -> Option<String> {
     url_at_byte_index(line, local)
 }
 
-type TerminalSendFn = dyn Fn(&[u8]) + Send + Sync + 'static;
+type TerminalSendFn = dyn Fn(&[u8]) -> bool + Send + Sync + 'static;
 type TerminalResizeFn = dyn Fn(u16, u16) + Send + Sync + 'static;
+type TerminalEventFn = dyn Fn(&TerminalEvent) + Send + Sync + 'static;
+type LinkOpenFn = dyn Fn(&str) + Send + Sync + 'static;
+type SmartLinkFn = dyn Fn(&SmartLinkMatch) -> bool + Send + Sync + 'static;
+type PasteConfirmFn = dyn Fn(&str, &PasteWarning) + Send + Sync + 'static;
+type OverlayRenderFn = dyn Fn(&mut Window, &mut App) -> gpui::AnyElement + 'static;
+type TerminalRestartFn = dyn Fn(WeakEntity<TerminalView>, &mut App) + 'static;
 
 pub struct TerminalInput {
     send: Box<TerminalSendFn>,
@@ -205,6 +495,17 @@ pub struct TerminalInput {
 
 impl TerminalInput {
     pub fn new(send: impl Fn(&[u8]) + Send + Sync + 'static) -> Self {
+        Self::new_fallible(move |bytes| {
+            send(bytes);
+            true
+        })
+    }
+
+    /// Like [`TerminalInput::new`], but `send` returns `false` when the bytes could not be
+    /// delivered (a backed-up writer channel, a reconnecting transport). The view then holds
+    /// typed input back, shows it at the cursor, and resends it on
+    /// [`TerminalView::flush_queued_input`].
+    pub fn new_fallible(send: impl Fn(&[u8]) -> bool + Send + Sync + 'static) -> Self {
         Self {
             send: Box::new(send),
         }
@@ -213,6 +514,12 @@ impl TerminalInput {
     pub fn send(&self, bytes: &[u8]) {
         (self.send)(bytes);
     }
+
+    /// Sends `bytes`, returning `false` when a [`TerminalInput::new_fallible`] sender could not
+    /// deliver them.
+    pub fn try_send(&self, bytes: &[u8]) -> bool {
+        (self.send)(bytes)
+    }
 }
 
 /// Callback for terminal resize events.
@@ -233,26 +540,247 @@ impl TerminalResizeCallback {
     }
 }
 
+/// Callback for events raised by terminal output, such as the bell.
+/// Use it to request taskbar attention or show desktop notifications.
+pub struct TerminalEventCallback {
+    callback: Box<TerminalEventFn>,
+}
+
+impl TerminalEventCallback {
+    pub fn new(callback: impl Fn(&TerminalEvent) + Send + Sync + 'static) -> Self {
+        Self {
+            callback: Box::new(callback),
+        }
+    }
+
+    fn call(&self, event: &TerminalEvent) {
+        (self.callback)(event);
+    }
+}
+
+/// How cmd/ctrl-clicked links are opened: with the OS's handler (`cx.open_url`, the default),
+/// by copying them to the clipboard, or through a callback, e.g. to confirm or allow-list URLs
+/// first. Adding shift to the click always copies, whatever the strategy.
+pub struct UrlOpener {
+    strategy: UrlOpenStrategy,
+}
+
+enum UrlOpenStrategy {
+    System,
+    Clipboard,
+    Callback(Box<LinkOpenFn>),
+}
+
+impl Default for UrlOpener {
+    fn default() -> Self {
+        Self::system()
+    }
+}
+
+impl UrlOpener {
+    /// Open links through `callback` instead of the OS.
+    pub fn new(callback: impl Fn(&str) + Send + Sync + 'static) -> Self {
+        Self {
+            strategy: UrlOpenStrategy::Callback(Box::new(callback)),
+        }
+    }
+
+    /// Open links in the OS's default handler for their scheme (usually the browser).
+    pub fn system() -> Self {
+        Self {
+            strategy: UrlOpenStrategy::System,
+        }
+    }
+
+    /// Copy links to the clipboard rather than opening them.
+    pub fn copy_to_clipboard() -> Self {
+        Self {
+            strategy: UrlOpenStrategy::Clipboard,
+        }
+    }
+
+    fn open(&self, url: &str, cx: &mut App) {
+        match &self.strategy {
+            UrlOpenStrategy::System => cx.open_url(url),
+            UrlOpenStrategy::Clipboard => copy_url(url, cx),
+            UrlOpenStrategy::Callback(callback) => callback(url),
+        }
+    }
+}
+
+/// Callback for cmd/ctrl-clicked `link-rule` and `smart-link` matches, with the matched text
+/// and the rule's id, e.g. to open `file:line:col` locations in an editor. Returns whether it
+/// handled the click; if not, a match with a URL goes to the [`UrlOpener`].
+pub struct SmartLinkHandler {
+    callback: Box<SmartLinkFn>,
+}
+
+impl SmartLinkHandler {
+    pub fn new(callback: impl Fn(&SmartLinkMatch) -> bool + Send + Sync + 'static) -> Self {
+        Self {
+            callback: Box::new(callback),
+        }
+    }
+
+    fn call(&self, link: &SmartLinkMatch) -> bool {
+        (self.callback)(link)
+    }
+}
+
+fn copy_url(url: &str, cx: &mut App) {
+    let item = ClipboardItem::new_string(url.to_string());
+    cx.write_to_clipboard(item.clone());
+    #[cfg(any(target_os = "linux", target_os = "freebsd"))]
+    cx.write_to_primary(item);
+}
+
+/// Callback asked to confirm a paste flagged by `paste_protection` (see
+/// [`TerminalConfig::paste_protection`](crate::TerminalConfig::paste_protection)) or the view's
+/// [`PastePolicy`]. It receives the text about to be pasted and why it was held; the paste waits
+/// until the host calls [`TerminalView::confirm_paste`] or [`TerminalView::cancel_paste`], e.g.
+/// from a dialog.
+pub struct PasteConfirmCallback {
+    callback: Box<PasteConfirmFn>,
+}
+
+impl PasteConfirmCallback {
+    pub fn new(callback: impl Fn(&str, &PasteWarning) + Send + Sync + 'static) -> Self {
+        Self {
+            callback: Box::new(callback),
+        }
+    }
+
+    fn call(&self, text: &str, warning: &PasteWarning) {
+        (self.callback)(text, warning);
+    }
+}
+
+/// Link underlined while the platform modifier is held over it.
+#[derive(Clone, Debug, PartialEq)]
+enum HoveredLink {
+    /// A link-rule or URL match: viewport row and byte range in that row's text.
+    Rule(usize, Range<usize>),
+    /// Every cell of an OSC 8 hyperlink, which may span several rows.
+    Hyperlink(NonZeroU32),
+}
+
+/// Link under a cmd/ctrl-click.
+enum ClickedLink {
+    /// An OSC 8 hyperlink or plain URL.
+    Url(String),
+    Rule(SmartLinkMatch),
+}
+
+/// Content drawn over the whole terminal on a translucent scrim, set with
+/// [`TerminalView::set_overlay`] while the terminal cannot be used (the process exited, the
+/// connection dropped). Mouse input does not reach the grid while an overlay is shown.
+pub enum TerminalOverlay {
+    /// The child process exited, with its exit code when known.
+    ProcessExited { exit_code: Option<i32> },
+    /// The host is reconnecting to a remote session; `attempt` counts from 1.
+    Reconnecting { attempt: Option<u32> },
+    /// The host was refused access (a PTY, a remote shell), with an optional reason.
+    PermissionDenied { message: Option<String> },
+    /// Any element, rendered centered on the scrim.
+    Custom(Box<OverlayRenderFn>),
+}
+
+impl TerminalOverlay {
+    pub fn custom(render: impl Fn(&mut Window, &mut App) -> gpui::AnyElement + 'static) -> Self {
+        Self::Custom(Box::new(render))
+    }
+
+    /// Text shown for the built-in variants.
+    pub fn label(&self) -> Option<String> {
+        match self {
+            Self::ProcessExited {
+                exit_code: Some(code),
+            } => Some(format!("Process exited with code {}", code)),
+            Self::ProcessExited { exit_code: None } => Some("Process exited".to_string()),
+            Self::Reconnecting {
+                attempt: Some(attempt),
+            } => Some(format!("Reconnecting… (attempt {})", attempt)),
+            Self::Reconnecting { attempt: None } => Some("Reconnecting…".to_string()),
+            Self::PermissionDenied {
+                message: Some(message),
+            } => Some(format!("Permission denied: {}", message)),
+            Self::PermissionDenied { message: None } => Some("Permission denied".to_string()),
+            Self::Custom(_) => None,
+        }
+    }
+}
+
 pub struct TerminalView {
     session: TerminalSession,
     viewport_lines: Vec<String>,
     viewport_line_offsets: Vec<usize>,
     viewport_total_len: usize,
+    /// Style runs per viewport row. The row buffers are refilled in place, not reallocated.
     viewport_style_runs: Vec<Vec<StyleRun>>,
-    line_layouts: Vec<Option<gpui::ShapedLine>>,
-    line_layout_key: Option<(Pixels, Pixels)>,
+    /// Dirty row list reused by every output reconcile.
+    dirty_rows: Vec<u16>,
+    /// Scratch rows that dirty-row dumps are read into before swapping places with the rows they
+    /// replace, so updating rows allocates nothing once warmed up.
+    dirty_row_lines: Vec<String>,
+    dirty_row_style_runs: Vec<Vec<StyleRun>>,
+    line_layouts: Vec<Option<ShapedRow>>,
+    /// Rows shaped in recent frames, so unchanged rows are not reshaped after a refresh.
+    row_cache: RowCache<ShapedRow>,
+    /// Font size, line height and ligature setting `line_layouts` were shaped with.
+    line_layout_key: Option<(Pixels, Pixels, bool)>,
+    /// Selection recolored with `selection_foreground` in `line_layouts`.
+    line_layout_selection: Option<Range<usize>>,
     last_bounds: Option<Bounds<Pixels>>,
     focus_handle: FocusHandle,
     last_window_title: Option<String>,
     input: Option<TerminalInput>,
     resize_callback: Option<TerminalResizeCallback>,
+    event_callback: Option<TerminalEventCallback>,
+    url_opener: UrlOpener,
+    smart_link_handler: Option<SmartLinkHandler>,
+    paste_confirm: Option<PasteConfirmCallback>,
+    paste_policy: PastePolicy,
+    report_appearance_changes: bool,
+    pending_paste: Option<String>,
+    bell_flash: bool,
+    text_blink_hidden: bool,
+    text_blink_task: Option<Task<()>>,
+    cursor_focused: bool,
+    cursor_blink_hidden: bool,
+    cursor_blink_timer: bool,
+    cursor_blink_epoch: u64,
+    cursor_animation: CursorAnimation,
+    cursor_animation_timer: bool,
+    scrollbar: Scrollbar,
+    scrollbar_timer: bool,
+    resize_overlay: ResizeOverlay,
+    resize_overlay_timer: bool,
+    new_lines_below: u32,
     pending_output: Vec<u8>,
+    output_timer: bool,
+    render_waiters: RenderWaiters,
     pending_refresh: bool,
     selection: Option<ByteSelection>,
     marked_text: Option<SharedString>,
     marked_selected_range_utf16: Range<usize>,
     font: gpui::Font,
     font_size: Option<Pixels>,
+    /// Runtime zoom (`ZoomIn`/`ZoomOut`) on top of `font_size`; cleared by `ZoomReset`.
+    zoom_font_size: Option<Pixels>,
+    font_ligatures: Option<bool>,
+    kitty_images: KittyImageCache,
+    link_cache: RowLinkCache,
+    hovered_link: Option<HoveredLink>,
+    /// The window-activating click is held down and its drag and release are being swallowed.
+    swallowing_focus_click: bool,
+    search: Option<SearchBar>,
+    clipboard_picker: Option<ClipboardPicker>,
+    notes_open: bool,
+    input_locked: bool,
+    queued_input: Vec<u8>,
+    overlay: Option<TerminalOverlay>,
+    restart_handler: Option<Rc<TerminalRestartFn>>,
+    close_requested: bool,
 }
 
 #[derive(Clone, Copy, Debug)]
@@ -279,20 +807,62 @@ impl TerminalView {
             viewport_line_offsets: Vec::new(),
             viewport_total_len: 0,
             viewport_style_runs: Vec::new(),
+            dirty_rows: Vec::new(),
+            dirty_row_lines: Vec::new(),
+            dirty_row_style_runs: Vec::new(),
             line_layouts: Vec::new(),
+            row_cache: RowCache::default(),
             line_layout_key: None,
+            line_layout_selection: None,
             last_bounds: None,
             focus_handle,
             last_window_title: None,
             input: None,
             resize_callback: None,
+            event_callback: None,
+            url_opener: UrlOpener::default(),
+            smart_link_handler: None,
+            paste_confirm: None,
+            paste_policy: PastePolicy::default(),
+            report_appearance_changes: false,
+            pending_paste: None,
+            bell_flash: false,
+            text_blink_hidden: false,
+            text_blink_task: None,
+            cursor_focused: false,
+            cursor_blink_hidden: false,
+            cursor_blink_timer: false,
+            cursor_blink_epoch: 0,
+            cursor_animation: CursorAnimation::default(),
+            cursor_animation_timer: false,
+            scrollbar: Scrollbar::default(),
+            scrollbar_timer: false,
+            resize_overlay: ResizeOverlay::default(),
+            resize_overlay_timer: false,
+            new_lines_below: 0,
             pending_output: Vec::new(),
+            output_timer: false,
+            render_waiters: RenderWaiters::default(),
             pending_refresh: false,
             selection: None,
             marked_text: None,
             marked_selected_range_utf16: 0..0,
             font: crate::default_terminal_font(),
             font_size: None,
+            zoom_font_size: None,
+            font_ligatures: None,
+            kitty_images: KittyImageCache::default(),
+            link_cache: RowLinkCache::default(),
+            hovered_link: None,
+            swallowing_focus_click: false,
+            search: None,
+            clipboard_picker: None,
+            notes_open: false,
+            input_locked: false,
+            queued_input: Vec::new(),
+            overlay: None,
+            restart_handler: None,
+            close_requested: false,
         }
         .with_refreshed_viewport()
     }
@@ -330,6 +900,16 @@ impl TerminalView {
         &mut self.session
     }
 
+    /// Answers a clipboard read the session's [`ClipboardAccessPolicy`](crate::ClipboardAccessPolicy)
+    /// deferred with `Prompt`: `Some(contents)` sends them to the application, `None` denies it.
+    pub fn answer_clipboard_read(&mut self, contents: Option<&str>) {
+        if let Some(reply) = self.session.answer_clipboard_read(contents)
+            && let Some(input) = self.input.as_ref()
+        {
+            input.send(&reply);
+        }
+    }
+
     pub fn new_with_input(
         session: TerminalSession,
         focus_handle: FocusHandle,
@@ -341,35 +921,184 @@ impl TerminalView {
             viewport_line_offsets: Vec::new(),
             viewport_total_len: 0,
             viewport_style_runs: Vec::new(),
+            dirty_rows: Vec::new(),
+            dirty_row_lines: Vec::new(),
+            dirty_row_style_runs: Vec::new(),
             line_layouts: Vec::new(),
+            row_cache: RowCache::default(),
             line_layout_key: None,
+            line_layout_selection: None,
             last_bounds: None,
             focus_handle,
             last_window_title: None,
             input: Some(input),
             resize_callback: None,
+            event_callback: None,
+            url_opener: UrlOpener::default(),
+            smart_link_handler: None,
+            paste_confirm: None,
+            paste_policy: PastePolicy::default(),
+            report_appearance_changes: false,
+            pending_paste: None,
+            bell_flash: false,
+            text_blink_hidden: false,
+            text_blink_task: None,
+            cursor_focused: false,
+            cursor_blink_hidden: false,
+            cursor_blink_timer: false,
+            cursor_blink_epoch: 0,
+            cursor_animation: CursorAnimation::default(),
+            cursor_animation_timer: false,
+            scrollbar: Scrollbar::default(),
+            scrollbar_timer: false,
+            resize_overlay: ResizeOverlay::default(),
+            resize_overlay_timer: false,
+            new_lines_below: 0,
             pending_output: Vec::new(),
+            output_timer: false,
+            render_waiters: RenderWaiters::default(),
             pending_refresh: false,
             selection: None,
             marked_text: None,
             marked_selected_range_utf16: 0..0,
             font: crate::default_terminal_font(),
             font_size: None,
+            zoom_font_size: None,
+            font_ligatures: None,
+            kitty_images: KittyImageCache::default(),
+            link_cache: RowLinkCache::default(),
+            hovered_link: None,
+            swallowing_focus_click: false,
+            search: None,
+            clipboard_picker: None,
+            notes_open: false,
+            input_locked: false,
+            queued_input: Vec::new(),
+            overlay: None,
+            restart_handler: None,
+            close_requested: false,
         }
         .with_refreshed_viewport()
     }
 
+    /// A view around a session that was running detached, wired back to its PTY.
+    pub(crate) fn from_detached(detached: DetachedSession, focus_handle: FocusHandle) -> Self {
+        let mut view = Self::new(detached.session, focus_handle);
+        view.input = detached.input;
+        view.resize_callback = detached.resize_callback;
+        view
+    }
+
+    /// Moves the session, PTY input and resize callback out so they can outlive the view, after
+    /// parsing any queued output. The view is left with a blank session of the same config;
+    /// `None` if that cannot be created.
+    pub(crate) fn take_session(&mut self) -> Option<DetachedSession> {
+        self.drain_pending_output(None);
+        let blank = TerminalSession::new(self.session.config().clone()).ok()?;
+        let session = std::mem::replace(&mut self.session, blank);
+        self.pending_output.clear();
+        self.search = None;
+        self.row_cache.clear();
+        self.refresh_viewport();
+        Some(DetachedSession {
+            session,
+            input: self.input.take(),
+            resize_callback: self.resize_callback.take(),
+        })
+    }
+
     /// Set a callback to be invoked when the terminal grid size changes.
-    /// The callback receives (cols, rows) and should resize the PTY accordingly.
+    /// The view computes cols/rows from its element bounds and resizes the session itself; the
+    /// callback receives (cols, rows) and should resize the PTY accordingly.
     pub fn set_resize_callback(&mut self, callback: TerminalResizeCallback) {
         self.resize_callback = Some(callback);
     }
 
+    /// Set a callback to be invoked for each [`TerminalEvent`] raised by terminal output.
+    pub fn set_event_callback(&mut self, callback: TerminalEventCallback) {
+        self.event_callback = Some(callback);
+    }
+
+    /// Set how cmd/ctrl-clicked links are opened; see [`UrlOpener`].
+    pub fn set_url_opener(&mut self, opener: UrlOpener) {
+        self.url_opener = opener;
+    }
+
+    /// Set the callback for cmd/ctrl-clicked `link-rule` and `smart-link` matches.
+    pub fn set_smart_link_handler(&mut self, handler: SmartLinkHandler) {
+        self.smart_link_handler = Some(handler);
+    }
+
+    /// Set the callback asked to confirm pastes flagged by `paste_protection` or the
+    /// [`PastePolicy`]. Without one, flagged pastes go through unconfirmed.
+    pub fn set_paste_confirm_callback(&mut self, callback: PasteConfirmCallback) {
+        self.paste_confirm = Some(callback);
+    }
+
+    /// Set extra rules (line limits, command patterns) for pastes held for confirmation.
+    pub fn set_paste_policy(&mut self, policy: PastePolicy) {
+        self.paste_policy = policy;
+    }
+
+    /// Switches a `theme = dark:X,light:Y` config to the variant for the system appearance and
+    /// repaints with it. Hosts call this from `Window::observe_window_appearance`. Returns
+    /// `false` when the theme has no dark/light variants.
+    pub fn set_appearance(&mut self, is_dark: bool, cx: &mut Context<Self>) -> bool {
+        if !reload_theme_for_appearance(self.session.config_mut(), is_dark) {
+            return false;
+        }
+        self.theme_colors_changed(cx);
+        true
+    }
+
+    /// Switch to the named theme (embedded or a Ghostty theme file), recoloring the palette,
+    /// default, selection and cursor colors live. Cheap enough to call on every highlight change
+    /// of a theme picker for a live preview; re-apply the original name to cancel.
+    pub fn apply_theme(&mut self, name: &str, cx: &mut Context<Self>) -> Result<(), ConfigError> {
+        apply_theme(self.session.config_mut(), name)?;
+        self.theme_colors_changed(cx);
+        Ok(())
+    }
+
+    fn theme_colors_changed(&mut self, cx: &mut Context<Self>) {
+        self.session.apply_theme_colors();
+        // Palette changes are not part of the row cache key.
+        self.row_cache.clear();
+        if self.report_appearance_changes
+            && let Some(input) = self.input.as_ref()
+        {
+            input.send(self.session.default_color_reports().as_bytes());
+        }
+        self.schedule_viewport_refresh(cx);
+    }
+
+    /// Swap in a freshly loaded config: colors, palette, theme and font take effect on the next
+    /// frame, and a changed font size refits the grid. The grid keeps its live size; see
+    /// [`TerminalSession::apply_config`] for what else reaches the terminal.
+    pub fn apply_config(&mut self, config: TerminalConfig, cx: &mut Context<Self>) {
+        self.font = crate::terminal_font(&config);
+        self.font_size = config.font_size.map(px);
+        self.session.apply_config(config);
+        self.line_layouts.clear();
+        self.line_layout_key = None;
+        self.row_cache.clear();
+        self.ensure_text_blink_timer(cx);
+        self.schedule_viewport_refresh(cx);
+    }
+
+    /// Also send OSC 10/11 color reports to the application when [`Self::set_appearance`]
+    /// switches the theme, so TUIs that track the background (e.g. Neovim's `background`) can
+    /// adapt. Off by default.
+    pub fn set_report_appearance_changes(&mut self, enabled: bool) {
+        self.report_appearance_changes = enabled;
+    }
+
     /// Set the font used for terminal rendering.
     pub fn set_font(&mut self, font: gpui::Font) {
         self.font = font;
         // Clear line layouts to force re-shaping with new font
         self.line_layouts.clear();
+        self.row_cache.clear();
         self.line_layout_key = None;
     }
 
@@ -381,6 +1110,105 @@ impl TerminalView {
         self.line_layout_key = None;
     }
 
+    /// The font size text is rendered at: the zoomed size if zoomed, else the configured one.
+    pub fn effective_font_size(&self) -> Option<Pixels> {
+        self.zoom_font_size.or(self.font_size)
+    }
+
+    /// Grows the font by one pixel, resizing the grid (and notifying the resize callback).
+    pub fn zoom_in(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        let size = self.zoom_base_size(window) + px(ZOOM_STEP);
+        self.set_zoom_font_size(Some(size.min(px(MAX_FONT_SIZE))), window, cx);
+    }
+
+    /// Shrinks the font by one pixel, resizing the grid (and notifying the resize callback).
+    pub fn zoom_out(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        let size = self.zoom_base_size(window) - px(ZOOM_STEP);
+        self.set_zoom_font_size(Some(size.max(px(MIN_FONT_SIZE))), window, cx);
+    }
+
+    /// Drops the runtime zoom, going back to the configured font size.
+    pub fn zoom_reset(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        self.set_zoom_font_size(None, window, cx);
+    }
+
+    fn zoom_base_size(&self, window: &Window) -> Pixels {
+        self.effective_font_size()
+            .unwrap_or_else(|| window.text_style().font_size.to_pixels(window.rem_size()))
+    }
+
+    fn set_zoom_font_size(
+        &mut self,
+        size: Option<Pixels>,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        if self.zoom_font_size == size {
+            return;
+        }
+        self.zoom_font_size = size;
+        self.line_layouts.clear();
+        self.line_layout_key = None;
+        // Resize now rather than at the next prepaint so the host can resize the PTY before the
+        // application draws at the old size.
+        if let (Some(bounds), Some((cell_w, cell_h))) = (
+            self.last_bounds,
+            cell_metrics(window, &self.font, self.effective_font_size()),
+        ) {
+            self.fit_grid(bounds, cell_w, cell_h, window.scale_factor(), cx);
+        }
+        cx.notify();
+    }
+
+    /// Resizes the grid to fill `bounds` with cells of the given size, notifying the resize
+    /// callback and flashing the size overlay when the grid size changes.
+    fn fit_grid(
+        &mut self,
+        bounds: Bounds<Pixels>,
+        cell_w: f32,
+        cell_h: f32,
+        scale: f32,
+        cx: &mut Context<Self>,
+    ) {
+        let width = f32::from(bounds.size.width);
+        let height = f32::from(bounds.size.height);
+        let (cols, rows) = self.session.config().clamp_grid_size(
+            (width / cell_w).floor().max(1.0) as u16,
+            (height / cell_h).floor().max(1.0) as u16,
+        );
+
+        self.session.set_cell_pixel_size(
+            (cell_w * scale).round() as u32,
+            (cell_h * scale).round() as u32,
+        );
+
+        if cols != self.session.cols() || rows != self.session.rows() {
+            // Notify external callback (e.g., PTY resize) if set
+            if let Some(callback) = self.resize_callback.as_ref() {
+                callback.call(cols, rows);
+            }
+            // Resize the internal terminal session
+            let _ = self.session.resize(cols, rows);
+            self.sync_viewport_scroll_tracking();
+            self.pending_refresh = true;
+            let policy = self.session.config().resize_overlay;
+            if self.resize_overlay.resized(policy, Instant::now()) {
+                self.ensure_resize_overlay_timer(cx);
+            }
+        }
+    }
+
+    /// Overrides the config's `font_ligatures` for this view; `None` follows the config again.
+    pub fn set_font_ligatures(&mut self, enabled: Option<bool>) {
+        self.font_ligatures = enabled;
+    }
+
+    /// Whether text in this view is shaped with ligatures.
+    pub fn font_ligatures(&self) -> bool {
+        self.font_ligatures
+            .unwrap_or(self.session.config().font_ligatures)
+    }
+
     fn utf16_len(s: &str) -> usize {
         s.chars().map(|ch| ch.len_utf16()).sum()
     }
@@ -473,17 +1301,51 @@ impl TerminalView {
             return;
         }
 
+        if self.clipboard_picker.is_some() {
+            return;
+        }
+
+        if self.notes_open {
+            self.session.notes_mut().push_str(text);
+            cx.notify();
+            return;
+        }
+
+        if let Some(search) = self.search.as_mut() {
+            search.query.push_str(text);
+            self.session.clear_search();
+            self.step_search(SearchDirection::Backward, cx);
+            return;
+        }
+
         self.send_input_parts(&[text.as_bytes()], cx);
     }
 
     fn send_input_parts(&mut self, parts: &[&[u8]], cx: &mut Context<Self>) {
-        if parts.is_empty() {
+        if parts.is_empty() || self.input_locked {
             return;
         }
+        self.reset_cursor_blink();
 
-        if let Some(input) = self.input.as_ref() {
+        if self.input.is_some() {
+            if self.session.config().scroll_to_bottom_on_keystroke
+                && self.session.scroll_offset() > 0
+            {
+                self.scroll_to_bottom(cx);
+            }
+            self.flush_queued_input(cx);
             for bytes in parts {
-                input.send(bytes);
+                self.session.record_input(bytes);
+            }
+            if let Some(input) = self.input.as_ref() {
+                for bytes in parts {
+                    if !self.queued_input.is_empty() || !input.try_send(bytes) {
+                        self.queued_input.extend_from_slice(bytes);
+                    }
+                }
+            }
+            if !self.queued_input.is_empty() {
+                cx.notify();
             }
             return;
         }
@@ -495,14 +1357,64 @@ impl TerminalView {
         self.schedule_viewport_refresh(cx);
     }
 
+    /// Input typed while the transport refused sends, in the order it was typed.
+    pub fn queued_input(&self) -> &[u8] {
+        &self.queued_input
+    }
+
+    /// Resends input held back by a [`TerminalInput::new_fallible`] sender, e.g. once a
+    /// reconnecting transport is back. Returns `true` when nothing is left queued.
+    ///
+    /// Queued input is also retried on the next keystroke and whenever output arrives.
+    pub fn flush_queued_input(&mut self, cx: &mut Context<Self>) -> bool {
+        if self.queued_input.is_empty() {
+            return true;
+        }
+        let Some(input) = self.input.as_ref() else {
+            return false;
+        };
+        if !input.try_send(&self.queued_input) {
+            return false;
+        }
+        self.queued_input.clear();
+        cx.notify();
+        true
+    }
+
     fn feed_output_bytes_to_session(&mut self, bytes: &[u8]) {
+        let offset_before = self.session.scroll_offset();
         if let Some(input) = self.input.as_ref() {
-            let _ = self
-                .session
-                .feed_with_pty_responses(bytes, |resp| input.send(resp));
+            let _ = self.session.feed_with_pty_responses(bytes, |resp| {
+                input.send(resp);
+            });
         } else {
             let _ = self.session.feed(bytes);
         }
+
+        // While scrolled back the viewport stays on the same text, so every line of new output
+        // pushes it one row further from the bottom.
+        let offset_after = self.session.scroll_offset();
+        if offset_after == 0 {
+            self.new_lines_below = 0;
+        } else {
+            self.new_lines_below = self
+                .new_lines_below
+                .saturating_add(offset_after.saturating_sub(offset_before));
+        }
+    }
+
+    /// Lines of output that arrived below the viewport while it was scrolled back, shown as a
+    /// "new lines" pill until the view returns to the bottom.
+    pub fn new_lines_below(&self) -> u32 {
+        self.new_lines_below
+    }
+
+    /// Scrolls to the bottom, following output again.
+    pub fn scroll_to_bottom(&mut self, cx: &mut Context<Self>) {
+        let _ = self.session.scroll_viewport_bottom();
+        self.new_lines_below = 0;
+        self.sync_viewport_scroll_tracking();
+        self.schedule_viewport_refresh(cx);
     }
 
     fn sync_viewport_scroll_tracking(&mut self) {
@@ -550,7 +1462,8 @@ impl TerminalView {
                 }
             }
 
-            let dirty_rows: Vec<u16> = (rows - delta_abs..rows).map(|row| row as u16).collect();
+            let dirty_rows: SmallVec<[u16; 64]> =
+                (rows - delta_abs..rows).map(|row| row as u16).collect();
             let _ = self.apply_dirty_viewport_rows(&dirty_rows);
             return;
         }
@@ -569,7 +1482,7 @@ impl TerminalView {
             }
         }
 
-        let dirty_rows: Vec<u16> = (0..delta_abs).map(|row| row as u16).collect();
+        let dirty_rows: SmallVec<[u16; 64]> = (0..delta_abs).map(|row| row as u16).collect();
         let _ = self.apply_dirty_viewport_rows(&dirty_rows);
     }
 
@@ -577,10 +1490,17 @@ impl TerminalView {
         let delta = self.session.take_viewport_scroll_delta();
         self.apply_viewport_scroll_delta(delta);
 
-        let dirty = self.session.take_dirty_viewport_rows();
+        // OSC 10/11 recolor every default-colored cell, not just the rows that were written.
+        if self.session.take_default_colors_changed() {
+            self.pending_refresh = true;
+        }
+
+        let mut dirty = std::mem::take(&mut self.dirty_rows);
+        self.session.take_dirty_viewport_rows_into(&mut dirty);
         if !dirty.is_empty() && !self.apply_dirty_viewport_rows(&dirty) {
             self.pending_refresh = true;
         }
+        self.dirty_rows = dirty;
     }
 
     fn with_refreshed_viewport(mut self) -> Self {
@@ -589,39 +1509,52 @@ impl TerminalView {
     }
 
     fn refresh_viewport(&mut self) {
-        let viewport = self.session.dump_viewport().unwrap_or_default();
-        self.viewport_lines = split_viewport_lines(&viewport);
-        self.viewport_line_offsets = Self::compute_viewport_line_offsets(&self.viewport_lines);
-        self.viewport_total_len = Self::compute_viewport_total_len(&self.viewport_lines);
-        self.viewport_style_runs = (0..self.session.rows())
-            .map(|row| {
-                self.session
-                    .dump_viewport_row_style_runs(row)
-                    .unwrap_or_default()
-            })
-            .collect();
+        // One FFI call for every row's text and style runs, refilling the row buffers in place.
+        if self
+            .session
+            .dump_viewport_rows_into(
+                0..self.session.rows(),
+                &mut self.viewport_lines,
+                &mut self.viewport_style_runs,
+            )
+            .is_err()
+        {
+            self.viewport_lines.clear();
+            self.viewport_style_runs.clear();
+        }
+        self.update_viewport_line_offsets();
         self.line_layouts.clear();
         self.line_layout_key = None;
         self.selection = None;
+        self.link_cache.clear();
+        self.hovered_link = None;
     }
 
-    fn compute_viewport_line_offsets(lines: &[String]) -> Vec<usize> {
-        let mut offsets = Vec::with_capacity(lines.len());
+    /// Recomputes each row's byte offset into the joined viewport text, and its total length,
+    /// reusing the offsets buffer.
+    fn update_viewport_line_offsets(&mut self) {
+        self.viewport_line_offsets.clear();
         let mut offset = 0usize;
-        for line in lines {
-            offsets.push(offset);
+        for line in &self.viewport_lines {
+            self.viewport_line_offsets.push(offset);
             offset = offset.saturating_add(line.len() + 1);
         }
-        offsets
+        self.viewport_total_len = offset;
     }
 
-    fn compute_viewport_total_len(lines: &[String]) -> usize {
-        lines
-            .iter()
-            .fold(0usize, |acc, line| acc.saturating_add(line.len() + 1))
+    fn viewport_slice(&self, range: Range<usize>) -> String {
+        self.viewport_copy_text(range, &[], false)
     }
 
-    fn viewport_slice(&self, range: Range<usize>) -> String {
+    /// Like [`Self::viewport_slice`], but drops the newline after each row flagged in `wraps`
+    /// so soft-wrapped lines come out whole, and with `trim_trailing` strips the padding
+    /// spaces at the end of each line.
+    fn viewport_copy_text(
+        &self,
+        range: Range<usize>,
+        wraps: &[bool],
+        trim_trailing: bool,
+    ) -> String {
         if range.is_empty() || self.viewport_lines.is_empty() {
             return String::new();
         }
@@ -632,6 +1565,13 @@ impl TerminalView {
             return String::new();
         }
 
+        let trim = |text: &str| -> usize {
+            if trim_trailing {
+                text.trim_end_matches([' ', '\t']).len()
+            } else {
+                text.len()
+            }
+        };
         let mut out = String::new();
         let mut i = 0usize;
         while i < self.viewport_lines.len() {
@@ -653,7 +1593,8 @@ impl TerminalView {
                         out.push_str(seg);
                     }
                 }
-                if local_end > line.len() {
+                if local_end > line.len() && !wraps.get(i).copied().unwrap_or(false) {
+                    out.truncate(trim(&out));
                     out.push('\n');
                 }
             }
@@ -661,9 +1602,72 @@ impl TerminalView {
             i += 1;
         }
 
+        out.truncate(trim(&out));
         out
     }
 
+    /// OSC 8 hyperlink id of the given 1-based cell, read from the cached style runs so mouse
+    /// moves need no FFI call.
+    fn hyperlink_id_at_cell(&self, point: ViewportPoint) -> Option<NonZeroU32> {
+        self.viewport_style_runs
+            .get(Cell::from(point).row as usize)?
+            .iter()
+            .find(|run| (run.start_col..=run.end_col).contains(&point.col))?
+            .hyperlink_id
+    }
+
+    /// The plain `http(s)://` URL under the given 1-based cell, as a hover target.
+    fn url_range_at_cell(&self, point: ViewportPoint) -> Option<HoveredLink> {
+        let row = Cell::from(point).row as usize;
+        let line = self.viewport_lines.get(row)?;
+        if line.is_empty() {
+            return None;
+        }
+        let index = byte_index_for_column_in_line(line, point.col).min(line.len() - 1);
+        url_range_at_byte_index(line, index).map(|range| HoveredLink::Rule(row, range))
+    }
+
+    /// Returns the configured link-rule match under the given 1-based cell.
+    fn link_rule_match_at_cell(&mut self, point: ViewportPoint) -> Option<(usize, RowLink)> {
+        let row_index = Cell::from(point).row as usize;
+        let line = self.viewport_lines.get(row_index)?;
+        let index = byte_index_for_column_in_line(line, point.col);
+        let link =
+            self.link_cache
+                .link_at(row_index, line, index, &self.session.config().link_rules)?;
+        Some((row_index, link))
+    }
+
+    /// Underlines the link under the pointer (and shows a pointing hand) so it is clear a
+    /// cmd/ctrl-click opens it.
+    fn update_hovered_link(
+        &mut self,
+        position: gpui::Point<Pixels>,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let in_bounds = self
+            .last_bounds
+            .is_some_and(|bounds| bounds.contains(&position));
+        let hovered = if in_bounds {
+            self.mouse_position_to_cell(position, window)
+                .and_then(|point| match self.hyperlink_id_at_cell(point) {
+                    Some(id) => Some(HoveredLink::Hyperlink(id)),
+                    None => self
+                        .link_rule_match_at_cell(point)
+                        .map(|(row, link)| HoveredLink::Rule(row, link.range))
+                        .or_else(|| self.url_range_at_cell(point)),
+                })
+        } else {
+            None
+        };
+
+        if hovered != self.hovered_link {
+            self.hovered_link = hovered;
+            cx.notify();
+        }
+    }
+
     fn url_at_viewport_index(&self, index: usize) -> Option<String> {
         if self.viewport_lines.is_empty() {
             return None;
@@ -685,6 +1689,24 @@ impl TerminalView {
         url_at_byte_index(line, local)
     }
 
+    fn word_at_viewport_index(&self, index: usize) -> Option<Range<usize>> {
+        let row = self
+            .viewport_line_offsets
+            .iter()
+            .enumerate()
+            .rfind(|(_, offset)| **offset <= index)
+            .map(|(i, _)| i)?;
+
+        let line = self.viewport_lines.get(row)?.as_str();
+        let line_start = *self.viewport_line_offsets.get(row).unwrap_or(&0);
+        let range = word_range_in_line(
+            line,
+            index.saturating_sub(line_start),
+            &self.session.config().effective_word_separators(),
+        )?;
+        Some(line_start + range.start..line_start + range.end)
+    }
+
     fn apply_dirty_viewport_rows(&mut self, dirty_rows: &[u16]) -> bool {
         if dirty_rows.is_empty() {
             return false;
@@ -700,34 +1722,44 @@ impl TerminalView {
             return true;
         }
 
-        for &row in dirty_rows {
-            let row = row as usize;
-            if row >= self.viewport_lines.len() {
-                continue;
+        // Dump each span of consecutive dirty rows with one FFI call. The scratch buffers swap
+        // places with the rows they replace, so their allocations are reused for the next span.
+        let mut lines = std::mem::take(&mut self.dirty_row_lines);
+        let mut style_runs = std::mem::take(&mut self.dirty_row_style_runs);
+        let mut rows = dirty_rows
+            .iter()
+            .map(|&row| row as usize)
+            .filter(|&row| row < expected_rows)
+            .peekable();
+        while let Some(start) = rows.next() {
+            let mut end = start + 1;
+            while rows.next_if_eq(&end).is_some() {
+                end += 1;
             }
 
-            let line = match self.session.dump_viewport_row(row as u16) {
-                Ok(s) => s,
-                Err(_) => {
-                    self.refresh_viewport();
-                    return true;
-                }
-            };
-
-            let line = line.strip_suffix('\n').unwrap_or(line.as_str());
-            self.viewport_lines[row].clear();
-            self.viewport_lines[row].push_str(line);
-            self.viewport_style_runs[row] = self
+            if self
                 .session
-                .dump_viewport_row_style_runs(row as u16)
-                .unwrap_or_default();
-            if row < self.line_layouts.len() {
-                self.line_layouts[row] = None;
+                .dump_viewport_rows_into(start as u16..end as u16, &mut lines, &mut style_runs)
+                .is_err()
+                || lines.len() != end - start
+            {
+                self.refresh_viewport();
+                return true;
+            }
+
+            for (offset, (line, runs)) in lines.iter_mut().zip(style_runs.iter_mut()).enumerate() {
+                let row = start + offset;
+                std::mem::swap(&mut self.viewport_lines[row], line);
+                std::mem::swap(&mut self.viewport_style_runs[row], runs);
+                if row < self.line_layouts.len() {
+                    self.line_layouts[row] = None;
+                }
             }
         }
+        self.dirty_row_lines = lines;
+        self.dirty_row_style_runs = style_runs;
 
-        self.viewport_line_offsets = Self::compute_viewport_line_offsets(&self.viewport_lines);
-        self.viewport_total_len = Self::compute_viewport_total_len(&self.viewport_lines);
+        self.update_viewport_line_offsets();
         self.selection = None;
         true
     }
@@ -741,6 +1773,299 @@ impl TerminalView {
         if let Some(text) = self.session.take_clipboard_write() {
             cx.write_to_clipboard(ClipboardItem::new_string(text));
         }
+
+        for event in self.session.take_events() {
+            if event == TerminalEvent::Bell && self.session.config().visual_bell {
+                self.flash_bell(cx);
+            }
+            if let Some(callback) = self.event_callback.as_ref() {
+                callback.call(&event);
+            }
+        }
+    }
+
+    fn flash_bell(&mut self, cx: &mut Context<Self>) {
+        const BELL_FLASH_DURATION: Duration = Duration::from_millis(120);
+
+        if self.bell_flash {
+            return;
+        }
+        self.bell_flash = true;
+        cx.notify();
+        cx.spawn(async move |this, cx| {
+            cx.background_executor().timer(BELL_FLASH_DURATION).await;
+            this.update(cx, |view, cx| {
+                view.bell_flash = false;
+                cx.notify();
+            })
+            .ok();
+        })
+        .detach();
+    }
+
+    fn has_blinking_text(&self) -> bool {
+        self.viewport_style_runs
+            .iter()
+            .flatten()
+            .any(|run| run.flags & CELL_STYLE_FLAG_BLINK != 0)
+    }
+
+    fn invalidate_blinking_rows(&mut self) {
+        for (row, runs) in self.viewport_style_runs.iter().enumerate() {
+            if runs
+                .iter()
+                .any(|run| run.flags & CELL_STYLE_FLAG_BLINK != 0)
+                && let Some(slot) = self.line_layouts.get_mut(row)
+            {
+                *slot = None;
+            }
+        }
+    }
+
+    /// Starts the `SGR 5` blink timer while blinking text is on screen. The timer stops itself
+    /// (leaving the text visible) once no blinking text remains; turning `text_blink` off or
+    /// setting `text_blink_interval_ms` to 0 cancels it.
+    fn ensure_text_blink_timer(&mut self, cx: &mut Context<Self>) {
+        let config = self.session.config();
+        if !config.text_blink || config.text_blink_interval_ms == 0 {
+            self.stop_text_blink();
+            return;
+        }
+        if self.text_blink_task.is_some() || !self.has_blinking_text() {
+            return;
+        }
+        self.text_blink_task = Some(cx.spawn(async move |this, cx| {
+            loop {
+                let interval = this
+                    .update(cx, |view, _| view.session.config().text_blink_interval_ms)
+                    .unwrap_or(0);
+                if interval == 0 {
+                    break;
+                }
+                cx.background_executor()
+                    .timer(Duration::from_millis(u64::from(interval)))
+                    .await;
+                let running = this
+                    .update(cx, |view, cx| {
+                        let blinking = view.session.config().text_blink && view.has_blinking_text();
+                        if blinking {
+                            view.text_blink_hidden = !view.text_blink_hidden;
+                            view.invalidate_blinking_rows();
+                        } else {
+                            view.stop_text_blink();
+                        }
+                        cx.notify();
+                        blinking
+                    })
+                    .unwrap_or(false);
+                if !running {
+                    break;
+                }
+            }
+        }));
+    }
+
+    /// Cancels the `SGR 5` blink timer and shows blinking text again.
+    fn stop_text_blink(&mut self) {
+        self.text_blink_task = None;
+        if std::mem::take(&mut self.text_blink_hidden) {
+            self.invalidate_blinking_rows();
+        }
+    }
+
+    /// Shows the cursor and restarts its blink phase, so it stays visible while typing.
+    fn reset_cursor_blink(&mut self) {
+        self.cursor_blink_hidden = false;
+        self.cursor_blink_epoch = self.cursor_blink_epoch.wrapping_add(1);
+    }
+
+    /// Starts the cursor blink timer while the view is focused and the cursor should blink.
+    fn ensure_cursor_blink_timer(&mut self, cx: &mut Context<Self>) {
+        if self.cursor_blink_timer || !self.cursor_focused || !self.session.cursor_should_blink() {
+            return;
+        }
+        self.cursor_blink_timer = true;
+        let mut epoch = self.cursor_blink_epoch;
+        cx.spawn(async move |this, cx| {
+            loop {
+                cx.background_executor().timer(CURSOR_BLINK_INTERVAL).await;
+                let running = this
+                    .update(cx, |view, cx| {
+                        let blinking = view.cursor_focused && view.session.cursor_should_blink();
+                        if !blinking {
+                            view.cursor_blink_hidden = false;
+                            view.cursor_blink_timer = false;
+                        } else if view.cursor_blink_epoch != epoch {
+                            epoch = view.cursor_blink_epoch;
+                            view.cursor_blink_hidden = false;
+                        } else {
+                            view.cursor_blink_hidden = !view.cursor_blink_hidden;
+                        }
+                        cx.notify();
+                        blinking
+                    })
+                    .unwrap_or(false);
+                if !running {
+                    break;
+                }
+            }
+        })
+        .detach();
+    }
+
+    /// Redraws while the overlay scrollbar fades out, until it is hidden.
+    fn ensure_scrollbar_timer(&mut self, cx: &mut Context<Self>) {
+        const SCROLLBAR_FRAME: Duration = Duration::from_millis(32);
+
+        if self.scrollbar_timer || !self.session.config().scrollbar {
+            return;
+        }
+        self.scrollbar_timer = true;
+        cx.spawn(async move |this, cx| {
+            loop {
+                cx.background_executor().timer(SCROLLBAR_FRAME).await;
+                let running = this
+                    .update(cx, |view, cx| {
+                        let opacity = view.scrollbar.opacity(Instant::now());
+                        if opacity < 1.0 {
+                            cx.notify();
+                        }
+                        let shown = opacity > 0.0;
+                        if !shown {
+                            view.scrollbar_timer = false;
+                        }
+                        shown
+                    })
+                    .unwrap_or(false);
+                if !running {
+                    break;
+                }
+            }
+        })
+        .detach();
+    }
+
+    /// Redraws while the resize overlay fades out, until it is hidden.
+    fn ensure_resize_overlay_timer(&mut self, cx: &mut Context<Self>) {
+        const RESIZE_OVERLAY_FRAME: Duration = Duration::from_millis(32);
+
+        if self.resize_overlay_timer {
+            return;
+        }
+        self.resize_overlay_timer = true;
+        cx.spawn(async move |this, cx| {
+            loop {
+                cx.background_executor().timer(RESIZE_OVERLAY_FRAME).await;
+                let running = this
+                    .update(cx, |view, cx| {
+                        let opacity = view.resize_overlay.opacity(Instant::now());
+                        if opacity < 1.0 {
+                            cx.notify();
+                        }
+                        let shown = opacity > 0.0;
+                        if !shown {
+                            view.resize_overlay_timer = false;
+                        }
+                        shown
+                    })
+                    .unwrap_or(false);
+                if !running {
+                    break;
+                }
+            }
+        })
+        .detach();
+    }
+
+    /// The overlay scrollbar's thumb for the current scroll position, if there is history.
+    fn scrollbar_thumb(&self) -> Option<Thumb> {
+        let height = f32::from(self.last_bounds?.size.height);
+        scrollbar::scrollbar_thumb(
+            height,
+            self.session.total_lines(),
+            u32::from(self.session.rows()),
+            self.session.scroll_offset(),
+        )
+    }
+
+    /// Starts dragging the scrollbar if `position` lands on it.
+    fn scrollbar_mouse_down(
+        &mut self,
+        position: gpui::Point<Pixels>,
+        cx: &mut Context<Self>,
+    ) -> bool {
+        let Some(bounds) = self.last_bounds else {
+            return false;
+        };
+        let local = self.mouse_position_to_local(position);
+        if !self.session.config().scrollbar
+            || !self.scrollbar.hit(
+                f32::from(local.x),
+                f32::from(bounds.size.width),
+                Instant::now(),
+            )
+        {
+            return false;
+        }
+        let Some(thumb) = self.scrollbar_thumb() else {
+            return false;
+        };
+        self.scrollbar.begin_drag(f32::from(local.y), thumb);
+        self.scrollbar_drag_to(position, cx);
+        true
+    }
+
+    /// Scrolls so the dragged thumb sits under `position`.
+    fn scrollbar_drag_to(&mut self, position: gpui::Point<Pixels>, cx: &mut Context<Self>) {
+        let local = self.mouse_position_to_local(position);
+        let (Some(bounds), Some(thumb), Some(thumb_top)) = (
+            self.last_bounds,
+            self.scrollbar_thumb(),
+            self.scrollbar.drag_thumb_top(f32::from(local.y)),
+        ) else {
+            return;
+        };
+        let row = row_for_thumb_top(
+            thumb_top,
+            f32::from(bounds.size.height),
+            thumb,
+            self.session.total_lines(),
+            u32::from(self.session.rows()),
+        );
+        if row != self.session.viewport_top_row() {
+            let _ = self.session.scroll_row_to_top(row);
+            self.sync_viewport_scroll_tracking();
+            self.schedule_viewport_refresh(cx);
+        }
+    }
+
+    /// Redraws at frame rate while the `cursor-animation` glide is in progress.
+    fn ensure_cursor_animation_timer(&mut self, cx: &mut Context<Self>) {
+        const CURSOR_ANIMATION_FRAME: Duration = Duration::from_millis(16);
+
+        if self.cursor_animation_timer || !self.cursor_animation.is_moving(Instant::now()) {
+            return;
+        }
+        self.cursor_animation_timer = true;
+        cx.spawn(async move |this, cx| {
+            loop {
+                cx.background_executor().timer(CURSOR_ANIMATION_FRAME).await;
+                let running = this
+                    .update(cx, |view, cx| {
+                        cx.notify();
+                        let moving = view.cursor_animation.is_moving(Instant::now());
+                        if !moving {
+                            view.cursor_animation_timer = false;
+                        }
+                        moving
+                    })
+                    .unwrap_or(false);
+                if !running {
+                    break;
+                }
+            }
+        })
+        .detach();
     }
 
     pub fn feed_output_bytes(&mut self, bytes: &[u8], cx: &mut Context<Self>) {
@@ -750,37 +2075,105 @@ impl TerminalView {
         cx.notify();
     }
 
+    /// Queues PTY output to be parsed off the input path: each frame and a frame-rate timer
+    /// between frames feed it to the session in time-budgeted slices, so a `cat bigfile` keeps
+    /// painting instead of stalling until the whole burst is parsed.
     pub fn queue_output_bytes(&mut self, bytes: &[u8], cx: &mut Context<Self>) {
         const MAX_PENDING_OUTPUT_BYTES: usize = 256 * 1024;
 
-        if self.pending_output.len().saturating_add(bytes.len()) <= MAX_PENDING_OUTPUT_BYTES {
-            self.pending_output.extend_from_slice(bytes);
-            cx.notify();
-            return;
-        }
+        self.flush_queued_input(cx);
 
-        if !self.pending_output.is_empty() {
-            let pending = std::mem::take(&mut self.pending_output);
-            self.feed_output_bytes_to_session(&pending);
+        // Backpressure: once the producer outruns the parser, parse part of the backlog now,
+        // within the same budget as a frame, so a flood neither grows the queue unchecked nor
+        // stalls the UI.
+        if self.pending_output.len().saturating_add(bytes.len()) > MAX_PENDING_OUTPUT_BYTES {
+            self.drain_pending_output(Some(OUTPUT_FEED_BUDGET));
             self.apply_side_effects(cx);
             self.reconcile_dirty_viewport_after_output();
         }
 
-        if bytes.len() > MAX_PENDING_OUTPUT_BYTES {
-            let mut offset = 0usize;
-            while offset < bytes.len() {
-                let end = (offset + MAX_PENDING_OUTPUT_BYTES).min(bytes.len());
-                self.feed_output_bytes_to_session(&bytes[offset..end]);
-                offset = end;
+        self.pending_output.extend_from_slice(bytes);
+        self.ensure_output_timer(cx);
+        cx.notify();
+    }
+
+    /// Feeds queued output to the session in slices until it is empty or `budget` has elapsed.
+    /// Returns `true` when nothing is left queued.
+    fn drain_pending_output(&mut self, budget: Option<Duration>) -> bool {
+        const OUTPUT_SLICE_BYTES: usize = 64 * 1024;
+
+        if self.pending_output.is_empty() {
+            return true;
+        }
+        let start = Instant::now();
+        let mut pending = std::mem::take(&mut self.pending_output);
+        let mut offset = 0usize;
+        while offset < pending.len() {
+            let end = (offset + OUTPUT_SLICE_BYTES).min(pending.len());
+            self.feed_output_bytes_to_session(&pending[offset..end]);
+            offset = end;
+            if budget.is_some_and(|budget| start.elapsed() >= budget) {
+                break;
             }
-            self.apply_side_effects(cx);
-            self.reconcile_dirty_viewport_after_output();
-            cx.notify();
+        }
+        pending.drain(..offset);
+        self.pending_output = pending;
+        self.pending_output.is_empty()
+    }
+
+    /// Parses queued output between frames at most once per frame interval, and redraws with
+    /// whatever was parsed, until the queue is empty.
+    fn ensure_output_timer(&mut self, cx: &mut Context<Self>) {
+        if self.output_timer {
             return;
         }
+        self.output_timer = true;
+        cx.spawn(async move |this, cx| {
+            loop {
+                cx.background_executor().timer(OUTPUT_FRAME).await;
+                let running = this
+                    .update(cx, |view, cx| {
+                        if view.pending_output.is_empty() {
+                            view.output_timer = false;
+                            return false;
+                        }
+                        view.drain_pending_output(Some(OUTPUT_FEED_BUDGET));
+                        view.apply_side_effects(cx);
+                        view.reconcile_dirty_viewport_after_output();
+                        cx.notify();
+                        true
+                    })
+                    .unwrap_or(false);
+                if !running {
+                    break;
+                }
+            }
+        })
+        .detach();
+    }
+
+    /// Queues `bytes` like [`queue_output_bytes`](Self::queue_output_bytes) and returns a future
+    /// that resolves, with the [`TerminalSession::generation`] painted, once they have been
+    /// applied and a frame showing them has been painted, so tests and automation can await
+    /// visual effects instead of sleeping. The future never
+    /// resolves if the view is not drawn again.
+    pub fn feed_async(
+        &mut self,
+        bytes: &[u8],
+        cx: &mut Context<Self>,
+    ) -> impl Future<Output = u64> + use<> {
+        let painted = self.render_waiters.next();
+        self.queue_output_bytes(bytes, cx);
+        if self.pending_output.is_empty() {
+            self.render_waiters.mark_applied(self.session.generation());
+        }
+        painted
+    }
 
-        self.pending_output.extend_from_slice(bytes);
-        cx.notify();
+    /// The [`TerminalSession::generation`] shown by the last painted frame (see
+    /// [`Self::feed_async`]).
+    pub fn rendered_generation(&self) -> u64 {
+        self.render_waiters.painted()
     }
 
     pub fn resize_terminal(&mut self, cols: u16, rows: u16, cx: &mut Context<Self>) {
@@ -790,11 +2183,110 @@ impl TerminalView {
         cx.notify();
     }
 
+    /// Hard-resets the terminal (see [`TerminalSession::reset`]) and redraws it, for a host's
+    /// "Reset Terminal / Clear Scrollback" menu item. The PTY is left running.
+    pub fn reset_terminal(&mut self, cx: &mut Context<Self>) {
+        let _ = self.session.reset();
+        self.sync_viewport_scroll_tracking();
+        self.apply_side_effects(cx);
+        self.schedule_viewport_refresh(cx);
+    }
+
+    /// Drops the scrollback but keeps the current screen (see
+    /// [`TerminalSession::clear_scrollback`]). Bound to cmd-k as [`ClearScrollback`].
+    pub fn clear_scrollback(&mut self, cx: &mut Context<Self>) {
+        let _ = self.session.clear_scrollback();
+        self.sync_viewport_scroll_tracking();
+        if let Some(search) = self.search.as_mut() {
+            search.matches = self.session.search_all(&search.query);
+            search.current = None;
+        }
+        self.schedule_viewport_refresh(cx);
+    }
+
+    fn on_clear_scrollback(
+        &mut self,
+        _: &ClearScrollback,
+        _window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        self.clear_scrollback(cx);
+    }
+
+    /// Soft-resets the terminal (see [`TerminalSession::reset_soft`]), keeping its contents.
+    pub fn reset_terminal_soft(&mut self, cx: &mut Context<Self>) {
+        let _ = self.session.reset_soft();
+        self.schedule_viewport_refresh(cx);
+    }
+
+    /// Draws host text into the grid (see [`TerminalSession::write_text_at`]) and redraws.
+    /// Cells outside the active area are ignored.
+    pub fn write_text_at(
+        &mut self,
+        col: u16,
+        row: u16,
+        styled_text: &[u8],
+        cx: &mut Context<Self>,
+    ) {
+        let _ = self.session.write_text_at(col, row, styled_text);
+        self.schedule_viewport_refresh(cx);
+    }
+
     fn on_paste(&mut self, _: &Paste, _window: &mut Window, cx: &mut Context<Self>) {
         let Some(text) = cx.read_from_clipboard().and_then(|item| item.text()) else {
             return;
         };
+        self.paste(&text, cx);
+    }
 
+    /// Runs pasted `text` through the paste options and [`PastePolicy`] and sends it, or holds it
+    /// for the [`PasteConfirmCallback`].
+    fn paste(&mut self, text: &str, cx: &mut Context<Self>) {
+        let config = self.session.config();
+        let text = paste::paste_text(
+            text,
+            config.paste_html_as_text,
+            config.paste_preserve_ansi,
+            config.paste_strip_control,
+        );
+        if text.is_empty() {
+            return;
+        }
+
+        if let Some(confirm) = self.paste_confirm.as_ref()
+            && let Some(warning) = self.paste_policy.check(
+                &text,
+                self.session.bracketed_paste_enabled(),
+                config.paste_protection,
+            )
+        {
+            confirm.call(&text, &warning);
+            self.pending_paste = Some(text);
+            return;
+        }
+        self.send_paste(&text, cx);
+    }
+
+    /// A paste waiting for the host's [`PasteConfirmCallback`] to be answered.
+    pub fn pending_paste(&self) -> Option<&str> {
+        self.pending_paste.as_deref()
+    }
+
+    /// Sends the paste held for confirmation. Returns `false` if none was pending.
+    pub fn confirm_paste(&mut self, cx: &mut Context<Self>) -> bool {
+        let Some(text) = self.pending_paste.take() else {
+            return false;
+        };
+        self.send_paste(&text, cx);
+        true
+    }
+
+    /// Drops the paste held for confirmation.
+    pub fn cancel_paste(&mut self) {
+        self.pending_paste = None;
+    }
+
+    fn send_paste(&mut self, text: &str, cx: &mut Context<Self>) {
         if self.session.bracketed_paste_enabled() {
             self.send_input_parts(&[b"\x1b[200~", text.as_bytes(), b"\x1b[201~"], cx);
         } else {
@@ -803,13 +2295,16 @@ impl TerminalView {
     }
 
     fn on_copy(&mut self, _: &Copy, _window: &mut Window, cx: &mut Context<Self>) {
+        // Copy logical lines, so a long command pastes back without the wrap points' newlines.
+        let wraps = self.session.viewport_row_wraps().unwrap_or_default();
+        let trim = self.session.config().clipboard_trim_trailing_spaces;
         let selection = self
             .selection
             .map(|s| s.range())
             .filter(|range| !range.is_empty())
-            .map(|range| self.viewport_slice(range))
+            .map(|range| self.viewport_copy_text(range, &wraps, trim))
             .filter(|s| !s.is_empty())
-            .unwrap_or_else(|| self.viewport_slice(0..self.viewport_total_len));
+            .unwrap_or_else(|| self.viewport_copy_text(0..self.viewport_total_len, &wraps, trim));
 
         let item = ClipboardItem::new_string(selection.to_string());
         cx.write_to_clipboard(item.clone());
@@ -831,6 +2326,360 @@ impl TerminalView {
         }
     }
 
+    fn on_search(&mut self, _: &Search, _window: &mut Window, cx: &mut Context<Self>) {
+        if self.search.is_none() {
+            self.search = Some(SearchBar::default());
+        }
+        cx.notify();
+    }
+
+    // Searches start at the bottom of the scrollback and walk upwards, so "next" moves
+    // towards older output.
+    fn on_search_next(&mut self, _: &SearchNext, _window: &mut Window, cx: &mut Context<Self>) {
+        self.step_search(SearchDirection::Backward, cx);
+    }
+
+    fn on_search_previous(
+        &mut self,
+        _: &SearchPrevious,
+        _window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        self.step_search(SearchDirection::Forward, cx);
+    }
+
+    fn on_jump_to_previous_prompt(
+        &mut self,
+        _: &JumpToPreviousPrompt,
+        _window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let top = self.session.viewport_top_row();
+        let Some(row) = self.session.previous_prompt_mark(top).map(|m| m.prompt_row) else {
+            return;
+        };
+        let _ = self.session.scroll_row_to_top(row);
+        self.sync_viewport_scroll_tracking();
+        self.schedule_viewport_refresh(cx);
+    }
+
+    fn on_jump_to_next_prompt(
+        &mut self,
+        _: &JumpToNextPrompt,
+        _window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let top = self.session.viewport_top_row();
+        let _ = match self.session.next_prompt_mark(top).map(|m| m.prompt_row) {
+            Some(row) => self.session.scroll_row_to_top(row),
+            None => self.session.scroll_viewport_bottom(),
+        };
+        self.sync_viewport_scroll_tracking();
+        self.schedule_viewport_refresh(cx);
+    }
+
+    /// Re-runs the find bar query and moves to the next match in `direction`.
+    fn step_search(&mut self, direction: SearchDirection, cx: &mut Context<Self>) {
+        let Some(search) = self.search.as_mut() else {
+            return;
+        };
+
+        if search.query.is_empty() {
+            search.matches.clear();
+            search.current = None;
+            cx.notify();
+            return;
+        }
+
+        search.matches = self.session.search_all(&search.query);
+        search.current =
+            self.session
+                .step_search_matches(&search.query, direction, &search.matches);
+        if let Some(current) = search.current {
+            let _ = self.session.scroll_to_row(current.row);
+            self.sync_viewport_scroll_tracking();
+            self.schedule_viewport_refresh(cx);
+        } else {
+            cx.notify();
+        }
+    }
+
+    /// Returns the find bar's query while it is open.
+    pub fn search_query(&self) -> Option<&str> {
+        self.search.as_ref().map(|search| search.query.as_str())
+    }
+
+    /// Opens the find bar on `current`, a match of `query`, scrolls it into view and focuses the
+    /// view. Containers use this to jump to a result from [`search_all_panes`].
+    pub fn reveal_search_match(
+        &mut self,
+        query: &str,
+        current: SearchMatch,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        self.session.set_current_search_match(query, current);
+        self.search = Some(SearchBar {
+            query: query.to_string(),
+            matches: self.session.search_all(query),
+            current: Some(current),
+        });
+        let _ = self.session.scroll_to_row(current.row);
+        self.sync_viewport_scroll_tracking();
+        self.schedule_viewport_refresh(cx);
+        self.focus_handle.focus(window, cx);
+    }
+
+    fn close_search(&mut self, cx: &mut Context<Self>) {
+        self.search = None;
+        self.session.clear_search();
+        cx.notify();
+    }
+
+    /// Handles editing keys while the find bar is open. Text arrives through `commit_text`.
+    fn on_search_key_down(&mut self, keystroke: &gpui::Keystroke, cx: &mut Context<Self>) {
+        match keystroke.key.as_str() {
+            "escape" => self.close_search(cx),
+            "enter" | "return" | "kp_enter" | "numpad_enter" => {
+                let direction = if keystroke.modifiers.shift {
+                    SearchDirection::Forward
+                } else {
+                    SearchDirection::Backward
+                };
+                self.step_search(direction, cx);
+            }
+            "backspace" => {
+                if let Some(search) = self.search.as_mut() {
+                    search.query.pop();
+                }
+                self.session.clear_search();
+                self.step_search(SearchDirection::Backward, cx);
+            }
+            _ => {}
+        }
+    }
+
+    /// Returns true while keyboard and mouse input is blocked from reaching the terminal.
+    pub fn is_input_locked(&self) -> bool {
+        self.input_locked
+    }
+
+    /// Blocks or unblocks keystrokes, typed text, pastes, and mouse reports from reaching the
+    /// terminal. While locked, the mouse selects and scrolls locally.
+    pub fn set_input_locked(&mut self, locked: bool, cx: &mut Context<Self>) {
+        self.input_locked = locked;
+        cx.notify();
+    }
+
+    /// Sets the translucent badge drawn in the top-right corner (e.g. a hostname or environment
+    /// name); `None` removes it. See [`TerminalSession::set_badge_format`] for the format.
+    pub fn set_badge(&mut self, format: Option<String>, cx: &mut Context<Self>) {
+        self.session.set_badge_format(format);
+        cx.notify();
+    }
+
+    /// Reports whether the foreground process runs as root and redraws the view's
+    /// [`RootIndicator`](crate::RootIndicator). See [`TerminalSession::set_foreground_root`].
+    pub fn set_foreground_root(&mut self, root: bool, cx: &mut Context<Self>) {
+        if self.session.is_foreground_root() != root {
+            self.session.set_foreground_root(root);
+            cx.notify();
+        }
+    }
+
+    /// Shows `overlay` above the terminal, or removes it with `None`.
+    pub fn set_overlay(&mut self, overlay: Option<TerminalOverlay>, cx: &mut Context<Self>) {
+        self.overlay = overlay;
+        cx.notify();
+    }
+
+    pub fn overlay(&self) -> Option<&TerminalOverlay> {
+        self.overlay.as_ref()
+    }
+
+    /// Set what starts a new process when the user restarts one that exited (see
+    /// [`Self::process_exited`]). The handler runs after the current update with the view to
+    /// wire the new PTY to through [`Self::attach_process`].
+    pub fn set_restart_handler(
+        &mut self,
+        handler: impl Fn(WeakEntity<TerminalView>, &mut App) + 'static,
+    ) {
+        self.restart_handler = Some(Rc::new(handler));
+    }
+
+    /// Call when the PTY child exits. Raises [`TerminalEvent::ProcessExited`], then follows the
+    /// config's [`ProcessExitPolicy`]: by default the terminal is dimmed under a
+    /// [`TerminalOverlay::ProcessExited`] until Enter restarts the process or closes the view.
+    pub fn process_exited(&mut self, exit_code: Option<i32>, cx: &mut Context<Self>) {
+        self.drain_pending_output(None);
+        self.session.process_exited(exit_code);
+        self.apply_side_effects(cx);
+        self.schedule_viewport_refresh(cx);
+        match self.session.config().process_exit {
+            ProcessExitPolicy::Close => self.request_close(cx),
+            ProcessExitPolicy::Restart if self.restart_handler.is_some() => {
+                self.restart_process(cx)
+            }
+            ProcessExitPolicy::Wait | ProcessExitPolicy::Restart => {
+                self.set_overlay(Some(TerminalOverlay::ProcessExited { exit_code }), cx)
+            }
+        }
+    }
+
+    /// Wires a newly started process to the view after a restart: replaces the PTY input and
+    /// resize callback, tells the callback the current grid size, and removes the exit overlay.
+    pub fn attach_process(
+        &mut self,
+        input: TerminalInput,
+        resize_callback: TerminalResizeCallback,
+        cx: &mut Context<Self>,
+    ) {
+        resize_callback.call(self.session.cols(), self.session.rows());
+        self.input = Some(input);
+        self.resize_callback = Some(resize_callback);
+        self.queued_input.clear();
+        self.close_requested = false;
+        if matches!(self.overlay, Some(TerminalOverlay::ProcessExited { .. })) {
+            self.overlay = None;
+        }
+        cx.notify();
+    }
+
+    /// Whether the view asks its host to close it: its process exited and the user pressed
+    /// Enter without a restart handler set, or the [`ProcessExitPolicy`] is `Close`.
+    /// [`TerminalPanel`](crate::TerminalPanel) closes such panes.
+    pub fn is_close_requested(&self) -> bool {
+        self.close_requested
+    }
+
+    fn request_close(&mut self, cx: &mut Context<Self>) {
+        self.close_requested = true;
+        cx.notify();
+    }
+
+    /// Clears what the exited program left behind (alternate screen, modes) and asks the
+    /// restart handler for a new process.
+    fn restart_process(&mut self, cx: &mut Context<Self>) {
+        let Some(handler) = self.restart_handler.clone() else {
+            self.request_close(cx);
+            return;
+        };
+        self.overlay = None;
+        self.input = None;
+        let _ = self.session.feed(b"\x1b[?1049l\x1b[!p\r\n");
+        self.schedule_viewport_refresh(cx);
+        let view = cx.weak_entity();
+        cx.defer(move |cx| handler(view, cx));
+    }
+
+    /// Enter on a [`TerminalOverlay::ProcessExited`] restarts or closes; other keys are ignored
+    /// while it is shown. Returns whether the key was handled here.
+    fn on_process_exited_key_down(&mut self, key: &str, cx: &mut Context<Self>) -> bool {
+        if !matches!(self.overlay, Some(TerminalOverlay::ProcessExited { .. })) {
+            return false;
+        }
+        if key == "enter" {
+            self.restart_process(cx);
+        }
+        true
+    }
+
+    fn on_lock_input(&mut self, _: &LockInput, _window: &mut Window, cx: &mut Context<Self>) {
+        self.set_input_locked(!self.input_locked, cx);
+    }
+
+    /// Whether the notes panel (see [`TerminalSession::notes`]) is shown. While it is, typed
+    /// text edits the notes instead of reaching the terminal.
+    pub fn is_notes_open(&self) -> bool {
+        self.notes_open
+    }
+
+    /// Shows or hides the notes panel. Bound to cmd-shift-n as [`ToggleNotes`].
+    pub fn set_notes_open(&mut self, open: bool, cx: &mut Context<Self>) {
+        self.notes_open = open;
+        cx.notify();
+    }
+
+    fn on_zoom_in(&mut self, _: &ZoomIn, window: &mut Window, cx: &mut Context<Self>) {
+        self.zoom_in(window, cx);
+    }
+
+    fn on_zoom_out(&mut self, _: &ZoomOut, window: &mut Window, cx: &mut Context<Self>) {
+        self.zoom_out(window, cx);
+    }
+
+    fn on_zoom_reset(&mut self, _: &ZoomReset, window: &mut Window, cx: &mut Context<Self>) {
+        self.zoom_reset(window, cx);
+    }
+
+    fn on_toggle_notes(&mut self, _: &ToggleNotes, _window: &mut Window, cx: &mut Context<Self>) {
+        self.set_notes_open(!self.notes_open, cx);
+    }
+
+    /// Appends the selected text to the session's notes on a line of its own.
+    pub fn add_selection_to_notes(&mut self, cx: &mut Context<Self>) {
+        let Some(range) = self
+            .selection
+            .map(|s| s.range())
+            .filter(|range| !range.is_empty())
+        else {
+            return;
+        };
+        let text = self.viewport_slice(range);
+        notes::append_note(self.session.notes_mut(), &text);
+        cx.notify();
+    }
+
+    fn on_add_selection_to_notes(
+        &mut self,
+        _: &AddSelectionToNotes,
+        _window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        self.add_selection_to_notes(cx);
+    }
+
+    fn on_show_clipboard_history(
+        &mut self,
+        _: &ShowClipboardHistory,
+        _window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        self.clipboard_picker = match self.clipboard_picker {
+            Some(_) => None,
+            None => Some(ClipboardPicker::default()),
+        };
+        cx.notify();
+    }
+
+    /// Handles navigation keys while the clipboard history picker is open.
+    fn on_clipboard_picker_key_down(
+        &mut self,
+        keystroke: &gpui::Keystroke,
+        cx: &mut Context<Self>,
+    ) {
+        let Some(picker) = self.clipboard_picker.as_mut() else {
+            return;
+        };
+        let history = self.session.clipboard_history();
+        match keystroke.key.as_str() {
+            "escape" => self.clipboard_picker = None,
+            "up" => picker.move_selection(-1, history.len()),
+            "down" => picker.move_selection(1, history.len()),
+            "enter" | "return" | "kp_enter" | "numpad_enter" => {
+                if let Some(entry) = picker.selected_entry(history) {
+                    let item = ClipboardItem::new_string(entry.text.clone());
+                    cx.write_to_clipboard(item.clone());
+                    #[cfg(any(target_os = "linux", target_os = "freebsd"))]
+                    cx.write_to_primary(item);
+                }
+                self.clipboard_picker = None;
+            }
+            _ => return,
+        }
+        cx.notify();
+    }
+
     fn on_select_all(&mut self, _: &SelectAll, window: &mut Window, cx: &mut Context<Self>) {
         self.selection = Some(ByteSelection {
             anchor: 0,
@@ -840,68 +2689,185 @@ impl TerminalView {
         cx.notify();
     }
 
-    fn on_mouse_down(
+    /// Selects the word at the active end of the selection, or at the cursor when nothing is
+    /// selected.
+    fn on_select_word(&mut self, _: &SelectWord, _window: &mut Window, cx: &mut Context<Self>) {
+        let index = match self.selection {
+            Some(selection) => Some(selection.active),
+            None => self.session.cursor_position().and_then(|cursor| {
+                let row = Cell::from(cursor).row as usize;
+                let line = self.viewport_lines.get(row)?;
+                let offset = *self.viewport_line_offsets.get(row).unwrap_or(&0);
+                Some(offset + byte_index_for_column_in_line(line, cursor.col))
+            }),
+        };
+        if let Some(range) = index.and_then(|index| self.word_at_viewport_index(index)) {
+            self.selection = Some(ByteSelection {
+                anchor: range.start,
+                active: range.end,
+            });
+            cx.notify();
+        }
+    }
+
+    /// Selects the output of the most recent finished command, scrolling it into view. Output
+    /// taller than the viewport is selected from its first row down to the viewport bottom.
+    fn on_select_last_command_output(
         &mut self,
-        event: &MouseDownEvent,
-        window: &mut Window,
+        _: &SelectLastCommandOutput,
+        _window: &mut Window,
         cx: &mut Context<Self>,
     ) {
-        self.focus_handle.focus(window, cx);
+        let Some(rows) = self
+            .session
+            .last_finished_prompt_mark()
+            .and_then(|mark| mark.output_rows())
+            .filter(|rows| !rows.is_empty())
+        else {
+            return;
+        };
+
+        let viewport_rows = u32::from(self.session.rows());
+        let top = self.session.viewport_top_row();
+        if rows.start < top || rows.end > top + viewport_rows {
+            let _ = self.session.scroll_row_to_top(rows.start);
+            self.sync_viewport_scroll_tracking();
+            self.refresh_viewport();
+        }
 
-        if event.first_mouse {
+        let top = self.session.viewport_top_row();
+        let first = rows.start.saturating_sub(top) as usize;
+        let last = (rows.end.saturating_sub(top).min(viewport_rows) as usize)
+            .min(self.viewport_lines.len());
+        if first >= last {
             return;
         }
+        let end_offset = *self.viewport_line_offsets.get(last - 1).unwrap_or(&0);
+        self.selection = Some(ByteSelection {
+            anchor: *self.viewport_line_offsets.get(first).unwrap_or(&0),
+            active: end_offset + self.viewport_lines[last - 1].len(),
+        });
+        cx.notify();
+    }
 
-        if event.button == MouseButton::Left && event.modifiers.platform {
-            if let Some((col, row)) = self.mouse_position_to_cell(event.position, window) {
-                if let Some(link) = self.session.hyperlink_at(col, row) {
-                    let item = ClipboardItem::new_string(link);
-                    cx.write_to_clipboard(item.clone());
-                    #[cfg(any(target_os = "linux", target_os = "freebsd"))]
-                    cx.write_to_primary(item);
+    /// OSC 8 hyperlink, link-rule match, or plain URL under `position`.
+    fn link_at_position(
+        &mut self,
+        position: gpui::Point<Pixels>,
+        window: &mut Window,
+    ) -> Option<ClickedLink> {
+        if let Some(point) = self.mouse_position_to_cell(position, window) {
+            if self.hyperlink_id_at_cell(point).is_some()
+                && let Some(link) = self.session.hyperlink_at(point)
+            {
+                return Some(ClickedLink::Url(link));
+            }
+
+            if let Some((row, link)) = self.link_rule_match_at_cell(point) {
+                let text = self.viewport_lines[row][link.range].to_string();
+                return Some(ClickedLink::Rule(SmartLinkMatch {
+                    pattern_id: link.pattern_id,
+                    text,
+                    url: link.url,
+                }));
+            }
+
+            if let Some(line) = self.viewport_lines.get(Cell::from(point).row as usize)
+                && let Some(url) = url_at_column_in_line(line, point.col)
+            {
+                return Some(ClickedLink::Url(url));
+            }
+        }
+
+        let index = self.mouse_position_to_viewport_index(position, window)?;
+        self.url_at_viewport_index(index).map(ClickedLink::Url)
+    }
+
+    /// Opens `link` with the [`SmartLinkHandler`] or [`UrlOpener`], or copies it when shift is
+    /// held.
+    fn open_link(&self, link: ClickedLink, modifiers: Modifiers, cx: &mut Context<Self>) {
+        let url = match link {
+            ClickedLink::Url(url) => url,
+            ClickedLink::Rule(link) => {
+                if modifiers.shift {
+                    copy_url(link.url.as_deref().unwrap_or(&link.text), cx);
                     return;
                 }
-
-                if let Some(line) = self.viewport_lines.get(row.saturating_sub(1) as usize)
-                    && let Some(url) = url_at_column_in_line(line, col)
+                if self
+                    .smart_link_handler
+                    .as_ref()
+                    .is_some_and(|handler| handler.call(&link))
                 {
-                    let item = ClipboardItem::new_string(url);
-                    cx.write_to_clipboard(item.clone());
-                    #[cfg(any(target_os = "linux", target_os = "freebsd"))]
-                    cx.write_to_primary(item);
                     return;
                 }
+                match link.url {
+                    Some(url) => url,
+                    None => return,
+                }
             }
+        };
+        if modifiers.shift {
+            copy_url(&url, cx);
+        } else {
+            self.url_opener.open(&url, cx);
+        }
+    }
 
-            if let Some(index) = self.mouse_position_to_viewport_index(event.position, window)
-                && let Some(url) = self.url_at_viewport_index(index)
-            {
-                let item = ClipboardItem::new_string(url);
-                cx.write_to_clipboard(item.clone());
-                #[cfg(any(target_os = "linux", target_os = "freebsd"))]
-                cx.write_to_primary(item);
-                return;
-            }
+    fn on_mouse_down(
+        &mut self,
+        event: &MouseDownEvent,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let policy = self.session.config().focus_click_policy;
+        if !event.first_mouse || policy != FocusClickPolicy::Ignore {
+            self.focus_handle.focus(window, cx);
+        }
+
+        if event.first_mouse && policy != FocusClickPolicy::Forward {
+            self.swallowing_focus_click = true;
+            return;
+        }
+
+        if event.button == MouseButton::Left && self.scrollbar_mouse_down(event.position, cx) {
+            return;
         }
 
-        if event.modifiers.shift
-            || self.input.is_none()
-            || !self.session.mouse_reporting_enabled()
-            || !self.session.mouse_sgr_enabled()
+        if event.button == MouseButton::Left
+            && event.modifiers.platform
+            && let Some(link) = self.link_at_position(event.position, window)
         {
+            self.open_link(link, event.modifiers, cx);
+            return;
+        }
+
+        if event.modifiers.shift || !self.reports_mouse() {
+            #[cfg(any(target_os = "linux", target_os = "freebsd"))]
+            if event.button == MouseButton::Middle {
+                if let Some(text) = cx.read_from_primary().and_then(|item| item.text()) {
+                    self.paste(&text, cx);
+                }
+                return;
+            }
+
             if event.button == MouseButton::Left
                 && let Some(index) = self.mouse_position_to_viewport_index(event.position, window)
             {
+                let range = match event.click_count {
+                    2 => self.word_at_viewport_index(index),
+                    _ => None,
+                }
+                .unwrap_or(index..index);
                 self.selection = Some(ByteSelection {
-                    anchor: index,
-                    active: index,
+                    anchor: range.start,
+                    active: range.end,
                 });
                 cx.notify();
             }
             return;
         }
 
-        let Some((col, row)) = self.mouse_position_to_cell(event.position, window) else {
+        let Some(point) = self.mouse_report_point(event.position, window) else {
             return;
         };
 
@@ -920,17 +2886,24 @@ impl TerminalView {
                 event.modifiers.alt,
                 event.modifiers.control,
             );
-            let seq = sgr_mouse_sequence(button_value, col, row, true);
-            input.send(seq.as_bytes());
+            if let Some(seq) =
+                mouse_sequence(self.session.mouse_encoding(), button_value, point, true)
+            {
+                input.send(&seq);
+            }
         }
     }
 
     fn on_mouse_up(&mut self, event: &MouseUpEvent, window: &mut Window, cx: &mut Context<Self>) {
-        if event.modifiers.shift
-            || self.input.is_none()
-            || !self.session.mouse_reporting_enabled()
-            || !self.session.mouse_sgr_enabled()
-        {
+        if std::mem::take(&mut self.swallowing_focus_click) {
+            return;
+        }
+        if self.scrollbar.end_drag(Instant::now()) {
+            self.ensure_scrollbar_timer(cx);
+            return;
+        }
+
+        if event.modifiers.shift || !self.reports_mouse() {
             if let Some(selection) = self.selection {
                 if selection.range().is_empty() {
                     self.selection = None;
@@ -940,7 +2913,7 @@ impl TerminalView {
             return;
         }
 
-        let Some((col, row)) = self.mouse_position_to_cell(event.position, window) else {
+        let Some(point) = self.mouse_report_point(event.position, window) else {
             return;
         };
 
@@ -959,8 +2932,11 @@ impl TerminalView {
                 event.modifiers.alt,
                 event.modifiers.control,
             );
-            let seq = sgr_mouse_sequence(button_value, col, row, false);
-            input.send(seq.as_bytes());
+            if let Some(seq) =
+                mouse_sequence(self.session.mouse_encoding(), button_value, point, false)
+            {
+                input.send(&seq);
+            }
         }
     }
 
@@ -970,11 +2946,22 @@ impl TerminalView {
         window: &mut Window,
         cx: &mut Context<Self>,
     ) {
-        if !event.modifiers.shift
-            && self.input.is_some()
-            && self.session.mouse_reporting_enabled()
-            && self.session.mouse_sgr_enabled()
-        {
+        if self.scrollbar.is_dragging() {
+            self.scrollbar_drag_to(event.position, cx);
+            return;
+        }
+
+        self.update_hovered_link(event.position, window, cx);
+
+        if self.swallowing_focus_click {
+            if event.pressed_button.is_some() {
+                return;
+            }
+            // The release happened outside the view.
+            self.swallowing_focus_click = false;
+        }
+
+        if !event.modifiers.shift && self.reports_mouse() {
             let send_motion = if self.session.mouse_any_event_enabled() {
                 true
             } else if self.session.mouse_button_event_enabled() {
@@ -984,7 +2971,7 @@ impl TerminalView {
             };
 
             if send_motion {
-                let Some((col, row)) = self.mouse_position_to_cell(event.position, window) else {
+                let Some(point) = self.mouse_report_point(event.position, window) else {
                     return;
                 };
 
@@ -1003,9 +2990,11 @@ impl TerminalView {
                     event.modifiers.alt,
                     event.modifiers.control,
                 );
-                if let Some(input) = self.input.as_ref() {
-                    let seq = sgr_mouse_sequence(button_value, col, row, true);
-                    input.send(seq.as_bytes());
+                if let Some(input) = self.input.as_ref()
+                    && let Some(seq) =
+                        mouse_sequence(self.session.mouse_encoding(), button_value, point, true)
+                {
+                    input.send(&seq);
                 }
                 return;
             }
@@ -1038,13 +3027,45 @@ impl TerminalView {
         }
         let keystroke = raw_keystroke.with_simulated_ime();
 
-        if keystroke.modifiers.platform || keystroke.modifiers.function {
+        if keystroke.modifiers.platform {
+            return;
+        }
+        if keystroke.modifiers.function
+            && self.session.config().function_key_policy == FunctionKeyPolicy::Ignore
+        {
+            return;
+        }
+
+        if self.on_process_exited_key_down(&keystroke.key, cx) {
+            return;
+        }
+
+        if self.clipboard_picker.is_some() {
+            self.on_clipboard_picker_key_down(&keystroke, cx);
+            return;
+        }
+
+        if self.notes_open {
+            if keystroke.key == "escape" {
+                self.set_notes_open(false, cx);
+            } else if notes::apply_notes_key(self.session.notes_mut(), &keystroke.key) {
+                cx.notify();
+            }
+            return;
+        }
+
+        if self.search.is_some() {
+            self.on_search_key_down(&keystroke, cx);
+            return;
+        }
+
+        if self.input_locked {
             return;
         }
 
         let scroll_step = (self.session.rows() as i32 / 2).max(1);
 
-        if let Some(input) = self.input.as_ref() {
+        if self.input.is_some() {
             if keystroke.modifiers.shift {
                 match keystroke.key.as_str() {
                     "home" => {
@@ -1079,18 +3100,24 @@ impl TerminalView {
                 }
             }
 
+            if let Some(seq) =
+                modify_other_keys_sequence(self.session.modify_other_keys(), &keystroke)
+            {
+                self.send_input_parts(&[seq.as_bytes()], cx);
+                return;
+            }
+
             if keystroke.modifiers.control
                 && let Some(b) = ctrl_byte_for_keystroke(&keystroke)
             {
-                input.send(&[b]);
+                self.send_input_parts(&[&[b]], cx);
                 return;
             }
 
             if keystroke.modifiers.alt
                 && let Some(text) = keystroke.key_char.as_deref()
             {
-                input.send(&[0x1b]);
-                input.send(text.as_bytes());
+                self.send_input_parts(&[b"\x1b", text.as_bytes()], cx);
                 return;
             }
 
@@ -1100,8 +3127,10 @@ impl TerminalView {
                 alt: keystroke.modifiers.alt,
                 super_key: false,
             };
-            if let Some(encoded) = encode_key_named(&keystroke.key, modifiers) {
-                input.send(&encoded);
+            if let Some(encoded) =
+                encode_key_named_with_modes(&keystroke.key, modifiers, self.session.key_modes())
+            {
+                self.send_input_parts(&[&encoded], cx);
                 return;
             }
             return;
@@ -1145,7 +3174,9 @@ impl TerminalView {
             alt: keystroke.modifiers.alt,
             super_key: false,
         };
-        if let Some(encoded) = encode_key_named(&keystroke.key, modifiers) {
+        if let Some(encoded) =
+            encode_key_named_with_modes(&keystroke.key, modifiers, self.session.key_modes())
+        {
             let _ = self.session.feed(&encoded);
             self.apply_side_effects(cx);
             self.schedule_viewport_refresh(cx);
@@ -1153,8 +3184,8 @@ impl TerminalView {
         }
 
         if keystroke.key == "backspace" {
-            if let Some(input) = self.input.as_ref() {
-                input.send(&[0x7f]);
+            if self.input.is_some() {
+                self.send_input_parts(&[&[0x7f]], cx);
                 return;
             }
             let _ = self.session.feed(&[0x08]);
@@ -1181,13 +3212,9 @@ impl TerminalView {
 
         if let Some(input) = self.input.as_ref()
             && !event.modifiers.shift
-            && self.session.mouse_reporting_enabled()
-            && self.session.mouse_sgr_enabled()
+            && self.reports_mouse()
+            && let Some(point) = self.mouse_report_point(event.position, window)
         {
-            let Some((col, row)) = self.mouse_position_to_cell(event.position, window) else {
-                return;
-            };
-
             let button = if delta_lines < 0 { 64 } else { 65 };
             let button_value = sgr_mouse_button_value(
                 button,
@@ -1197,9 +3224,35 @@ impl TerminalView {
                 event.modifiers.control,
             );
             let steps = delta_lines.unsigned_abs().min(10);
-            for _ in 0..steps {
-                let seq = sgr_mouse_sequence(button_value, col, row, true);
-                input.send(seq.as_bytes());
+            if let Some(seq) =
+                mouse_sequence(self.session.mouse_encoding(), button_value, point, true)
+            {
+                for _ in 0..steps {
+                    input.send(&seq);
+                }
+            }
+            return;
+        }
+
+        // Alternate screen apps have no scrollback; let them scroll via arrow keys instead.
+        let lines_per_tick = u32::from(self.session.config().alternate_scroll_lines);
+        if self.session.is_alt_screen()
+            && !self.session.mouse_reporting_enabled()
+            && !self.input_locked
+            && lines_per_tick > 0
+        {
+            if let Some(input) = self.input.as_ref()
+                && let Some(encoded) = encode_key_named_with_modes(
+                    if delta_lines < 0 { "up" } else { "down" },
+                    KeyModifiers::default(),
+                    self.session.key_modes(),
+                )
+            {
+                let presses = (delta_lines.unsigned_abs() * lines_per_tick)
+                    .min(u32::from(self.session.rows()).max(1));
+                for _ in 0..presses {
+                    input.send(&encoded);
+                }
             }
             return;
         }
@@ -1220,7 +3273,7 @@ impl TerminalView {
             return None;
         }
 
-        let (_, cell_height) = cell_metrics(window, &self.font, self.font_size)?;
+        let (_, cell_height) = cell_metrics(window, &self.font, self.effective_font_size())?;
         let y = f32::from(position.y);
         let mut row_index = (y / cell_height).floor() as i32;
         if row_index < 0 {
@@ -1231,7 +3284,7 @@ impl TerminalView {
         }
         let row_index = row_index as usize;
 
-        if let Some(Some(line)) = self.line_layouts.get(row_index) {
+        if let Some(Some(ShapedRow { line, .. })) = self.line_layouts.get(row_index) {
             let byte_index = line
                 .closest_index_for_x(px(f32::from(position.x)))
                 .min(line.text.len());
@@ -1239,10 +3292,10 @@ impl TerminalView {
             return Some(offset.saturating_add(byte_index));
         }
 
-        let (col, row) = self.mouse_position_to_cell(position, window)?;
-        let row_index = row.saturating_sub(1) as usize;
+        let point = self.mouse_position_to_cell(position, window)?;
+        let row_index = Cell::from(point).row as usize;
         let line = self.viewport_lines.get(row_index)?.as_str();
-        let byte_index = byte_index_for_column_in_line(line, col).min(line.len());
+        let byte_index = byte_index_for_column_in_line(line, point.col).min(line.len());
         let offset = *self.viewport_line_offsets.get(row_index).unwrap_or(&0);
         Some(offset.saturating_add(byte_index))
     }
@@ -1251,12 +3304,13 @@ impl TerminalView {
         &self,
         position: gpui::Point<gpui::Pixels>,
         window: &mut Window,
-    ) -> Option<(u16, u16)> {
+    ) -> Option<ViewportPoint> {
         let cols = self.session.cols();
         let rows = self.session.rows();
 
         let position = self.mouse_position_to_local(position);
-        let (cell_width, cell_height) = cell_metrics(window, &self.font, self.font_size)?;
+        let (cell_width, cell_height) =
+            cell_metrics(window, &self.font, self.effective_font_size())?;
         let x = f32::from(position.x);
         let y = f32::from(position.y);
 
@@ -1276,7 +3330,57 @@ impl TerminalView {
             row = rows as i32;
         }
 
-        Some((col as u16, row as u16))
+        Some(ViewportPoint {
+            col: col as u16,
+            row: row as u16,
+        })
+    }
+
+    /// Sends `CSI I` / `CSI O` when the application asked for focus events (mode 1004).
+    fn report_focus_change(&self, focused: bool) {
+        if !self.session.focus_reporting_enabled() {
+            return;
+        }
+        if let Some(input) = self.input.as_ref() {
+            input.send(if focused { b"\x1b[I" } else { b"\x1b[O" });
+        }
+    }
+
+    /// Whether mouse events go to the application: it enabled mouse reporting, and the view has
+    /// an input sender that is not locked.
+    fn reports_mouse(&self) -> bool {
+        self.input.is_some() && !self.input_locked && self.session.mouse_reporting_enabled()
+    }
+
+    /// Cell to report to the application for a mouse event, in active-screen coordinates, or
+    /// the 1-based pixel within the grid when SGR-Pixels (mode 1016) is on.
+    ///
+    /// Returns `None` while the viewport is scrolled back and the pointer is over history rows,
+    /// which the application cannot address.
+    fn mouse_report_point(
+        &self,
+        position: gpui::Point<gpui::Pixels>,
+        window: &mut Window,
+    ) -> Option<ViewportPoint> {
+        let cell = self.mouse_position_to_cell(position, window)?;
+        let active = self.session.viewport_point_to_active(cell)?;
+        if self.session.mouse_encoding() != MouseEncoding::SgrPixels {
+            return Some(active);
+        }
+
+        let (cell_width, cell_height) =
+            cell_metrics(window, &self.font, self.effective_font_size())?;
+        let width = cell_width * f32::from(self.session.cols());
+        let height = cell_height * f32::from(self.session.rows());
+        let local = self.mouse_position_to_local(position);
+        // Shift by the rows between the viewport and the active screen while scrolled back.
+        let row_shift = (f32::from(active.row) - f32::from(cell.row)) * cell_height;
+        let x = f32::from(local.x).clamp(0.0, (width - 1.0).max(0.0));
+        let y = (f32::from(local.y) + row_shift).clamp(0.0, (height - 1.0).max(0.0));
+        Some(ViewportPoint {
+            col: (x.floor() as u16).saturating_add(1),
+            row: (y.floor() as u16).saturating_add(1),
+        })
     }
 
     fn mouse_position_to_local(
@@ -1361,11 +3465,12 @@ impl EntityInputHandler for TerminalView {
         window: &mut Window,
         _cx: &mut Context<Self>,
     ) -> Option<Bounds<Pixels>> {
-        let (col, row) = self.session.cursor_position()?;
-        let (cell_width, cell_height) = cell_metrics(window, &self.font, self.font_size)?;
+        let cursor = Cell::from(self.session.cursor_position()?);
+        let (cell_width, cell_height) =
+            cell_metrics(window, &self.font, self.effective_font_size())?;
 
-        let base_x = element_bounds.left() + px(cell_width * (col.saturating_sub(1)) as f32);
-        let base_y = element_bounds.top() + px(cell_height * (row.saturating_sub(1)) as f32);
+        let base_x = element_bounds.left() + px(cell_width * cursor.col as f32);
+        let base_y = element_bounds.top() + px(cell_height * cursor.row as f32);
 
         let offset_cells = self
             .marked_text
@@ -1395,9 +3500,18 @@ struct TerminalPrepaintState {
     background_quads: Vec<PaintQuad>,
     selection_quads: Vec<PaintQuad>,
     box_drawing_quads: Vec<PaintQuad>,
+    box_drawing_paths: Vec<(Path<Pixels>, gpui::Hsla)>,
+    underline_quads: Vec<PaintQuad>,
     marked_text: Option<(gpui::ShapedLine, gpui::Point<Pixels>)>,
     marked_text_background: Option<PaintQuad>,
-    cursor: Option<PaintQuad>,
+    badge_lines: Vec<(gpui::ShapedLine, gpui::Point<Pixels>)>,
+    cursor_quads: Vec<PaintQuad>,
+    kitty_images: Vec<KittyImagePaint>,
+    link_underlines: Vec<PaintQuad>,
+    search_quads: Vec<PaintQuad>,
+    failed_command_quads: Vec<PaintQuad>,
+    scrollbar_quad: Option<PaintQuad>,
+    resize_overlay: Option<(PaintQuad, gpui::ShapedLine, gpui::Point<Pixels>)>,
 }
 
 const CELL_STYLE_FLAG_BOLD: u8 = 0x02;
@@ -1405,11 +3519,15 @@ const CELL_STYLE_FLAG_ITALIC: u8 = 0x04;
 const CELL_STYLE_FLAG_UNDERLINE: u8 = 0x08;
 const CELL_STYLE_FLAG_FAINT: u8 = 0x10;
 const CELL_STYLE_FLAG_STRIKETHROUGH: u8 = 0x40;
+const CELL_STYLE_FLAG_BLINK: u8 = 0x80;
 
 #[derive(Clone, Copy, Debug, PartialEq, Eq)]
 struct TextRunKey {
     fg: Rgb,
+    bg: Rgb,
     flags: u8,
+    underline: Underline,
+    underline_color: Option<Rgb>,
 }
 
 fn hsla_from_rgb(rgb: Rgb) -> gpui::Hsla {
@@ -1426,6 +3544,82 @@ fn hsla_from_rgb_with_alpha(rgb: Rgb, alpha: f32) -> gpui::Hsla {
     rgba.into()
 }
 
+/// Cursor rectangle inside `cell`. Bar and underline cursors are `thickness` pixels wide or
+/// tall; block and bar cursors are `height_fraction` of the cell tall, anchored to its bottom.
+fn cursor_bounds_in_cell(
+    style: ghostty_vt::CursorStyle,
+    cell: Bounds<Pixels>,
+    thickness: f32,
+    height_fraction: Option<f32>,
+) -> Bounds<Pixels> {
+    let cell_w = f32::from(cell.size.width);
+    let cell_h = f32::from(cell.size.height);
+    let height = cell_h * height_fraction.unwrap_or(1.0).clamp(0.0, 1.0);
+    let (width, height) = match style {
+        ghostty_vt::CursorStyle::Block => (cell_w, height),
+        ghostty_vt::CursorStyle::Bar => (thickness.min(cell_w), height),
+        ghostty_vt::CursorStyle::Underline => (cell_w, thickness.min(cell_h)),
+    };
+    Bounds::new(
+        point(cell.left(), cell.bottom() - px(height)),
+        size(px(width), px(height)),
+    )
+}
+
+/// Outline of `cell` drawn as four `thickness`-wide edges, used for the unfocused cursor.
+fn hollow_block_quads(
+    cell: Bounds<Pixels>,
+    thickness: Pixels,
+    color: gpui::Hsla,
+) -> Vec<PaintQuad> {
+    let (left, top) = (cell.left(), cell.top());
+    let (right, bottom) = (cell.right(), cell.bottom());
+    vec![
+        fill(
+            Bounds::from_corners(point(left, top), point(right, top + thickness)),
+            color,
+        ),
+        fill(
+            Bounds::from_corners(point(left, bottom - thickness), point(right, bottom)),
+            color,
+        ),
+        fill(
+            Bounds::from_corners(point(left, top), point(left + thickness, bottom)),
+            color,
+        ),
+        fill(
+            Bounds::from_corners(point(right - thickness, top), point(right, bottom)),
+            color,
+        ),
+    ]
+}
+
+/// Splits `runs` at the edges of `range` (byte offsets into the line) and paints the text inside
+/// it with `color`.
+fn recolor_text_runs(runs: Vec<TextRun>, range: Range<usize>, color: gpui::Hsla) -> Vec<TextRun> {
+    let mut out = Vec::with_capacity(runs.len() + 2);
+    let mut pos = 0usize;
+    for run in runs {
+        let (start, end) = (pos, pos + run.len);
+        pos = end;
+        let cuts = [
+            start,
+            range.start.clamp(start, end),
+            range.end.clamp(start, end),
+            end,
+        ];
+        for part in cuts.windows(2).filter(|part| part[1] > part[0]) {
+            let mut piece = run.clone();
+            piece.len = part[1] - part[0];
+            if part[0] >= range.start && part[1] <= range.end {
+                piece.color = color;
+            }
+            out.push(piece);
+        }
+    }
+    out
+}
+
 fn cursor_color_for_background(background: Rgb) -> gpui::Hsla {
     let bg = hsla_from_rgb(background);
     let mut cursor = if bg.l > 0.6 {
@@ -1448,121 +3642,90 @@ fn font_for_flags(base: &gpui::Font, flags: u8) -> gpui::Font {
     font
 }
 
-fn color_for_key(key: TextRunKey) -> gpui::Hsla {
-    let mut color = hsla_from_rgb(key.fg);
+/// Text color for `key`. Faint text is blended toward its background by `faint_opacity` rather
+/// than made translucent, so it stays legible over colored and translucent backgrounds.
+fn color_for_key(key: TextRunKey, faint_opacity: f32) -> gpui::Hsla {
     if key.flags & CELL_STYLE_FLAG_FAINT != 0 {
-        color = color.alpha(0.65);
+        hsla_from_rgb(blend_rgb(key.bg, key.fg, faint_opacity))
+    } else {
+        hsla_from_rgb(key.fg)
     }
-    color
 }
 
-pub(crate) const BOX_DIR_LEFT: u8 = 0x01;
-pub(crate) const BOX_DIR_RIGHT: u8 = 0x02;
-pub(crate) const BOX_DIR_UP: u8 = 0x04;
-pub(crate) const BOX_DIR_DOWN: u8 = 0x08;
-
-pub(crate) fn box_drawing_mask(ch: char) -> Option<(u8, f32)> {
-    let light = 1.0;
-    let heavy = 1.35;
-    let double = 1.15;
-
-    let mask = match ch {
-        '─' | '━' | '═' => BOX_DIR_LEFT | BOX_DIR_RIGHT,
-        '│' | '┃' | '║' => BOX_DIR_UP | BOX_DIR_DOWN,
-        '┌' | '┏' | '╔' | '╭' => BOX_DIR_RIGHT | BOX_DIR_DOWN,
-        '┐' | '┓' | '╗' | '╮' => BOX_DIR_LEFT | BOX_DIR_DOWN,
-        '└' | '┗' | '╚' | '╰' => BOX_DIR_RIGHT | BOX_DIR_UP,
-        '┘' | '┛' | '╝' | '╯' => BOX_DIR_LEFT | BOX_DIR_UP,
-        '├' | '┣' | '╠' => BOX_DIR_RIGHT | BOX_DIR_UP | BOX_DIR_DOWN,
-        '┤' | '┫' | '╣' => BOX_DIR_LEFT | BOX_DIR_UP | BOX_DIR_DOWN,
-        '┬' | '┳' | '╦' => BOX_DIR_LEFT | BOX_DIR_RIGHT | BOX_DIR_DOWN,
-        '┴' | '┻' | '╩' => BOX_DIR_LEFT | BOX_DIR_RIGHT | BOX_DIR_UP,
-        '┼' | '╋' | '╬' => BOX_DIR_LEFT | BOX_DIR_RIGHT | BOX_DIR_UP | BOX_DIR_DOWN,
-        _ => return None,
-    };
-
-    let scale = match ch {
-        '━' | '┃' | '┏' | '┓' | '┗' | '┛' | '┣' | '┫' | '┳' | '┻' | '╋' => {
-            heavy
-        }
-        '═' | '║' | '╔' | '╗' | '╚' | '╝' | '╠' | '╣' | '╦' | '╩' | '╬' => {
-            double
-        }
-        _ => light,
-    };
-
-    Some((mask, scale))
+/// Mixes `to` over `from`: `amount` 0.0 gives `from`, 1.0 gives `to`.
+fn blend_rgb(from: Rgb, to: Rgb, amount: f32) -> Rgb {
+    let amount = amount.clamp(0.0, 1.0);
+    let mix = |a: u8, b: u8| (a as f32 + (b as f32 - a as f32) * amount).round() as u8;
+    Rgb {
+        r: mix(from.r, to.r),
+        g: mix(from.g, to.g),
+        b: mix(from.b, to.b),
+    }
 }
 
-fn box_drawing_quads_for_char(
+fn underline_quads_for_run(
     bounds: Bounds<Pixels>,
     line_height: Pixels,
     cell_width: f32,
     color: gpui::Hsla,
-    ch: char,
+    underline: Underline,
 ) -> Vec<PaintQuad> {
-    let Some((mask, scale)) = box_drawing_mask(ch) else {
-        return Vec::new();
+    let thickness = (f32::from(line_height) / 14.0).max(1.0);
+    let x0 = f32::from(bounds.left());
+    let x1 = f32::from(bounds.right());
+    let y = bounds.top() + line_height - px(thickness * 2.0);
+    let segment = |start: f32, end: f32, y: Pixels| {
+        fill(
+            Bounds::from_corners(
+                point(px(start), y),
+                point(px(end.min(x1)), y + px(thickness)),
+            ),
+            color,
+        )
     };
 
-    let x0 = bounds.left();
-    let x1 = x0 + px(cell_width);
-    let y0 = bounds.top();
-    let y1 = y0 + line_height;
-
-    let mid_x = x0 + px(cell_width * 0.5);
-    let mid_y = y0 + line_height * 0.5;
-
-    let thickness = px(((f32::from(line_height) / 12.0).max(1.0) * scale).max(1.0));
-    let half_t = thickness * 0.5;
-
-    let has_left = mask & BOX_DIR_LEFT != 0;
-    let has_right = mask & BOX_DIR_RIGHT != 0;
-    let has_up = mask & BOX_DIR_UP != 0;
-    let has_down = mask & BOX_DIR_DOWN != 0;
-
     let mut quads = Vec::new();
-
-    if has_left || has_right {
-        let (start_x, end_x) = if has_left && has_right {
-            (x0, x1)
-        } else if has_left {
-            (x0, mid_x)
-        } else {
-            (mid_x, x1)
-        };
-        quads.push(fill(
-            Bounds::from_corners(point(start_x, mid_y - half_t), point(end_x, mid_y + half_t)),
-            color,
-        ));
-    }
-
-    if has_up || has_down {
-        let (start_y, end_y) = if has_up && has_down {
-            (y0, y1)
-        } else if has_up {
-            (y0, mid_y)
-        } else {
-            (mid_y, y1)
-        };
-
-        quads.push(fill(
-            Bounds::from_corners(point(mid_x - half_t, start_y), point(mid_x + half_t, end_y)),
-            color,
-        ));
+    match underline {
+        Underline::Double => {
+            quads.push(segment(x0, x1, y));
+            quads.push(segment(x0, x1, y - px(thickness * 2.0)));
+        }
+        Underline::Dotted => {
+            let mut x = x0;
+            while x < x1 {
+                quads.push(segment(x, x + thickness, y));
+                x += thickness * 2.0;
+            }
+        }
+        Underline::Dashed => {
+            let dash = (cell_width * 0.5).max(thickness);
+            let mut x = x0;
+            while x < x1 {
+                quads.push(segment(x, x + dash, y));
+                x += cell_width.max(dash + thickness);
+            }
+        }
+        Underline::None | Underline::Single | Underline::Curly => {}
     }
-
     quads
 }
 
-fn text_run_for_key(base_font: &gpui::Font, key: TextRunKey, len: usize) -> TextRun {
+fn text_run_for_key(
+    base_font: &gpui::Font,
+    key: TextRunKey,
+    faint_opacity: f32,
+    len: usize,
+) -> TextRun {
     let font = font_for_flags(base_font, key.flags);
-    let color = color_for_key(key);
+    let color = color_for_key(key, faint_opacity);
 
-    let underline = (key.flags & CELL_STYLE_FLAG_UNDERLINE != 0).then_some(UnderlineStyle {
-        color: Some(color),
+    // Double, dotted and dashed underlines are painted as quads by `underline_quads_for_run`.
+    let underline = (key.flags & CELL_STYLE_FLAG_UNDERLINE != 0
+        && matches!(key.underline, Underline::Single | Underline::Curly))
+    .then_some(UnderlineStyle {
+        color: Some(key.underline_color.map_or(color, hsla_from_rgb)),
         thickness: px(1.0),
-        wavy: false,
+        wavy: key.underline == Underline::Curly,
     });
 
     let strikethrough =
@@ -1658,12 +3821,16 @@ impl Element for TerminalTextElement {
         cx: &mut App,
     ) -> Self::PrepaintState {
         let mut style = window.text_style();
-        let (font, configured_font_size) = {
+        let (font, configured_font_size, ligatures) = {
             let view = self.view.read(cx);
-            (view.font.clone(), view.font_size)
+            (
+                view.font.clone(),
+                view.effective_font_size(),
+                view.font_ligatures(),
+            )
         };
         style.font_family = font.family.clone();
-        style.font_features = crate::default_terminal_font_features();
+        style.font_features = crate::terminal_font_features(ligatures);
         style.font_fallbacks = font.fallbacks.clone();
         // Apply configured font size if set
         if let Some(size) = configured_font_size {
@@ -1677,37 +3844,16 @@ impl Element for TerminalTextElement {
 
         let run_font = style.font();
         let run_color = style.color;
+        let faint_opacity = self.view.read(cx).session.config().faint_opacity;
 
         let cell_width = cell_metrics(window, &font, configured_font_size).map(|(w, _)| px(w));
 
         // Auto-resize: calculate grid size from actual element bounds
         if let Some((cell_w, cell_h)) = cell_metrics(window, &font, configured_font_size) {
-            let width = f32::from(bounds.size.width);
-            let height = f32::from(bounds.size.height);
-            let cols = (width / cell_w).floor().max(1.0) as u16;
-            let rows = (height / cell_h).floor().max(1.0) as u16;
-
-            let (current_cols, current_rows, resize_callback) = {
-                let view = self.view.read(cx);
-                (
-                    view.session.cols(),
-                    view.session.rows(),
-                    view.resize_callback.as_ref(),
-                )
-            };
-
-            if cols != current_cols || rows != current_rows {
-                // Notify external callback (e.g., PTY resize) if set
-                if let Some(callback) = resize_callback {
-                    callback.call(cols, rows);
-                }
-                // Resize the internal terminal session
-                self.view.update(cx, |view, _cx| {
-                    let _ = view.session.resize(cols, rows);
-                    view.sync_viewport_scroll_tracking();
-                    view.pending_refresh = true;
-                });
-            }
+            let scale = window.scale_factor();
+            self.view.update(cx, |view, cx| {
+                view.fit_grid(bounds, cell_w, cell_h, scale, cx);
+            });
         }
 
         self.view.update(cx, |view, _cx| {
@@ -1717,40 +3863,123 @@ impl Element for TerminalTextElement {
                 return;
             }
 
-            if view.line_layout_key != Some((font_size, line_height))
+            if view.line_layout_key != Some((font_size, line_height, ligatures))
                 || view.line_layouts.len() != view.viewport_lines.len()
             {
-                view.line_layout_key = Some((font_size, line_height));
+                view.line_layout_key = Some((font_size, line_height, ligatures));
                 view.line_layouts = vec![None; view.viewport_lines.len()];
             }
 
+            let blink_hidden = view.text_blink_hidden && view.session.config().text_blink;
+            let default_bg = view.session.default_background();
+            let cell_alpha = if view.session.config().background_opacity_cells {
+                view.session.background_opacity().clamp(0.0, 1.0)
+            } else {
+                1.0
+            };
+
+            let selection_fg = view
+                .session
+                .config()
+                .selection_foreground
+                .map(hsla_from_rgb);
+
+            // Everything frame-wide that shaped rows and their backgrounds depend on.
+            let settings = {
+                let mut hasher = std::hash::DefaultHasher::new();
+                (
+                    f32::from(font_size).to_bits(),
+                    f32::from(line_height).to_bits(),
+                    ligatures,
+                    cell_width.map(|w| f32::from(w).to_bits()),
+                    faint_opacity.to_bits(),
+                    cell_alpha.to_bits(),
+                )
+                    .hash(&mut hasher);
+                hash_rgb(default_fg, &mut hasher);
+                hash_rgb(default_bg, &mut hasher);
+                if let Some(color) = view.session.config().selection_foreground {
+                    hash_rgb(color, &mut hasher);
+                }
+                hasher.finish()
+            };
+            view.row_cache.begin_frame(settings);
+            let selected = selection_fg
+                .and(view.selection)
+                .map(|sel| sel.range())
+                .filter(|range| !range.is_empty());
+            if view.line_layout_selection != selected {
+                // Reshape rows entering or leaving the selection so their text color follows it.
+                let changed = [view.line_layout_selection.clone(), selected.clone()];
+                for (row, line) in view.viewport_lines.iter().enumerate() {
+                    let start = view.viewport_line_offsets.get(row).copied().unwrap_or(0);
+                    let end = start + line.len();
+                    if changed
+                        .iter()
+                        .flatten()
+                        .any(|range| range.start <= end && range.end > start)
+                        && let Some(slot) = view.line_layouts.get_mut(row)
+                    {
+                        *slot = None;
+                    }
+                }
+                view.line_layout_selection = selected.clone();
+            }
+
             for (idx, line) in view.viewport_lines.iter().enumerate() {
                 let Some(slot) = view.line_layouts.get_mut(idx) else {
                     continue;
                 };
 
                 if let Some(existing) = slot.as_ref()
-                    && existing.text.as_str() == line.as_str()
+                    && existing.line.text.as_str() == line.as_str()
                 {
+                    view.row_cache.touch(existing.hash);
+                    continue;
+                }
+
+                let style_runs = view
+                    .viewport_style_runs
+                    .get(idx)
+                    .map(Vec::as_slice)
+                    .unwrap_or_default();
+                let line_start = view.viewport_line_offsets.get(idx).copied().unwrap_or(0);
+                let local_selection = selected
+                    .as_ref()
+                    .filter(|_| selection_fg.is_some())
+                    .map(|range| {
+                        range.start.saturating_sub(line_start).min(line.len())
+                            ..range.end.saturating_sub(line_start).min(line.len())
+                    })
+                    .filter(|local| !local.is_empty());
+                let row_blink_hidden = blink_hidden
+                    && style_runs
+                        .iter()
+                        .any(|run| run.flags & CELL_STYLE_FLAG_BLINK != 0);
+                let hash =
+                    row_content_hash(line, style_runs, local_selection.clone(), row_blink_hidden);
+                if let Some(row) = view.row_cache.get(hash) {
+                    *slot = Some(row);
                     continue;
                 }
 
                 let text = SharedString::from(line.clone());
                 let mut runs: Vec<TextRun> = Vec::new();
 
-                if let Some(style_runs) = view.viewport_style_runs.get(idx)
-                    && !style_runs.is_empty()
-                {
+                if !style_runs.is_empty() {
                     let mut byte_pos = 0usize;
                     for style in style_runs.iter() {
                         let key = TextRunKey {
                             fg: style.fg,
+                            bg: style.bg,
                             flags: style.flags
                                 & (CELL_STYLE_FLAG_BOLD
                                     | CELL_STYLE_FLAG_ITALIC
                                     | CELL_STYLE_FLAG_UNDERLINE
                                     | CELL_STYLE_FLAG_FAINT
                                     | CELL_STYLE_FLAG_STRIKETHROUGH),
+                            underline: style.underline,
+                            underline_color: style.underline_color,
                         };
 
                         let start = byte_index_for_column_in_line(text.as_str(), style.start_col)
@@ -1774,7 +4003,18 @@ impl Element for TerminalTextElement {
                         }
 
                         if end > start {
-                            runs.push(text_run_for_key(&run_font, key, end.saturating_sub(start)));
+                            let mut run = text_run_for_key(
+                                &run_font,
+                                key,
+                                faint_opacity,
+                                end.saturating_sub(start),
+                            );
+                            if blink_hidden && style.flags & CELL_STYLE_FLAG_BLINK != 0 {
+                                run.color = run.color.alpha(0.0);
+                                run.underline = None;
+                                run.strikethrough = None;
+                            }
+                            runs.push(run);
                             byte_pos = end;
                         }
                     }
@@ -1802,94 +4042,172 @@ impl Element for TerminalTextElement {
                     });
                 }
 
-                let force_width = cell_width.and_then(|cell_width| {
+                if let (Some(local), Some(color)) = (local_selection, selection_fg) {
+                    runs = recolor_text_runs(runs, local, color);
+                }
+
+                // Custom-drawn glyphs keep their advance from the font but are painted as quads
+                // and paths, so the font's own glyph is hidden.
+                for range in custom_glyph_ranges(text.as_str()) {
+                    runs = recolor_text_runs(runs, range, gpui::transparent_black());
+                }
+
+                // Forcing the advance places glyphs by glyph index, which would pull the text
+                // after a ligature (several cells, one glyph) left. Ligature fonts are
+                // monospaced, so their natural advances already follow the grid.
+                let force_width = cell_width.filter(|_| !ligatures).and_then(|cell_width| {
                     use unicode_width::UnicodeWidthChar as _;
                     let has_wide = text.as_str().chars().any(|ch| ch.width().unwrap_or(0) > 1);
                     (!has_wide).then_some(cell_width)
                 });
                 let shaped = window
-                    .text_system()
-                    .shape_line(text, font_size, &runs, force_width);
-                *slot = Some(shaped);
-            }
-        });
-
-        let default_bg = { self.view.read(cx).session.default_background() };
-        let background_quads = cell_metrics(window, &font, configured_font_size)
-            .map(|(cell_width, _)| {
-                let origin = bounds.origin;
-                let mut quads: Vec<PaintQuad> = Vec::new();
-
-                let view = self.view.read(cx);
-                for (row, runs) in view.viewport_style_runs.iter().enumerate() {
-                    if runs.is_empty() {
-                        continue;
-                    }
-
-                    let y = origin.y + line_height * row as f32;
-                    for run in runs.iter() {
-                        if run.bg == default_bg {
-                            continue;
-                        }
+                    .text_system()
+                    .shape_line(text, font_size, &runs, force_width);
 
-                        let x =
-                            origin.x + px(cell_width * (run.start_col.saturating_sub(1)) as f32);
-                        let w = px(cell_width
-                            * (run.end_col.saturating_sub(run.start_col).saturating_add(1)) as f32);
-                        let color = rgba(
-                            (u32::from(run.bg.r) << 24)
-                                | (u32::from(run.bg.g) << 16)
-                                | (u32::from(run.bg.b) << 8)
-                                | 0xFF,
-                        );
-                        quads.push(fill(Bounds::new(point(x, y), size(w, line_height)), color));
-                    }
-                }
+                let backgrounds: Vec<RowBackground> = cell_width
+                    .map(|cell_width| {
+                        let cell_width = f32::from(cell_width);
+                        style_runs
+                            .iter()
+                            .filter(|run| run.bg != default_bg)
+                            .map(|run| RowBackground {
+                                x: cell_width * run.start_col.saturating_sub(1) as f32,
+                                width: cell_width
+                                    * run.end_col.saturating_sub(run.start_col).saturating_add(1)
+                                        as f32,
+                                color: hsla_from_rgb_with_alpha(run.bg, cell_alpha),
+                            })
+                            .collect()
+                    })
+                    .unwrap_or_default();
+
+                let row = ShapedRow {
+                    hash,
+                    line: shaped,
+                    backgrounds: backgrounds.into(),
+                };
+                view.row_cache.insert(hash, row.clone());
+                *slot = Some(row);
+            }
 
-                quads
-            })
-            .unwrap_or_default();
+            let capacity = (view.line_layouts.len() * 4).max(256);
+            view.row_cache.end_frame(capacity);
+        });
+
+        let default_bg = { self.view.read(cx).session.default_background() };
+        let background_quads = {
+            let view = self.view.read(cx);
+            let origin = bounds.origin;
+            view.line_layouts
+                .iter()
+                .enumerate()
+                .flat_map(|(row, shaped)| {
+                    let y = origin.y + line_height * row as f32;
+                    shaped
+                        .iter()
+                        .flat_map(|shaped| shaped.backgrounds.iter())
+                        .map(move |bg| {
+                            fill(
+                                Bounds::new(
+                                    point(origin.x + px(bg.x), y),
+                                    size(px(bg.width), line_height),
+                                ),
+                                bg.color,
+                            )
+                        })
+                })
+                .collect::<Vec<_>>()
+        };
 
         let (shaped_lines, selection, line_offsets) = {
             let view = self.view.read(cx);
             (
                 view.line_layouts
                     .iter()
-                    .map(|line| line.clone().unwrap_or_default())
+                    .map(|row| row.as_ref().map(|row| row.line.clone()).unwrap_or_default())
                     .collect::<Vec<_>>(),
                 view.selection,
                 view.viewport_line_offsets.clone(),
             )
         };
 
+        // The badge is drawn right-aligned at twice the font size, behind the text.
+        let badge_lines = {
+            let badge = self.view.read(cx).session.badge();
+            let badge_font_size = font_size * 2.0;
+            let badge_line_height = line_height * 2.0;
+            let margin = line_height * 0.5;
+            badge
+                .iter()
+                .flat_map(|badge| badge.lines())
+                .filter(|line| !line.is_empty())
+                .enumerate()
+                .map(|(row, line)| {
+                    let run = TextRun {
+                        len: line.len(),
+                        font: run_font.clone().bold(),
+                        color: run_color.alpha(0.25),
+                        background_color: None,
+                        underline: None,
+                        strikethrough: None,
+                    };
+                    let shaped = window.text_system().shape_line(
+                        SharedString::from(line.to_string()),
+                        badge_font_size,
+                        &[run],
+                        None,
+                    );
+                    let origin = point(
+                        bounds.right() - margin - shaped.width,
+                        bounds.top() + margin + badge_line_height * row as f32,
+                    );
+                    (shaped, origin)
+                })
+                .collect::<Vec<_>>()
+        };
+
         let (marked_text, cursor_position, font) = {
             let view = self.view.read(cx);
+            let marked_text = view
+                .marked_text
+                .clone()
+                .map(|text| (text, false))
+                .or_else(|| {
+                    queued_input_preview(&view.queued_input).map(|text| (text.into(), true))
+                });
             (
-                view.marked_text.clone(),
+                marked_text,
                 view.session.cursor_position(),
                 view.font.clone(),
             )
         };
 
+        // IME preedit is drawn underlined; unsent queued input is drawn dimmed.
         let (marked_text, marked_text_background) = marked_text
-            .and_then(|text| {
+            .and_then(|(text, queued)| {
                 if text.is_empty() {
                     return None;
                 }
-                let (col, row) = cursor_position?;
+                let cursor_point = cursor_position?;
+                let cursor = Cell::from(cursor_point);
                 let (cell_width, _) = cell_metrics(window, &font, configured_font_size)?;
 
-                let origin_x = bounds.left() + px(cell_width * (col.saturating_sub(1)) as f32);
-                let origin_y = bounds.top() + line_height * (row.saturating_sub(1)) as f32;
+                let origin_x = bounds.left() + px(cell_width * cursor.col as f32);
+                let origin_y = bounds.top() + line_height * cursor.row as f32;
                 let origin = point(origin_x, origin_y);
 
+                let color = if queued {
+                    run_color.alpha(0.5)
+                } else {
+                    run_color
+                };
                 let run = TextRun {
                     len: text.len(),
                     font: run_font.clone(),
-                    color: run_color,
+                    color,
                     background_color: None,
-                    underline: Some(UnderlineStyle {
-                        color: Some(run_color),
+                    underline: (!queued).then_some(UnderlineStyle {
+                        color: Some(color),
                         thickness: px(1.0),
                         wavy: false,
                     }),
@@ -1907,12 +4225,13 @@ impl Element for TerminalTextElement {
 
                 let bg = {
                     let view = self.view.read(cx);
-                    let row_index = row.saturating_sub(1) as usize;
                     view.viewport_style_runs
-                        .get(row_index)
+                        .get(cursor.row as usize)
                         .and_then(|runs| {
                             runs.iter().find_map(|run| {
-                                (col >= run.start_col && col <= run.end_col).then_some(run.bg)
+                                (cursor_point.col >= run.start_col
+                                    && cursor_point.col <= run.end_col)
+                                    .then_some(run.bg)
                             })
                         })
                         .unwrap_or(default_bg)
@@ -1952,14 +4271,18 @@ impl Element for TerminalTextElement {
                 // Get selection color from config, or use default highlight
                 let highlight = {
                     let view = self.view.read(cx);
-                    view.session
-                        .config()
+                    let config = view.session.config();
+                    config
                         .selection_background
                         .map(|rgb| {
-                            // Use the configured selection background with some transparency
-                            let mut color = hsla_from_rgb(rgb);
-                            color.a = 0.5;
-                            color
+                            // Opaque when the selected text is recolored too, so the configured
+                            // pair keeps its contrast; otherwise keep the text behind visible.
+                            let alpha = if config.selection_foreground.is_some() {
+                                1.0
+                            } else {
+                                0.5
+                            };
+                            hsla_from_rgb_with_alpha(rgb, alpha)
                         })
                         .unwrap_or_else(|| hsla(0.58, 0.9, 0.55, 0.35))
                 };
@@ -2001,66 +4324,32 @@ impl Element for TerminalTextElement {
             })
             .unwrap_or_default();
 
-        let box_drawing_quads = cell_metrics(window, &font, configured_font_size)
+        let underline_quads = cell_metrics(window, &font, configured_font_size)
             .map(|(cell_width, _)| {
-                use unicode_width::UnicodeWidthChar as _;
-                let default_fg = run_color;
                 let mut quads = Vec::new();
 
                 let view = self.view.read(cx);
-                for (row, line) in view.viewport_lines.iter().enumerate() {
+                let blink_hidden = view.text_blink_hidden && view.session.config().text_blink;
+                for (row, runs) in view.viewport_style_runs.iter().enumerate() {
                     let y = bounds.top() + line_height * row as f32;
-                    let runs = view.viewport_style_runs.get(row).map(|v| v.as_slice());
-                    let mut run_idx: usize = 0;
-
-                    let mut col = 1usize;
-                    for ch in line.chars() {
-                        let width = ch.width().unwrap_or(0);
-                        if width == 0 {
+                    for run in runs.iter() {
+                        if run.flags & CELL_STYLE_FLAG_UNDERLINE == 0
+                            || (blink_hidden && run.flags & CELL_STYLE_FLAG_BLINK != 0)
+                        {
                             continue;
                         }
-
-                        if let Some((_, _)) = box_drawing_mask(ch) {
-                            let fg = runs
-                                .and_then(|runs| {
-                                    while let Some(run) = runs.get(run_idx) {
-                                        if (col as u16) <= run.end_col {
-                                            break;
-                                        }
-                                        run_idx = run_idx.saturating_add(1);
-                                    }
-                                    runs.get(run_idx).and_then(|run| {
-                                        (col as u16 >= run.start_col && (col as u16) <= run.end_col)
-                                            .then_some(run)
-                                    })
-                                })
-                                .map(|run| {
-                                    let key = TextRunKey {
-                                        fg: run.fg,
-                                        flags: run.flags
-                                            & (CELL_STYLE_FLAG_FAINT
-                                                | CELL_STYLE_FLAG_BOLD
-                                                | CELL_STYLE_FLAG_ITALIC
-                                                | CELL_STYLE_FLAG_UNDERLINE
-                                                | CELL_STYLE_FLAG_STRIKETHROUGH),
-                                    };
-                                    color_for_key(key)
-                                })
-                                .unwrap_or(default_fg);
-
-                            let x = bounds.left() + px(cell_width * (col.saturating_sub(1)) as f32);
-                            let cell_bounds =
-                                Bounds::new(point(x, y), size(px(cell_width), line_height));
-                            quads.extend(box_drawing_quads_for_char(
-                                cell_bounds,
-                                line_height,
-                                cell_width,
-                                fg,
-                                ch,
-                            ));
-                        }
-
-                        col = col.saturating_add(width);
+                        let x = bounds.left()
+                            + px(cell_width * (run.start_col.saturating_sub(1)) as f32);
+                        let w = px(cell_width
+                            * (run.end_col.saturating_sub(run.start_col).saturating_add(1)) as f32);
+                        let color = run.underline_color.unwrap_or(run.fg);
+                        quads.extend(underline_quads_for_run(
+                            Bounds::new(point(x, y), size(w, line_height)),
+                            line_height,
+                            cell_width,
+                            hsla_from_rgb(color),
+                            run.underline,
+                        ));
                     }
                 }
 
@@ -2068,61 +4357,328 @@ impl Element for TerminalTextElement {
             })
             .unwrap_or_default();
 
+        let (box_drawing_quads, box_drawing_paths) =
+            cell_metrics(window, &font, configured_font_size)
+                .map(|(cell_width, _)| {
+                    use unicode_width::UnicodeWidthChar as _;
+                    let default_fg = run_color;
+                    let mut quads = Vec::new();
+                    let mut paths = Vec::new();
+
+                    let view = self.view.read(cx);
+                    for (row, line) in view.viewport_lines.iter().enumerate() {
+                        let y = bounds.top() + line_height * row as f32;
+                        let runs = view.viewport_style_runs.get(row).map(|v| v.as_slice());
+                        let mut run_idx: usize = 0;
+
+                        let mut col = 1usize;
+                        for ch in line.chars() {
+                            let width = ch.width().unwrap_or(0);
+                            if width == 0 {
+                                continue;
+                            }
+
+                            if is_custom_glyph(ch) {
+                                let fg = runs
+                                    .and_then(|runs| {
+                                        while let Some(run) = runs.get(run_idx) {
+                                            if (col as u16) <= run.end_col {
+                                                break;
+                                            }
+                                            run_idx = run_idx.saturating_add(1);
+                                        }
+                                        runs.get(run_idx).and_then(|run| {
+                                            (col as u16 >= run.start_col
+                                                && (col as u16) <= run.end_col)
+                                                .then_some(run)
+                                        })
+                                    })
+                                    .map(|run| {
+                                        let key = TextRunKey {
+                                            fg: run.fg,
+                                            bg: run.bg,
+                                            flags: run.flags
+                                                & (CELL_STYLE_FLAG_FAINT
+                                                    | CELL_STYLE_FLAG_BOLD
+                                                    | CELL_STYLE_FLAG_ITALIC
+                                                    | CELL_STYLE_FLAG_UNDERLINE
+                                                    | CELL_STYLE_FLAG_STRIKETHROUGH),
+                                            underline: run.underline,
+                                            underline_color: run.underline_color,
+                                        };
+                                        color_for_key(key, faint_opacity)
+                                    })
+                                    .unwrap_or(default_fg);
+
+                                let x =
+                                    bounds.left() + px(cell_width * (col.saturating_sub(1)) as f32);
+                                let cell_bounds =
+                                    Bounds::new(point(x, y), size(px(cell_width), line_height));
+                                paint_custom_glyph(ch, cell_bounds, fg, &mut quads, &mut paths);
+                            }
+
+                            col = col.saturating_add(width);
+                        }
+                    }
+
+                    (quads, paths)
+                })
+                .unwrap_or_default();
+
+        let is_focused = self.view.read(cx).focus_handle.is_focused(window);
         let cursor = {
             let view = self.view.read(cx);
-            let is_focused = view.focus_handle.is_focused(window);
             let cursor_visible = view.session.cursor_visible();
 
-            if is_focused && cursor_visible {
+            // A blinking cursor is hidden during the off phase; an unfocused view always shows a
+            // steady hollow block instead.
+            if cursor_visible && !(is_focused && view.cursor_blink_hidden) {
                 view.session.cursor_position()
             } else {
                 None
             }
         }
-        .and_then(|(col, row)| {
+        .and_then(|ViewportPoint { col, row }| {
             let view = self.view.read(cx);
             let background = view.session.default_background();
             let cursor_style = view.session.cursor_style();
             let config_cursor_height = view.session.config().adjust_cursor_height;
+            let config_cursor_thickness = view.session.config().cursor_thickness;
 
-            let cursor_color = cursor_color_for_background(background);
+            let cursor_color = view
+                .session
+                .cursor_color()
+                .map(hsla_from_rgb)
+                .unwrap_or_else(|| cursor_color_for_background(background));
             let y = bounds.top() + line_height * (row.saturating_sub(1)) as f32;
             let row_index = row.saturating_sub(1) as usize;
             let line = shaped_lines.get(row_index)?;
-            let byte_index = byte_index_for_column_in_line(line.text.as_str(), col);
-            let x = bounds.left() + line.x_for_index(byte_index.min(line.text.len()));
 
             // Calculate cell width for block cursor
             let (cell_w, _) = cell_metrics(window, &font, configured_font_size)?;
 
-            let cursor_bounds = match cursor_style {
-                ghostty_vt::CursorStyle::Block => {
-                    // Full cell block
-                    Bounds::new(point(x, y), size(px(cell_w), line_height))
-                }
-                ghostty_vt::CursorStyle::Bar => {
-                    // Vertical bar (2px wide, or adjusted height as percentage of line_height)
-                    let bar_width = if let Some(pct) = config_cursor_height {
-                        px(cell_w * pct.clamp(0.0, 1.0))
-                    } else {
-                        px(2.0)
+            // Inside a ligature there is no glyph for the cursor's column, so place it on the
+            // cell grid instead.
+            let x = if ligatures {
+                bounds.left() + px(cell_w * col.saturating_sub(1) as f32)
+            } else {
+                let byte_index = byte_index_for_column_in_line(line.text.as_str(), col);
+                bounds.left() + line.x_for_index(byte_index.min(line.text.len()))
+            };
+
+            if !is_focused {
+                let cell = Bounds::new(point(x, y), size(px(cell_w), line_height));
+                return Some((cell, cursor_color));
+            }
+
+            let cell = Bounds::new(point(x, y), size(px(cell_w), line_height));
+            let cursor_bounds = cursor_bounds_in_cell(
+                cursor_style,
+                cell,
+                config_cursor_thickness.unwrap_or(2.0),
+                config_cursor_height,
+            );
+
+            Some((cursor_bounds, cursor_color))
+        });
+        let cursor_animation = self.view.read(cx).session.config().cursor_animation;
+        let cursor_quads = match cursor {
+            None => Vec::new(),
+            Some((cell, cursor_color)) if !is_focused => {
+                hollow_block_quads(cell, px(1.0), cursor_color)
+            }
+            Some((cursor_bounds, cursor_color)) if cursor_animation => {
+                self.view.update(cx, |view, cx| {
+                    let frame = view
+                        .cursor_animation
+                        .frame(cursor_bounds.origin, Instant::now());
+                    view.ensure_cursor_animation_timer(cx);
+                    frame
+                        .into_iter()
+                        .map(|(origin, opacity)| {
+                            let color = gpui::Hsla {
+                                a: cursor_color.a * opacity,
+                                ..cursor_color
+                            };
+                            fill(Bounds::new(origin, cursor_bounds.size), color)
+                        })
+                        .collect()
+                })
+            }
+            Some((cursor_bounds, cursor_color)) => vec![fill(cursor_bounds, cursor_color)],
+        };
+
+        let search_quads = {
+            let view = self.view.read(cx);
+            let top = view.session.viewport_top_row();
+            let mut quads = Vec::new();
+            if let Some(search) = view.search.as_ref() {
+                for m in search.visible_matches(top, view.session.rows()) {
+                    let row = (m.row - top) as usize;
+                    let Some(line) = shaped_lines.get(row) else {
+                        continue;
                     };
-                    Bounds::new(point(x, y), size(bar_width, line_height))
-                }
-                ghostty_vt::CursorStyle::Underline => {
-                    // Horizontal underline (at bottom of cell)
-                    let underline_height = if let Some(pct) = config_cursor_height {
-                        line_height * pct.clamp(0.0, 1.0)
+                    let text = line.text.as_str();
+                    let start = byte_index_for_column_in_line(text, m.start_col).min(text.len());
+                    let end = byte_index_for_column_in_line(text, m.end_col.saturating_add(1))
+                        .min(text.len());
+                    let color = if search.current == Some(m) {
+                        hsla(0.08, 0.95, 0.55, 0.6)
                     } else {
-                        px(2.0)
+                        hsla(0.14, 0.9, 0.55, 0.35)
                     };
-                    let underline_y = y + line_height - underline_height;
-                    Bounds::new(point(x, underline_y), size(px(cell_w), underline_height))
+                    let y1 = bounds.top() + line_height * row as f32;
+                    quads.push(fill(
+                        Bounds::from_corners(
+                            point(bounds.left() + line.x_for_index(start), y1),
+                            point(bounds.left() + line.x_for_index(end), y1 + line_height),
+                        ),
+                        color,
+                    ));
+                }
+            }
+            quads
+        };
+
+        let failed_command_quads = {
+            let view = self.view.read(cx);
+            let top = view.session.viewport_top_row();
+            let bottom = top.saturating_add(u32::from(view.session.rows()));
+            let mut quads = Vec::new();
+            if view.session.config().failed_command_gutter {
+                for mark in view.session.prompt_marks().iter().filter(|m| m.failed()) {
+                    let rows = mark.rows();
+                    let (start, end) = (rows.start.max(top), rows.end.min(bottom));
+                    if start >= end {
+                        continue;
+                    }
+                    let y1 = bounds.top() + line_height * (start - top) as f32;
+                    let y2 = bounds.top() + line_height * (end - top) as f32;
+                    quads.push(fill(
+                        Bounds::from_corners(
+                            point(bounds.left(), y1),
+                            point(bounds.left() + px(2.0), y2),
+                        ),
+                        hsla(0.0, 0.8, 0.55, 0.9),
+                    ));
                 }
+            }
+            quads
+        };
+
+        let scrollbar_quad = {
+            let view = self.view.read(cx);
+            let opacity = view.scrollbar.opacity(Instant::now());
+            scrollbar::scrollbar_thumb(
+                f32::from(bounds.size.height),
+                view.session.total_lines(),
+                u32::from(view.session.rows()),
+                view.session.scroll_offset(),
+            )
+            .filter(|_| view.session.config().scrollbar && opacity > 0.0)
+            .map(|thumb| {
+                let right = bounds.right() - px(SCROLLBAR_MARGIN);
+                let top = bounds.top() + px(thumb.top);
+                fill(
+                    Bounds::from_corners(
+                        point(right - px(SCROLLBAR_WIDTH), top),
+                        point(right, top + px(thumb.height)),
+                    ),
+                    hsla_from_rgb_with_alpha(default_fg, 0.4 * opacity),
+                )
+                .corner_radii(px(SCROLLBAR_WIDTH / 2.0))
+            })
+        };
+
+        // The grid size, centered on a rounded panel, while the view is being resized.
+        let resize_overlay = {
+            let view = self.view.read(cx);
+            let opacity = view.resize_overlay.opacity(Instant::now());
+            (opacity > 0.0).then(|| {
+                let label = resize_overlay_label(view.session.cols(), view.session.rows());
+                let run = TextRun {
+                    len: label.len(),
+                    font: run_font.clone(),
+                    color: run_color.opacity(opacity),
+                    background_color: None,
+                    underline: None,
+                    strikethrough: None,
+                };
+                let shaped = window.text_system().shape_line(
+                    SharedString::from(label),
+                    font_size,
+                    &[run],
+                    None,
+                );
+                let padding = line_height * 0.5;
+                let center = bounds.center();
+                let origin = point(center.x - shaped.width / 2.0, center.y - line_height / 2.0);
+                let panel = fill(
+                    Bounds::from_corners(
+                        point(origin.x - padding, origin.y - padding),
+                        point(
+                            origin.x + shaped.width + padding,
+                            origin.y + line_height + padding,
+                        ),
+                    ),
+                    hsla_from_rgb_with_alpha(view.session.default_background(), 0.85 * opacity),
+                )
+                .corner_radii(padding);
+                (panel, shaped, origin)
+            })
+        };
+
+        let link_underlines = {
+            let view = self.view.read(cx);
+            let underline = |x1: Pixels, x2: Pixels, row: usize| {
+                let y = bounds.top() + line_height * (row + 1) as f32 - px(1.0);
+                fill(
+                    Bounds::from_corners(point(x1, y), point(x2, y + px(1.0))),
+                    run_color,
+                )
             };
+            match view.hovered_link.clone() {
+                Some(HoveredLink::Rule(row, range)) => shaped_lines
+                    .get(row)
+                    .map(|line| {
+                        let x1 = bounds.left() + line.x_for_index(range.start.min(line.text.len()));
+                        let x2 = bounds.left() + line.x_for_index(range.end.min(line.text.len()));
+                        underline(x1, x2, row)
+                    })
+                    .into_iter()
+                    .collect(),
+                Some(HoveredLink::Hyperlink(id)) => cell_width
+                    .map(|cell_width| {
+                        let cell_width = f32::from(cell_width);
+                        let mut quads = Vec::new();
+                        for (row, runs) in view.viewport_style_runs.iter().enumerate() {
+                            for run in runs.iter().filter(|run| run.hyperlink_id == Some(id)) {
+                                let x1 = bounds.left()
+                                    + px(cell_width * run.start_col.saturating_sub(1) as f32);
+                                let x2 = bounds.left() + px(cell_width * run.end_col as f32);
+                                quads.push(underline(x1, x2, row));
+                            }
+                        }
+                        quads
+                    })
+                    .unwrap_or_default(),
+                None => Vec::new(),
+            }
+        };
 
-            Some(fill(cursor_bounds, cursor_color))
-        });
+        let kitty_images = match cell_width {
+            Some(cell_width) => self.view.update(cx, |view, _cx| {
+                visible_kitty_images(
+                    &view.session,
+                    &mut view.kitty_images,
+                    bounds.origin,
+                    f32::from(cell_width),
+                    line_height,
+                    window,
+                )
+            }),
+            None => Vec::new(),
+        };
 
         TerminalPrepaintState {
             line_height,
@@ -2130,9 +4686,18 @@ impl Element for TerminalTextElement {
             background_quads,
             selection_quads,
             box_drawing_quads,
+            box_drawing_paths,
+            underline_quads,
             marked_text,
             marked_text_background,
-            cursor,
+            badge_lines,
+            cursor_quads,
+            kitty_images,
+            link_underlines,
+            search_quads,
+            failed_command_quads,
+            scrollbar_quad,
+            resize_overlay,
         }
     }
 
@@ -2148,6 +4713,8 @@ impl Element for TerminalTextElement {
     ) {
         self.view.update(cx, |view, _cx| {
             view.last_bounds = Some(bounds);
+            let generation = view.session.generation();
+            view.render_waiters.mark_painted(generation);
         });
 
         let focus_handle = { self.view.read(cx).focus_handle.clone() };
@@ -2158,26 +4725,45 @@ impl Element for TerminalTextElement {
         );
 
         window.paint_layer(bounds, |window| {
-            let (default_bg, bg_opacity) = {
-                let view = self.view.read(cx);
-                (
-                    view.session.default_background(),
-                    view.session.background_opacity(),
-                )
-            };
-            window.paint_quad(fill(
-                bounds,
-                hsla_from_rgb_with_alpha(default_bg, bg_opacity),
-            ));
-
+            // The default background is painted by the wrapping div in `render`, so it also
+            // covers any area outside these bounds.
             for quad in prepaint.background_quads.drain(..) {
                 window.paint_quad(quad);
             }
 
+            for image in prepaint
+                .kitty_images
+                .iter()
+                .filter(|image| image.below_text)
+            {
+                let _ = window.paint_image(
+                    image.bounds,
+                    gpui::Corners::default(),
+                    image.image.clone(),
+                    0,
+                    false,
+                );
+            }
+
+            for quad in prepaint.search_quads.drain(..) {
+                window.paint_quad(quad);
+            }
+
             for quad in prepaint.selection_quads.drain(..) {
                 window.paint_quad(quad);
             }
 
+            for (line, origin) in prepaint.badge_lines.drain(..) {
+                let _ = line.paint(
+                    origin,
+                    prepaint.line_height * 2.0,
+                    gpui::TextAlign::Left,
+                    None,
+                    window,
+                    cx,
+                );
+            }
+
             let origin = bounds.origin;
             for (row, line) in prepaint.shaped_lines.iter().enumerate() {
                 let y = origin.y + prepaint.line_height * row as f32;
@@ -2195,6 +4781,36 @@ impl Element for TerminalTextElement {
                 window.paint_quad(quad);
             }
 
+            for (path, color) in prepaint.box_drawing_paths.drain(..) {
+                window.paint_path(path, color);
+            }
+
+            for quad in prepaint.underline_quads.drain(..) {
+                window.paint_quad(quad);
+            }
+
+            for quad in prepaint.link_underlines.drain(..) {
+                window.paint_quad(quad);
+            }
+
+            for quad in prepaint.failed_command_quads.drain(..) {
+                window.paint_quad(quad);
+            }
+
+            for image in prepaint
+                .kitty_images
+                .iter()
+                .filter(|image| !image.below_text)
+            {
+                let _ = window.paint_image(
+                    image.bounds,
+                    gpui::Corners::default(),
+                    image.image.clone(),
+                    0,
+                    false,
+                );
+            }
+
             if let Some(bg) = prepaint.marked_text_background.take() {
                 window.paint_quad(bg);
             }
@@ -2210,8 +4826,24 @@ impl Element for TerminalTextElement {
                 );
             }
 
-            if let Some(cursor) = prepaint.cursor.take() {
-                window.paint_quad(cursor);
+            for quad in prepaint.cursor_quads.drain(..) {
+                window.paint_quad(quad);
+            }
+
+            if let Some(quad) = prepaint.scrollbar_quad.take() {
+                window.paint_quad(quad);
+            }
+
+            if let Some((panel, line, origin)) = prepaint.resize_overlay.take() {
+                window.paint_quad(panel);
+                let _ = line.paint(
+                    origin,
+                    prepaint.line_height,
+                    gpui::TextAlign::Left,
+                    None,
+                    window,
+                    cx,
+                );
             }
         });
     }
@@ -2222,16 +4854,37 @@ impl Render for TerminalView {
         ensure_key_bindings(cx);
 
         if !self.pending_output.is_empty() {
-            let bytes = std::mem::take(&mut self.pending_output);
-            self.feed_output_bytes_to_session(&bytes);
+            if self.drain_pending_output(Some(OUTPUT_FEED_BUDGET)) {
+                self.render_waiters.mark_applied(self.session.generation());
+            } else {
+                self.ensure_output_timer(cx);
+            }
             self.apply_side_effects(cx);
             self.reconcile_dirty_viewport_after_output();
+        } else {
+            self.render_waiters.mark_applied(self.session.generation());
         }
 
         if self.pending_refresh {
             self.refresh_viewport();
             self.pending_refresh = false;
         }
+        if self
+            .scrollbar
+            .track(self.session.viewport_top_row(), Instant::now())
+        {
+            self.ensure_scrollbar_timer(cx);
+        }
+        if self.session.scroll_offset() == 0 {
+            self.new_lines_below = 0;
+        }
+        self.ensure_text_blink_timer(cx);
+        let focused = self.focus_handle.is_focused(window);
+        if focused != self.cursor_focused {
+            self.report_focus_change(focused);
+        }
+        self.cursor_focused = focused;
+        self.ensure_cursor_blink_timer(cx);
 
         if self.session.window_title_updates_enabled() {
             let title = self
@@ -2245,6 +4898,29 @@ impl Render for TerminalView {
             }
         }
 
+        let fg = self.session.default_foreground();
+        let bg = self.session.default_background();
+        let bg_opacity = self.session.background_opacity().clamp(0.0, 1.0);
+        let root_indicator = if self.session.is_foreground_root() {
+            self.session.config().root_indicator
+        } else {
+            RootIndicator::Off
+        };
+        let root_color = hsla_from_rgb(self.session.config().palette.unwrap_or(DEFAULT_PALETTE)[1]);
+        let overlay = self.overlay.as_ref().map(|overlay| match overlay {
+            TerminalOverlay::Custom(render) => render(window, cx),
+            _ => div()
+                .px(px(12.0))
+                .py(px(6.0))
+                .rounded(px(4.0))
+                .border_1()
+                .border_color(hsla_from_rgb_with_alpha(fg, 0.3))
+                .bg(hsla_from_rgb(bg))
+                .text_color(hsla_from_rgb(fg))
+                .child(overlay_text(overlay, self.restart_handler.is_some()))
+                .into_any_element(),
+        });
+
         div()
             .w_full()
             .h_full()
@@ -2252,9 +4928,25 @@ impl Render for TerminalView {
             .min_h_full()
             .track_focus(&self.focus_handle)
             .key_context(KEY_CONTEXT)
+            .when(self.hovered_link.is_some(), |el| el.cursor_pointer())
+            .on_action(cx.listener(Self::on_clear_scrollback))
             .on_action(cx.listener(Self::on_copy))
             .on_action(cx.listener(Self::on_copy_last_output))
             .on_action(cx.listener(Self::on_select_all))
+            .on_action(cx.listener(Self::on_select_word))
+            .on_action(cx.listener(Self::on_select_last_command_output))
+            .on_action(cx.listener(Self::on_search))
+            .on_action(cx.listener(Self::on_search_next))
+            .on_action(cx.listener(Self::on_search_previous))
+            .on_action(cx.listener(Self::on_jump_to_previous_prompt))
+            .on_action(cx.listener(Self::on_jump_to_next_prompt))
+            .on_action(cx.listener(Self::on_show_clipboard_history))
+            .on_action(cx.listener(Self::on_toggle_notes))
+            .on_action(cx.listener(Self::on_zoom_in))
+            .on_action(cx.listener(Self::on_zoom_out))
+            .on_action(cx.listener(Self::on_zoom_reset))
+            .on_action(cx.listener(Self::on_add_selection_to_notes))
+            .on_action(cx.listener(Self::on_lock_input))
             .on_action(cx.listener(Self::on_paste))
             .on_action(cx.listener(Self::on_tab))
             .on_action(cx.listener(Self::on_tab_prev))
@@ -2267,10 +4959,182 @@ impl Render for TerminalView {
             .on_mouse_up(MouseButton::Left, cx.listener(Self::on_mouse_up))
             .on_mouse_up(MouseButton::Middle, cx.listener(Self::on_mouse_up))
             .on_mouse_up(MouseButton::Right, cx.listener(Self::on_mouse_up))
-            .text_color(hsla_from_rgb(self.session.default_foreground()))
+            .bg(hsla_from_rgb_with_alpha(bg, bg_opacity))
+            .text_color(hsla_from_rgb(fg))
             .font(self.font.clone())
             .whitespace_nowrap()
+            .relative()
+            .when(root_indicator == RootIndicator::Border, |el| {
+                el.border_2().border_color(root_color)
+            })
             .child(TerminalTextElement { view: cx.entity() })
+            .when(root_indicator == RootIndicator::Strip, |el| {
+                el.child(
+                    div()
+                        .absolute()
+                        .top_0()
+                        .left_0()
+                        .w_full()
+                        .px(px(8.0))
+                        .bg(root_color.opacity(0.85))
+                        .text_color(hsla_from_rgb(bg))
+                        .child("Running as root"),
+                )
+            })
+            .when(self.bell_flash, |el| {
+                el.child(div().absolute().top_0().left_0().size_full().bg(
+                    hsla_from_rgb_with_alpha(self.session.default_foreground(), 0.15),
+                ))
+            })
+            .when(self.new_lines_below > 0, |el| {
+                let fg = self.session.default_foreground();
+                let bg = self.session.default_background();
+                el.child(
+                    div()
+                        .absolute()
+                        .bottom(px(8.0))
+                        .left_0()
+                        .w_full()
+                        .flex()
+                        .justify_center()
+                        .child(
+                            div()
+                                .px(px(10.0))
+                                .py(px(4.0))
+                                .rounded(px(12.0))
+                                .border_1()
+                                .border_color(hsla_from_rgb_with_alpha(fg, 0.3))
+                                .bg(hsla_from_rgb(bg))
+                                .text_color(hsla_from_rgb(fg))
+                                .cursor_pointer()
+                                .on_mouse_down(
+                                    MouseButton::Left,
+                                    cx.listener(|this, _: &MouseDownEvent, _window, cx| {
+                                        this.scroll_to_bottom(cx);
+                                        cx.stop_propagation();
+                                    }),
+                                )
+                                .child(new_output_label(self.new_lines_below)),
+                        ),
+                )
+            })
+            .when(self.input_locked, |el| {
+                let fg = self.session.default_foreground();
+                let bg = self.session.default_background();
+                el.child(
+                    div()
+                        .absolute()
+                        .bottom(px(8.0))
+                        .right(px(8.0))
+                        .px(px(8.0))
+                        .py(px(4.0))
+                        .rounded(px(4.0))
+                        .border_1()
+                        .border_color(hsla_from_rgb_with_alpha(fg, 0.3))
+                        .bg(hsla_from_rgb(bg))
+                        .text_color(hsla_from_rgb(fg))
+                        .child("🔒 Input locked"),
+                )
+            })
+            .when_some(self.search.as_ref().map(SearchBar::label), |el, label| {
+                let fg = self.session.default_foreground();
+                let bg = self.session.default_background();
+                el.child(
+                    div()
+                        .absolute()
+                        .top(px(8.0))
+                        .right(px(8.0))
+                        .px(px(8.0))
+                        .py(px(4.0))
+                        .rounded(px(4.0))
+                        .border_1()
+                        .border_color(hsla_from_rgb_with_alpha(fg, 0.3))
+                        .bg(hsla_from_rgb(bg))
+                        .text_color(hsla_from_rgb(fg))
+                        .child(label),
+                )
+            })
+            .when_some(
+                self.clipboard_picker.as_ref().map(|picker| {
+                    picker.rows(
+                        self.session.clipboard_history(),
+                        std::time::SystemTime::now(),
+                    )
+                }),
+                |el, rows| {
+                    let fg = self.session.default_foreground();
+                    let bg = self.session.default_background();
+                    el.child(
+                        div()
+                            .absolute()
+                            .top(px(8.0))
+                            .left(px(8.0))
+                            .px(px(8.0))
+                            .py(px(4.0))
+                            .rounded(px(4.0))
+                            .border_1()
+                            .border_color(hsla_from_rgb_with_alpha(fg, 0.3))
+                            .bg(hsla_from_rgb(bg))
+                            .text_color(hsla_from_rgb(fg))
+                            .flex()
+                            .flex_col()
+                            .children(rows.into_iter().map(|(selected, label)| {
+                                div()
+                                    .px(px(4.0))
+                                    .when(selected, |row| row.bg(hsla_from_rgb_with_alpha(fg, 0.2)))
+                                    .child(label)
+                            })),
+                    )
+                },
+            )
+            .when(self.notes_open, |el| {
+                let fg = self.session.default_foreground();
+                let bg = self.session.default_background();
+                el.child(
+                    div()
+                        .absolute()
+                        .top_0()
+                        .right_0()
+                        .h_full()
+                        .w(px(NOTES_PANEL_WIDTH))
+                        .occlude()
+                        .px(px(8.0))
+                        .py(px(4.0))
+                        .border_l_1()
+                        .border_color(hsla_from_rgb_with_alpha(fg, 0.3))
+                        .bg(hsla_from_rgb(bg))
+                        .text_color(hsla_from_rgb(fg))
+                        .whitespace_normal()
+                        .flex()
+                        .flex_col()
+                        .child(
+                            div()
+                                .text_color(hsla_from_rgb_with_alpha(fg, 0.6))
+                                .child("Notes"),
+                        )
+                        .children(
+                            format!("{}▏", self.session.notes())
+                                .split('\n')
+                                .map(|line| div().child(line.to_string()))
+                                .collect::<Vec<_>>(),
+                        ),
+                )
+            })
+            .when_some(overlay, |el, overlay| {
+                el.child(
+                    div()
+                        .absolute()
+                        .top_0()
+                        .left_0()
+                        .size_full()
+                        .occlude()
+                        .bg(hsla_from_rgb_with_alpha(bg, 0.75))
+                        .flex()
+                        .items_center()
+                        .justify_center()
+                        .child(overlay),
+                )
+            })
     }
 }
 
@@ -2313,7 +5177,10 @@ pub(crate) fn cell_metrics(
 mod tests {
     use ghostty_vt::Rgb;
 
-    use super::{url_at_byte_index, url_at_column_in_line, window_position_to_local};
+    use super::{
+        cursor_bounds_in_cell, url_at_byte_index, url_at_column_in_line, url_range_at_byte_index,
+        window_position_to_local,
+    };
 
     #[test]
     fn url_detection_finds_https_links() {
@@ -2325,6 +5192,15 @@ mod tests {
         );
     }
 
+    #[test]
+    fn url_hover_range_excludes_trailing_punctuation() {
+        let text = "see https://example.com/docs.";
+        let idx = text.find("example").unwrap();
+        let range = url_range_at_byte_index(text, idx).unwrap();
+        assert_eq!(&text[range], "https://example.com/docs");
+        assert_eq!(url_range_at_byte_index(text, 0), None);
+    }
+
     #[test]
     fn url_detection_finds_https_links_by_cell_column() {
         let line = "https://google.com";
@@ -2367,4 +5243,126 @@ mod tests {
         assert!(cursor.l > 0.8);
         assert!((cursor.a - 0.72).abs() < f32::EPSILON);
     }
+
+    #[test]
+    fn new_output_pill_counts_lines() {
+        assert_eq!(super::new_output_label(1), "1 new line ↓");
+        assert_eq!(super::new_output_label(42), "42 new lines ↓");
+    }
+
+    #[test]
+    fn built_in_overlays_have_labels() {
+        use super::TerminalOverlay;
+
+        let label = |overlay: TerminalOverlay| overlay.label();
+        assert_eq!(
+            label(TerminalOverlay::ProcessExited { exit_code: Some(1) }).as_deref(),
+            Some("Process exited with code 1")
+        );
+        assert_eq!(
+            label(TerminalOverlay::Reconnecting { attempt: None }).as_deref(),
+            Some("Reconnecting…")
+        );
+        assert_eq!(
+            label(TerminalOverlay::PermissionDenied {
+                message: Some("/dev/ptmx".to_string())
+            })
+            .as_deref(),
+            Some("Permission denied: /dev/ptmx")
+        );
+        assert_eq!(
+            label(TerminalOverlay::custom(|_, _| {
+                gpui::IntoElement::into_any_element(gpui::div())
+            })),
+            None
+        );
+    }
+
+    #[test]
+    fn process_exit_overlay_says_what_enter_does() {
+        use super::{TerminalOverlay, overlay_text};
+
+        let exited = TerminalOverlay::ProcessExited { exit_code: Some(1) };
+        assert_eq!(
+            overlay_text(&exited, true),
+            "Process exited with code 1 — press Enter to restart"
+        );
+        assert_eq!(
+            overlay_text(&exited, false),
+            "Process exited with code 1 — press Enter to close"
+        );
+        assert_eq!(
+            overlay_text(&TerminalOverlay::Reconnecting { attempt: None }, true),
+            "Reconnecting…"
+        );
+    }
+
+    #[test]
+    fn recolor_text_runs_splits_at_selection_edges() {
+        let run = |len, color| gpui::TextRun {
+            len,
+            font: gpui::font("Menlo"),
+            color,
+            background_color: None,
+            underline: None,
+            strikethrough: None,
+        };
+        let (white, black) = (gpui::white(), gpui::black());
+        let runs = super::recolor_text_runs(vec![run(4, white), run(4, white)], 2..6, black);
+        let parts: Vec<_> = runs.iter().map(|r| (r.len, r.color)).collect();
+        assert_eq!(parts, [(2, white), (2, black), (2, black), (2, white)]);
+    }
+
+    #[test]
+    fn faint_text_blends_toward_background() {
+        let fg = Rgb {
+            r: 255,
+            g: 255,
+            b: 255,
+        };
+        let bg = Rgb { r: 0, g: 0, b: 200 };
+        assert_eq!(super::blend_rgb(bg, fg, 1.0), fg);
+        assert_eq!(super::blend_rgb(bg, fg, 0.0), bg);
+        assert_eq!(
+            super::blend_rgb(bg, fg, 0.5),
+            Rgb {
+                r: 128,
+                g: 128,
+                b: 228
+            }
+        );
+    }
+
+    #[test]
+    fn cursor_geometry_honors_thickness_and_height() {
+        use ghostty_vt::CursorStyle;
+        use gpui::{Bounds, point, px, size};
+
+        let cell = Bounds::new(point(px(10.0), px(20.0)), size(px(8.0), px(16.0)));
+        let rect = |style, thickness, height| {
+            let b = cursor_bounds_in_cell(style, cell, thickness, height);
+            (
+                f32::from(b.left()),
+                f32::from(b.top()),
+                f32::from(b.size.width),
+                f32::from(b.size.height),
+            )
+        };
+
+        assert_eq!(rect(CursorStyle::Block, 2.0, None), (10.0, 20.0, 8.0, 16.0));
+        assert_eq!(
+            rect(CursorStyle::Block, 2.0, Some(0.5)),
+            (10.0, 28.0, 8.0, 8.0)
+        );
+        assert_eq!(rect(CursorStyle::Bar, 3.0, None), (10.0, 20.0, 3.0, 16.0));
+        assert_eq!(
+            rect(CursorStyle::Bar, 3.0, Some(0.5)),
+            (10.0, 28.0, 3.0, 8.0)
+        );
+        assert_eq!(
+            rect(CursorStyle::Underline, 3.0, Some(0.5)),
+            (10.0, 33.0, 8.0, 3.0)
+        );
+        assert_eq!(rect(CursorStyle::Bar, 20.0, None).2, 8.0);
+    }
 }
@@ -0,0 +1,49 @@
+/// Width of the notes panel docked on the right of the view.
+pub(super) const NOTES_PANEL_WIDTH: f32 = 280.0;
+
+/// Applies an editing key typed into the notes panel. Returns false for keys it does not edit
+/// with, which the view then ignores rather than sending them to the terminal.
+pub(super) fn apply_notes_key(notes: &mut String, key: &str) -> bool {
+    match key {
+        "enter" | "return" | "kp_enter" | "numpad_enter" => notes.push('\n'),
+        "tab" => notes.push('\t'),
+        "backspace" => {
+            notes.pop();
+        }
+        _ => return false,
+    }
+    true
+}
+
+/// Appends `snippet` to `notes` on a line of its own.
+pub(super) fn append_note(notes: &mut String, snippet: &str) {
+    let snippet = snippet.trim_end_matches('\n');
+    if snippet.is_empty() {
+        return;
+    }
+    if !notes.is_empty() && !notes.ends_with('\n') {
+        notes.push('\n');
+    }
+    notes.push_str(snippet);
+    notes.push('\n');
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn notes_are_edited_at_the_end_and_snippets_get_their_own_line() {
+        let mut notes = String::from("deploy");
+        assert!(apply_notes_key(&mut notes, "backspace"));
+        assert!(apply_notes_key(&mut notes, "enter"));
+        assert!(!apply_notes_key(&mut notes, "left"));
+        assert_eq!(notes, "deplo\n");
+
+        let mut notes = String::from("todo");
+        append_note(&mut notes, "kubectl get pods\n");
+        append_note(&mut notes, "");
+        append_note(&mut notes, "make test");
+        assert_eq!(notes, "todo\nkubectl get pods\nmake test\n");
+    }
+}
@@ -0,0 +1,388 @@
+/// Elements that start on a new line when HTML is converted to text.
+const BLOCK_TAGS: &[&str] = &[
+    "address",
+    "article",
+    "aside",
+    "blockquote",
+    "dd",
+    "div",
+    "dl",
+    "dt",
+    "figure",
+    "footer",
+    "form",
+    "h1",
+    "h2",
+    "h3",
+    "h4",
+    "h5",
+    "h6",
+    "header",
+    "hr",
+    "li",
+    "main",
+    "nav",
+    "ol",
+    "p",
+    "pre",
+    "section",
+    "table",
+    "tr",
+    "ul",
+];
+
+/// Bracketed paste end marker; pasted text containing it could break out of the paste.
+const BRACKETED_PASTE_END: &str = "\x1b[201~";
+
+/// Why a paste is held for the host's [`PasteConfirmCallback`](super::PasteConfirmCallback).
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum PasteWarning {
+    /// Contains a newline while the shell has not enabled bracketed paste, so it would run.
+    Multiline,
+    /// Contains the bracketed paste end marker and could break out of the paste.
+    BracketedPasteEnd,
+    /// Has more lines than the [`PastePolicy`] allows; holds the line count.
+    TooManyLines(usize),
+    /// Matches one of the [`PastePolicy`] patterns; holds the matched text.
+    Pattern(String),
+}
+
+/// Host rules for pastes that need confirmation, checked in addition to `paste-protection`.
+/// Set with [`TerminalView::set_paste_policy`](super::TerminalView::set_paste_policy).
+#[derive(Clone, Debug, Default)]
+pub struct PastePolicy {
+    max_lines: Option<usize>,
+    patterns: Vec<regex::Regex>,
+}
+
+impl PastePolicy {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Holds pastes with more than `lines` lines.
+    pub fn max_lines(mut self, lines: usize) -> Self {
+        self.max_lines = Some(lines);
+        self
+    }
+
+    /// Holds pastes containing a match of `pattern`.
+    pub fn pattern(mut self, pattern: &str) -> Result<Self, regex::Error> {
+        self.patterns.push(regex::Regex::new(pattern)?);
+        Ok(self)
+    }
+
+    /// Holds pastes that run `sudo` or a recursive or forced `rm`.
+    pub fn destructive_commands(self) -> Self {
+        self.pattern(r"\bsudo\b")
+            .and_then(|policy| policy.pattern(r"\brm\s+(-\w*[rRf]|--recursive|--force)"))
+            .expect("built-in paste patterns are valid")
+    }
+
+    /// The first reason to hold `text`, if any. `protection` enables the `paste-protection`
+    /// checks; `bracketed` is whether the shell enabled bracketed paste.
+    pub(super) fn check(
+        &self,
+        text: &str,
+        bracketed: bool,
+        protection: bool,
+    ) -> Option<PasteWarning> {
+        if protection {
+            if text.contains(BRACKETED_PASTE_END) {
+                return Some(PasteWarning::BracketedPasteEnd);
+            }
+            if !bracketed && text.contains('\n') {
+                return Some(PasteWarning::Multiline);
+            }
+        }
+        let lines = text.lines().count();
+        if self.max_lines.is_some_and(|max| lines > max) {
+            return Some(PasteWarning::TooManyLines(lines));
+        }
+        self.patterns
+            .iter()
+            .find_map(|pattern| pattern.find(text))
+            .map(|found| PasteWarning::Pattern(found.as_str().to_string()))
+    }
+}
+
+/// Text to send for a paste, after the `paste-html-as-text`, `paste-preserve-ansi` and
+/// `paste-strip-control` options.
+pub(super) fn paste_text(
+    text: &str,
+    html_as_text: bool,
+    preserve_ansi: bool,
+    strip_control: bool,
+) -> String {
+    let text = if html_as_text && looks_like_html(text) {
+        html_to_text(text)
+    } else {
+        text.to_string()
+    };
+    let text = if preserve_ansi {
+        text
+    } else {
+        strip_escape_sequences(&text)
+    };
+    if strip_control {
+        strip_control_chars(&text, preserve_ansi)
+    } else {
+        text
+    }
+}
+
+/// Drops control characters other than `\n` and `\t` (and ESC when `keep_escape`), so a paste
+/// cannot send signals, erase input or submit a line with a stray `\r`.
+pub(super) fn strip_control_chars(text: &str, keep_escape: bool) -> String {
+    text.chars()
+        .filter(|&ch| !ch.is_control() || ch == '\n' || ch == '\t' || (keep_escape && ch == '\x1b'))
+        .collect()
+}
+
+/// Markup copied from a browser or rich-text editor: the whole clipboard is one tag soup.
+fn looks_like_html(text: &str) -> bool {
+    let text = text.trim();
+    (text.starts_with('<') && text.ends_with('>') && text.contains("</"))
+        || text.contains("<!--StartFragment-->")
+}
+
+/// Converts HTML to plain text: tags are dropped, block elements and `<br>` become line breaks,
+/// whitespace is collapsed outside `<pre>`, and common entities are decoded.
+pub(super) fn html_to_text(html: &str) -> String {
+    let mut out = String::new();
+    let mut pre_depth = 0usize;
+    let mut skip_until: Option<&'static str> = None;
+    let mut rest = html;
+
+    while !rest.is_empty() {
+        if let Some(comment) = rest.strip_prefix("<!--") {
+            rest = comment.find("-->").map_or("", |end| &comment[end + 3..]);
+            continue;
+        }
+
+        if rest.starts_with('<')
+            && let Some(end) = rest.find('>')
+        {
+            let tag = &rest[1..end];
+            rest = &rest[end + 1..];
+
+            let closing = tag.starts_with('/');
+            let name = tag
+                .trim_start_matches('/')
+                .split(|c: char| c.is_whitespace() || c == '/')
+                .next()
+                .unwrap_or("")
+                .to_ascii_lowercase();
+
+            if let Some(skipped) = skip_until {
+                if closing && name == skipped {
+                    skip_until = None;
+                }
+                continue;
+            }
+
+            match name.as_str() {
+                "script" if !closing => skip_until = Some("script"),
+                "style" if !closing => skip_until = Some("style"),
+                "head" if !closing => skip_until = Some("head"),
+                "br" => out.push('\n'),
+                "td" | "th" if closing => out.push('\t'),
+                "pre" if closing => pre_depth = pre_depth.saturating_sub(1),
+                "pre" => pre_depth += 1,
+                _ => {}
+            }
+            if BLOCK_TAGS.contains(&name.as_str()) && !out.is_empty() && !out.ends_with('\n') {
+                out.push('\n');
+            }
+            if name == "li" && !closing {
+                out.push_str("- ");
+            }
+            continue;
+        }
+
+        let end = rest
+            .char_indices()
+            .skip(1)
+            .find(|(_, ch)| *ch == '<')
+            .map_or(rest.len(), |(i, _)| i);
+        let (text, tail) = rest.split_at(end);
+        rest = tail;
+        if skip_until.is_some() {
+            continue;
+        }
+
+        let text = decode_entities(text);
+        if pre_depth > 0 {
+            out.push_str(&text);
+            continue;
+        }
+        for ch in text.chars() {
+            if ch.is_whitespace() && ch != '\u{a0}' {
+                if !out.is_empty() && !out.ends_with([' ', '\n', '\t']) {
+                    out.push(' ');
+                }
+            } else {
+                out.push(if ch == '\u{a0}' { ' ' } else { ch });
+            }
+        }
+    }
+
+    let mut lines: Vec<&str> = Vec::new();
+    for line in out.lines().map(|line| line.trim_end_matches([' ', '\t'])) {
+        if line.trim().is_empty() && lines.last().is_none_or(|last| last.trim().is_empty()) {
+            continue;
+        }
+        lines.push(line);
+    }
+    while lines.last().is_some_and(|line| line.trim().is_empty()) {
+        lines.pop();
+    }
+    lines.join("\n")
+}
+
+fn decode_entities(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    let mut rest = text;
+    while let Some(amp) = rest.find('&') {
+        out.push_str(&rest[..amp]);
+        rest = &rest[amp..];
+        let decoded = rest.find(';').filter(|end| *end <= 10).and_then(|end| {
+            let entity = &rest[1..end];
+            let ch = match entity {
+                "amp" => Some('&'),
+                "lt" => Some('<'),
+                "gt" => Some('>'),
+                "quot" => Some('"'),
+                "apos" => Some('\''),
+                "nbsp" => Some('\u{a0}'),
+                _ => entity.strip_prefix('#').and_then(|num| {
+                    let code = match num.strip_prefix(['x', 'X']) {
+                        Some(hex) => u32::from_str_radix(hex, 16).ok()?,
+                        None => num.parse().ok()?,
+                    };
+                    char::from_u32(code)
+                }),
+            }?;
+            Some((ch, end))
+        });
+        match decoded {
+            Some((ch, end)) => {
+                out.push(ch);
+                rest = &rest[end + 1..];
+            }
+            None => {
+                out.push('&');
+                rest = &rest[1..];
+            }
+        }
+    }
+    out.push_str(rest);
+    out
+}
+
+/// Drops CSI, OSC and other escape sequences so pasted text cannot restyle or reprogram the
+/// terminal.
+pub(super) fn strip_escape_sequences(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    let mut chars = text.chars().peekable();
+    while let Some(ch) = chars.next() {
+        if ch != '\x1b' {
+            out.push(ch);
+            continue;
+        }
+        match chars.next() {
+            Some('[') => {
+                for ch in chars.by_ref() {
+                    if ('@'..='~').contains(&ch) {
+                        break;
+                    }
+                }
+            }
+            Some(']' | 'P' | '_' | '^') => {
+                while let Some(ch) = chars.next() {
+                    if ch == '\x07' {
+                        break;
+                    }
+                    if ch == '\x1b' && chars.peek() == Some(&'\\') {
+                        chars.next();
+                        break;
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn html_is_converted_to_plain_text() {
+        let html = "<meta charset=\"utf-8\"><div><b>Hello</b>,\n   world &amp; friends</div>\
+                    <p>second&nbsp;line<br>third</p><ul><li>one</li><li>two</li></ul>\
+                    <pre>  keep\n  indent</pre><style>p { color: red }</style>";
+        assert_eq!(
+            html_to_text(html),
+            "Hello, world & friends\nsecond line\nthird\n- one\n- two\n  keep\n  indent"
+        );
+    }
+
+    #[test]
+    fn paste_text_honors_options() {
+        let html = "<p>a &lt; b</p>";
+        assert_eq!(paste_text(html, true, false, false), "a < b");
+        assert_eq!(paste_text(html, false, false, false), html);
+        assert_eq!(paste_text("x < y", true, false, false), "x < y");
+
+        let styled = "\x1b[31mred\x1b[0m \x1b]0;title\x07plain";
+        assert_eq!(paste_text(styled, true, false, false), "red plain");
+        assert_eq!(paste_text(styled, true, true, false), styled);
+        assert_eq!(
+            paste_text(styled, true, true, true),
+            "\x1b[31mred\x1b[0m \x1b]0;titleplain"
+        );
+
+        let controls = "ls\x03\r\n\tcd\x7f\u{9b}x";
+        assert_eq!(paste_text(controls, false, false, true), "ls\n\tcdx");
+        assert_eq!(paste_text(controls, false, false, false), controls);
+    }
+
+    #[test]
+    fn unsafe_pastes_are_detected() {
+        let none = PastePolicy::new();
+        assert_eq!(none.check("echo hi", false, true), None);
+        assert_eq!(
+            none.check("echo hi\n", false, true),
+            Some(PasteWarning::Multiline)
+        );
+        assert_eq!(none.check("echo hi\n", true, true), None);
+        assert_eq!(none.check("echo hi\n", false, false), None);
+        assert_eq!(
+            none.check("x\x1b[201~rm -rf ~\n", true, true),
+            Some(PasteWarning::BracketedPasteEnd)
+        );
+    }
+
+    #[test]
+    fn paste_policy_limits_lines_and_matches_patterns() {
+        let policy = PastePolicy::new().max_lines(2).destructive_commands();
+        assert_eq!(policy.check("a\nb", true, true), None);
+        assert_eq!(
+            policy.check("a\nb\nc", true, true),
+            Some(PasteWarning::TooManyLines(3))
+        );
+        assert_eq!(
+            policy.check("cd /tmp && rm -rf build", true, true),
+            Some(PasteWarning::Pattern("rm -rf".to_string()))
+        );
+        assert_eq!(
+            policy.check("sudo make install", true, false),
+            Some(PasteWarning::Pattern("sudo".to_string()))
+        );
+        assert_eq!(policy.check("rm file.txt; pseudocode", true, true), None);
+        assert!(PastePolicy::new().pattern("(").is_err());
+    }
+}
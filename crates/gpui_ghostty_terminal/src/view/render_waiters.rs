@@ -0,0 +1,110 @@
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::{Arc, Mutex};
+use std::task::{Context, Poll, Waker};
+
+#[derive(Default)]
+struct Slot {
+    painted: Option<u64>,
+    waker: Option<Waker>,
+}
+
+/// Resolves, with the session generation painted, once the bytes it was created for are on
+/// screen.
+pub(super) struct PaintedFuture {
+    slot: Arc<Mutex<Slot>>,
+}
+
+impl Future for PaintedFuture {
+    type Output = u64;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<u64> {
+        let mut slot = self.slot.lock().unwrap();
+        match slot.painted {
+            Some(generation) => Poll::Ready(generation),
+            None => {
+                slot.waker = Some(cx.waker().clone());
+                Poll::Pending
+            }
+        }
+    }
+}
+
+/// Tracks which fed output has reached the session and the screen, and wakes the futures
+/// returned by `feed_async` once a frame shows their bytes. Progress is measured in
+/// [`TerminalSession::generation`](crate::TerminalSession::generation)s.
+#[derive(Default)]
+pub(super) struct RenderWaiters {
+    /// Waiters whose bytes are still queued.
+    queued: Vec<Arc<Mutex<Slot>>>,
+    /// Waiters whose bytes were applied, with the session generation that includes them.
+    applied: Vec<(u64, Arc<Mutex<Slot>>)>,
+    painted: u64,
+}
+
+impl RenderWaiters {
+    /// Returns a future for bytes about to be queued.
+    pub fn next(&mut self) -> PaintedFuture {
+        let slot = Arc::new(Mutex::new(Slot::default()));
+        self.queued.push(slot.clone());
+        PaintedFuture { slot }
+    }
+
+    /// Everything queued so far has been fed to the session, which is now at `generation`.
+    pub fn mark_applied(&mut self, generation: u64) {
+        self.applied
+            .extend(self.queued.drain(..).map(|slot| (generation, slot)));
+    }
+
+    /// A frame showing session `generation` was painted: resolves the waiters it includes.
+    pub fn mark_painted(&mut self, generation: u64) {
+        self.painted = generation;
+        self.applied.retain(|(applied, slot)| {
+            if *applied > generation {
+                return true;
+            }
+            let mut slot = slot.lock().unwrap();
+            slot.painted = Some(generation);
+            if let Some(waker) = slot.waker.take() {
+                waker.wake();
+            }
+            false
+        });
+    }
+
+    pub fn painted(&self) -> u64 {
+        self.painted
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn poll(future: &mut PaintedFuture) -> Poll<u64> {
+        Pin::new(future).poll(&mut Context::from_waker(Waker::noop()))
+    }
+
+    #[test]
+    fn future_resolves_on_the_first_paint_after_its_bytes_are_applied() {
+        let mut waiters = RenderWaiters::default();
+        let mut first = waiters.next();
+        assert_eq!(poll(&mut first), Poll::Pending);
+
+        // A frame painted before the bytes reached the session does not count.
+        waiters.mark_painted(0);
+        assert_eq!(poll(&mut first), Poll::Pending);
+
+        waiters.mark_applied(3);
+        let mut second = waiters.next();
+        waiters.mark_painted(3);
+        assert_eq!(poll(&mut first), Poll::Ready(3));
+        assert_eq!(poll(&mut second), Poll::Pending);
+
+        // Bytes that changed nothing visible resolve with the next frame.
+        waiters.mark_applied(3);
+        waiters.mark_painted(3);
+        assert_eq!(poll(&mut second), Poll::Ready(3));
+        assert_eq!(waiters.painted(), 3);
+    }
+}
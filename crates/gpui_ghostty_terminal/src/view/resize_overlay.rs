@@ -0,0 +1,76 @@
+use std::time::{Duration, Instant};
+
+use crate::ResizeOverlayPolicy;
+
+/// How long the size stays fully visible after the grid last changed.
+const RESIZE_OVERLAY_VISIBLE: Duration = Duration::from_millis(750);
+/// How long it then takes to fade out.
+const RESIZE_OVERLAY_FADE: Duration = Duration::from_millis(250);
+
+/// The centered "cols×rows" overlay flashed while a view is being resized.
+#[derive(Default)]
+pub(super) struct ResizeOverlay {
+    /// The first fit to the view's bounds has happened.
+    fitted: bool,
+    shown_at: Option<Instant>,
+}
+
+impl ResizeOverlay {
+    /// Notes a grid resize; returns true when the policy shows the overlay for it.
+    pub fn resized(&mut self, policy: ResizeOverlayPolicy, now: Instant) -> bool {
+        let first = !std::mem::replace(&mut self.fitted, true);
+        let show = match policy {
+            ResizeOverlayPolicy::Always => true,
+            ResizeOverlayPolicy::Never => false,
+            ResizeOverlayPolicy::AfterFirst => !first,
+        };
+        if show {
+            self.shown_at = Some(now);
+        }
+        show
+    }
+
+    /// Opacity factor for the overlay: 1.0 right after a resize, fading to 0.0 after.
+    pub fn opacity(&self, now: Instant) -> f32 {
+        let Some(shown_at) = self.shown_at else {
+            return 0.0;
+        };
+        let elapsed = now.saturating_duration_since(shown_at);
+        if elapsed <= RESIZE_OVERLAY_VISIBLE {
+            1.0
+        } else {
+            let fading = (elapsed - RESIZE_OVERLAY_VISIBLE).as_secs_f32()
+                / RESIZE_OVERLAY_FADE.as_secs_f32();
+            (1.0 - fading).max(0.0)
+        }
+    }
+}
+
+/// The overlay's text for a grid size.
+pub(super) fn resize_overlay_label(cols: u16, rows: u16) -> String {
+    format!("{cols}×{rows}")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn overlay_skips_first_fit_and_fades() {
+        let start = Instant::now();
+        let mut overlay = ResizeOverlay::default();
+        assert!(!overlay.resized(ResizeOverlayPolicy::AfterFirst, start));
+        assert_eq!(overlay.opacity(start), 0.0);
+        assert!(overlay.resized(ResizeOverlayPolicy::AfterFirst, start));
+        assert_eq!(overlay.opacity(start + RESIZE_OVERLAY_VISIBLE), 1.0);
+        assert_eq!(
+            overlay.opacity(start + RESIZE_OVERLAY_VISIBLE + RESIZE_OVERLAY_FADE),
+            0.0
+        );
+        assert!(!overlay.resized(ResizeOverlayPolicy::Never, start));
+
+        let mut always = ResizeOverlay::default();
+        assert!(always.resized(ResizeOverlayPolicy::Always, start));
+        assert_eq!(resize_overlay_label(120, 40), "120×40");
+    }
+}
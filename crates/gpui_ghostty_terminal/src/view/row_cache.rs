@@ -0,0 +1,173 @@
+//! Shaped rows kept across frames.
+//!
+//! Rows are keyed by a hash of their text, style runs and the row-local render state (selection,
+//! blink phase), so a row whose content is unchanged is neither reshaped nor has its background
+//! quads rebuilt, even after a full viewport refresh, a scroll or a blink toggle.
+
+use std::collections::HashMap;
+use std::hash::{DefaultHasher, Hash, Hasher};
+use std::ops::Range;
+use std::sync::Arc;
+
+use ghostty_vt::{Rgb, StyleRun};
+
+/// A background rectangle within a row, in pixels from the row's left edge.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub(super) struct RowBackground {
+    pub x: f32,
+    pub width: f32,
+    pub color: gpui::Hsla,
+}
+
+/// A shaped row and its background quads, as cached and as kept per viewport row.
+#[derive(Clone)]
+pub(super) struct ShapedRow {
+    /// The row's [`row_content_hash`].
+    pub hash: u64,
+    pub line: gpui::ShapedLine,
+    pub backgrounds: Arc<[RowBackground]>,
+}
+
+/// Rows shaped in recent frames, keyed by [`row_content_hash`].
+pub(super) struct RowCache<T> {
+    rows: HashMap<u64, (T, u64)>,
+    /// Hash of the frame-wide render settings the cached rows were built with.
+    settings: Option<u64>,
+    frame: u64,
+}
+
+impl<T> Default for RowCache<T> {
+    fn default() -> Self {
+        Self {
+            rows: HashMap::new(),
+            settings: None,
+            frame: 0,
+        }
+    }
+}
+
+impl<T: Clone> RowCache<T> {
+    /// Starts a frame rendered with the given settings hash, dropping every row if the settings
+    /// changed since the last frame.
+    pub fn begin_frame(&mut self, settings: u64) {
+        if self.settings != Some(settings) {
+            self.rows.clear();
+            self.settings = Some(settings);
+        }
+        self.frame += 1;
+    }
+
+    pub fn get(&mut self, hash: u64) -> Option<T> {
+        let (row, used) = self.rows.get_mut(&hash)?;
+        *used = self.frame;
+        Some(row.clone())
+    }
+
+    /// Marks a row as used this frame without fetching it.
+    pub fn touch(&mut self, hash: u64) {
+        if let Some((_, used)) = self.rows.get_mut(&hash) {
+            *used = self.frame;
+        }
+    }
+
+    pub fn insert(&mut self, hash: u64, row: T) {
+        self.rows.insert(hash, (row, self.frame));
+    }
+
+    /// Ends a frame. Once more than `capacity` rows are kept, rows unused this frame are dropped.
+    pub fn end_frame(&mut self, capacity: usize) {
+        if self.rows.len() > capacity {
+            let frame = self.frame;
+            self.rows.retain(|_, (_, used)| *used == frame);
+        }
+    }
+
+    pub fn clear(&mut self) {
+        self.rows.clear();
+        self.settings = None;
+    }
+
+    #[cfg(test)]
+    fn len(&self) -> usize {
+        self.rows.len()
+    }
+}
+
+/// Hash of everything that affects how a row is shaped and filled: its text, style runs, the
+/// part of it that is selected and whether its blinking text is currently hidden.
+pub(super) fn row_content_hash(
+    text: &str,
+    runs: &[StyleRun],
+    selected: Option<Range<usize>>,
+    blink_hidden: bool,
+) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    text.hash(&mut hasher);
+    for run in runs {
+        (run.start_col, run.end_col, run.flags, run.underline).hash(&mut hasher);
+        hash_rgb(run.fg, &mut hasher);
+        hash_rgb(run.bg, &mut hasher);
+        run.underline_color.is_some().hash(&mut hasher);
+        if let Some(color) = run.underline_color {
+            hash_rgb(color, &mut hasher);
+        }
+    }
+    selected.hash(&mut hasher);
+    blink_hidden.hash(&mut hasher);
+    hasher.finish()
+}
+
+pub(super) fn hash_rgb(rgb: Rgb, hasher: &mut impl Hasher) {
+    (rgb.r, rgb.g, rgb.b).hash(hasher);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ghostty_vt::Underline;
+
+    fn run(fg: Rgb) -> StyleRun {
+        StyleRun {
+            start_col: 1,
+            end_col: 3,
+            fg,
+            bg: Rgb { r: 0, g: 0, b: 0 },
+            flags: 0,
+            underline: Underline::None,
+            underline_color: None,
+            hyperlink_id: None,
+        }
+    }
+
+    #[test]
+    fn rows_are_keyed_by_content_and_evicted_when_unused() {
+        let red = run(Rgb { r: 255, g: 0, b: 0 });
+        let blue = run(Rgb { r: 0, g: 0, b: 255 });
+        let base = row_content_hash("abc", &[red], None, false);
+        assert_eq!(base, row_content_hash("abc", &[red], None, false));
+        assert_ne!(base, row_content_hash("abc", &[blue], None, false));
+        assert_ne!(base, row_content_hash("abd", &[red], None, false));
+        assert_ne!(base, row_content_hash("abc", &[red], Some(0..1), false));
+        assert_ne!(base, row_content_hash("abc", &[red], None, true));
+
+        let mut cache = RowCache::default();
+        cache.begin_frame(1);
+        cache.insert(1, "one");
+        cache.insert(2, "two");
+        cache.end_frame(8);
+
+        cache.begin_frame(1);
+        cache.touch(2);
+        cache.end_frame(8);
+        assert_eq!(cache.len(), 2);
+
+        cache.begin_frame(1);
+        assert_eq!(cache.get(1), Some("one"));
+        cache.end_frame(1);
+        assert_eq!(cache.len(), 1);
+        assert_eq!(cache.get(2), None);
+
+        cache.begin_frame(2);
+        assert_eq!(cache.get(1), None);
+    }
+}
@@ -0,0 +1,171 @@
+use std::time::{Duration, Instant};
+
+/// How long the scrollbar stays fully visible after the viewport last moved.
+const SCROLLBAR_VISIBLE: Duration = Duration::from_millis(1000);
+/// How long it then takes to fade out.
+const SCROLLBAR_FADE: Duration = Duration::from_millis(300);
+/// Width of the drawn thumb.
+pub(super) const SCROLLBAR_WIDTH: f32 = 6.0;
+/// Gap between the thumb and the right edge of the view.
+pub(super) const SCROLLBAR_MARGIN: f32 = 2.0;
+/// Width of the strip along the right edge that grabs the mouse while the scrollbar shows.
+const SCROLLBAR_HIT_WIDTH: f32 = 12.0;
+/// Shortest thumb drawn, so it stays grabbable in long scrollback.
+const SCROLLBAR_MIN_THUMB: f32 = 20.0;
+
+/// Vertical extent of the scrollbar thumb, in pixels from the top of the view.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub(super) struct Thumb {
+    pub top: f32,
+    pub height: f32,
+}
+
+/// Thumb for a view `height` pixels tall showing `rows` of `total_lines`, scrolled back by
+/// `offset` rows. `None` when everything fits and there is nothing to scroll.
+pub(super) fn scrollbar_thumb(
+    height: f32,
+    total_lines: u32,
+    rows: u32,
+    offset: u32,
+) -> Option<Thumb> {
+    if total_lines <= rows || height <= 0.0 {
+        return None;
+    }
+    let scrollable = (total_lines - rows) as f32;
+    let thumb_height = (height * rows as f32 / total_lines as f32)
+        .max(SCROLLBAR_MIN_THUMB)
+        .min(height);
+    let travel = height - thumb_height;
+    let from_top = scrollable - offset.min(total_lines - rows) as f32;
+    Some(Thumb {
+        top: travel * from_top / scrollable,
+        height: thumb_height,
+    })
+}
+
+/// Screen-absolute row to put at the top of the viewport when the thumb's top is dragged to
+/// `thumb_top` pixels.
+pub(super) fn row_for_thumb_top(
+    thumb_top: f32,
+    height: f32,
+    thumb: Thumb,
+    total_lines: u32,
+    rows: u32,
+) -> u32 {
+    let scrollable = total_lines.saturating_sub(rows);
+    let travel = height - thumb.height;
+    if travel <= 0.0 {
+        return 0;
+    }
+    let fraction = (thumb_top / travel).clamp(0.0, 1.0);
+    (fraction * scrollable as f32).round() as u32
+}
+
+/// Overlay scrollbar state: when the viewport last moved and any drag in progress.
+#[derive(Default)]
+pub(super) struct Scrollbar {
+    last_top_row: Option<u32>,
+    shown_at: Option<Instant>,
+    /// Distance from the thumb's top to the pointer while dragging.
+    drag_grab: Option<f32>,
+}
+
+impl Scrollbar {
+    /// Notes the viewport's top row; returns true when it moved, which shows the scrollbar.
+    pub fn track(&mut self, top_row: u32, now: Instant) -> bool {
+        let moved = self.last_top_row.is_some_and(|last| last != top_row);
+        self.last_top_row = Some(top_row);
+        if moved {
+            self.shown_at = Some(now);
+        }
+        moved
+    }
+
+    /// Opacity factor for the thumb: 1.0 while scrolling or dragging, fading to 0.0 after.
+    pub fn opacity(&self, now: Instant) -> f32 {
+        if self.drag_grab.is_some() {
+            return 1.0;
+        }
+        let Some(shown_at) = self.shown_at else {
+            return 0.0;
+        };
+        let elapsed = now.saturating_duration_since(shown_at);
+        if elapsed <= SCROLLBAR_VISIBLE {
+            1.0
+        } else {
+            let fading = (elapsed - SCROLLBAR_VISIBLE).as_secs_f32() / SCROLLBAR_FADE.as_secs_f32();
+            (1.0 - fading).max(0.0)
+        }
+    }
+
+    /// Whether a press at local `x` (of a view `width` wide) lands on the visible scrollbar.
+    pub fn hit(&self, x: f32, width: f32, now: Instant) -> bool {
+        self.opacity(now) > 0.0 && x >= width - SCROLLBAR_HIT_WIDTH
+    }
+
+    /// Starts a drag at local `y`. Pressing off the thumb centers it under the pointer first.
+    pub fn begin_drag(&mut self, y: f32, thumb: Thumb) {
+        let grab = if y >= thumb.top && y <= thumb.top + thumb.height {
+            y - thumb.top
+        } else {
+            thumb.height / 2.0
+        };
+        self.drag_grab = Some(grab);
+    }
+
+    /// Thumb top for a pointer at local `y` during a drag.
+    pub fn drag_thumb_top(&self, y: f32) -> Option<f32> {
+        self.drag_grab.map(|grab| y - grab)
+    }
+
+    /// Ends a drag; returns true if one was in progress. The fade restarts from now.
+    pub fn end_drag(&mut self, now: Instant) -> bool {
+        let dragging = self.drag_grab.take().is_some();
+        if dragging {
+            self.shown_at = Some(now);
+        }
+        dragging
+    }
+
+    pub fn is_dragging(&self) -> bool {
+        self.drag_grab.is_some()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn thumb_tracks_scroll_position_and_fades() {
+        assert_eq!(scrollbar_thumb(480.0, 24, 24, 0), None);
+
+        let bottom = scrollbar_thumb(480.0, 96, 24, 0).unwrap();
+        assert_eq!(bottom.height, 120.0);
+        assert_eq!(bottom.top, 360.0);
+        let top = scrollbar_thumb(480.0, 96, 24, 72).unwrap();
+        assert_eq!(top.top, 0.0);
+        assert_eq!(row_for_thumb_top(top.top, 480.0, top, 96, 24), 0);
+        assert_eq!(row_for_thumb_top(180.0, 480.0, top, 96, 24), 36);
+        assert_eq!(row_for_thumb_top(900.0, 480.0, top, 96, 24), 72);
+
+        let start = Instant::now();
+        let mut scrollbar = Scrollbar::default();
+        assert!(!scrollbar.track(72, start));
+        assert_eq!(scrollbar.opacity(start), 0.0);
+        assert!(scrollbar.track(60, start));
+        assert_eq!(scrollbar.opacity(start + SCROLLBAR_VISIBLE), 1.0);
+        assert_eq!(
+            scrollbar.opacity(start + SCROLLBAR_VISIBLE + SCROLLBAR_FADE),
+            0.0
+        );
+        assert!(scrollbar.hit(795.0, 800.0, start));
+        assert!(!scrollbar.hit(700.0, 800.0, start));
+
+        scrollbar.begin_drag(10.0, top);
+        assert_eq!(scrollbar.drag_thumb_top(50.0), Some(40.0));
+        assert_eq!(scrollbar.opacity(start + SCROLLBAR_VISIBLE * 5), 1.0);
+        assert!(scrollbar.end_drag(start));
+        assert!(!scrollbar.is_dragging());
+    }
+}
@@ -0,0 +1,89 @@
+use gpui::{App, Entity};
+
+use super::TerminalView;
+use crate::SearchMatch;
+
+/// A match found by [`search_all_panes`], tagged with the pane it came from.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct PaneSearchMatch<P> {
+    /// The identifier the container passed in for the pane.
+    pub pane: P,
+    pub search_match: SearchMatch,
+    /// Text of the matching row, for showing in a result list.
+    pub line: String,
+}
+
+/// Runs `pattern` over the scrollback of every pane, in the order given, for a container-level
+/// "search all panes" result list. Pass a picked result to
+/// [`TerminalView::reveal_search_match`] on its pane to focus it and scroll to the match.
+pub fn search_all_panes<'a, P: Clone>(
+    panes: impl IntoIterator<Item = (P, &'a Entity<TerminalView>)>,
+    pattern: &str,
+    cx: &App,
+) -> Vec<PaneSearchMatch<P>> {
+    let mut results = Vec::new();
+    if pattern.is_empty() {
+        return results;
+    }
+    for (pane, view) in panes {
+        let session = &view.read(cx).session;
+        let mut line: Option<(u32, String)> = None;
+        for search_match in session.search_all(pattern) {
+            if line
+                .as_ref()
+                .is_none_or(|(row, _)| *row != search_match.row)
+            {
+                let text = session
+                    .dump_screen_row(search_match.row)
+                    .unwrap_or_default();
+                line = Some((search_match.row, text));
+            }
+            results.push(PaneSearchMatch {
+                pane: pane.clone(),
+                search_match,
+                line: line
+                    .as_ref()
+                    .map(|(_, text)| text.clone())
+                    .unwrap_or_default(),
+            });
+        }
+    }
+    results
+}
+
+/// State of the find bar while it is open.
+#[derive(Default)]
+pub(super) struct SearchBar {
+    pub query: String,
+    pub matches: Vec<SearchMatch>,
+    pub current: Option<SearchMatch>,
+}
+
+impl SearchBar {
+    /// Text shown in the find bar, e.g. `Find: foo  2/5`.
+    pub fn label(&self) -> String {
+        let position = match self.current {
+            Some(current) => {
+                let index = self
+                    .matches
+                    .iter()
+                    .position(|m| *m == current)
+                    .map(|i| i + 1)
+                    .unwrap_or(0);
+                format!("{}/{}", index, self.matches.len())
+            }
+            None if self.query.is_empty() => String::new(),
+            None => "no matches".to_string(),
+        };
+        format!("Find: {}  {}", self.query, position)
+    }
+
+    /// Matches that fall within `top..top + rows` (screen-absolute rows).
+    pub fn visible_matches(&self, top: u32, rows: u16) -> impl Iterator<Item = SearchMatch> + '_ {
+        let bottom = top.saturating_add(u32::from(rows));
+        self.matches
+            .iter()
+            .copied()
+            .filter(move |m| m.row >= top && m.row < bottom)
+    }
+}
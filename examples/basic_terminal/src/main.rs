@@ -1,14 +1,12 @@
 fn main() {
     use gpui::{App, AppContext, Application, KeyBinding, WindowOptions};
-    use gpui_ghostty_terminal::{
-        TerminalConfig, TerminalSession, view::Copy, view::Paste, view::SelectAll,
-    };
+    use gpui_ghostty_terminal::prelude::*;
 
     Application::new().run(|cx: &mut App| {
         cx.bind_keys([
-            KeyBinding::new("cmd-a", SelectAll, None),
-            KeyBinding::new("cmd-c", Copy, None),
-            KeyBinding::new("cmd-v", Paste, None),
+            KeyBinding::new("cmd-a", actions::SelectAll, None),
+            KeyBinding::new("cmd-c", actions::Copy, None),
+            KeyBinding::new("cmd-v", actions::Paste, None),
         ]);
 
         cx.open_window(WindowOptions::default(), |window, cx| {
@@ -18,7 +16,7 @@ fn main() {
 
                 let mut session = TerminalSession::new(TerminalConfig::default()).unwrap();
                 session.feed(b"Hello from GPUI + Ghostty VT\r\n").unwrap();
-                gpui_ghostty_terminal::view::TerminalView::new(session, focus_handle)
+                TerminalView::new(session, focus_handle)
             })
         })
         .unwrap();
@@ -4,21 +4,30 @@ use std::thread;
 use std::time::Duration;
 
 use gpui::{App, AppContext, Application, KeyBinding, px};
-use gpui_ghostty_terminal::view::{
-    Copy, CopyLastOutput, Paste, SelectAll, TerminalInput, TerminalView,
-};
-use gpui_ghostty_terminal::{
-    TerminalConfig, TerminalSession, load_config, terminal_font, window_options_for_config,
-};
+use gpui_ghostty_terminal::prelude::*;
 use portable_pty::{CommandBuilder, PtySize, native_pty_system};
 
+#[cfg(unix)]
+fn foreground_runs_as_root(master: &dyn portable_pty::MasterPty) -> bool {
+    master
+        .process_group_leader()
+        .and_then(|pid| u32::try_from(pid).ok())
+        .and_then(process_runs_as_root)
+        .unwrap_or(false)
+}
+
+#[cfg(not(unix))]
+fn foreground_runs_as_root(_master: &dyn portable_pty::MasterPty) -> bool {
+    false
+}
+
 fn main() {
     Application::new().run(|cx: &mut App| {
         cx.bind_keys([
-            KeyBinding::new("cmd-a", SelectAll, None),
-            KeyBinding::new("cmd-c", Copy, None),
-            KeyBinding::new("cmd-shift-c", CopyLastOutput, None),
-            KeyBinding::new("cmd-v", Paste, None),
+            KeyBinding::new("cmd-a", actions::SelectAll, None),
+            KeyBinding::new("cmd-c", actions::Copy, None),
+            KeyBinding::new("cmd-shift-c", actions::CopyLastOutput, None),
+            KeyBinding::new("cmd-v", actions::Paste, None),
         ]);
 
         // Load config before opening window so we can set background appearance
@@ -38,30 +47,19 @@ fn main() {
 
             let master = pty_pair.master;
 
-            // Use command from config, or fall back to $SHELL, or /bin/zsh
-            let shell_cmd = config.command.clone().unwrap_or_else(|| {
-                std::env::var("SHELL").unwrap_or_else(|_| "/bin/zsh".to_string())
-            });
-
-            // Parse command - if it contains spaces, treat as command + args
-            let mut parts = shell_cmd.split_whitespace();
-            let shell = parts.next().unwrap_or("/bin/zsh");
-            let mut cmd = CommandBuilder::new(shell);
-
-            // Add remaining parts as arguments
-            for arg in parts {
-                cmd.arg(arg);
+            // The config's command (or $SHELL as a login shell) with this terminal's TERM,
+            // COLORTERM and TERM_PROGRAM.
+            let spawn = SpawnOptions::from_config(&config);
+            let (program, args) = spawn.program_and_args();
+            let mut cmd = CommandBuilder::new(program);
+            cmd.args(args);
+            if let Some(cwd) = spawn.working_dir() {
+                cmd.cwd(cwd);
             }
-
-            // If no args were in the command string, add login flag for shells
-            if shell_cmd.split_whitespace().count() == 1 {
-                cmd.arg("-l");
+            for (key, value) in spawn.environment() {
+                cmd.env(key, value);
             }
 
-            cmd.env("TERM", "xterm-256color");
-            cmd.env("COLORTERM", "truecolor");
-            cmd.env("TERM_PROGRAM", "gpui-ghostty");
-
             // Enable Ghostty shell integration so fish emits OSC 133 markers.
             // Fish auto-sources vendor_conf.d/*.fish files from XDG_DATA_DIRS;
             // prepending the vendored integration directory activates it.
@@ -84,16 +82,23 @@ fn main() {
                 .spawn_command(cmd)
                 .expect("spawn shell failed");
 
+            let (exit_tx, exit_rx) = mpsc::channel::<Option<i32>>();
             thread::spawn(move || {
-                let _ = child.wait();
+                let exit_code = child
+                    .wait()
+                    .ok()
+                    .and_then(|status| i32::try_from(status.exit_code()).ok());
+                let _ = exit_tx.send(exit_code);
             });
 
             let mut pty_reader = master.try_clone_reader().expect("pty reader");
             let mut pty_writer = master.take_writer().expect("pty writer");
 
-            let (stdin_tx, stdin_rx) = mpsc::channel::<Vec<u8>>();
+            // Bounded so a stalled PTY writer refuses input and the view queues it.
+            let (stdin_tx, stdin_rx) = mpsc::sync_channel::<Vec<u8>>(64);
             let (stdout_tx, stdout_rx) = mpsc::channel::<Vec<u8>>();
             let (resize_tx, resize_rx) = mpsc::channel::<(u16, u16)>();
+            let (root_tx, root_rx) = mpsc::channel::<bool>();
 
             if let Ok(cmd) = std::env::var("GPUI_GHOSTTY_PTY_DEMO_COMMAND") {
                 let stdin_tx = stdin_tx.clone();
@@ -129,15 +134,31 @@ fn main() {
             });
 
             // Handle PTY resize events in a separate thread
-            // Move the master into this thread since it owns the resize capability
+            // Move the master into this thread since it owns the resize capability.
+            // Between resizes, check once a second whether the foreground process runs as root.
             thread::spawn(move || {
-                while let Ok((cols, rows)) = resize_rx.recv() {
-                    let _ = master.resize(PtySize {
-                        rows,
-                        cols,
-                        pixel_width: 0,
-                        pixel_height: 0,
-                    });
+                let mut root = false;
+                loop {
+                    match resize_rx.recv_timeout(Duration::from_secs(1)) {
+                        Ok((cols, rows)) => {
+                            let _ = master.resize(PtySize {
+                                rows,
+                                cols,
+                                pixel_width: 0,
+                                pixel_height: 0,
+                            });
+                        }
+                        Err(mpsc::RecvTimeoutError::Timeout) => {}
+                        Err(mpsc::RecvTimeoutError::Disconnected) => break,
+                    }
+
+                    let now_root = foreground_runs_as_root(master.as_ref());
+                    if now_root != root {
+                        root = now_root;
+                        if root_tx.send(root).is_err() {
+                            break;
+                        }
+                    }
                 }
             });
 
@@ -145,10 +166,9 @@ fn main() {
             let config_for_view = config.clone();
 
             // Set up PTY resize callback
-            let resize_callback =
-                gpui_ghostty_terminal::view::TerminalResizeCallback::new(move |cols, rows| {
-                    let _ = resize_tx.send((cols, rows));
-                });
+            let resize_callback = TerminalResizeCallback::new(move |cols, rows| {
+                let _ = resize_tx.send((cols, rows));
+            });
 
             let view = cx.new(|cx| {
                 let focus_handle = cx.focus_handle();
@@ -156,8 +176,8 @@ fn main() {
 
                 let session = TerminalSession::new(config).expect("vt init");
                 let stdin_tx = stdin_tx.clone();
-                let input = TerminalInput::new(move |bytes| {
-                    let _ = stdin_tx.send(bytes.to_vec());
+                let input = TerminalInput::new_fallible(move |bytes| {
+                    stdin_tx.try_send(bytes.to_vec()).is_ok()
                 });
 
                 let mut view = TerminalView::new_with_input(session, focus_handle, input);
@@ -171,6 +191,14 @@ fn main() {
                 view
             });
 
+            // Enter on the "process exited" overlay (or `process-exit = close`) quits.
+            cx.observe(&view, |view, cx| {
+                if view.read(cx).is_close_requested() {
+                    cx.quit();
+                }
+            })
+            .detach();
+
             let view_for_task = view.clone();
             window
                 .spawn(cx, async move |cx| {
@@ -182,16 +210,29 @@ fn main() {
                         while let Ok(chunk) = stdout_rx.try_recv() {
                             batch.extend_from_slice(&chunk);
                         }
-                        if batch.is_empty() {
+                        let root = root_rx.try_iter().last();
+                        let exit = exit_rx.try_recv().ok();
+                        if batch.is_empty() && root.is_none() && exit.is_none() {
                             continue;
                         }
 
                         cx.update(|_, cx| {
                             view_for_task.update(cx, |this, cx| {
-                                this.queue_output_bytes(&batch, cx);
+                                if let Some(root) = root {
+                                    this.set_foreground_root(root, cx);
+                                }
+                                if !batch.is_empty() {
+                                    this.queue_output_bytes(&batch, cx);
+                                }
+                                if let Some(exit_code) = exit {
+                                    this.process_exited(exit_code, cx);
+                                }
                             });
                         })
                         .ok();
+                        if exit.is_some() {
+                            break;
+                        }
                     }
                 })
                 .detach();
@@ -1,52 +1,72 @@
 use std::io::{Read, Write};
-use std::sync::Arc;
 use std::sync::mpsc;
 use std::thread;
 use std::time::Duration;
 
 use gpui::{
-    App, Application, CursorStyle, Entity, KeyBinding, SharedString, Window, WindowOptions, div,
-    prelude::*, px, rgba,
+    App, Application, CursorStyle, Entity, KeyBinding, WeakEntity, Window, WindowOptions, actions,
+    div, prelude::*, px, rgba,
 };
-use gpui_ghostty_terminal::view::{Copy, Paste, SelectAll, TerminalInput, TerminalView};
-use gpui_ghostty_terminal::{TerminalConfig, TerminalSession, default_terminal_font};
+use gpui_ghostty_terminal::TerminalPanelEvent;
+use gpui_ghostty_terminal::prelude::*;
 use portable_pty::{CommandBuilder, PtySize, native_pty_system};
 
-struct Pane {
-    view: Entity<TerminalView>,
-    master: Arc<dyn portable_pty::MasterPty + Send>,
-    stdout_rx: mpsc::Receiver<Vec<u8>>,
+actions!(split_pty_terminal, [SearchAllPanes]);
+
+/// Creates a pane running a login shell; `TerminalPanel` calls this for every new pane. Enter on
+/// the "process exited" overlay starts a fresh shell in the same pane.
+fn spawn_shell_pane(_window: &mut Window, cx: &mut App) -> Entity<TerminalView> {
+    let view = cx.new(|cx| {
+        let session = TerminalSession::new(TerminalConfig::default()).expect("vt init");
+        let mut view = TerminalView::new(session, cx.focus_handle());
+        view.set_restart_handler(start_shell);
+        view
+    });
+    start_shell(view.downgrade(), cx);
+    view
 }
 
-fn spawn_shell_pane(cx: &mut gpui::App) -> Pane {
-    let config = TerminalConfig::default();
+/// Spawns a login shell on a PTY sized to the view and wires it to the view.
+fn start_shell(view: WeakEntity<TerminalView>, cx: &mut App) {
+    let Some((cols, rows)) = view
+        .read_with(cx, |view, _| (view.session().cols(), view.session().rows()))
+        .ok()
+    else {
+        return;
+    };
 
     let pty_system = native_pty_system();
     let pty_pair = pty_system
         .openpty(PtySize {
-            rows: config.rows,
-            cols: config.cols,
+            rows,
+            cols,
             pixel_width: 0,
             pixel_height: 0,
         })
         .expect("openpty failed");
 
-    let master: Arc<dyn portable_pty::MasterPty + Send> = Arc::from(pty_pair.master);
+    let master = pty_pair.master;
 
-    let shell = std::env::var("SHELL").unwrap_or_else(|_| "/bin/zsh".to_string());
-    let mut cmd = CommandBuilder::new(shell);
-    cmd.arg("-l");
-    cmd.env("TERM", "xterm-256color");
-    cmd.env("COLORTERM", "truecolor");
-    cmd.env("TERM_PROGRAM", "gpui-ghostty");
+    let spawn = SpawnOptions::new();
+    let (program, args) = spawn.program_and_args();
+    let mut cmd = CommandBuilder::new(program);
+    cmd.args(args);
+    for (key, value) in spawn.environment() {
+        cmd.env(key, value);
+    }
 
     let mut child = pty_pair
         .slave
         .spawn_command(cmd)
         .expect("spawn login shell failed");
 
+    let (exit_tx, exit_rx) = mpsc::channel::<Option<i32>>();
     thread::spawn(move || {
-        let _ = child.wait();
+        let exit_code = child
+            .wait()
+            .ok()
+            .and_then(|status| i32::try_from(status.exit_code()).ok());
+        let _ = exit_tx.send(exit_code);
     });
 
     let mut pty_reader = master.try_clone_reader().expect("pty reader");
@@ -54,6 +74,7 @@ fn spawn_shell_pane(cx: &mut gpui::App) -> Pane {
 
     let (stdin_tx, stdin_rx) = mpsc::channel::<Vec<u8>>();
     let (stdout_tx, stdout_rx) = mpsc::channel::<Vec<u8>>();
+    let (resize_tx, resize_rx) = mpsc::channel::<(u16, u16)>();
 
     thread::spawn(move || {
         while let Ok(bytes) = stdin_rx.recv() {
@@ -76,167 +97,175 @@ fn spawn_shell_pane(cx: &mut gpui::App) -> Pane {
         }
     });
 
-    let view = cx.new(|cx| {
-        let focus_handle = cx.focus_handle();
-        let session = TerminalSession::new(config).expect("vt init");
-        let input = TerminalInput::new(move |bytes| {
-            let _ = stdin_tx.send(bytes.to_vec());
-        });
-        TerminalView::new_with_input(session, focus_handle, input)
+    // The view sizes its grid to the pane and reports the new size; forward it to the PTY.
+    thread::spawn(move || {
+        while let Ok((cols, rows)) = resize_rx.recv() {
+            let _ = master.resize(PtySize {
+                rows,
+                cols,
+                pixel_width: 0,
+                pixel_height: 0,
+            });
+        }
     });
 
-    Pane {
-        view,
-        master,
-        stdout_rx,
-    }
-}
+    let input = TerminalInput::new(move |bytes| {
+        let _ = stdin_tx.send(bytes.to_vec());
+    });
+    let resize_callback = TerminalResizeCallback::new(move |cols, rows| {
+        let _ = resize_tx.send((cols, rows));
+    });
+    view.update(cx, |view, cx| {
+        view.attach_process(input, resize_callback, cx)
+    })
+    .ok();
 
-fn compute_cell_metrics(window: &mut Window) -> Option<(f32, f32)> {
-    let mut style = window.text_style();
-    let font = default_terminal_font();
-    style.font_family = font.family.clone();
-    style.font_features = gpui_ghostty_terminal::default_terminal_font_features();
-    style.font_fallbacks = font.fallbacks.clone();
-
-    let rem_size = window.rem_size();
-    let font_size = style.font_size.to_pixels(rem_size);
-    let line_height = style.line_height.to_pixels(style.font_size, rem_size);
-
-    let run = style.to_run(1);
-    let lines = window
-        .text_system()
-        .shape_text(SharedString::from("M"), font_size, &[run], None, Some(1))
-        .ok()?;
-    let line = lines.first()?;
-
-    let cell_width = f32::from(line.width()).max(1.0);
-    let cell_height = f32::from(line_height).max(1.0);
-    Some((cell_width, cell_height))
+    cx.spawn(async move |cx| {
+        loop {
+            cx.background_executor()
+                .timer(Duration::from_millis(16))
+                .await;
+
+            let exit = exit_rx.try_recv().ok();
+            let batch: Vec<u8> = stdout_rx.try_iter().flatten().collect();
+            if batch.is_empty() && exit.is_none() {
+                continue;
+            }
+
+            let updated = view.update(cx, |view, cx| {
+                if !batch.is_empty() {
+                    view.queue_output_bytes(&batch, cx);
+                }
+                if let Some(exit_code) = exit {
+                    // Shows the exit overlay; the panel closes the pane on Enter.
+                    view.process_exited(exit_code, cx);
+                }
+            });
+            if updated.is_err() || exit.is_some() {
+                break;
+            }
+        }
+    })
+    .detach();
 }
 
 struct SplitTerminal {
-    left: Entity<TerminalView>,
-    right: Entity<TerminalView>,
+    panel: Entity<TerminalPanel>,
+    /// Query and results of the last "search all panes", while the result list is open.
+    all_panes_search: Option<(String, Vec<PaneSearchMatch<usize>>)>,
 }
 
+impl SplitTerminal {
+    /// Runs the open find bar's query (cmd-f) across all panes, or closes the result list.
+    fn search_all_panes(
+        &mut self,
+        _: &SearchAllPanes,
+        _: &mut Window,
+        cx: &mut gpui::Context<Self>,
+    ) {
+        if self.all_panes_search.take().is_none() {
+            let panes = self.panel.read(cx).all_panes();
+            let query = panes
+                .iter()
+                .find_map(|view| view.read(cx).search_query().map(str::to_string))
+                .unwrap_or_default();
+            let results = search_all_panes(panes.iter().enumerate(), &query, cx);
+            self.all_panes_search = Some((query, results));
+        }
+        cx.notify();
+    }
+
+    fn reveal(
+        &mut self,
+        result: &PaneSearchMatch<usize>,
+        window: &mut Window,
+        cx: &mut gpui::Context<Self>,
+    ) {
+        let Some((query, _)) = self.all_panes_search.take() else {
+            return;
+        };
+        if let Some(view) = self.panel.read(cx).all_panes().get(result.pane) {
+            view.update(cx, |view, cx| {
+                view.reveal_search_match(&query, result.search_match, window, cx)
+            });
+        }
+        cx.notify();
+    }
+}
 impl Render for SplitTerminal {
-    fn render(&mut self, _: &mut Window, _: &mut gpui::Context<Self>) -> impl IntoElement {
+    fn render(&mut self, _: &mut Window, cx: &mut gpui::Context<Self>) -> impl IntoElement {
+        let results =
+            self.all_panes_search.as_ref().map(|(query, results)| {
+                let header = if query.is_empty() {
+                    "Type a query in a pane's find bar (cmd-f) first".to_string()
+                } else {
+                    format!("{} matches for \"{}\" in all panes", results.len(), query)
+                };
+                div()
+                    .id("all-panes-search")
+                    .absolute()
+                    .bottom_0()
+                    .left_0()
+                    .w_full()
+                    .max_h(px(240.))
+                    .overflow_y_scroll()
+                    .bg(rgba(0x1e1e1eff))
+                    .border_t_1()
+                    .border_color(rgba(0x404040ff))
+                    .text_color(rgba(0xd0d0d0ff))
+                    .child(div().px(px(8.)).py(px(4.)).child(header))
+                    .children(results.iter().enumerate().map(|(ix, result)| {
+                        let result = result.clone();
+                        div()
+                            .id(ix)
+                            .px(px(8.))
+                            .cursor_pointer()
+                            .hover(|row| row.bg(rgba(0x2a2d2eff)))
+                            .child(format!(
+                                "[pane {}] {}: {}",
+                                result.pane + 1,
+                                result.search_match.row,
+                                result.line.trim()
+                            ))
+                            .on_click(cx.listener(move |this, _, window, cx| {
+                                this.reveal(&result, window, cx)
+                            }))
+                    }))
+            });
+
         div()
             .size_full()
             .flex()
             .flex_row()
+            .relative()
             .cursor(CursorStyle::IBeam)
-            .child(div().flex_1().h_full().child(self.left.clone()))
-            .child(div().w(px(1.)).h_full().bg(rgba(0x404040ff)))
-            .child(div().flex_1().h_full().child(self.right.clone()))
+            .on_action(cx.listener(Self::search_all_panes))
+            .child(self.panel.clone())
+            .children(results)
     }
 }
 
 fn main() {
     Application::new().run(|cx: &mut App| {
         cx.bind_keys([
-            KeyBinding::new("cmd-a", SelectAll, None),
-            KeyBinding::new("cmd-c", Copy, None),
-            KeyBinding::new("cmd-v", Paste, None),
+            KeyBinding::new("cmd-a", actions::SelectAll, None),
+            KeyBinding::new("cmd-c", actions::Copy, None),
+            KeyBinding::new("cmd-v", actions::Paste, None),
+            KeyBinding::new("cmd-shift-f", SearchAllPanes, None),
         ]);
 
         cx.open_window(WindowOptions::default(), |window, cx| {
-            let left = spawn_shell_pane(cx);
-            let right = spawn_shell_pane(cx);
-
-            let left_view = left.view.clone();
-            let right_view = right.view.clone();
-            let left_view_for_resize = left_view.clone();
-            let right_view_for_resize = right_view.clone();
-            let left_view_for_task = left_view.clone();
-            let right_view_for_task = right_view.clone();
-
-            let left_master = left.master.clone();
-            let right_master = right.master.clone();
-
-            let left_rx = left.stdout_rx;
-            let right_rx = right.stdout_rx;
-
-            let split = cx.new(|_| SplitTerminal {
-                left: left_view.clone(),
-                right: right_view.clone(),
-            });
-
-            let subscription = split.update(cx, |_, cx| {
-                cx.observe_window_bounds(window, move |_, window, cx| {
-                    let size = window.viewport_size();
-                    let width = f32::from(size.width).max(1.0);
-                    let height = f32::from(size.height).max(1.0);
-
-                    let Some((cell_width, cell_height)) = compute_cell_metrics(window) else {
-                        return;
-                    };
-
-                    let divider_width = 1.0f32;
-                    let pane_width = ((width - divider_width) / 2.0).max(1.0);
-
-                    let cols = (pane_width / cell_width).floor().max(1.0) as u16;
-                    let rows = (height / cell_height).floor().max(1.0) as u16;
-
-                    let _ = left_master.resize(PtySize {
-                        rows,
-                        cols,
-                        pixel_width: 0,
-                        pixel_height: 0,
-                    });
-                    let _ = right_master.resize(PtySize {
-                        rows,
-                        cols,
-                        pixel_width: 0,
-                        pixel_height: 0,
-                    });
-
-                    left_view_for_resize
-                        .update(cx, |this, cx| this.resize_terminal(cols, rows, cx));
-                    right_view_for_resize
-                        .update(cx, |this, cx| this.resize_terminal(cols, rows, cx));
-                })
-            });
-            subscription.detach();
-
-            window
-                .spawn(cx, async move |cx| {
-                    loop {
-                        cx.background_executor()
-                            .timer(Duration::from_millis(16))
-                            .await;
-
-                        let mut left_batch = Vec::new();
-                        while let Ok(chunk) = left_rx.try_recv() {
-                            left_batch.extend_from_slice(&chunk);
-                        }
-                        if !left_batch.is_empty() {
-                            cx.update(|_, cx| {
-                                left_view_for_task.update(cx, |this, cx| {
-                                    this.queue_output_bytes(&left_batch, cx);
-                                });
-                            })
-                            .ok();
-                        }
-
-                        let mut right_batch = Vec::new();
-                        while let Ok(chunk) = right_rx.try_recv() {
-                            right_batch.extend_from_slice(&chunk);
-                        }
-                        if !right_batch.is_empty() {
-                            cx.update(|_, cx| {
-                                right_view_for_task.update(cx, |this, cx| {
-                                    this.queue_output_bytes(&right_batch, cx);
-                                });
-                            })
-                            .ok();
-                        }
-                    }
-                })
-                .detach();
-
-            split
+            let panel = cx.new(|cx| TerminalPanel::new(spawn_shell_pane, window, cx));
+            cx.subscribe(&panel, |_, event, cx| {
+                if *event == TerminalPanelEvent::Empty {
+                    cx.quit();
+                }
+            })
+            .detach();
+            cx.new(|_| SplitTerminal {
+                panel,
+                all_panes_search: None,
+            })
         })
         .unwrap();
     });